@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use crate::graph::Edge;
+
+// 邻接表的存储方式，构造时选择：Graph::with_representation(Repr::Csr)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Repr {
+    // 每个节点一个 Vec，增删边代价低
+    #[default]
+    List,
+    // 压缩稀疏行：所有出边连续存放在一个数组里，按节点偏移量切分。
+    // 省去每个节点的 Vec 和扩容余量，适合构建后只读的大图；增删边要移动后面的数据，代价为 O(E)
+    Csr,
+}
+
+// 节点 -> 出边表。两种存储都按节点 ID 升序、出边按插入顺序，遍历顺序一致
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum Adjacency<W> {
+    List(BTreeMap<i64, Vec<Edge<W>>>),
+    Csr {
+        // 升序的节点 ID；nodes[i] 的出边为 edges[offsets[i]..offsets[i + 1]]
+        nodes: Vec<i64>,
+        offsets: Vec<usize>,
+        edges: Vec<Edge<W>>,
+    },
+}
+
+impl<W> Default for Adjacency<W> {
+    fn default() -> Self {
+        Adjacency::List(BTreeMap::new())
+    }
+}
+
+impl<W> Adjacency<W> {
+    pub(crate) fn from_lists(repr: Repr, lists: BTreeMap<i64, Vec<Edge<W>>>) -> Self {
+        match repr {
+            Repr::List => Adjacency::List(lists),
+            Repr::Csr => {
+                let mut nodes = Vec::with_capacity(lists.len());
+                let mut offsets = Vec::with_capacity(lists.len() + 1);
+                let mut edges = Vec::with_capacity(lists.values().map(Vec::len).sum());
+                offsets.push(0);
+                for (u, list) in lists {
+                    nodes.push(u);
+                    edges.extend(list);
+                    offsets.push(edges.len());
+                }
+                Adjacency::Csr {
+                    nodes,
+                    offsets,
+                    edges,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn into_lists(self) -> BTreeMap<i64, Vec<Edge<W>>> {
+        match self {
+            Adjacency::List(lists) => lists,
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => {
+                let mut edges = edges.into_iter();
+                nodes
+                    .into_iter()
+                    .zip(offsets.windows(2))
+                    .map(|(u, w)| (u, edges.by_ref().take(w[1] - w[0]).collect()))
+                    .collect()
+            }
+        }
+    }
+
+    pub(crate) fn repr(&self) -> Repr {
+        match self {
+            Adjacency::List(_) => Repr::List,
+            Adjacency::Csr { .. } => Repr::Csr,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Adjacency::List(lists) => lists.len(),
+            Adjacency::Csr { nodes, .. } => nodes.len(),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, u: &i64) -> bool {
+        match self {
+            Adjacency::List(lists) => lists.contains_key(u),
+            Adjacency::Csr { nodes, .. } => nodes.binary_search(u).is_ok(),
+        }
+    }
+
+    pub(crate) fn get(&self, u: &i64) -> Option<&[Edge<W>]> {
+        match self {
+            Adjacency::List(lists) => lists.get(u).map(Vec::as_slice),
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => {
+                let i = nodes.binary_search(u).ok()?;
+                Some(&edges[offsets[i]..offsets[i + 1]])
+            }
+        }
+    }
+
+    pub(crate) fn keys(&self) -> Box<dyn Iterator<Item = &i64> + '_> {
+        match self {
+            Adjacency::List(lists) => Box::new(lists.keys()),
+            Adjacency::Csr { nodes, .. } => Box::new(nodes.iter()),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Box<dyn Iterator<Item = (&i64, &[Edge<W>])> + '_> {
+        match self {
+            Adjacency::List(lists) => Box::new(lists.iter().map(|(u, l)| (u, l.as_slice()))),
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => Box::new(
+                nodes
+                    .iter()
+                    .zip(offsets.windows(2))
+                    .map(|(u, w)| (u, &edges[w[0]..w[1]])),
+            ),
+        }
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (&i64, &mut [Edge<W>])> + '_> {
+        match self {
+            Adjacency::List(lists) => {
+                Box::new(lists.iter_mut().map(|(u, l)| (u, l.as_mut_slice())))
+            }
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => {
+                let mut rest = edges.as_mut_slice();
+                let mut slices = Vec::with_capacity(nodes.len());
+                for (u, w) in nodes.iter().zip(offsets.windows(2)) {
+                    let (head, tail) = std::mem::take(&mut rest).split_at_mut(w[1] - w[0]);
+                    slices.push((u, head));
+                    rest = tail;
+                }
+                Box::new(slices.into_iter())
+            }
+        }
+    }
+
+    // 登记节点，已存在时不做改动
+    pub(crate) fn add_node(&mut self, u: i64) {
+        self.modify(u, |_| {});
+    }
+
+    // 删除节点及其出边，返回删除的出边
+    pub(crate) fn remove(&mut self, u: &i64) -> Option<Vec<Edge<W>>> {
+        match self {
+            Adjacency::List(lists) => lists.remove(u),
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => {
+                let i = nodes.binary_search(u).ok()?;
+                let (start, end) = (offsets[i], offsets[i + 1]);
+                nodes.remove(i);
+                offsets.remove(i + 1);
+                for o in &mut offsets[i + 1..] {
+                    *o -= end - start;
+                }
+                Some(edges.drain(start..end).collect())
+            }
+        }
+    }
+
+    // 修改 u 的出边表，u 不存在时先登记
+    pub(crate) fn modify<R>(&mut self, u: i64, f: impl FnOnce(&mut Vec<Edge<W>>) -> R) -> R {
+        match self {
+            Adjacency::List(lists) => f(lists.entry(u).or_default()),
+            Adjacency::Csr {
+                nodes,
+                offsets,
+                edges,
+            } => {
+                let i = nodes.binary_search(&u).unwrap_or_else(|i| {
+                    nodes.insert(i, u);
+                    offsets.insert(i + 1, offsets[i]);
+                    i
+                });
+                let (start, end) = (offsets[i], offsets[i + 1]);
+                let mut list: Vec<Edge<W>> = edges.drain(start..end).collect();
+                let result = f(&mut list);
+
+                let len = list.len();
+                edges.splice(start..start, list);
+                for o in &mut offsets[i + 1..] {
+                    *o = *o + len - (end - start);
+                }
+                result
+            }
+        }
+    }
+
+    // 依次修改每个节点的出边表；CSR 下整体重建一次，而不是逐个节点移动数据
+    pub(crate) fn update_all(&mut self, mut f: impl FnMut(i64, &mut Vec<Edge<W>>)) {
+        let repr = self.repr();
+        let mut lists = std::mem::take(self).into_lists();
+        for (&u, list) in lists.iter_mut() {
+            f(u, list);
+        }
+        *self = Self::from_lists(repr, lists);
+    }
+}
+
+impl<'a, W> IntoIterator for &'a Adjacency<W> {
+    type Item = (&'a i64, &'a [Edge<W>]);
+    type IntoIter = Box<dyn Iterator<Item = Self::Item> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,506 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::graph::{BellmanFordResult, DijkstraResult, Graph, MstResult, NegativeCycle, State};
+use crate::trace::StepEvent;
+use crate::weight::Weight;
+
+// 遍历与最短路径算法对图的全部要求：节点列表与带权出边。
+// Graph、隐式图（implicit::Indexed）以及用户自己的图结构实现它之后，
+// 都能直接调用本模块的 dfs/bfs/dijkstra/prim，共用同一份实现
+pub trait GraphLike {
+    type Weight: Weight;
+
+    // 全部节点；隐式图可以只返回目前已生成的部分
+    fn nodes(&self) -> Vec<i64>;
+
+    // u 的出边 (终点, 权重)，平行边各出现一次，顺序即遍历时考察邻居的顺序
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, Self::Weight)> + '_;
+
+    // u -> v 的边权，有平行边时取最小的
+    fn weight(&self, u: i64, v: i64) -> Option<Self::Weight> {
+        self.neighbors(u)
+            .filter(|&(x, _)| x == v)
+            .map(|(_, w)| w)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+impl<W: Weight> GraphLike for Graph<W> {
+    type Weight = W;
+
+    fn nodes(&self) -> Vec<i64> {
+        Graph::nodes(self)
+    }
+
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, W)> + '_ {
+        self.out_edges(u).map(|e| (e.to, e.weight))
+    }
+}
+
+pub fn dfs<G: GraphLike>(g: &G, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
+    let mut visited: HashSet<i64> = HashSet::new();
+    let mut visited_nodes: Vec<i64> = Vec::new();
+    let mut visited_edges: Vec<(i64, i64)> = Vec::new();
+
+    dfs_helper(
+        g,
+        s,
+        &mut visited,
+        &mut visited_nodes,
+        &mut visited_edges,
+        &mut |_| {},
+    );
+
+    (visited_nodes, visited_edges)
+}
+
+pub fn dfs_steps<G: GraphLike>(g: &G, s: i64) -> Vec<StepEvent<G::Weight>> {
+    let mut steps = Vec::new();
+    dfs_helper(
+        g,
+        s,
+        &mut HashSet::new(),
+        &mut Vec::new(),
+        &mut Vec::new(),
+        &mut |e| steps.push(e),
+    );
+    steps
+}
+
+fn dfs_helper<G: GraphLike>(
+    g: &G,
+    curr: i64,
+    visited: &mut HashSet<i64>,
+    visited_nodes: &mut Vec<i64>,
+    visited_edges: &mut Vec<(i64, i64)>,
+    emit: &mut dyn FnMut(StepEvent<G::Weight>),
+) -> bool {
+    visited.insert(curr);
+    visited_nodes.push(curr);
+    emit(StepEvent::Visit { u: curr });
+
+    for (v, _) in g.neighbors(curr) {
+        if !visited.contains(&v) {
+            visited_edges.push((curr, v));
+            emit(StepEvent::TreeEdge { u: curr, v });
+            if dfs_helper(g, v, visited, visited_nodes, visited_edges, emit) {
+                return true;
+            }
+        } else {
+            emit(StepEvent::Skip { u: curr, v });
+        }
+    }
+    emit(StepEvent::Backtrack { u: curr });
+    false
+}
+
+pub fn bfs<G: GraphLike>(g: &G, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
+    bfs_traced(g, &[s], &mut |_| {})
+}
+
+pub fn bfs_steps<G: GraphLike>(g: &G, s: i64) -> Vec<StepEvent<G::Weight>> {
+    let mut steps = Vec::new();
+    bfs_traced(g, &[s], &mut |e| steps.push(e));
+    steps
+}
+
+// 多源 BFS：所有起点一开始都在队列中（距离为 0），得到的是一片 BFS 森林，
+// 每个节点挂在离它最近的起点下面
+pub fn bfs_multi<G: GraphLike>(g: &G, sources: &[i64]) -> (Vec<i64>, Vec<(i64, i64)>) {
+    bfs_traced(g, sources, &mut |_| {})
+}
+
+pub fn bfs_multi_steps<G: GraphLike>(g: &G, sources: &[i64]) -> Vec<StepEvent<G::Weight>> {
+    let mut steps = Vec::new();
+    bfs_traced(g, sources, &mut |e| steps.push(e));
+    steps
+}
+
+fn bfs_traced<G: GraphLike>(
+    g: &G,
+    sources: &[i64],
+    emit: &mut dyn FnMut(StepEvent<G::Weight>),
+) -> (Vec<i64>, Vec<(i64, i64)>) {
+    let mut visited: HashSet<i64> = HashSet::new();
+    let mut visited_nodes: Vec<i64> = Vec::new();
+    let mut visited_edges: Vec<(i64, i64)> = Vec::new();
+    let mut q: VecDeque<i64> = VecDeque::new();
+
+    for &s in sources {
+        if visited.insert(s) {
+            q.push_back(s);
+            visited_nodes.push(s);
+            emit(StepEvent::Visit { u: s });
+        }
+    }
+
+    while let Some(u) = q.pop_front() {
+        emit(StepEvent::Dequeue { u });
+        for (v, _) in g.neighbors(u) {
+            if !visited.contains(&v) {
+                q.push_back(v);
+                visited.insert(v);
+                visited_nodes.push(v);
+                visited_edges.push((u, v));
+                emit(StepEvent::TreeEdge { u, v });
+                emit(StepEvent::Visit { u: v });
+            } else {
+                emit(StepEvent::Skip { u, v });
+            }
+        }
+    }
+
+    (visited_nodes, visited_edges)
+}
+
+pub fn dijkstra<G: GraphLike>(g: &G, s: i64) -> DijkstraResult<G::Weight> {
+    dijkstra_traced(g, s, &mut |_| {})
+}
+
+// 算法执行过程中的结构化步骤，用于生成带原因的解释日志
+pub fn dijkstra_steps<G: GraphLike>(g: &G, s: i64) -> Vec<StepEvent<G::Weight>> {
+    let mut steps = Vec::new();
+    dijkstra_traced(g, s, &mut |e| steps.push(e));
+    steps
+}
+
+fn dijkstra_traced<G: GraphLike>(
+    g: &G,
+    s: i64,
+    emit: &mut dyn FnMut(StepEvent<G::Weight>),
+) -> DijkstraResult<G::Weight> {
+    let mut dist: HashMap<i64, G::Weight> = HashMap::new();
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut visited_nodes: Vec<i64> = Vec::new();
+    let mut visited_edges: Vec<(i64, i64)> = Vec::new();
+    let mut processed: HashSet<i64> = HashSet::new();
+
+    let mut pq: BinaryHeap<State<G::Weight>> = BinaryHeap::new();
+
+    dist.insert(s, G::Weight::zero());
+    pq.push(State {
+        cost: G::Weight::zero(),
+        node: s,
+    });
+
+    while let Some(u) = pq.pop() {
+        if dist.get(&u.node).is_some_and(|&d| d.less_than(u.cost)) {
+            continue;
+        }
+        if !processed.contains(&u.node) {
+            visited_nodes.push(u.node);
+            processed.insert(u.node);
+            emit(StepEvent::Settle {
+                u: u.node,
+                dist: u.cost,
+            });
+        }
+        for (v, w) in g.neighbors(u.node) {
+            let cost = u.cost.add_or_infinity(w);
+            let current = *dist.get(&v).unwrap_or(&G::Weight::infinity());
+            if cost.less_than(current) {
+                dist.insert(v, cost);
+                parent.insert(v, u.node);
+                pq.push(State { cost, node: v });
+                visited_edges.push((u.node, v));
+                emit(StepEvent::Relax {
+                    u: u.node,
+                    v,
+                    base: u.cost,
+                    w,
+                    old: current,
+                });
+            } else if !processed.contains(&v) {
+                emit(StepEvent::NoRelax {
+                    u: u.node,
+                    v,
+                    base: u.cost,
+                    w,
+                    current,
+                });
+            }
+        }
+    }
+
+    DijkstraResult {
+        source: s,
+        visited_nodes,
+        visited_edges,
+        dist,
+        parent,
+    }
+}
+
+// Kahn 算法，同时可以入队的节点按 ID 从小到大取出，结果唯一。
+// 有环时返回 None；无向边相当于两条方向相反的边，构成环
+pub fn topological_sort<G: GraphLike>(g: &G) -> Option<Vec<i64>> {
+    let nodes = g.nodes();
+    let mut indegree: HashMap<i64, usize> = nodes.iter().map(|&u| (u, 0)).collect();
+    for &u in &nodes {
+        for (v, _) in g.neighbors(u) {
+            *indegree.entry(v).or_default() += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<i64>> = indegree
+        .iter()
+        .filter(|&(_, &d)| d == 0)
+        .map(|(&u, _)| Reverse(u))
+        .collect();
+    let mut order = Vec::with_capacity(indegree.len());
+    while let Some(Reverse(u)) = ready.pop() {
+        order.push(u);
+        for (v, _) in g.neighbors(u) {
+            let d = indegree.get_mut(&v).expect("终点已统计入度");
+            *d -= 1;
+            if *d == 0 {
+                ready.push(Reverse(v));
+            }
+        }
+    }
+    (order.len() == indegree.len()).then_some(order)
+}
+
+// DAG 上的单源最短路径：按拓扑序松弛每个节点的出边，O(V + E)，允许负权。
+// 图中有环时返回 None；visited_nodes 为可达节点的拓扑序
+pub fn dag_shortest_paths<G: GraphLike>(g: &G, s: i64) -> Option<DijkstraResult<G::Weight>> {
+    dag_shortest_paths_traced(g, s, &mut |_| {})
+}
+
+pub fn dag_shortest_paths_steps<G: GraphLike>(g: &G, s: i64) -> Option<Vec<StepEvent<G::Weight>>> {
+    let mut steps = Vec::new();
+    dag_shortest_paths_traced(g, s, &mut |e| steps.push(e))?;
+    Some(steps)
+}
+
+fn dag_shortest_paths_traced<G: GraphLike>(
+    g: &G,
+    s: i64,
+    emit: &mut dyn FnMut(StepEvent<G::Weight>),
+) -> Option<DijkstraResult<G::Weight>> {
+    let order = topological_sort(g)?;
+    let mut dist: HashMap<i64, G::Weight> = HashMap::from([(s, G::Weight::zero())]);
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut visited_nodes = Vec::new();
+    let mut visited_edges = Vec::new();
+
+    for u in order {
+        // 拓扑序中排在 s 之前或从 s 不可达的节点没有距离
+        let Some(&base) = dist.get(&u) else {
+            continue;
+        };
+        visited_nodes.push(u);
+        emit(StepEvent::Settle { u, dist: base });
+        for (v, w) in g.neighbors(u) {
+            let cost = base.add_or_infinity(w);
+            let current = *dist.get(&v).unwrap_or(&G::Weight::infinity());
+            if cost.less_than(current) {
+                dist.insert(v, cost);
+                parent.insert(v, u);
+                visited_edges.push((u, v));
+                emit(StepEvent::Relax {
+                    u,
+                    v,
+                    base,
+                    w,
+                    old: current,
+                });
+            } else {
+                emit(StepEvent::NoRelax {
+                    u,
+                    v,
+                    base,
+                    w,
+                    current,
+                });
+            }
+        }
+    }
+
+    Some(DijkstraResult {
+        source: s,
+        visited_nodes,
+        visited_edges,
+        dist,
+        parent,
+    })
+}
+
+// Bellman-Ford：最多 |V| - 1 轮，每轮按 nodes() 的顺序松弛已有距离的节点的所有出边，允许负权；
+// 某一轮没有任何更新时提前结束。之后再松弛一遍，仍有更新说明存在从 s 可达的负环，
+// 沿最后更新的节点的前驱走 |V| 步一定落在环上。无向的负权边本身就是一个两条边的负环
+pub fn bellman_ford<G: GraphLike>(g: &G, s: i64) -> BellmanFordResult<G::Weight> {
+    let nodes = g.nodes();
+    let mut dist: HashMap<i64, G::Weight> = HashMap::from([(s, G::Weight::zero())]);
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut visited_nodes = vec![s];
+    let mut visited_edges = Vec::new();
+    let mut rounds = Vec::new();
+
+    for _ in 1..nodes.len() {
+        let mut events = Vec::new();
+        for &u in &nodes {
+            let Some(&base) = dist.get(&u) else {
+                continue;
+            };
+            for (v, w) in g.neighbors(u) {
+                let cost = base.add_or_infinity(w);
+                let current = *dist.get(&v).unwrap_or(&G::Weight::infinity());
+                if cost.less_than(current) {
+                    if !dist.contains_key(&v) {
+                        visited_nodes.push(v);
+                    }
+                    dist.insert(v, cost);
+                    parent.insert(v, u);
+                    visited_edges.push((u, v));
+                    events.push(StepEvent::Relax {
+                        u,
+                        v,
+                        base,
+                        w,
+                        old: current,
+                    });
+                } else {
+                    events.push(StepEvent::NoRelax {
+                        u,
+                        v,
+                        base,
+                        w,
+                        current,
+                    });
+                }
+            }
+        }
+        let changed = events.iter().any(|e| matches!(e, StepEvent::Relax { .. }));
+        rounds.push(events);
+        if !changed {
+            break;
+        }
+    }
+
+    // 第 |V| 遍只用于检查，在副本上松弛，不改变结果
+    let mut check_dist = dist.clone();
+    let mut check_parent = parent.clone();
+    let mut first = None;
+    let mut last = None;
+    for &u in &nodes {
+        let Some(&base) = check_dist.get(&u) else {
+            continue;
+        };
+        for (v, w) in g.neighbors(u) {
+            let cost = base.add_or_infinity(w);
+            if check_dist.get(&v).is_none_or(|&d| cost.less_than(d)) {
+                check_dist.insert(v, cost);
+                check_parent.insert(v, u);
+                first.get_or_insert((u, v));
+                last = Some(v);
+            }
+        }
+    }
+    let negative_cycle = first.zip(last).and_then(|(edge, last)| {
+        let mut x = last;
+        for _ in 0..nodes.len() {
+            x = *check_parent.get(&x)?;
+        }
+        let mut cycle = vec![x];
+        let mut y = *check_parent.get(&x)?;
+        while y != x {
+            cycle.push(y);
+            y = *check_parent.get(&y)?;
+        }
+        cycle.reverse();
+        let weight = cycle
+            .iter()
+            .zip(cycle.iter().cycle().skip(1))
+            .map(|(&a, &b)| g.weight(a, b).unwrap_or_else(G::Weight::infinity))
+            .fold(G::Weight::zero(), |acc, w| acc.add_or_infinity(w));
+        Some(NegativeCycle {
+            edge,
+            nodes: cycle,
+            weight,
+        })
+    });
+
+    BellmanFordResult {
+        paths: DijkstraResult {
+            source: s,
+            visited_nodes,
+            visited_edges,
+            dist,
+            parent,
+        },
+        rounds,
+        negative_cycle,
+    }
+}
+
+// 从 s 出发的 Prim，只覆盖 s 所在的连通分量
+pub fn prim<G: GraphLike>(g: &G, s: i64) -> MstResult<G::Weight> {
+    prim_traced(g, s, &mut |_| {})
+}
+
+pub fn prim_steps<G: GraphLike>(g: &G, s: i64) -> Vec<StepEvent<G::Weight>> {
+    let mut steps = Vec::new();
+    prim_traced(g, s, &mut |e| steps.push(e));
+    steps
+}
+
+fn prim_traced<G: GraphLike>(
+    g: &G,
+    s: i64,
+    emit: &mut dyn FnMut(StepEvent<G::Weight>),
+) -> MstResult<G::Weight> {
+    let mut dist: HashMap<i64, G::Weight> = HashMap::new();
+    let mut booked: HashSet<i64> = HashSet::new();
+    let mut visited_nodes: Vec<i64> = Vec::new();
+    let mut tree_edges: Vec<(i64, i64, G::Weight)> = Vec::new();
+
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut pq: BinaryHeap<State<G::Weight>> = BinaryHeap::new();
+
+    dist.insert(s, G::Weight::zero());
+    pq.push(State {
+        cost: G::Weight::zero(),
+        node: s,
+    });
+
+    while let Some(State { cost, node: u }) = pq.pop() {
+        if booked.contains(&u) {
+            continue;
+        }
+        if dist.get(&u).is_some_and(|&d| d.less_than(cost)) {
+            continue;
+        }
+        booked.insert(u);
+        visited_nodes.push(u);
+
+        if let Some(&p) = parent.get(&u) {
+            tree_edges.push((p, u, cost));
+            emit(StepEvent::MstEdge {
+                u: p,
+                v: u,
+                w: cost,
+            });
+        }
+        emit(StepEvent::Visit { u });
+
+        for (v, w) in g.neighbors(u) {
+            let current = *dist.get(&v).unwrap_or(&G::Weight::infinity());
+            if !booked.contains(&v) && w.less_than(current) {
+                dist.insert(v, w);
+                parent.insert(v, u);
+                pq.push(State { cost: w, node: v });
+                emit(StepEvent::Candidate {
+                    u,
+                    v,
+                    w,
+                    old: current,
+                });
+            }
+        }
+    }
+
+    let is_spanning = visited_nodes.len() == g.nodes().len();
+    MstResult::new(visited_nodes, tree_edges, is_spanning)
+}
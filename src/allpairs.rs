@@ -0,0 +1,99 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// 对每个源点分别调用 f；启用 rayon 特性时各源点在线程池中并行计算。
+// 每完成一个源点 progress 加一，界面线程可以据此显示进度；
+// cancel 被置位后不再开始新的源点，整体返回 None
+fn per_source<T: Send>(
+    nodes: &[i64],
+    progress: &AtomicUsize,
+    cancel: &AtomicBool,
+    f: impl Fn(i64) -> T + Sync,
+) -> Option<Vec<T>> {
+    let run = |&s: &i64| {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let result = f(s);
+        progress.fetch_add(1, Ordering::Relaxed);
+        Some(result)
+    };
+    #[cfg(feature = "rayon")]
+    return nodes.par_iter().map(run).collect();
+    #[cfg(not(feature = "rayon"))]
+    return nodes.iter().map(run).collect();
+}
+
+// 全源最短距离：源点 -> (节点 -> 距离)，不可达的节点为 W::infinity()；被取消时返回 None
+pub fn distances<W: Weight>(
+    g: &Graph<W>,
+    progress: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> Option<BTreeMap<i64, HashMap<i64, W>>> {
+    let nodes = g.nodes();
+    let dist = per_source(&nodes, progress, cancel, |s| g.dijkstra(s).dist)?;
+    Some(nodes.into_iter().zip(dist).collect())
+}
+
+// Brandes 介数中心性：经过每个节点的最短路径比例之和，边权需为正。
+// 无向图中 s -> t 和 t -> s 是同一条路径，结果除以 2。被取消时返回 None
+pub fn betweenness<W: Weight>(
+    g: &Graph<W>,
+    progress: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> Option<HashMap<i64, f64>> {
+    let nodes = g.nodes();
+    let parts = per_source(&nodes, progress, cancel, |s| dependencies(g, s))?;
+
+    let scale = if g.is_directed() { 1.0 } else { 0.5 };
+    let mut result: HashMap<i64, f64> = nodes.iter().map(|&u| (u, 0.0)).collect();
+    for part in parts {
+        for (u, d) in part {
+            *result.entry(u).or_default() += d * scale;
+        }
+    }
+    Some(result)
+}
+
+// 单个源点 s 对其他节点的依赖度 delta_s(v)
+fn dependencies<W: Weight>(g: &Graph<W>, s: i64) -> Vec<(i64, f64)> {
+    let dist = g.dijkstra(s).dist;
+    let mut order: Vec<(i64, W)> = dist
+        .iter()
+        .filter(|(_, d)| **d != W::infinity())
+        .map(|(&u, &d)| (u, d))
+        .collect();
+    order.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    // 最短路径数和最短路径上的前驱，按距离从小到大累加
+    let mut sigma: HashMap<i64, f64> = HashMap::from([(s, 1.0)]);
+    let mut preds: HashMap<i64, Vec<i64>> = HashMap::new();
+    for &(u, du) in &order {
+        let su = sigma.get(&u).copied().unwrap_or(0.0);
+        for e in g.out_edges(u) {
+            if e.to != s && du.add_or_infinity(e.weight).approx_eq(dist[&e.to]) {
+                *sigma.entry(e.to).or_default() += su;
+                preds.entry(e.to).or_default().push(u);
+            }
+        }
+    }
+
+    let mut delta: HashMap<i64, f64> = HashMap::new();
+    let mut result = Vec::new();
+    for &(w, _) in order.iter().rev() {
+        let dw = delta.get(&w).copied().unwrap_or(0.0);
+        for &v in preds.get(&w).into_iter().flatten() {
+            *delta.entry(v).or_default() += sigma[&v] / sigma[&w] * (1.0 + dw);
+        }
+        if w != s {
+            result.push((w, dw));
+        }
+    }
+    result
+}
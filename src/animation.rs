@@ -0,0 +1,287 @@
+use crate::trace::{AlgorithmTrace, StepEvent, StepKind};
+use crate::weight::Weight;
+
+// 动画每一步的间隔（秒）
+pub const STEP_SECONDS: f64 = 0.2;
+
+// 动画节奏：各类步骤点亮后停留的时间，以 STEP_SECONDS 为单位。由注册表中的算法各自指定，
+// 例如 Dijkstra 在出堆（确定节点）时多停一会儿、松弛时快一些。
+// 说明类步骤（松弛失败、出队、回溯等）本身不点亮，它们的停留时间加到前一步上
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pacing {
+    pub node: f64,
+    pub edge: f64,
+    pub note: f64,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Self {
+            node: 1.0,
+            edge: 1.0,
+            note: 0.0,
+        }
+    }
+}
+
+impl Pacing {
+    fn seconds(&self, kind: StepKind) -> f64 {
+        STEP_SECONDS
+            * match kind {
+                StepKind::Node => self.node,
+                StepKind::Edge => self.edge,
+                StepKind::Note => self.note,
+            }
+    }
+}
+
+// 动画中点亮的一步：第 index 个节点或第 index 条边（节点和边分别计数，
+// 与步骤记录中节点类/边类步骤的序号对应）
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnimationStep {
+    Node { index: usize, node: i64 },
+    Edge { index: usize, edge: (i64, i64) },
+}
+
+// 不依赖界面的算法动画：按顺序逐个点亮一列节点和边，连续多条边或只有边都可以。
+// 界面、测试和其他前端调用 tick(dt) 推进，用 visited_nodes / visited_edges 查询目前点亮的部分。
+// 界面在开始时先调用一次 step()，让起点立即点亮；seek 跳到任意一步后暂停，resume 继续播放。
+// 每一步停留的时间默认都是 STEP_SECONDS，from_events_paced 按算法的 Pacing 调整
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationPlayer {
+    steps: Vec<AnimationStep>,
+    node_count: usize,
+    edge_count: usize,
+    // 每一步点亮后到下一步之前停留的秒数
+    holds: Vec<f64>,
+    shown: usize,
+    timer: f64,
+    paused: bool,
+}
+
+impl AnimationPlayer {
+    // 节点和边交替：nodes[0], edges[0], nodes[1], edges[1], …，较长的一方剩下的接在最后
+    pub fn new(nodes: Vec<i64>, edges: Vec<(i64, i64)>) -> Self {
+        let mut player = Self::default();
+        for i in 0..nodes.len().max(edges.len()) {
+            if let Some(&u) = nodes.get(i) {
+                player.push_node(u, STEP_SECONDS);
+            }
+            if let Some(&e) = edges.get(i) {
+                player.push_edge(e, STEP_SECONDS);
+            }
+        }
+        player
+    }
+
+    // 按步骤记录的原始顺序：访问/确定节点的步骤作为节点，树边、松弛成功的边、
+    // 生成树边、回路边和发现负环的边作为边，说明类步骤跳过。回路边同时点亮它的终点
+    pub fn from_events<W: Weight>(events: &[StepEvent<W>]) -> Self {
+        Self::from_events_paced(events, Pacing::default())
+    }
+
+    // 同 from_events，每一步停留的时间按 pacing 计算
+    pub fn from_events_paced<W: Weight>(events: &[StepEvent<W>], pacing: Pacing) -> Self {
+        let mut player = Self::default();
+        let node = pacing.seconds(StepKind::Node);
+        let edge = pacing.seconds(StepKind::Edge);
+        for event in events {
+            match *event {
+                StepEvent::Visit { u } | StepEvent::Settle { u, .. } => player.push_node(u, node),
+                StepEvent::TreeEdge { u, v }
+                | StepEvent::Relax { u, v, .. }
+                | StepEvent::MstEdge { u, v, .. }
+                | StepEvent::NegativeCycle { u, v } => player.push_edge((u, v), edge),
+                StepEvent::TourEdge { u, v, .. } => {
+                    if player.node_count == 0 {
+                        player.push_node(u, node);
+                    }
+                    player.push_edge((u, v), edge);
+                    player.push_node(v, node);
+                }
+                _ => {
+                    if let Some(hold) = player.holds.last_mut() {
+                        *hold += pacing.seconds(StepKind::Note);
+                    }
+                }
+            }
+        }
+        player
+    }
+
+    pub fn from_trace<W: Weight>(trace: &AlgorithmTrace<W>) -> Self {
+        Self::from_events(&trace.steps)
+    }
+
+    fn push_node(&mut self, node: i64, hold: f64) {
+        self.steps.push(AnimationStep::Node {
+            index: self.node_count,
+            node,
+        });
+        self.holds.push(hold);
+        self.node_count += 1;
+    }
+
+    fn push_edge(&mut self, edge: (i64, i64), hold: f64) {
+        self.steps.push(AnimationStep::Edge {
+            index: self.edge_count,
+            edge,
+        });
+        self.holds.push(hold);
+        self.edge_count += 1;
+    }
+
+    pub fn steps(&self) -> &[AnimationStep] {
+        &self.steps
+    }
+
+    // 全部节点，按点亮顺序
+    pub fn nodes(&self) -> Vec<i64> {
+        nodes_of(&self.steps)
+    }
+
+    pub fn edges(&self) -> Vec<(i64, i64)> {
+        edges_of(&self.steps)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    // 还有没点亮的节点或边
+    pub fn is_running(&self) -> bool {
+        self.shown < self.steps.len()
+    }
+
+    // 正在随时间推进：还有没点亮的步骤且没有暂停
+    pub fn is_playing(&self) -> bool {
+        self.is_running() && !self.paused
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // 跳到点亮前 shown 步之后的状态并暂停，超出总步数时停在最后一步
+    pub fn seek(&mut self, shown: usize) {
+        self.shown = shown.min(self.steps.len());
+        self.timer = 0.0;
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    // 最后点亮的一步
+    pub fn current(&self) -> Option<AnimationStep> {
+        self.shown.checked_sub(1).map(|i| self.steps[i])
+    }
+
+    // 已点亮的节点，按点亮顺序
+    pub fn visited_nodes(&self) -> Vec<i64> {
+        nodes_of(&self.steps[..self.shown])
+    }
+
+    pub fn visited_edges(&self) -> Vec<(i64, i64)> {
+        edges_of(&self.steps[..self.shown])
+    }
+
+    // 最后点亮的一步还要停留的总秒数，尚未点亮任何一步时为 STEP_SECONDS
+    pub fn hold(&self) -> f64 {
+        self.shown
+            .checked_sub(1)
+            .map_or(STEP_SECONDS, |i| self.holds[i])
+    }
+
+    // (已点亮的步数, 总步数)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.shown, self.steps.len())
+    }
+
+    // 经过 dt 秒，最后点亮的一步停留满 hold() 秒后走下一步，返回这期间点亮的节点和边；暂停时不动
+    pub fn tick(&mut self, dt: f64) -> Vec<AnimationStep> {
+        let mut shown = Vec::new();
+        if !self.is_playing() {
+            return shown;
+        }
+        self.timer += dt;
+        while self.is_running() && self.timer >= self.hold() {
+            self.timer -= self.hold();
+            shown.extend(self.step());
+        }
+        shown
+    }
+
+    // 不等计时立即点亮下一步，全部点亮后返回 None
+    pub fn step(&mut self) -> Option<AnimationStep> {
+        let step = *self.steps.get(self.shown)?;
+        self.shown += 1;
+        Some(step)
+    }
+}
+
+fn nodes_of(steps: &[AnimationStep]) -> Vec<i64> {
+    steps
+        .iter()
+        .filter_map(|step| match *step {
+            AnimationStep::Node { node, .. } => Some(node),
+            AnimationStep::Edge { .. } => None,
+        })
+        .collect()
+}
+
+fn edges_of(steps: &[AnimationStep]) -> Vec<(i64, i64)> {
+    steps
+        .iter()
+        .filter_map(|step| match *step {
+            AnimationStep::Edge { edge, .. } => Some(edge),
+            AnimationStep::Node { .. } => None,
+        })
+        .collect()
+}
+
+// 循环演示时动画播完后停留的时间（秒），让观众看清结果
+pub const REPLAY_HOLD_SECONDS: f64 = 3.0;
+
+// 循环演示：动画播完并停留 REPLAY_HOLD_SECONDS 秒后重新运行。
+// playlist 为空时重复上一次运行的菜单项，否则依次轮换其中的菜单项
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    playlist: Vec<String>,
+    next: usize,
+    waited: f64,
+}
+
+impl Replay {
+    pub fn new(playlist: Vec<String>) -> Self {
+        Self {
+            playlist,
+            ..Self::default()
+        }
+    }
+
+    pub fn playlist(&self) -> &[String] {
+        &self.playlist
+    }
+
+    // 每帧调用：idle 表示当前没有在播放的动画；空闲满停留时间后返回下一个要运行的菜单项，
+    // last 为上一次运行的菜单项。不空闲时重新计时
+    pub fn tick(&mut self, dt: f64, idle: bool, last: Option<&str>) -> Option<String> {
+        if !idle {
+            self.waited = 0.0;
+            return None;
+        }
+        self.waited += dt;
+        if self.waited < REPLAY_HOLD_SECONDS {
+            return None;
+        }
+        self.waited = 0.0;
+        if self.playlist.is_empty() {
+            return last.map(str::to_string);
+        }
+        let name = self.playlist[self.next % self.playlist.len()].clone();
+        self.next = (self.next + 1) % self.playlist.len();
+        Some(name)
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::algo::GraphLike;
+
+// 路径查询中禁行的节点和边，只是标记，图本身不变。禁行边不分方向，u-v 与 v-u 都不能走；
+// 禁行节点既不能经过也不能作为终点。查询时用 view 包一层只读视图代替原图传给算法
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Forbidden {
+    nodes: BTreeSet<i64>,
+    // 按 (较小端, 较大端) 保存
+    edges: BTreeSet<(i64, i64)>,
+}
+
+fn key(u: i64, v: i64) -> (i64, i64) {
+    (u.min(v), u.max(v))
+}
+
+impl Forbidden {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    // 按 ID 从小到大
+    pub fn nodes(&self) -> Vec<i64> {
+        self.nodes.iter().copied().collect()
+    }
+
+    // (较小端, 较大端)，从小到大
+    pub fn edges(&self) -> Vec<(i64, i64)> {
+        self.edges.iter().copied().collect()
+    }
+
+    pub fn has_node(&self, u: i64) -> bool {
+        self.nodes.contains(&u)
+    }
+
+    pub fn has_edge(&self, u: i64, v: i64) -> bool {
+        self.edges.contains(&key(u, v))
+    }
+
+    // 切换节点是否禁行，返回切换后的状态
+    pub fn toggle_node(&mut self, u: i64) -> bool {
+        if self.nodes.remove(&u) {
+            return false;
+        }
+        self.nodes.insert(u)
+    }
+
+    pub fn toggle_edge(&mut self, u: i64, v: i64) -> bool {
+        if self.edges.remove(&key(u, v)) {
+            return false;
+        }
+        self.edges.insert(key(u, v))
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+    }
+
+    // 能否沿 u -> v 走：两端都没有禁行，边也没有禁行
+    pub fn allows(&self, u: i64, v: i64) -> bool {
+        !self.has_node(u) && !self.has_node(v) && !self.has_edge(u, v)
+    }
+
+    // 去掉图中已经不存在的节点和边，图被编辑后调用；返回是否有标记被去掉
+    pub fn retain<G: GraphLike>(&mut self, g: &G) -> bool {
+        let before = (self.nodes.len(), self.edges.len());
+        let nodes: BTreeSet<i64> = g.nodes().into_iter().collect();
+        self.nodes.retain(|u| nodes.contains(u));
+        self.edges
+            .retain(|&(u, v)| g.weight(u, v).is_some() || g.weight(v, u).is_some());
+        before != (self.nodes.len(), self.edges.len())
+    }
+
+    // 图的节点重新编号后按 new_id（原 ID -> 新 ID）迁移标记，不在 new_id 中的节点一并去掉
+    pub fn relabel(&self, new_id: &HashMap<i64, i64>) -> Self {
+        Forbidden {
+            nodes: self
+                .nodes
+                .iter()
+                .filter_map(|u| new_id.get(u).copied())
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter_map(|(u, v)| Some(key(*new_id.get(u)?, *new_id.get(v)?)))
+                .collect(),
+        }
+    }
+
+    pub fn view<'a, G: GraphLike>(&'a self, graph: &'a G) -> Avoiding<'a, G> {
+        Avoiding {
+            graph,
+            forbidden: self,
+        }
+    }
+}
+
+// 去掉禁行节点和禁行边之后的只读视图，可以直接交给 algo 中的任何算法
+pub struct Avoiding<'a, G> {
+    graph: &'a G,
+    forbidden: &'a Forbidden,
+}
+
+impl<G: GraphLike> GraphLike for Avoiding<'_, G> {
+    type Weight = G::Weight;
+
+    fn nodes(&self) -> Vec<i64> {
+        self.graph
+            .nodes()
+            .into_iter()
+            .filter(|&u| !self.forbidden.has_node(u))
+            .collect()
+    }
+
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, G::Weight)> + '_ {
+        self.graph
+            .neighbors(u)
+            .filter(move |&(v, _)| self.forbidden.allows(u, v))
+    }
+}
@@ -0,0 +1,82 @@
+// 终端响铃提示：在重要事件时向终端输出 BEL（\x07），便于无障碍使用，
+// 或在做别的事时注意到算法已经完成。每种事件响几声可在配置文件中设置，0 表示不响
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cue {
+    // 确定一个节点的最短距离（Dijkstra 出堆）
+    Settle,
+    // 发现从起点可达的负环
+    NegativeCycle,
+    // 动画播完或后台计算结束
+    Complete,
+}
+
+// 同一事件连续几声之间的间隔（秒），太密时终端会把它们合成一声
+pub const BEEP_GAP_SECONDS: f64 = 0.15;
+
+// 各事件响铃的次数
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BellPattern {
+    pub settle: u16,
+    pub negative_cycle: u16,
+    pub complete: u16,
+}
+
+impl Default for BellPattern {
+    fn default() -> Self {
+        Self {
+            settle: 1,
+            negative_cycle: 3,
+            complete: 2,
+        }
+    }
+}
+
+impl BellPattern {
+    pub fn beeps(&self, cue: Cue) -> u16 {
+        match cue {
+            Cue::Settle => self.settle,
+            Cue::NegativeCycle => self.negative_cycle,
+            Cue::Complete => self.complete,
+        }
+    }
+}
+
+// 等待发出的响铃。界面在事件发生时调用 cue，每帧调用 tick，返回 true 时输出一声 BEL。
+// 关闭时 cue 不起作用；前一组还没响完时新的一组不叠加，只补足到较多的次数
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bell {
+    pub enabled: bool,
+    pub pattern: BellPattern,
+    pending: u16,
+    // 距离下一声还要等的秒数
+    wait: f64,
+}
+
+impl Bell {
+    pub fn new(enabled: bool, pattern: BellPattern) -> Self {
+        Self {
+            enabled,
+            pattern,
+            ..Self::default()
+        }
+    }
+
+    pub fn cue(&mut self, cue: Cue) {
+        if self.enabled {
+            self.pending = self.pending.max(self.pattern.beeps(cue));
+        }
+    }
+
+    pub fn tick(&mut self, dt: f64) -> bool {
+        self.wait = (self.wait - dt).max(0.0);
+        if !self.enabled {
+            self.pending = 0;
+        }
+        if self.pending == 0 || self.wait > 0.0 {
+            return false;
+        }
+        self.pending -= 1;
+        self.wait = BEEP_GAP_SECONDS;
+        true
+    }
+}
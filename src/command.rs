@@ -0,0 +1,534 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::graph::{EdgeType, Graph};
+use crate::turns::{TurnRestrictions, TurnRule};
+use crate::weight::Weight;
+
+// 命令栏（按 : 打开）中的一条命令
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command<W = i64> {
+    // 添加边，directed 为 false 时是无向边
+    Edge {
+        u: i64,
+        v: i64,
+        w: W,
+        directed: bool,
+    },
+    Node(i64),
+    // 删除 u、v 之间所有方向的边
+    DelEdge(i64, i64),
+    DelNode(i64),
+    // 按名称运行菜单项（不区分大小写），source 为起点
+    Run {
+        algorithm: String,
+        source: Option<i64>,
+    },
+    Save(String),
+    Load(String),
+    Set(Setting),
+    // 循环演示的播放列表（菜单项名称），为空时重复上一次运行的菜单项
+    Playlist(Vec<String>),
+    // 清空当前图，脚本从空图开始建图时使用
+    Clear,
+    // 切换节点或边在路径查询中的禁行标记（边不分方向）
+    ForbidNode(i64),
+    ForbidEdge(i64, i64),
+    ClearForbidden,
+    // 转向 from -> via -> to 的限制，penalty 为 None 时禁止，为 0 时取消限制
+    Turn {
+        from: i64,
+        via: i64,
+        to: i64,
+        penalty: Option<W>,
+    },
+    ClearTurns,
+    // 把节点放进名为 name 的分组（原来的分组被替换），nodes 为空时用多选的节点
+    Group {
+        name: String,
+        nodes: Vec<i64>,
+    },
+    ClearGroups,
+}
+
+// set 命令可修改的显示设置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    // 关闭时按无颜色终端显示
+    Color(bool),
+    Accessible(bool),
+    Grid(bool),
+    Curved(bool),
+    // 边权显示的小数位数
+    Precision(usize),
+    // 循环演示：动画播完后自动重新运行
+    Replay(bool),
+    // 重要事件时响铃
+    Bell(bool),
+}
+
+// 命令名、用法和说明，用于补全和出错提示
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "edge",
+        usage: "edge <u> <v> [权重]",
+        help: "添加无向边，权重默认为 1",
+    },
+    CommandSpec {
+        name: "arc",
+        usage: "arc <u> <v> [权重]",
+        help: "添加单向边 u -> v",
+    },
+    CommandSpec {
+        name: "node",
+        usage: "node <u>",
+        help: "添加孤立节点",
+    },
+    CommandSpec {
+        name: "deledge",
+        usage: "deledge <u> <v>",
+        help: "删除 u、v 之间的边",
+    },
+    CommandSpec {
+        name: "delnode",
+        usage: "delnode <u>",
+        help: "删除节点及与它相连的边",
+    },
+    CommandSpec {
+        name: "run",
+        usage: "run <菜单项> [起点]",
+        help: "运行菜单项，如 run dijkstra 3",
+    },
+    CommandSpec {
+        name: "save",
+        usage: "save <文件>",
+        help: "把当前图保存为命令脚本",
+    },
+    CommandSpec {
+        name: "load",
+        usage: "load <文件>",
+        help: "从命令脚本读入图，替换当前图",
+    },
+    CommandSpec {
+        name: "clear",
+        usage: "clear",
+        help: "清空当前图",
+    },
+    CommandSpec {
+        name: "set",
+        usage: "set <color|accessible|grid|curved|replay|bell> <on|off> 或 set precision <位数>",
+        help: "修改显示设置",
+    },
+    CommandSpec {
+        name: "playlist",
+        usage: "playlist [菜单项, 菜单项, …]",
+        help: "循环演示时依次轮换这些菜单项，不带参数时重复上一次运行的菜单项",
+    },
+    CommandSpec {
+        name: "forbid",
+        usage: "forbid <u> [v] | forbid clear",
+        help: "切换节点或边的禁行标记，最短路径查询会绕开禁行的部分",
+    },
+    CommandSpec {
+        name: "turn",
+        usage: "turn <from> <via> <to> [罚时] | turn clear",
+        help: "禁止从 from 经 via 转向 to，给出罚时时改为加罚，罚时为 0 时取消限制",
+    },
+    CommandSpec {
+        name: "group",
+        usage: "group <名称> [节点…] | group clear",
+        help: "把节点放进分组，不写节点时用多选的节点",
+    },
+];
+
+pub fn spec(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|c| c.name == name)
+}
+
+impl<W: Weight> Command<W> {
+    // 解析一行命令，开头的 : 可有可无；出错时返回可直接显示的说明
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim().trim_start_matches(':');
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = parts.split_first() else {
+            return Err("空命令".to_string());
+        };
+        let Some(spec) = spec(name) else {
+            let names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+            return Err(format!("未知命令: {}，可用: {}", name, names.join(" ")));
+        };
+        let usage = || format!("用法: {}", spec.usage);
+        let id = |s: &str| {
+            s.parse::<i64>()
+                .map_err(|_| format!("节点 ID 需要是整数: {}", s))
+        };
+
+        match (name, args) {
+            ("edge" | "arc", [u, v, rest @ ..]) if rest.len() <= 1 => Ok(Command::Edge {
+                u: id(u)?,
+                v: id(v)?,
+                w: match rest.first() {
+                    Some(w) => W::parse(w).ok_or_else(|| format!("无法解析权重: {}", w))?,
+                    None => W::parse("1").unwrap_or_else(W::zero),
+                },
+                directed: name == "arc",
+            }),
+            ("node", [u]) => Ok(Command::Node(id(u)?)),
+            ("deledge", [u, v]) => Ok(Command::DelEdge(id(u)?, id(v)?)),
+            ("delnode", [u]) => Ok(Command::DelNode(id(u)?)),
+            // 菜单项名称可能含空格，最后一段是整数时作为起点
+            ("run", [words @ .., last]) => {
+                let (words, source) = match last.parse::<i64>() {
+                    Ok(s) if !words.is_empty() => (words.to_vec(), Some(s)),
+                    _ => ([words, &[*last]].concat(), None),
+                };
+                Ok(Command::Run {
+                    algorithm: words.join(" "),
+                    source,
+                })
+            }
+            ("save", [path]) => Ok(Command::Save(path.to_string())),
+            ("load", [path]) => Ok(Command::Load(path.to_string())),
+            ("clear", []) => Ok(Command::Clear),
+            ("forbid", ["clear"]) => Ok(Command::ClearForbidden),
+            ("forbid", [u]) => Ok(Command::ForbidNode(id(u)?)),
+            ("forbid", [u, v]) => Ok(Command::ForbidEdge(id(u)?, id(v)?)),
+            ("turn", ["clear"]) => Ok(Command::ClearTurns),
+            ("group", ["clear"]) => Ok(Command::ClearGroups),
+            ("group", [name, nodes @ ..]) => Ok(Command::Group {
+                name: name.to_string(),
+                nodes: nodes.iter().map(|u| id(u)).collect::<Result<_, _>>()?,
+            }),
+            ("turn", [from, via, to, rest @ ..]) if rest.len() <= 1 => Ok(Command::Turn {
+                from: id(from)?,
+                via: id(via)?,
+                to: id(to)?,
+                penalty: match rest.first() {
+                    Some(p) => Some(W::parse(p).ok_or_else(|| format!("无法解析罚时: {}", p))?),
+                    None => None,
+                },
+            }),
+            ("set", ["precision", n]) => n
+                .parse()
+                .map(|n| Command::Set(Setting::Precision(n)))
+                .map_err(|_| format!("位数需要是非负整数: {}", n)),
+            ("set", [option, value]) => {
+                let setting: fn(bool) -> Setting = match *option {
+                    "color" => Setting::Color,
+                    "accessible" => Setting::Accessible,
+                    "grid" => Setting::Grid,
+                    "curved" => Setting::Curved,
+                    "replay" => Setting::Replay,
+                    "bell" => Setting::Bell,
+                    _ => return Err(format!("未知设置: {}，{}", option, usage())),
+                };
+                match *value {
+                    "on" => Ok(Command::Set(setting(true))),
+                    "off" => Ok(Command::Set(setting(false))),
+                    _ => Err(usage()),
+                }
+            }
+            // 菜单项名称可能含空格，用逗号分隔
+            ("playlist", words) => Ok(Command::Playlist(
+                words
+                    .join(" ")
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            )),
+            _ => Err(usage()),
+        }
+    }
+}
+
+// 输入时显示在命令栏后面的提示：命令名未输完时列出可能的命令及用法，
+// 能解析时给出说明，否则给出错误（如缺少参数时的用法）
+pub fn hint<W: Weight>(line: &str) -> String {
+    let line = line.trim_start().trim_start_matches(':');
+    let name = line.split_whitespace().next().unwrap_or("");
+    if !line.contains(' ') && spec(name).is_none() {
+        let matches: Vec<&str> = COMMANDS
+            .iter()
+            .filter(|c| c.name.starts_with(name))
+            .map(|c| c.usage)
+            .collect();
+        if matches.is_empty() {
+            return format!("未知命令: {}", name);
+        }
+        return matches.join(" | ");
+    }
+    match Command::<W>::parse(line) {
+        Ok(_) => spec(name).map_or(String::new(), |s| s.help.to_string()),
+        Err(e) => e,
+    }
+}
+
+// 命令栏的 Tab 补全：第一个词补全命令名，run 之后补全菜单项名称，
+// 其余位置（以及 run 末尾的起点）补全节点 ID
+pub fn complete_line(line: &str, algorithms: &[&str], nodes: &[i64]) -> String {
+    let ids: Vec<String> = nodes.iter().map(|u| u.to_string()).collect();
+    let complete_ids = |head: &str, last: &str| {
+        complete(last, ids.iter().map(String::as_str)).map(|id| format!("{}{}", head, id))
+    };
+    let completed = || match line.split_once(' ') {
+        None => complete(line, COMMANDS.iter().map(|c| c.name)),
+        Some(("run", rest)) => complete(rest, algorithms.iter().copied())
+            .map(|name| format!("run {}", name))
+            .or_else(|| {
+                let (head, last) = rest.rsplit_once(' ')?;
+                complete_ids(&format!("run {} ", head), last)
+            }),
+        Some(_) => {
+            let (head, last) = line.rsplit_once(' ')?;
+            complete_ids(&format!("{} ", head), last)
+        }
+    };
+    completed().unwrap_or_else(|| line.to_string())
+}
+
+// 在候选中补全 prefix：唯一匹配时补全为该项，多个匹配时补到它们的最长公共前缀
+pub fn complete<'a>(prefix: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let matches: Vec<&str> = candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().starts_with(&prefix.to_lowercase()))
+        .collect();
+    let (first, rest) = matches.split_first()?;
+    let mut common = first.to_string();
+    for m in rest {
+        let n = common
+            .chars()
+            .zip(m.chars())
+            .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+            .count();
+        common = common.chars().take(n).collect();
+    }
+    (common.chars().count() >= prefix.chars().count()).then_some(common)
+}
+
+// 把图写成命令脚本：孤立节点写 node，无向边写 edge，单向边写 arc，分组写 group，可用 load 读回。
+// 命令脚本表达不了的内容见 unsaved_fields
+pub fn script<W: Weight>(graph: &Graph<W>) -> String {
+    let mut out = String::new();
+    let edges = graph.edges();
+    for u in graph.nodes() {
+        if graph.degree(u) == 0 && !edges.iter().any(|&(_, v, _, _)| v == u) {
+            let _ = writeln!(out, "node {}", u);
+        }
+    }
+    for (u, v, w, edge_type) in edges {
+        let name = match edge_type {
+            EdgeType::Both => "edge",
+            EdgeType::Single => "arc",
+        };
+        let _ = writeln!(out, "{} {} {} {}", name, u, v, w);
+    }
+    for (name, members) in graph.groups() {
+        if name.contains(char::is_whitespace) {
+            continue;
+        }
+        let members: Vec<String> = members.iter().map(|u| u.to_string()).collect();
+        let _ = writeln!(out, "group {} {}", name, members.join(" "));
+    }
+    out
+}
+
+// script 写不出、保存后会丢失的内容，按固定顺序列出名称，保存时在日志中提示
+pub fn unsaved_fields<W: Weight>(graph: &Graph<W>) -> Vec<&'static str> {
+    let nodes = graph.nodes();
+    let edges = || nodes.iter().flat_map(|&u| graph.out_edges(u));
+    [
+        (
+            "节点标签",
+            nodes.iter().any(|&u| graph.node_label(u).is_some()),
+        ),
+        (
+            "节点属性",
+            nodes.iter().any(|&u| graph.node_attrs(u).next().is_some()),
+        ),
+        (
+            "名称含空格的分组",
+            graph
+                .groups()
+                .keys()
+                .any(|name| name.contains(char::is_whitespace)),
+        ),
+        ("边标签", edges().any(|e| e.label.is_some())),
+        (
+            "边容量",
+            edges().any(|e| e.capacity.total_cmp(&e.weight) != std::cmp::Ordering::Equal),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, present)| present.then_some(name))
+    .collect()
+}
+
+// 把转向限制写成 turn 命令，接在 script 的结果后面即为完整的项目文件
+pub fn turn_script<W: Weight>(turns: &TurnRestrictions<W>) -> String {
+    let mut out = String::new();
+    for ((from, via, to), rule) in turns.iter() {
+        let _ = match rule {
+            TurnRule::Ban => writeln!(out, "turn {} {} {}", from, via, to),
+            TurnRule::Penalty(p) => writeln!(out, "turn {} {} {} {}", from, via, to, p),
+        };
+    }
+    out
+}
+
+// 执行命令脚本中建图的命令（node、edge、arc），得到一张新图；# 开头的行和空行被忽略。
+// turn 行也可以出现，但会被丢弃，需要转向限制时用 load_project
+pub fn load_script<W: Weight>(text: &str) -> Result<Graph<W>, String> {
+    load_project(text).map(|(graph, _)| graph)
+}
+
+// 读入项目文件：建图的命令之外还可以有 group 命令给出分组、turn 命令给出转向限制
+pub fn load_project<W: Weight>(text: &str) -> Result<(Graph<W>, TurnRestrictions<W>), String> {
+    let mut graph = Graph::default();
+    let mut turns = TurnRestrictions::default();
+    for (no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match Command::parse(line).map_err(|e| format!("第 {} 行: {}", no + 1, e))? {
+            Command::Edge { u, v, w, directed } => {
+                let edge_type = if directed {
+                    EdgeType::Single
+                } else {
+                    EdgeType::Both
+                };
+                graph.add_edge(u, v, w, edge_type);
+            }
+            Command::Node(u) => graph.add_node(u),
+            Command::Group { name, nodes } if !nodes.is_empty() => {
+                if let Some(u) = nodes.iter().find(|&&u| !graph.contains_node(u)) {
+                    return Err(format!("第 {} 行: 节点 {} 不存在", no + 1, u));
+                }
+                for u in nodes {
+                    graph.set_group(u, &name);
+                }
+            }
+            Command::Turn {
+                from,
+                via,
+                to,
+                penalty,
+            } => turns.set(
+                (from, via, to),
+                penalty.map_or(TurnRule::Ban, TurnRule::Penalty),
+            ),
+            _ => {
+                return Err(format!(
+                    "第 {} 行: 只能包含 node/edge/arc/group/turn",
+                    no + 1
+                ));
+            }
+        }
+    }
+    Ok((graph, turns))
+}
+
+// 命令历史最多保留的条数
+pub const HISTORY_LIMIT: usize = 500;
+
+// 命令栏的历史：↑/↓ 在已执行的命令间移动，回到最新处时恢复正在输入的内容。
+// 每行一条命令，保存在配置目录的 history 文件中，跨会话保留
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    entries: Vec<String>,
+    // 正在浏览的条目下标，None 表示在最新处
+    cursor: Option<usize>,
+    // 开始浏览前正在输入的内容
+    draft: String,
+}
+
+impl History {
+    pub fn parse(text: &str) -> Self {
+        let mut history = Self::default();
+        for line in text.lines() {
+            history.push(line);
+        }
+        history
+    }
+
+    pub fn to_text(&self) -> String {
+        self.entries.iter().map(|e| format!("{}\n", e)).collect()
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    // 记录一条执行过的命令并回到最新处；空命令和与上一条相同的命令不记录
+    pub fn push(&mut self, line: &str) {
+        self.cursor = None;
+        let line = line.trim();
+        if line.is_empty() || self.entries.last().is_some_and(|e| e == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+    }
+
+    // 回到最新处，打开命令栏时调用
+    pub fn rewind(&mut self) {
+        self.cursor = None;
+    }
+
+    // 上一条命令；current 为当前输入，从最新处开始浏览时保存下来。已到最早一条时停在原处
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let i = match self.cursor {
+            None => {
+                self.draft = current.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+            Some(i) => i.saturating_sub(1),
+        };
+        self.cursor = Some(i);
+        Some(&self.entries[i])
+    }
+
+    // 下一条命令，越过最新一条时返回开始浏览前的输入；不在浏览时返回 None
+    pub fn newer(&mut self) -> Option<&str> {
+        let i = self.cursor? + 1;
+        if i < self.entries.len() {
+            self.cursor = Some(i);
+            Some(&self.entries[i])
+        } else {
+            self.cursor = None;
+            Some(&self.draft)
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        Some(Config::dir()?.join("history"))
+    }
+
+    // 读取历史文件，不存在或无法读取时为空
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map_or_else(Self::default, |text| Self::parse(&text))
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+}
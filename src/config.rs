@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use crate::bell::BellPattern;
+
+// 界面设置，每行 "键 = 值"，# 开头为注释；未知的键和无法解析的值忽略，保留默认值
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    // 画布占整个窗口宽度的百分比，其余为侧栏
+    pub canvas_ratio: u16,
+    // 侧栏中当前面板占的高度百分比，其余显示日志；100 表示不分割
+    pub panel_ratio: u16,
+    // 重要事件时响铃（bell = on/off），各事件响几声由 bell_settle 等键设置
+    pub bell: bool,
+    pub bell_pattern: BellPattern,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            canvas_ratio: 70,
+            panel_ratio: 100,
+            bell: false,
+            bell_pattern: BellPattern::default(),
+        }
+    }
+}
+
+impl Config {
+    pub const CANVAS_RANGE: (u16, u16) = (10, 90);
+    pub const PANEL_RANGE: (u16, u16) = (20, 100);
+
+    pub fn parse(text: &str) -> Self {
+        let mut config = Self::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            if key.trim() == "bell" {
+                match value {
+                    "on" => config.bell = true,
+                    "off" => config.bell = false,
+                    _ => {}
+                }
+                continue;
+            }
+            let Ok(value) = value.parse::<u16>() else {
+                continue;
+            };
+            match key.trim() {
+                "canvas_ratio" => {
+                    config.canvas_ratio = value.clamp(Self::CANVAS_RANGE.0, Self::CANVAS_RANGE.1)
+                }
+                "panel_ratio" => {
+                    config.panel_ratio = value.clamp(Self::PANEL_RANGE.0, Self::PANEL_RANGE.1)
+                }
+                "bell_settle" => config.bell_pattern.settle = value,
+                "bell_negative_cycle" => config.bell_pattern.negative_cycle = value,
+                "bell_complete" => config.bell_pattern.complete = value,
+                _ => {}
+            }
+        }
+        config
+    }
+
+    pub fn to_text(&self) -> String {
+        format!(
+            "# graph_algorithm_tui 界面设置，退出时自动保存\ncanvas_ratio = {}\npanel_ratio = {}\n\
+             # 响铃提示及各事件响几声，0 表示不响\nbell = {}\nbell_settle = {}\n\
+             bell_negative_cycle = {}\nbell_complete = {}\n",
+            self.canvas_ratio,
+            self.panel_ratio,
+            if self.bell { "on" } else { "off" },
+            self.bell_pattern.settle,
+            self.bell_pattern.negative_cycle,
+            self.bell_pattern.complete
+        )
+    }
+
+    // $XDG_CONFIG_HOME/graph_algorithm_tui，未设置时用 ~/.config；命令历史等也放在这里
+    pub fn dir() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("graph_algorithm_tui"))
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        Some(Self::dir()?.join("config"))
+    }
+
+    // 读取配置文件，不存在或无法读取时返回默认设置
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map_or_else(Self::default, |text| Self::parse(&text))
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+}
@@ -0,0 +1,46 @@
+use std::any::Any;
+use std::fmt::Write;
+use std::panic::PanicHookInfo;
+
+use crate::graph::{EdgeType, Graph};
+use crate::weight::Weight;
+
+// 崩溃时写入的日志文件，位于当前目录
+pub const CRASH_LOG: &str = "crash.log";
+
+// panic 的消息；payload 不是字符串（如 panic_any 传入的值）时给出占位文字
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<非字符串的 panic 信息>".to_string()
+    }
+}
+
+// 终端上打印的简短报告：消息与出错位置
+pub fn summary(info: &PanicHookInfo) -> String {
+    let location = info.location().map_or("未知".to_string(), |l| {
+        format!("{}:{}", l.file(), l.line())
+    });
+    format!(
+        "程序崩溃: {}\n位置: {}",
+        panic_message(info.payload()),
+        location
+    )
+}
+
+// 崩溃日志：报告加上当前图，图按 "u v 权重" 每行一条有向边写出，可直接用来复现
+pub fn crash_log<W: Weight>(summary: &str, graph: &Graph<W>) -> String {
+    let mut log = format!("{}\n\n--- 当前图 ---\n", summary);
+    let _ = writeln!(log, "# 节点: {:?}", graph.nodes());
+    for (u, v, w, edge_type) in graph.edges() {
+        let _ = writeln!(log, "{} {} {}", u, v, w);
+        // 无向边写成两条方向相反的边
+        if edge_type == EdgeType::Both && u != v {
+            let _ = writeln!(log, "{} {} {}", v, u, w);
+        }
+    }
+    log
+}
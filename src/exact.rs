@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::algo::GraphLike;
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// 精确求解的搜索预算：超时或 cancel 被置位后停止搜索，返回目前找到的最好解。
+// explored 为已搜索的状态数，界面线程读取它显示进度
+pub struct Budget<'a> {
+    deadline: Instant,
+    cancel: &'a AtomicBool,
+    explored: &'a AtomicUsize,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(limit: Duration, cancel: &'a AtomicBool, explored: &'a AtomicUsize) -> Self {
+        Self {
+            deadline: Instant::now() + limit,
+            cancel,
+            explored,
+        }
+    }
+
+    // 记一个搜索状态，返回是否还能继续；每 1024 个状态才查一次时间
+    fn tick(&self) -> bool {
+        let n = self.explored.fetch_add(1, Ordering::Relaxed);
+        !self.cancel.load(Ordering::Relaxed)
+            && (!n.is_multiple_of(1024) || Instant::now() < self.deadline)
+    }
+}
+
+// 精确求解的结果：finished 为 true 时 best 是最优解（None 表示无解），
+// 否则是预算用完或被取消前找到的最好解
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exact<T> {
+    pub best: Option<T>,
+    pub finished: bool,
+}
+
+// 旅行商问题：经过每个节点恰好一次的最短回路，顺着单向边的方向走，平行边取最轻的一条。
+// 从编号最小的节点出发做分支限界，先走近的邻居；剪枝假定边权非负。
+// 返回 (回路上的节点，不含回到起点的最后一步, 总权重)
+pub fn tsp<W: Weight>(g: &Graph<W>, budget: &Budget) -> Exact<(Vec<i64>, W)> {
+    let nodes = g.nodes();
+    let Some(&start) = nodes.first() else {
+        return Exact {
+            best: None,
+            finished: true,
+        };
+    };
+    if nodes.len() == 1 {
+        return Exact {
+            best: Some((nodes, W::zero())),
+            finished: true,
+        };
+    }
+
+    struct Search<'g, 'b, W: Weight> {
+        g: &'g Graph<W>,
+        budget: &'g Budget<'b>,
+        start: i64,
+        total: usize,
+        path: Vec<i64>,
+        best: Option<(Vec<i64>, W)>,
+        stopped: bool,
+    }
+
+    impl<W: Weight> Search<'_, '_, W> {
+        fn visit(&mut self, u: i64, cost: W) {
+            if self.stopped || !self.budget.tick() {
+                self.stopped = true;
+                return;
+            }
+            if self.best.as_ref().is_some_and(|(_, b)| !cost.less_than(*b)) {
+                return;
+            }
+            if self.path.len() == self.total {
+                if let Some(w) = self.g.weight(u, self.start) {
+                    let cost = cost.add_or_infinity(w);
+                    if self.best.as_ref().is_none_or(|(_, b)| cost.less_than(*b)) {
+                        self.best = Some((self.path.clone(), cost));
+                    }
+                }
+                return;
+            }
+            let candidates: BTreeSet<i64> = self
+                .g
+                .neighbors(u)
+                .filter(|v| !self.path.contains(v))
+                .collect();
+            let mut next: Vec<(i64, W)> = candidates
+                .into_iter()
+                .filter_map(|v| Some((v, self.g.weight(u, v)?)))
+                .collect();
+            next.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            for (v, w) in next {
+                self.path.push(v);
+                self.visit(v, cost.add_or_infinity(w));
+                self.path.pop();
+            }
+        }
+    }
+
+    let mut search = Search {
+        g,
+        budget,
+        start,
+        total: nodes.len(),
+        path: vec![start],
+        best: None,
+        stopped: false,
+    };
+    search.visit(start, W::zero());
+    Exact {
+        best: search.best,
+        finished: !search.stopped,
+    }
+}
+
+// 忽略方向的简单图，节点换成 0..n 的下标
+fn undirected_sets<W: Weight>(g: &Graph<W>) -> (Vec<i64>, Vec<BTreeSet<usize>>, bool) {
+    let nodes = g.nodes();
+    let index: BTreeMap<i64, usize> = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+    let mut adj = vec![BTreeSet::new(); nodes.len()];
+    let mut self_loop = false;
+    for (u, v, _, _) in g.edges() {
+        if u == v {
+            self_loop = true;
+            continue;
+        }
+        adj[index[&u]].insert(index[&v]);
+        adj[index[&v]].insert(index[&u]);
+    }
+    (nodes, adj, self_loop)
+}
+
+// 最少着色：相邻节点颜色不同、所用颜色数最少，忽略方向。先用贪心着色作为初始解，
+// 再按度数从大到小回溯，颜色数不少于当前最好解时剪枝。有自环时无解。
+// 返回节点 -> 颜色编号（从 0 开始）
+pub fn coloring<W: Weight>(g: &Graph<W>, budget: &Budget) -> Exact<BTreeMap<i64, usize>> {
+    let (nodes, adj, self_loop) = undirected_sets(g);
+    if self_loop {
+        return Exact {
+            best: None,
+            finished: true,
+        };
+    }
+    let mut order: Vec<usize> = (0..nodes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(adj[i].len()));
+
+    struct Search<'a, 'b> {
+        adj: &'a [BTreeSet<usize>],
+        order: &'a [usize],
+        budget: &'a Budget<'b>,
+        color: Vec<Option<usize>>,
+        best: Vec<usize>,
+        best_count: usize,
+        stopped: bool,
+    }
+
+    impl Search<'_, '_> {
+        fn assign(&mut self, k: usize, used: usize) {
+            if self.stopped || !self.budget.tick() {
+                self.stopped = true;
+                return;
+            }
+            if used >= self.best_count {
+                return;
+            }
+            let Some(&u) = self.order.get(k) else {
+                self.best = self.color.iter().map(|c| c.unwrap_or(0)).collect();
+                self.best_count = used;
+                return;
+            };
+            // 新开一种颜色时只试编号最小的那种，避免颜色互换的重复搜索
+            for c in 0..=used {
+                if c == used && used + 1 >= self.best_count {
+                    break;
+                }
+                if self.adj[u].iter().any(|&v| self.color[v] == Some(c)) {
+                    continue;
+                }
+                self.color[u] = Some(c);
+                self.assign(k + 1, used.max(c + 1));
+                self.color[u] = None;
+            }
+        }
+    }
+
+    // 按同样的顺序贪心着色，得到初始上界
+    let mut greedy = vec![0; nodes.len()];
+    for (k, &u) in order.iter().enumerate() {
+        let taken: BTreeSet<usize> = order[..k]
+            .iter()
+            .filter(|v| adj[u].contains(v))
+            .map(|&v| greedy[v])
+            .collect();
+        greedy[u] = (0..).find(|c| !taken.contains(c)).unwrap_or(0);
+    }
+    let greedy_count = greedy.iter().map(|&c| c + 1).max().unwrap_or(0);
+
+    let mut search = Search {
+        adj: &adj,
+        order: &order,
+        budget,
+        color: vec![None; nodes.len()],
+        best: greedy,
+        best_count: greedy_count,
+        stopped: false,
+    };
+    search.assign(0, 0);
+    Exact {
+        best: Some(nodes.into_iter().zip(search.best).collect()),
+        finished: !search.stopped,
+    }
+}
+
+// 最小顶点覆盖，忽略方向：以 2-近似覆盖为初始解，每次取未覆盖边最多的节点 u 分支——
+// 要么选 u，要么选 u 所有未覆盖的邻居；已选节点数加上剩余边的匹配数不小于当前最好解时剪枝。
+// 有自环的节点必须入选。返回按编号升序的覆盖
+pub fn vertex_cover<W: Weight>(g: &Graph<W>, budget: &Budget) -> Exact<Vec<i64>> {
+    let (nodes, adj, _) = undirected_sets(g);
+    let forced: Vec<usize> = nodes
+        .iter()
+        .enumerate()
+        .filter(|&(_, &u)| g.contains_edge(u, u))
+        .map(|(i, _)| i)
+        .collect();
+
+    struct Search<'a, 'b> {
+        adj: &'a [BTreeSet<usize>],
+        budget: &'a Budget<'b>,
+        chosen: Vec<bool>,
+        best: Vec<bool>,
+        best_count: usize,
+        stopped: bool,
+    }
+
+    impl Search<'_, '_> {
+        fn uncovered(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+            self.adj[u]
+                .iter()
+                .copied()
+                .filter(move |&v| !self.chosen[u] && !self.chosen[v])
+        }
+
+        // 剩余未覆盖边的一个极大匹配的大小，是还需选多少节点的下界
+        fn matching_bound(&self) -> usize {
+            let mut matched = vec![false; self.adj.len()];
+            let mut count = 0;
+            for u in 0..self.adj.len() {
+                if matched[u] {
+                    continue;
+                }
+                if let Some(v) = self.uncovered(u).find(|&v| !matched[v]) {
+                    matched[u] = true;
+                    matched[v] = true;
+                    count += 1;
+                }
+            }
+            count
+        }
+
+        fn branch(&mut self, count: usize) {
+            if self.stopped || !self.budget.tick() {
+                self.stopped = true;
+                return;
+            }
+            if count + self.matching_bound() >= self.best_count {
+                return;
+            }
+            let Some(u) = (0..self.adj.len())
+                .filter(|&u| self.uncovered(u).next().is_some())
+                .max_by_key(|&u| (self.uncovered(u).count(), std::cmp::Reverse(u)))
+            else {
+                self.best.clone_from(&self.chosen);
+                self.best_count = count;
+                return;
+            };
+
+            self.chosen[u] = true;
+            self.branch(count + 1);
+            self.chosen[u] = false;
+
+            let neighbors: Vec<usize> = self.uncovered(u).collect();
+            for &v in &neighbors {
+                self.chosen[v] = true;
+            }
+            self.branch(count + neighbors.len());
+            for &v in &neighbors {
+                self.chosen[v] = false;
+            }
+        }
+    }
+
+    let index: BTreeMap<i64, usize> = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+    let mut best = vec![false; nodes.len()];
+    for u in g.vertex_cover_2approx().0 {
+        best[index[&u]] = true;
+    }
+    let mut chosen = vec![false; nodes.len()];
+    for &i in &forced {
+        chosen[i] = true;
+        best[i] = true;
+    }
+
+    let best_count = best.iter().filter(|&&b| b).count();
+    let mut search = Search {
+        adj: &adj,
+        budget,
+        chosen,
+        best,
+        best_count,
+        stopped: false,
+    };
+    search.branch(forced.len());
+    Exact {
+        best: Some(
+            nodes
+                .into_iter()
+                .zip(search.best)
+                .filter(|&(_, b)| b)
+                .map(|(u, _)| u)
+                .collect(),
+        ),
+        finished: !search.stopped,
+    }
+}
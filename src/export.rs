@@ -0,0 +1,3 @@
+// 把图和算法结果导出为外部工具能读取的格式
+pub mod csv;
+pub mod dot;
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// 每个节点一行的指标表，供 pandas/Excel 分析：
+// node,label,degree,bfs_depth,distance[,centrality]
+// 从 source 不可达时 bfs_depth 和 distance 留空；centrality 只在给出时输出该列
+pub fn node_metrics<W: Weight>(
+    g: &Graph<W>,
+    source: i64,
+    centrality: Option<&HashMap<i64, f64>>,
+    precision: usize,
+) -> String {
+    let (_, tree) = g.bfs(source);
+    let mut depth: HashMap<i64, usize> = HashMap::from([(source, 0)]);
+    for (p, c) in tree {
+        depth.insert(c, depth[&p] + 1);
+    }
+    let dist = g.dijkstra(source).dist;
+
+    let mut out = String::from("node,label,degree,bfs_depth,distance");
+    if centrality.is_some() {
+        out.push_str(",centrality");
+    }
+    out.push('\n');
+
+    for u in g.nodes() {
+        let mut fields = vec![
+            u.to_string(),
+            escape(g.node_label(u).unwrap_or("")),
+            g.degree(u).to_string(),
+            depth.get(&u).map_or(String::new(), |d| d.to_string()),
+            dist.get(&u)
+                .filter(|&&d| d != W::infinity())
+                .map_or(String::new(), |d| d.format(precision)),
+        ];
+        if let Some(c) = centrality {
+            fields.push(c.get(&u).map_or(String::new(), |c| c.to_string()));
+        }
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// 含逗号、引号或换行的字段用双引号括起，内部引号写两遍
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use crate::graph::{EdgeType, Graph};
+use crate::weight::Weight;
+
+// Graphviz DOT 格式：统一写成 digraph，单向边画箭头，无向边加 dir=none。
+// 节点有标签时用作显示文字，边权写在边标签上
+pub fn to_dot<W: Weight>(g: &Graph<W>, precision: usize) -> String {
+    let mut out = String::from("digraph G {\n");
+    for u in g.nodes() {
+        match g.node_label(u) {
+            Some(label) => {
+                let _ = writeln!(out, "    {} [label=\"{}\"];", u, escape(label));
+            }
+            None => {
+                let _ = writeln!(out, "    {};", u);
+            }
+        }
+    }
+    for (u, v, w, edge_type) in g.edges() {
+        let dir = match edge_type {
+            EdgeType::Single => "",
+            EdgeType::Both => ", dir=none",
+        };
+        let _ = writeln!(
+            out,
+            "    {} -> {} [label=\"{}\"{}];",
+            u,
+            v,
+            w.format(precision),
+            dir
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// 读入 DOT 的常用子集，供 --watch 在编辑器中改图时使用：graph/digraph 中的节点语句
+// (`3 [label="A"]`) 与边语句 (`1 -> 2 -> 3 [label="5"]`、`1 -- 2`)。
+// 边权取 weight 属性，没有时取 label，都没有时为 1；digraph 中 dir=none 的边和 graph 中的边都是无向边。
+// 节点 ID 必须是整数；node/edge/graph 默认属性和图属性语句被忽略
+pub fn parse<W: Weight>(text: &str) -> Result<Graph<W>, String> {
+    let text: String = text
+        .lines()
+        .filter(|l| !l.trim_start().starts_with('#') && !l.trim_start().starts_with("//"))
+        .map(|l| format!("{}\n", l))
+        .collect();
+    let (Some(open), Some(close)) = (text.find('{'), text.rfind('}')) else {
+        return Err("缺少 { }".to_string());
+    };
+    let undirected = !text[..open].contains("digraph");
+
+    let mut g = Graph::default();
+    for stmt in split_outside_quotes(&text[open + 1..close], &[';', '\n']) {
+        let stmt = stmt.trim();
+        let err = |msg: &str| format!("{}: {}", msg, stmt);
+        let (body, attrs) = match find_outside_quotes(stmt, '[') {
+            Some(i) => {
+                let end = stmt.rfind(']').ok_or_else(|| err("属性缺少 ]"))?;
+                (stmt[..i].trim(), parse_attrs(&stmt[i + 1..end]))
+            }
+            None => (stmt, Vec::new()),
+        };
+        let attr = |key: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        if body.is_empty() || matches!(body, "node" | "edge" | "graph") || body.contains('=') {
+            continue;
+        }
+
+        let ids = body
+            .split("->")
+            .flat_map(|part| part.split("--"))
+            .map(|id| {
+                let id = id.trim().trim_matches('"');
+                id.parse::<i64>().map_err(|_| err("节点 ID 需要是整数"))
+            })
+            .collect::<Result<Vec<i64>, String>>()?;
+        if let [u] = ids[..] {
+            g.add_node(u);
+            if let Some(label) = attr("label") {
+                g.set_node_label(u, label);
+            }
+            continue;
+        }
+
+        let w = match attr("weight").or(attr("label")) {
+            Some(w) => W::parse(w).ok_or_else(|| err("无法解析边权"))?,
+            None => W::parse("1").unwrap_or_else(W::zero),
+        };
+        let edge_type = if undirected || attr("dir") == Some("none") {
+            EdgeType::Both
+        } else {
+            EdgeType::Single
+        };
+        for pair in ids.windows(2) {
+            g.add_edge(pair[0], pair[1], w, edge_type);
+        }
+    }
+    Ok(g)
+}
+
+// 引号外第一次出现 c 的位置
+fn find_outside_quotes(s: &str, c: char) -> Option<usize> {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            _ if ch == c && !quoted => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// 按引号外的分隔符切分
+fn split_outside_quotes(s: &str, separators: &[char]) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    let mut escaped = false;
+    for ch in s.chars() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            _ if !quoted && separators.contains(&ch) => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().expect("至少有一段").push(ch);
+    }
+    parts
+}
+
+// 方括号内的 key=value 列表，以逗号、分号或空白分隔，等号两边可以有空格，值可以带引号
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<String> = Vec::new();
+    for token in split_outside_quotes(s, &[',', ';', ' ', '\t', '\n']) {
+        if token.is_empty() {
+            continue;
+        }
+        match pairs.last_mut() {
+            Some(last) if last.ends_with('=') || token.starts_with('=') => last.push_str(&token),
+            _ => pairs.push(token),
+        }
+    }
+    pairs
+        .iter()
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=')?;
+            let v = v.trim();
+            let v = v
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .map_or(v.to_string(), |v| {
+                    v.replace("\\\"", "\"").replace("\\\\", "\\")
+                });
+            Some((k.trim().to_string(), v))
+        })
+        .collect()
+}
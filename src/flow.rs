@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+// 容量为小整数的流网络，每次增广推一个单位，节点为 0..n 的下标。
+// 弧成对存放，第 i 条弧的反向弧为 i ^ 1；无向边的两条弧容量都为 1，沿一个方向推流后另一个方向的剩余容量变为 2，
+// 因此两个方向的流会自动抵消，不会在同一条边上来回走
+pub(crate) struct UnitFlow {
+    head: Vec<usize>,
+    cap: Vec<usize>,
+    // 每条弧的初始容量，流量 = 初始容量 - 剩余容量
+    init: Vec<usize>,
+    out: Vec<Vec<usize>>,
+}
+
+impl UnitFlow {
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            head: Vec::new(),
+            cap: Vec::new(),
+            init: Vec::new(),
+            out: vec![Vec::new(); n],
+        }
+    }
+
+    // 添加 u -> v 的弧，反向容量为 0 时是单向边，与正向相同时是无向边
+    pub(crate) fn add(&mut self, u: usize, v: usize, cap: usize, back: usize) {
+        for (from, to, c) in [(u, v, cap), (v, u, back)] {
+            self.out[from].push(self.head.len());
+            self.head.push(to);
+            self.cap.push(c);
+            self.init.push(c);
+        }
+    }
+
+    // 清空流量，换一对源汇重新计算
+    pub(crate) fn reset(&mut self) {
+        self.cap.clone_from(&self.init);
+    }
+
+    // Edmonds-Karp：每次沿 BFS 找到的最短增广路推一个单位，返回最大流
+    pub(crate) fn max_flow(&mut self, s: usize, t: usize) -> usize {
+        let mut flow = 0;
+        while let Some(arcs) = self.augmenting_path(s, t) {
+            for a in arcs {
+                self.cap[a] -= 1;
+                self.cap[a ^ 1] += 1;
+            }
+            flow += 1;
+        }
+        flow
+    }
+
+    fn augmenting_path(&self, s: usize, t: usize) -> Option<Vec<usize>> {
+        let mut via: Vec<Option<usize>> = vec![None; self.out.len()];
+        let mut seen = vec![false; self.out.len()];
+        seen[s] = true;
+        let mut queue = VecDeque::from([s]);
+        while let Some(x) = queue.pop_front() {
+            if x == t {
+                let mut arcs = Vec::new();
+                let mut cur = t;
+                while let Some(a) = via[cur] {
+                    arcs.push(a);
+                    cur = self.head[a ^ 1];
+                }
+                return Some(arcs);
+            }
+            for &a in &self.out[x] {
+                let y = self.head[a];
+                if self.cap[a] > 0 && !seen[y] {
+                    seen[y] = true;
+                    via[y] = Some(a);
+                    queue.push_back(y);
+                }
+            }
+        }
+        None
+    }
+
+    // max_flow 之后的最小割：从 s 沿剩余容量能到达的一侧指向另一侧的原有弧 (起点, 终点)
+    pub(crate) fn min_cut(&self, s: usize) -> Vec<(usize, usize)> {
+        let mut seen = vec![false; self.out.len()];
+        seen[s] = true;
+        let mut stack = vec![s];
+        while let Some(x) = stack.pop() {
+            for &a in &self.out[x] {
+                if self.cap[a] > 0 && !seen[self.head[a]] {
+                    seen[self.head[a]] = true;
+                    stack.push(self.head[a]);
+                }
+            }
+        }
+        (0..self.head.len())
+            .filter(|&a| self.init[a] > 0 && seen[self.head[a ^ 1]] && !seen[self.head[a]])
+            .map(|a| (self.head[a ^ 1], self.head[a]))
+            .collect()
+    }
+
+    // 把 max_flow 得到的流分解成 s 到 t 的路径（节点下标序列），路径上的环被去掉
+    pub(crate) fn paths(&self, s: usize, t: usize) -> Vec<Vec<usize>> {
+        let mut left: Vec<usize> = (0..self.head.len())
+            .map(|a| self.init[a].saturating_sub(self.cap[a]))
+            .collect();
+        let mut paths = Vec::new();
+        loop {
+            let mut path = vec![s];
+            let mut x = s;
+            while x != t {
+                let Some(&a) = self.out[x].iter().find(|&&a| left[a] > 0) else {
+                    return paths;
+                };
+                left[a] -= 1;
+                x = self.head[a];
+                if let Some(i) = path.iter().position(|&p| p == x) {
+                    path.truncate(i);
+                }
+                path.push(x);
+            }
+            paths.push(path);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::harness::Case;
+
+// 随机测试检查的算法不变式
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Invariant {
+    // Dijkstra 的最短距离与 Bellman-Ford 一致
+    DijkstraMatchesBellmanFord,
+    // Prim 与 Kruskal 的生成树总权重一致（只比较起点所在连通分量）
+    PrimMatchesKruskal,
+}
+
+impl Invariant {
+    pub const ALL: [Invariant; 2] = [
+        Invariant::DijkstraMatchesBellmanFord,
+        Invariant::PrimMatchesKruskal,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Invariant::DijkstraMatchesBellmanFord => "Dijkstra = Bellman-Ford",
+            Invariant::PrimMatchesKruskal => "Prim = Kruskal",
+        }
+    }
+
+    // 不变式成立返回 Ok，否则返回不一致之处
+    pub fn check(self, case: &Case) -> Result<(), String> {
+        let g = case.graph();
+        match self {
+            Invariant::DijkstraMatchesBellmanFord => {
+                let dist = g.dijkstra(case.source).dist;
+                let reference = bellman_ford(case);
+                for u in 1..=case.n {
+                    let (d, r) = (dist.get(&u), reference.get(&u));
+                    if d != r {
+                        return Err(format!(
+                            "节点 {}: Dijkstra {:?}，Bellman-Ford {:?}",
+                            u, d, r
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            Invariant::PrimMatchesKruskal => {
+                let prim = g.prim(case.source).total_weight;
+                let reachable: HashSet<i64> = g.reachable_from(case.source).into_iter().collect();
+                let kruskal: i64 = g
+                    .kruskal()
+                    .edges
+                    .iter()
+                    .filter(|(u, _, _)| reachable.contains(u))
+                    .map(|&(_, _, w)| w)
+                    .sum();
+                if prim != kruskal {
+                    return Err(format!("Prim {}，Kruskal {}", prim, kruskal));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// 作为对照的 Bellman-Ford：对所有无向边做 n - 1 轮松弛。
+// 特意不用 algo::bellman_ford：对照实现直接读 case 的边表，不经过 Graph 和 GraphLike，
+// 两边共用的代码出错时也能发现
+fn bellman_ford(case: &Case) -> BTreeMap<i64, i64> {
+    let mut dist: BTreeMap<i64, i64> = BTreeMap::new();
+    dist.insert(case.source, 0);
+    for _ in 1..case.n {
+        let mut changed = false;
+        for &(u, v, w) in &case.edges {
+            for (a, b) in [(u, v), (v, u)] {
+                if let Some(&da) = dist.get(&a)
+                    && let Some(d) = da.checked_add(w)
+                    && dist.get(&b).is_none_or(|&db| d < db)
+                {
+                    dist.insert(b, d);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    dist
+}
+
+// 在保持 fails 为真的前提下，反复删边、把边权改为 1、去掉末尾的孤立节点，
+// 直到无法再缩小，得到最小反例
+pub fn shrink(mut case: Case, fails: impl Fn(&Case) -> bool) -> Case {
+    loop {
+        let mut progress = false;
+
+        let mut i = 0;
+        while i < case.edges.len() {
+            let mut smaller = case.clone();
+            smaller.edges.remove(i);
+            if fails(&smaller) {
+                case = smaller;
+                progress = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        for i in 0..case.edges.len() {
+            if case.edges[i].2 != 1 {
+                let mut smaller = case.clone();
+                smaller.edges[i].2 = 1;
+                if fails(&smaller) {
+                    case = smaller;
+                    progress = true;
+                }
+            }
+        }
+
+        let used = case
+            .edges
+            .iter()
+            .flat_map(|&(u, v, _)| [u, v])
+            .chain([case.source])
+            .max()
+            .unwrap_or(1);
+        if case.n > used {
+            let mut smaller = case.clone();
+            smaller.n = used;
+            if fails(&smaller) {
+                case = smaller;
+                progress = true;
+            }
+        }
+
+        if !progress {
+            return case;
+        }
+    }
+}
+
+// 一次失败：原始随机图与缩小后的最小反例
+#[derive(Debug)]
+pub struct Counterexample {
+    pub invariant: Invariant,
+    pub original: Case,
+    pub shrunk: Case,
+    pub message: String,
+}
+
+// 在 cases 个随机图上检查所有不变式，返回第一个反例（已缩小）
+pub fn fuzz(cases: usize, seed: u64) -> Option<Counterexample> {
+    for i in 0..cases {
+        let n = 2 + (i as i64 % 12);
+        let case = Case::random(seed + i as u64, n, (n as usize) * 2);
+        for invariant in Invariant::ALL {
+            if invariant.check(&case).is_err() {
+                let shrunk = shrink(case.clone(), |c| invariant.check(c).is_err());
+                let message = invariant.check(&shrunk).unwrap_err();
+                return Some(Counterexample {
+                    invariant,
+                    original: case,
+                    shrunk,
+                    message,
+                });
+            }
+        }
+    }
+    None
+}
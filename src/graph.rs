@@ -1,225 +1,2282 @@
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
+use crate::adjacency::{Adjacency, Repr};
+use crate::algo;
+use crate::flow::UnitFlow;
+use crate::trace::StepEvent;
+use crate::weight::Weight;
+
+// 边的方向性，记录在每条边上。Both 的边在两端各存一条记录，两条都标为 Both；
+// 只有一侧的 Single 边即使恰好存在反向的 Single 边，也仍是两条独立的有向边
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeType {
     Single,
     Both,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: i64,
-    node: i64,
+// 相对于 (u, v) 的边方向
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeDirection {
+    // u -> v
+    Forward,
+    // v -> u
+    Backward,
+    Both,
+}
+
+impl EdgeDirection {
+    // 编辑时的切换顺序：u→v、v→u、双向
+    pub fn next(self) -> Self {
+        match self {
+            EdgeDirection::Forward => EdgeDirection::Backward,
+            EdgeDirection::Backward => EdgeDirection::Both,
+            EdgeDirection::Both => EdgeDirection::Forward,
+        }
+    }
+}
+
+// 重复添加同一条边（同起点同终点）时的处理方式，导入数据时常见
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddEdgePolicy {
+    // 保留为平行边（add_edge 的行为）
+    #[default]
+    Parallel,
+    KeepMin,
+    KeepMax,
+    Sum,
+    KeepLast,
+}
+
+impl AddEdgePolicy {
+    pub const ALL: [AddEdgePolicy; 5] = [
+        AddEdgePolicy::Parallel,
+        AddEdgePolicy::KeepMin,
+        AddEdgePolicy::KeepMax,
+        AddEdgePolicy::Sum,
+        AddEdgePolicy::KeepLast,
+    ];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "parallel" => Some(AddEdgePolicy::Parallel),
+            "min" => Some(AddEdgePolicy::KeepMin),
+            "max" => Some(AddEdgePolicy::KeepMax),
+            "sum" => Some(AddEdgePolicy::Sum),
+            "last" => Some(AddEdgePolicy::KeepLast),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AddEdgePolicy::Parallel => "保留平行边",
+            AddEdgePolicy::KeepMin => "取最小权重",
+            AddEdgePolicy::KeepMax => "取最大权重",
+            AddEdgePolicy::Sum => "权重求和",
+            AddEdgePolicy::KeepLast => "取最后一次",
+        }
+    }
+
+    // 把 u -> v 的一条边并入出边表；已有同终点的边时只改它的权重，容量、标签和方向性保持不变
+    fn apply<W: Weight>(self, list: &mut Vec<Edge<W>>, v: i64, w: W, edge_type: EdgeType) {
+        let existing = list.iter_mut().find(|e| e.to == v);
+        match (self, existing) {
+            (AddEdgePolicy::Parallel, _) | (_, None) => list.push(Edge::new(v, w, edge_type)),
+            (AddEdgePolicy::KeepMin, Some(e)) => {
+                if w.less_than(e.weight) {
+                    e.weight = w;
+                }
+            }
+            (AddEdgePolicy::KeepMax, Some(e)) => {
+                if e.weight.less_than(w) {
+                    e.weight = w;
+                }
+            }
+            // 溢出时取 infinity()，与路径长度的加法一致
+            (AddEdgePolicy::Sum, Some(e)) => e.weight = e.weight.add_or_infinity(w),
+            (AddEdgePolicy::KeepLast, Some(e)) => e.weight = w,
+        }
+    }
+}
+
+// 带种子的伪随机数（xorshift64*），相同种子得到相同序列，随机图、随机定向和 Karger 共用，便于复现
+pub(crate) struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            state: seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407),
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(2685821657736338717)
+    }
+
+    // [0, bound) 中的整数，bound 不能为 0
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        (self.next_u64() >> 33) % bound
+    }
+}
+
+// 优先队列中的项，按 cost 从小到大出队
+#[derive(Copy, Clone)]
+pub(crate) struct State<W> {
+    pub(crate) cost: W,
+    pub(crate) node: i64,
+}
+
+impl<W: Weight> Ord for State<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl<W: Weight> PartialOrd for State<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Weight> PartialEq for State<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for State<W> {}
+
+// Dijkstra 的运行结果：确定顺序、松弛过的边、最短距离与最短路径树
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DijkstraResult<W = i64> {
+    pub source: i64,
+    pub visited_nodes: Vec<i64>,
+    pub visited_edges: Vec<(i64, i64)>,
+    pub dist: HashMap<i64, W>,
+    pub parent: HashMap<i64, i64>,
+}
+
+impl<W> DijkstraResult<W> {
+    // 从起点到 target 的最短路径（含两端），不可达时返回 None
+    pub fn path_to(&self, target: i64) -> Option<Vec<i64>> {
+        if !self.dist.contains_key(&target) {
+            return None;
+        }
+        path_from_parents(&self.parent, self.source, target)
+    }
+}
+
+// 距离表的排序方式
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DistanceOrder {
+    #[default]
+    Node,
+    Distance,
+}
+
+// 距离表中的一行：节点、最短距离、最短路径树上的前驱（起点没有）、从起点出发的路径
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceRow<W = i64> {
+    pub node: i64,
+    pub dist: W,
+    pub parent: Option<i64>,
+    pub path: Vec<i64>,
+}
+
+impl<W: Weight> DijkstraResult<W> {
+    // 每个可达节点一行，按 order 排序，距离相同时按节点编号
+    pub fn rows(&self, order: DistanceOrder) -> Vec<DistanceRow<W>> {
+        let mut rows: Vec<DistanceRow<W>> = self
+            .dist
+            .iter()
+            .map(|(&node, &dist)| DistanceRow {
+                node,
+                dist,
+                parent: self.parent.get(&node).copied(),
+                path: self.path_to(node).unwrap_or_default(),
+            })
+            .collect();
+        match order {
+            DistanceOrder::Node => rows.sort_by_key(|r| r.node),
+            DistanceOrder::Distance => {
+                rows.sort_by(|a, b| a.dist.total_cmp(&b.dist).then(a.node.cmp(&b.node)))
+            }
+        }
+        rows
+    }
+
+    // 边 u-v（两个方向）的权重改变后增量更新 dist 和 parent，g 为修改后的图，
+    // 返回距离发生变化的节点（升序）。修改的是最短路径树上的边时，先作废它下方整棵子树的距离，
+    // 再从子树外的入边和 u、v 两端出发做局部 Dijkstra；不在树上的边变大则什么也不用做。
+    // visited_nodes/visited_edges 记录的是原来的执行过程，保持不变。
+    // 新权重为负时返回 None 且不做任何修改：双向的负权边自成负环，松弛不会停止
+    pub fn update_edge(&mut self, g: &Graph<W>, u: i64, v: i64) -> Option<Vec<i64>> {
+        let negative = [(u, v), (v, u)].into_iter().any(|(a, b)| {
+            g.out_edges(a)
+                .any(|e| e.to == b && e.weight.less_than(W::zero()))
+        });
+        if negative {
+            return None;
+        }
+        let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (&c, &p) in &self.parent {
+            children.entry(p).or_default().push(c);
+        }
+        let mut affected: HashSet<i64> = HashSet::new();
+        for (a, b) in [(u, v), (v, u)] {
+            if self.parent.get(&b) == Some(&a) {
+                let mut stack = vec![b];
+                while let Some(x) = stack.pop() {
+                    if affected.insert(x) {
+                        stack.extend(children.get(&x).into_iter().flatten());
+                    }
+                }
+            }
+        }
+
+        // 被改动过的节点及其原来的距离
+        let mut old: HashMap<i64, W> = HashMap::new();
+        for &x in &affected {
+            old.insert(x, self.dist[&x]);
+            self.dist.insert(x, W::infinity());
+            self.parent.remove(&x);
+        }
+
+        let mut pq: BinaryHeap<State<W>> = BinaryHeap::new();
+        if !affected.is_empty() {
+            for (&x, list) in &g.adj {
+                if affected.contains(&x) {
+                    continue;
+                }
+                for e in list.iter().filter(|e| affected.contains(&e.to)) {
+                    self.relax(x, e, &mut old, &mut pq);
+                }
+            }
+        }
+        for (a, b) in [(u, v), (v, u)] {
+            for e in g.out_edges(a).filter(|e| e.to == b) {
+                self.relax(a, e, &mut old, &mut pq);
+            }
+        }
+
+        while let Some(State { cost, node }) = pq.pop() {
+            if self.dist[&node].less_than(cost) {
+                continue;
+            }
+            for e in g.out_edges(node) {
+                self.relax(node, e, &mut old, &mut pq);
+            }
+        }
+
+        self.dist.retain(|_, d| *d != W::infinity());
+        let mut changed: Vec<i64> = old
+            .into_iter()
+            .filter(|(x, before)| {
+                self.dist
+                    .get(x)
+                    .is_none_or(|after| !after.approx_eq(*before))
+            })
+            .map(|(x, _)| x)
+            .collect();
+        changed.sort();
+        Some(changed)
+    }
+
+    fn relax(
+        &mut self,
+        a: i64,
+        e: &Edge<W>,
+        old: &mut HashMap<i64, W>,
+        pq: &mut BinaryHeap<State<W>>,
+    ) {
+        let Some(&base) = self.dist.get(&a).filter(|d| **d != W::infinity()) else {
+            return;
+        };
+        let cost = base.add_or_infinity(e.weight);
+        let current = self.dist.get(&e.to).copied().unwrap_or(W::infinity());
+        if cost.less_than(current) {
+            old.entry(e.to).or_insert(current);
+            self.dist.insert(e.to, cost);
+            self.parent.insert(e.to, a);
+            pq.push(State { cost, node: e.to });
+        }
+    }
+}
+
+// 从起点可达的负环。edge 为第 |V| 轮仍能松弛的边，nodes 为环上的节点（按经过顺序，首节点不重复），
+// weight 为环上边权之和（平行边取最小的）
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NegativeCycle<W = i64> {
+    pub edge: (i64, i64),
+    pub nodes: Vec<i64>,
+    pub weight: W,
+}
+
+// Bellman-Ford 的运行结果。paths 与 Dijkstra 的结果结构相同，可以直接显示在距离面板；
+// rounds 为每一轮的松弛步骤（Relax/NoRelax），某一轮没有任何更新时提前结束，这一轮也包含在内。
+// 有负环时 paths 为 |V| - 1 轮之后的状态，其中的距离没有意义
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BellmanFordResult<W = i64> {
+    pub paths: DijkstraResult<W>,
+    pub rounds: Vec<Vec<StepEvent<W>>>,
+    pub negative_cycle: Option<NegativeCycle<W>>,
+}
+
+impl<W: Weight> BellmanFordResult<W> {
+    // 按顺序排好的全部步骤：每一轮前加一个 Round，发现负环时最后加一个 NegativeCycle
+    pub fn steps(&self) -> Vec<StepEvent<W>> {
+        let mut steps = Vec::new();
+        for (i, round) in self.rounds.iter().enumerate() {
+            steps.push(StepEvent::Round { round: i + 1 });
+            steps.extend(round.iter().cloned());
+        }
+        if let Some(cycle) = &self.negative_cycle {
+            let (u, v) = cycle.edge;
+            steps.push(StepEvent::NegativeCycle { u, v });
+        }
+        steps
+    }
+}
+
+// 最小生成树：边附带各自的权重，total_weight 由这些边求和得到；
+// is_spanning 表示是否覆盖了图中全部节点（图不连通时为 false）
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MstResult<W = i64> {
+    // 树覆盖的节点，Prim 按加入顺序
+    pub nodes: Vec<i64>,
+    pub edges: Vec<(i64, i64, W)>,
+    pub total_weight: W,
+    pub is_spanning: bool,
+}
+
+impl<W: Weight> MstResult<W> {
+    pub(crate) fn new(nodes: Vec<i64>, edges: Vec<(i64, i64, W)>, is_spanning: bool) -> Self {
+        let total_weight = edges
+            .iter()
+            .fold(W::zero(), |acc, &(_, _, w)| acc.add_or_infinity(w));
+        Self {
+            nodes,
+            edges,
+            total_weight,
+            is_spanning,
+        }
+    }
+
+    // 不带权重的树边 (u, v)，用于动画
+    pub fn tree_edges(&self) -> Vec<(i64, i64)> {
+        self.edges.iter().map(|&(u, v, _)| (u, v)).collect()
+    }
+}
+
+// Karger 随机收缩的一次试验：不断随机选一条边收缩，直到只剩两个超级节点，
+// 两者之间的边就是得到的割。边按无向处理，相邻节点之间只算一条
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KargerTrial<W = i64> {
+    // 依次收缩的边，端点为原图中的节点
+    pub contractions: Vec<(i64, i64)>,
+    // 包含编号最小节点的那一侧，升序
+    pub side: Vec<i64>,
+    // 两侧之间的边
+    pub cut_edges: Vec<(i64, i64, W)>,
+}
+
+impl<W: Weight> KargerTrial<W> {
+    pub fn cut_weight(&self) -> W {
+        self.cut_edges
+            .iter()
+            .fold(W::zero(), |acc, &(_, _, w)| acc + w)
+    }
+}
+
+// 中国邮递员问题的解：经过每条边至少一次、费用最小的闭合回路
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PostmanTour<W = i64> {
+    // 回路依次走过的边 (u, v, 权重)，前一条的终点是后一条的起点，最后回到出发点；
+    // 图中没有边时为空
+    pub tour: Vec<(i64, i64, W)>,
+    pub cost: W,
+    // 为使所有节点度数为偶数而多走一遍的边
+    pub repeated: Vec<(i64, i64, W)>,
+}
+
+// 奇度节点超过这个数时不求邮递员回路，配对用的状态压缩 DP 为 O(2^k · k)
+pub const POSTMAN_ODD_LIMIT: usize = 20;
+
+// 沿前驱表从 target 回溯到 source
+pub fn path_from_parents(parent: &HashMap<i64, i64>, source: i64, target: i64) -> Option<Vec<i64>> {
+    let mut path = vec![target];
+    let mut current = target;
+    while current != source {
+        current = *parent.get(&current)?;
+        if path.len() > parent.len() {
+            // 前驱表有环，不是一棵树
+            return None;
+        }
+        path.push(current);
+    }
+    path.reverse();
+    Some(path)
+}
+
+// Hierholzer 算法：所有节点度数为偶数且连通的无向多重图中，从 start 出发经过每条边恰好一次的回路
+fn euler_circuit<W: Copy>(edges: &[(i64, i64, W)], start: i64) -> Vec<(i64, i64, W)> {
+    let mut incident: HashMap<i64, Vec<usize>> = HashMap::new();
+    for (i, &(u, v, _)) in edges.iter().enumerate() {
+        incident.entry(u).or_default().push(i);
+        if u != v {
+            incident.entry(v).or_default().push(i);
+        }
+    }
+    let mut used = vec![false; edges.len()];
+    // (当前节点, 走到这里用的边)，栈中的上一项就是这条边的起点
+    let mut stack: Vec<(i64, Option<usize>)> = vec![(start, None)];
+    let mut circuit = Vec::new();
+    while let Some(&(x, via)) = stack.last() {
+        let list = incident.entry(x).or_default();
+        while list.last().is_some_and(|&i| used[i]) {
+            list.pop();
+        }
+        match list.pop() {
+            Some(i) => {
+                used[i] = true;
+                let (u, v, _) = edges[i];
+                stack.push((if u == x { v } else { u }, Some(i)));
+            }
+            None => {
+                stack.pop();
+                if let (Some(i), Some(&(from, _))) = (via, stack.last()) {
+                    circuit.push((from, x, edges[i].2));
+                }
+            }
+        }
+    }
+    circuit.reverse();
+    circuit
+}
+
+// 在 BFS/DFS 返回的树边 (父, 子) 中找从 source 到 target 的路径
+pub fn tree_path(source: i64, tree_edges: &[(i64, i64)], target: i64) -> Option<Vec<i64>> {
+    let parent: HashMap<i64, i64> = tree_edges.iter().map(|&(p, c)| (c, p)).collect();
+    path_from_parents(&parent, source, target)
+}
+
+// 边上可供算法选用的属性
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeAttr {
+    Weight,
+    Capacity,
+}
+
+// 邻接表中的一条边记录：最短路等使用 weight，流算法使用 capacity
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edge<W = i64> {
+    pub to: i64,
+    pub weight: W,
+    pub capacity: W,
+    pub label: Option<String>,
+    pub edge_type: EdgeType,
+}
+
+impl<W: Weight> Edge<W> {
+    pub fn new(to: i64, weight: W, edge_type: EdgeType) -> Self {
+        Self {
+            to,
+            weight,
+            capacity: weight,
+            label: None,
+            edge_type,
+        }
+    }
+
+    pub fn get(&self, attr: EdgeAttr) -> W {
+        match attr {
+            EdgeAttr::Weight => self.weight,
+            EdgeAttr::Capacity => self.capacity,
+        }
+    }
+}
+
+// 节点属性值，支持字符串和数字
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttrValue {
+    Str(String),
+    Num(f64),
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
+impl fmt::Display for AttrValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttrValue::Str(s) => write!(f, "{}", s),
+            AttrValue::Num(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl From<&str> for AttrValue {
+    fn from(s: &str) -> Self {
+        AttrValue::Str(s.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(s: String) -> Self {
+        AttrValue::Str(s)
+    }
+}
+
+impl From<f64> for AttrValue {
+    fn from(n: f64) -> Self {
+        AttrValue::Num(n)
+    }
+}
+
+impl From<i64> for AttrValue {
+    fn from(n: i64) -> Self {
+        AttrValue::Num(n as f64)
+    }
+}
+
+impl AttrValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttrValue::Str(s) => Some(s),
+            AttrValue::Num(_) => None,
+        }
+    }
+
+    pub fn as_num(&self) -> Option<f64> {
+        match self {
+            AttrValue::Num(n) => Some(*n),
+            AttrValue::Str(_) => None,
+        }
+    }
+}
+
+// 节点附带的元数据
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeMeta {
+    pub label: Option<String>,
+    pub group: Option<String>,
+    pub attrs: BTreeMap<String, AttrValue>,
+}
+
+// validate() 发现的问题
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphIssue<W = i64> {
+    // 同一方向上出现多条 u -> v
+    DuplicateEdge {
+        u: i64,
+        v: i64,
+        count: usize,
+    },
+    // u -> v 与 v -> u 同时存在但权重不同
+    AsymmetricEdge {
+        u: i64,
+        v: i64,
+        forward: W,
+        backward: W,
+    },
+    SelfLoop {
+        u: i64,
+    },
+    // 边指向了未登记的节点
+    DanglingEdge {
+        u: i64,
+        v: i64,
+    },
+}
+
+impl<W: Weight> fmt::Display for GraphIssue<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphIssue::DuplicateEdge { u, v, count } => {
+                write!(f, "重复边: {} -> {} 出现 {} 次", u, v, count)
+            }
+            GraphIssue::AsymmetricEdge {
+                u,
+                v,
+                forward,
+                backward,
+            } => write!(
+                f,
+                "双向边权重不一致: {} -> {} = {}, {} -> {} = {}",
+                u, v, forward, v, u, backward
+            ),
+            GraphIssue::SelfLoop { u } => write!(f, "自环: {} -> {}", u, u),
+            GraphIssue::DanglingEdge { u, v } => {
+                write!(f, "悬空边: {} -> {}（节点 {} 不存在）", u, v, v)
+            }
+        }
+    }
+}
+
+// 边权类型默认为 i64，也可使用 Graph<f64> 处理浮点权（如地理距离）
+// 邻接表节点按 ID 有序、出边按插入顺序，保证同一张图每次遍历顺序一致；
+// 默认每个节点一个 Vec，大图可用 with_representation(Repr::Csr) 改为压缩稀疏行存储
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph<W = i64> {
+    adj: Adjacency<W>,
+    meta: BTreeMap<i64, NodeMeta>,
+    // 标签 -> 节点，用于按标签查找节点
+    labels: BTreeMap<String, i64>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Graph {
+            adj: Adjacency::default(),
+            meta: BTreeMap::new(),
+            labels: BTreeMap::new(),
+        }
+    }
+
+    // 由 (u, v, w) 列表构造无向图
+    pub fn from_edges(edges: &[(i64, i64, i64)]) -> Self {
+        edges.iter().copied().collect()
+    }
+}
+
+impl<W: Weight> FromIterator<(i64, i64, W)> for Graph<W> {
+    fn from_iter<I: IntoIterator<Item = (i64, i64, W)>>(iter: I) -> Self {
+        let mut g = Graph::default();
+        for (u, v, w) in iter {
+            g.add_edge(u, v, w, EdgeType::Both);
+        }
+        g
+    }
+}
+
+// 两张图相等：节点集合相同、边记录的多重集相同（不计出边顺序、存储方式，权重按 total_cmp 比较），
+// 且非空的节点元数据相同。测试中可直接 assert_eq!(a, b)
+impl<W: Weight> PartialEq for Graph<W> {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.canonical_edges(), other.canonical_edges());
+        self.nodes() == other.nodes()
+            && self.annotated_meta().eq(other.annotated_meta())
+            && a.len() == b.len()
+            && a.iter()
+                .zip(&b)
+                .all(|(x, y)| cmp_record(x, y) == Ordering::Equal)
+    }
+}
+
+impl<W: Weight + Eq> Eq for Graph<W> {}
+
+impl<W: Weight + Hash> Hash for Graph<W> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nodes().hash(state);
+        for (u, e) in self.canonical_edges() {
+            (u, e.to, e.weight, e.capacity, &e.label, e.edge_type).hash(state);
+        }
+        // 属性值可能是浮点数，只参与相等比较，不参与哈希
+        self.labels.hash(state);
+    }
+}
+
+// 边记录的全序：起点、终点、方向性、权重、容量、标签
+fn cmp_record<W: Weight>(a: &(i64, Edge<W>), b: &(i64, Edge<W>)) -> Ordering {
+    (a.0, a.1.to, a.1.edge_type)
+        .cmp(&(b.0, b.1.to, b.1.edge_type))
+        .then_with(|| a.1.weight.total_cmp(&b.1.weight))
+        .then_with(|| a.1.capacity.total_cmp(&b.1.capacity))
+        .then_with(|| a.1.label.cmp(&b.1.label))
+}
+
+// 链式构图：GraphBuilder::new().edge(1, 2, 3).directed_edge(2, 3, 4).node(7).build()
+#[derive(Default)]
+pub struct GraphBuilder<W = i64> {
+    graph: Graph<W>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<W: Weight> GraphBuilder<W> {
+    // 无向边
+    pub fn edge(mut self, u: i64, v: i64, w: W) -> Self {
+        self.graph.add_edge(u, v, w, EdgeType::Both);
+        self
+    }
+
+    pub fn directed_edge(mut self, u: i64, v: i64, w: W) -> Self {
+        self.graph.add_edge(u, v, w, EdgeType::Single);
+        self
+    }
+
+    // 不带边的节点
+    pub fn node(mut self, u: i64) -> Self {
+        self.graph.add_node(u);
+        self
+    }
+
+    pub fn build(self) -> Graph<W> {
+        self.graph
+    }
+}
+
+impl<W: Weight> Graph<W> {
+    // 规范形式：全部边记录（无向边两侧各一条）按起点、终点、方向性、权重等排序，
+    // 与插入顺序和存储方式无关，两张图相等当且仅当节点、元数据和这个列表都相同
+    pub fn canonical_edges(&self) -> Vec<(i64, Edge<W>)> {
+        let mut records: Vec<(i64, Edge<W>)> = self
+            .adj
+            .iter()
+            .flat_map(|(&u, list)| list.iter().map(move |e| (u, e.clone())))
+            .collect();
+        records.sort_by(cmp_record);
+        records
+    }
+
+    // 不是默认值的节点元数据，没有标签、分组和属性的节点视为没有元数据
+    fn annotated_meta(&self) -> impl Iterator<Item = (&i64, &NodeMeta)> {
+        self.meta.iter().filter(|(_, m)| **m != NodeMeta::default())
+    }
+
+    pub fn set_node_attr(&mut self, u: i64, key: &str, value: impl Into<AttrValue>) {
+        self.meta
+            .entry(u)
+            .or_default()
+            .attrs
+            .insert(key.to_string(), value.into());
+    }
+
+    pub fn get_node_attr(&self, u: i64, key: &str) -> Option<&AttrValue> {
+        self.meta.get(&u)?.attrs.get(key)
+    }
+
+    pub fn remove_node_attr(&mut self, u: i64, key: &str) -> Option<AttrValue> {
+        self.meta.get_mut(&u)?.attrs.remove(key)
+    }
+
+    // 节点的全部属性，按键名排序
+    pub fn node_attrs(&self, u: i64) -> impl Iterator<Item = (&str, &AttrValue)> {
+        self.meta
+            .get(&u)
+            .into_iter()
+            .flat_map(|m| m.attrs.iter().map(|(k, v)| (k.as_str(), v)))
+    }
+
+    // 设置节点标签，标签在图内唯一：已被其他节点使用时从原节点上移走
+    pub fn set_node_label(&mut self, u: i64, label: &str) {
+        if let Some(old) = self.meta.get_mut(&u).and_then(|m| m.label.take()) {
+            self.labels.remove(&old);
+        }
+        if let Some(prev) = self.labels.insert(label.to_string(), u)
+            && let Some(m) = self.meta.get_mut(&prev)
+        {
+            m.label = None;
+        }
+        self.meta.entry(u).or_default().label = Some(label.to_string());
+    }
+
+    pub fn node_label(&self, u: i64) -> Option<&str> {
+        self.meta.get(&u).and_then(|m| m.label.as_deref())
+    }
+
+    // 按节点 ID 或标签查找节点，ID 优先
+    pub fn find_node(&self, query: &str) -> Option<i64> {
+        let query = query.trim();
+        if let Ok(u) = query.parse::<i64>()
+            && self.contains_node(u)
+        {
+            return Some(u);
+        }
+        self.labels.get(query).copied()
+    }
+
+    pub fn set_group(&mut self, u: i64, group: &str) {
+        self.meta.entry(u).or_default().group = Some(group.to_string());
+    }
+
+    pub fn group_of(&self, u: i64) -> Option<&str> {
+        self.meta.get(&u).and_then(|m| m.group.as_deref())
+    }
+
+    pub fn clear_groups(&mut self) {
+        for m in self.meta.values_mut() {
+            m.group = None;
+        }
+    }
+
+    // 分组名 -> 组内节点（升序）
+    pub fn groups(&self) -> BTreeMap<String, Vec<i64>> {
+        let mut result: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+        for (&u, m) in &self.meta {
+            if let Some(g) = &m.group {
+                result.entry(g.clone()).or_default().push(u);
+            }
+        }
+        for members in result.values_mut() {
+            members.sort();
+        }
+        result
+    }
+
+    // 模块度贪心社区检测（Louvain 第一阶段）：把节点依次移到模块度增益最大的邻居社区，
+    // 边按无向、无权处理，返回 节点 -> 社区标签
+    pub fn communities(&self) -> HashMap<i64, i64> {
+        let mut nodes = self.nodes();
+        nodes.sort();
+
+        let mut neighbors: HashMap<i64, HashSet<i64>> = HashMap::new();
+        for (&u, v_list) in &self.adj {
+            for e in v_list {
+                let v = e.to;
+                if u != v {
+                    neighbors.entry(u).or_default().insert(v);
+                    neighbors.entry(v).or_default().insert(u);
+                }
+            }
+        }
+        let degree = |u: i64| neighbors.get(&u).map_or(0, |n| n.len()) as f64;
+        let m2: f64 = nodes.iter().map(|&u| degree(u)).sum();
+
+        let mut label: HashMap<i64, i64> = nodes.iter().map(|&u| (u, u)).collect();
+        if m2 == 0.0 {
+            return label;
+        }
+        let mut total: HashMap<i64, f64> = nodes.iter().map(|&u| (u, degree(u))).collect();
+
+        for _ in 0..100 {
+            let mut changed = false;
+            for &u in &nodes {
+                let k = degree(u);
+                let current = label[&u];
+                *total.get_mut(&current).unwrap() -= k;
+
+                let mut links: BTreeMap<i64, f64> = BTreeMap::new();
+                links.insert(current, 0.0);
+                for v in neighbors.get(&u).into_iter().flatten() {
+                    *links.entry(label[v]).or_default() += 1.0;
+                }
+
+                let gain = |c: i64, k_in: f64| k_in - total[&c] * k / m2;
+                let mut best = (current, gain(current, links[&current]));
+                for (&c, &k_in) in &links {
+                    let g = gain(c, k_in);
+                    if g > best.1 + 1e-12 {
+                        best = (c, g);
+                    }
+                }
+
+                *total.get_mut(&best.0).unwrap() += k;
+                if best.0 != current {
+                    label.insert(u, best.0);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        label
+    }
+
+    // 空图，邻接表使用指定的存储方式
+    pub fn with_representation(repr: Repr) -> Self {
+        Graph {
+            adj: Adjacency::from_lists(repr, BTreeMap::new()),
+            meta: BTreeMap::new(),
+            labels: BTreeMap::new(),
+        }
+    }
+
+    pub fn representation(&self) -> Repr {
+        self.adj.repr()
+    }
+
+    // 转换存储方式，例如先用默认方式逐条加边，再一次性压缩为 CSR
+    pub fn set_representation(&mut self, repr: Repr) {
+        let lists = std::mem::take(&mut self.adj).into_lists();
+        self.adj = Adjacency::from_lists(repr, lists);
+    }
+
+    // 注册一个节点（可以没有任何边），已存在时不做改动
+    pub fn add_node(&mut self, u: i64) {
+        self.adj.add_node(u);
+    }
+
+    // 删除节点、与它相连的所有边以及它的元数据，返回节点是否存在
+    pub fn remove_node(&mut self, u: i64) -> bool {
+        if self.adj.remove(&u).is_none() {
+            return false;
+        }
+        self.adj.update_all(|_, list| list.retain(|e| e.to != u));
+        if let Some(label) = self.meta.remove(&u).and_then(|m| m.label) {
+            self.labels.remove(&label);
+        }
+        true
+    }
+
+    // 把 piece 的节点和边加入本图。ID 不冲突的节点保持原编号，冲突的依次改为当前最大编号 + 1；
+    // 标签已被本图使用时丢弃粘贴节点上的标签。返回 piece 中的 ID -> 本图中的 ID
+    pub fn paste(&mut self, piece: &Graph<W>) -> BTreeMap<i64, i64> {
+        let mut next = self
+            .adj
+            .keys()
+            .chain(piece.adj.keys())
+            .max()
+            .map_or(1, |&m| m + 1);
+        let mapping: BTreeMap<i64, i64> = piece
+            .adj
+            .keys()
+            .map(|&u| {
+                if self.contains_node(u) {
+                    next += 1;
+                    (u, next - 1)
+                } else {
+                    (u, u)
+                }
+            })
+            .collect();
+
+        for (u, list) in &piece.adj {
+            let edges: Vec<Edge<W>> = list
+                .iter()
+                .map(|e| Edge {
+                    to: mapping[&e.to],
+                    ..e.clone()
+                })
+                .collect();
+            self.adj.modify(mapping[u], |l| l.extend(edges));
+        }
+        for (u, m) in &piece.meta {
+            let mut m = m.clone();
+            if let Some(label) = m.label.take()
+                && !self.labels.contains_key(&label)
+            {
+                self.labels.insert(label.clone(), mapping[u]);
+                m.label = Some(label);
+            }
+            self.meta.insert(mapping[u], m);
+        }
+        mapping
+    }
+
+    // 把 v 合并进 u：v 的边改连到 u，u、v 之间的边丢弃，
+    // 与 u 原有的边重复时按 policy 合并权重（如 Sum 求和、KeepMin 取最小）。返回是否合并成功
+    pub fn contract(&mut self, u: i64, v: i64, policy: AddEdgePolicy) -> bool {
+        if u == v || !self.contains_node(u) || !self.contains_node(v) {
+            return false;
+        }
+        let outgoing: Vec<(i64, W, EdgeType)> = self
+            .out_edges(v)
+            .filter(|e| e.to != u && e.to != v)
+            .map(|e| (e.to, e.weight, e.edge_type))
+            .collect();
+        let incoming: Vec<(i64, W, EdgeType)> = self
+            .adj
+            .iter()
+            .filter(|&(&y, _)| y != u && y != v)
+            .flat_map(|(&y, list)| {
+                list.iter()
+                    .filter(|e| e.to == v)
+                    .map(move |e| (y, e.weight, e.edge_type))
+            })
+            .collect();
+
+        self.remove_node(v);
+        for (x, w, t) in outgoing {
+            self.adj.modify(u, |list| policy.apply(list, x, w, t));
+        }
+        for (y, w, t) in incoming {
+            self.adj.modify(y, |list| policy.apply(list, u, w, t));
+        }
+        self.unpair_orphans();
+        true
+    }
+
+    pub fn add_edge(&mut self, u: i64, v: i64, w: W, edge_type: EdgeType) {
+        self.add_edge_with(u, v, w, edge_type, AddEdgePolicy::Parallel);
+    }
+
+    // 与 add_edge 相同，但已有同一条边时按 policy 合并权重；Both 时两个方向分别合并，
+    // 自环只合并一次，避免求和时加两遍
+    pub fn add_edge_with(
+        &mut self,
+        u: i64,
+        v: i64,
+        w: W,
+        edge_type: EdgeType,
+        policy: AddEdgePolicy,
+    ) {
+        match edge_type {
+            EdgeType::Single => {
+                self.adj
+                    .modify(u, |list| policy.apply(list, v, w, edge_type));
+                self.adj.add_node(v);
+            }
+
+            EdgeType::Both => {
+                self.adj
+                    .modify(u, |list| policy.apply(list, v, w, edge_type));
+                if u != v || policy == AddEdgePolicy::Parallel {
+                    self.adj
+                        .modify(v, |list| policy.apply(list, u, w, edge_type));
+                }
+            }
+        }
+        if policy != AddEdgePolicy::Parallel {
+            self.unpair_orphans();
+        }
+    }
+
+    // 另一侧已没有 Both 记录的 Both 边改为 Single，删边、合并后保持两侧记录成对
+    fn unpair_orphans(&mut self) {
+        let both: HashSet<(i64, i64)> = self
+            .adj
+            .iter()
+            .flat_map(|(&u, list)| {
+                list.iter()
+                    .filter(|e| e.edge_type == EdgeType::Both)
+                    .map(move |e| (u, e.to))
+            })
+            .collect();
+        if both.iter().all(|&(u, v)| both.contains(&(v, u))) {
+            return;
+        }
+        self.adj.update_all(|u, list| {
+            for e in list.iter_mut() {
+                if e.edge_type == EdgeType::Both && !both.contains(&(e.to, u)) {
+                    e.edge_type = EdgeType::Single;
+                }
+            }
+        });
+    }
+
+    // u、v 之间边的方向，两者之间没有边时返回 None
+    pub fn direction(&self, u: i64, v: i64) -> Option<EdgeDirection> {
+        match (self.contains_edge(u, v), self.contains_edge(v, u)) {
+            (true, true) => Some(EdgeDirection::Both),
+            (true, false) => Some(EdgeDirection::Forward),
+            (false, true) => Some(EdgeDirection::Backward),
+            (false, false) => None,
+        }
+    }
+
+    // 改变 u、v 之间边的方向：以原有的一个方向上的边记录为准（优先 u -> v），
+    // 先删掉两个方向的全部记录，再按 dir 重新加入，权重、容量和标签保持不变。
+    // 两者之间没有边或 u == v 时返回 false
+    pub fn set_direction(&mut self, u: i64, v: i64, dir: EdgeDirection) -> bool {
+        if u == v {
+            return false;
+        }
+        let forward: Vec<Edge<W>> = self.out_edges(u).filter(|e| e.to == v).cloned().collect();
+        let records = if forward.is_empty() {
+            self.out_edges(v).filter(|e| e.to == u).cloned().collect()
+        } else {
+            forward
+        };
+        if records.is_empty() {
+            return false;
+        }
+
+        self.remove_edge(u, v, EdgeType::Both);
+        let edge_type = match dir {
+            EdgeDirection::Both => EdgeType::Both,
+            _ => EdgeType::Single,
+        };
+        if dir != EdgeDirection::Backward {
+            let list = records.iter().map(|e| Edge {
+                to: v,
+                edge_type,
+                ..e.clone()
+            });
+            self.adj.modify(u, |l| l.extend(list));
+        }
+        if dir != EdgeDirection::Forward {
+            let list = records.iter().map(|e| Edge {
+                to: u,
+                edge_type,
+                ..e.clone()
+            });
+            self.adj.modify(v, |l| l.extend(list));
+        }
+        true
+    }
+
+    // 删除 u -> v 的所有边（Both 时连同 v -> u），节点保留；返回是否删除了边
+    pub fn remove_edge(&mut self, u: i64, v: i64, edge_type: EdgeType) -> bool {
+        let mut removed = false;
+        let mut remove = |a: i64, b: i64| {
+            if self.adj.contains_key(&a) {
+                removed |= self.adj.modify(a, |list| {
+                    let before = list.len();
+                    list.retain(|e| e.to != b);
+                    list.len() != before
+                });
+            }
+        };
+        remove(u, v);
+        if edge_type == EdgeType::Both {
+            remove(v, u);
+        } else if removed {
+            self.unpair_orphans();
+        }
+        removed
+    }
+
+    pub fn dijkstra(&self, s: i64) -> DijkstraResult<W> {
+        algo::dijkstra(self, s)
+    }
+
+    // 算法执行过程中的结构化步骤，用于生成带原因的解释日志
+    pub fn dijkstra_steps(&self, s: i64) -> Vec<StepEvent<W>> {
+        algo::dijkstra_steps(self, s)
+    }
+
+    // 拓扑排序，同时可选的节点按 ID 从小到大；有环（包括任何无向边）时返回 None
+    pub fn topological_sort(&self) -> Option<Vec<i64>> {
+        algo::topological_sort(self)
+    }
+
+    pub fn is_acyclic(&self) -> bool {
+        self.topological_sort().is_some()
+    }
+
+    // 按拓扑序松弛的 DAG 最短路径，允许负权；有环时返回 None
+    pub fn dag_shortest_paths(&self, s: i64) -> Option<DijkstraResult<W>> {
+        algo::dag_shortest_paths(self, s)
+    }
+
+    pub fn dag_shortest_paths_steps(&self, s: i64) -> Option<Vec<StepEvent<W>>> {
+        algo::dag_shortest_paths_steps(self, s)
+    }
+
+    // 允许负权的单源最短路径，同时检查从 s 可达的负环
+    pub fn bellman_ford(&self, s: i64) -> BellmanFordResult<W> {
+        algo::bellman_ford(self, s)
+    }
+
+    // 从 s 出发的 Prim，只覆盖 s 所在的连通分量
+    pub fn prim(&self, s: i64) -> MstResult<W> {
+        algo::prim(self, s)
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    pub fn prim_steps(&self, s: i64) -> Vec<StepEvent<W>> {
+        algo::prim_steps(self, s)
     }
-}
 
-pub struct Graph {
-    adj: HashMap<i64, Vec<(i64, i64)>>,
-}
+    // 按边权从小到大选边，用并查集跳过成环的边；图不连通时得到最小生成森林
+    // 有向边按无向处理，平行边都参与比较
+    pub fn kruskal(&self) -> MstResult<W> {
+        let mut edges: Vec<(i64, i64, W)> = self
+            .adj
+            .iter()
+            .flat_map(|(&u, list)| list.iter().map(move |e| (u, e.to, e.weight)))
+            .collect();
+        edges.sort_by(|a, b| a.2.total_cmp(&b.2));
 
-impl Graph {
-    pub fn new() -> Self {
-        Graph {
-            adj: HashMap::new(),
+        let mut root: HashMap<i64, i64> = self.nodes().into_iter().map(|u| (u, u)).collect();
+        fn find(root: &mut HashMap<i64, i64>, u: i64) -> i64 {
+            let p = root[&u];
+            if p == u {
+                return u;
+            }
+            let r = find(root, p);
+            root.insert(u, r);
+            r
+        }
+
+        let mut picked = Vec::new();
+        for (u, v, w) in edges {
+            let (ru, rv) = (find(&mut root, u), find(&mut root, v));
+            if ru != rv {
+                root.insert(ru, rv);
+                picked.push((u, v, w));
+            }
         }
+
+        let is_spanning = picked.len() + 1 >= self.adj.len();
+        MstResult::new(self.nodes(), picked, is_spanning)
     }
 
-    pub fn add_edge(&mut self, u: i64, v: i64, w: i64, edge_type: EdgeType) {
-        match edge_type {
-            EdgeType::Single => {
-                self.adj.entry(u).or_insert(Vec::new()).push((v, w));
+    // Karger 的一次试验。按随机顺序考察边（等价于每次在剩余边中均匀随机选一条），
+    // 端点已在同一超级节点内的边跳过；图不连通时剩下的分量不再收缩，割为空。
+    // 相同种子得到相同结果
+    pub fn karger_trial(&self, seed: u64) -> KargerTrial<W> {
+        let nodes = self.nodes();
+        let mut pairs: Vec<(i64, i64, W)> = self
+            .merged_pairs()
+            .into_iter()
+            .filter(|((u, v), _)| u != v)
+            .map(|((u, v), e)| (u, v, e.weight))
+            .collect();
+
+        let mut rng = SeededRng::new(seed);
+        for i in (1..pairs.len()).rev() {
+            let j = rng.below(i as u64 + 1) as usize;
+            pairs.swap(i, j);
+        }
+
+        let mut root: HashMap<i64, i64> = nodes.iter().map(|&u| (u, u)).collect();
+        fn find(root: &mut HashMap<i64, i64>, u: i64) -> i64 {
+            let p = root[&u];
+            if p == u {
+                return u;
             }
+            let r = find(root, p);
+            root.insert(u, r);
+            r
+        }
 
-            EdgeType::Both => {
-                self.adj.entry(u).or_insert(Vec::new()).push((v, w));
-                self.adj.entry(v).or_insert(Vec::new()).push((u, w));
+        let mut contractions = Vec::new();
+        let mut remaining = nodes.len();
+        for &(u, v, _) in &pairs {
+            if remaining <= 2 {
+                break;
             }
+            let (ru, rv) = (find(&mut root, u), find(&mut root, v));
+            if ru != rv {
+                root.insert(rv, ru);
+                contractions.push((u, v));
+                remaining -= 1;
+            }
+        }
+
+        let Some(&first) = nodes.first() else {
+            return KargerTrial::default();
+        };
+        let first = find(&mut root, first);
+        let side: Vec<i64> = nodes
+            .iter()
+            .copied()
+            .filter(|&u| find(&mut root, u) == first)
+            .collect();
+        let cut_edges = pairs
+            .into_iter()
+            .filter(|&(u, v, _)| (find(&mut root, u) == first) != (find(&mut root, v) == first))
+            .collect();
+        KargerTrial {
+            contractions,
+            side,
+            cut_edges,
         }
     }
 
-    pub fn dijkstra(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>, HashMap<i64, i64>, HashMap<i64, i64>) {
-        let mut dist: HashMap<i64, i64> = HashMap::new();
-        let mut parent: HashMap<i64, i64> = HashMap::new();
-        let mut visited_nodes: Vec<i64> = Vec::new();
-        let mut visited_edges: Vec<(i64, i64)> = Vec::new();
-        let mut processed: HashSet<i64> = HashSet::new();
+    // 重复 trials 次试验（种子依次为 seed、seed+1…），返回割边最少的一次。
+    // 单次找到最小割的概率至少为 2/(n(n-1))，约 n² ln n 次试验后失败概率低于 1/n
+    pub fn karger_min_cut(&self, trials: usize, seed: u64) -> KargerTrial<W> {
+        (0..trials.max(1) as u64)
+            .map(|t| self.karger_trial(seed.wrapping_add(t)))
+            .min_by_key(|trial| trial.cut_edges.len())
+            .unwrap_or_default()
+    }
 
-        let mut pq: BinaryHeap<State> = BinaryHeap::new();
+    pub fn bfs(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
+        algo::bfs(self, s)
+    }
 
-        dist.insert(s, 0);
-        pq.push(State { cost: 0, node: s });
+    pub fn bfs_steps(&self, s: i64) -> Vec<StepEvent<W>> {
+        algo::bfs_steps(self, s)
+    }
 
-        while !pq.is_empty() {
-            let u = pq.pop();
-            match u {
-                Some(u) => {
-                    if u.cost > *dist.get(&u.node).unwrap_or(&i64::MAX) {
-                        continue;
-                    }
-                    if !processed.contains(&u.node) {
-                        visited_nodes.push(u.node);
-                        processed.insert(u.node);
-                    }
-                    if let Some(v_list) = self.adj.get(&u.node) {
-                        for &(v, w) in v_list {
-                            let cost = u.cost + w;
-                            if cost < *dist.get(&v).unwrap_or(&i64::MAX) {
-                                dist.insert(v, cost);
-                                parent.insert(v, u.node);
-                                pq.push(State { cost, node: v });
-                                visited_edges.push((u.node, v));
-                            }
+    pub fn bfs_multi(&self, sources: &[i64]) -> (Vec<i64>, Vec<(i64, i64)>) {
+        algo::bfs_multi(self, sources)
+    }
+
+    pub fn bfs_multi_steps(&self, sources: &[i64]) -> Vec<StepEvent<W>> {
+        algo::bfs_multi_steps(self, sources)
+    }
+
+    pub fn dfs(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
+        algo::dfs(self, s)
+    }
+
+    pub fn dfs_steps(&self, s: i64) -> Vec<StepEvent<W>> {
+        algo::dfs_steps(self, s)
+    }
+
+    // 按 ID 升序
+    pub fn nodes(&self) -> Vec<i64> {
+        self.adj.keys().copied().collect()
+    }
+
+    // 把节点 ID 压缩为 1..=n（按原 ID 升序），返回新图以及 originals，
+    // 其中 originals[i] 是新 ID i + 1 对应的原 ID；边属性和节点元数据随节点一起迁移
+    pub fn relabel(&self) -> (Graph<W>, Vec<i64>) {
+        let originals = self.nodes();
+        let new_id: HashMap<i64, i64> = originals
+            .iter()
+            .enumerate()
+            .map(|(i, &u)| (u, i as i64 + 1))
+            .collect();
+
+        let lists = self
+            .adj
+            .iter()
+            .map(|(u, list)| {
+                let list = list
+                    .iter()
+                    .map(|e| Edge {
+                        to: new_id[&e.to],
+                        ..e.clone()
+                    })
+                    .collect();
+                (new_id[u], list)
+            })
+            .collect();
+        let adj = Adjacency::from_lists(self.adj.repr(), lists);
+        let meta = self
+            .meta
+            .iter()
+            .filter(|(u, _)| new_id.contains_key(u))
+            .map(|(u, m)| (new_id[u], m.clone()))
+            .collect();
+
+        let labels = self
+            .labels
+            .iter()
+            .map(|(label, u)| (label.clone(), new_id[u]))
+            .collect();
+
+        (Graph { adj, meta, labels }, originals)
+    }
+
+    pub fn validate(&self) -> Vec<GraphIssue<W>> {
+        let mut issues = Vec::new();
+
+        for (&u, list) in &self.adj {
+            let mut count: BTreeMap<i64, usize> = BTreeMap::new();
+            for e in list {
+                *count.entry(e.to).or_default() += 1;
+            }
+
+            for (&v, &c) in &count {
+                if u == v {
+                    issues.push(GraphIssue::SelfLoop { u });
+                }
+                if c > 1 {
+                    issues.push(GraphIssue::DuplicateEdge { u, v, count: c });
+                }
+                if !self.adj.contains_key(&v) {
+                    issues.push(GraphIssue::DanglingEdge { u, v });
+                }
+                if u < v
+                    && let (Some(f), Some(b)) = (self.edge(u, v), self.edge(v, u))
+                    && !f.weight.approx_eq(b.weight)
+                {
+                    issues.push(GraphIssue::AsymmetricEdge {
+                        u,
+                        v,
+                        forward: f.weight,
+                        backward: b.weight,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
+    // 修复 validate() 报告的问题：重复边合并为权重最小的一条，删除自环，补登悬空边的终点；
+    // 权重不一致的双向边无法判断哪个正确，保持原样。返回修复的问题数
+    pub fn repair(&mut self) -> usize {
+        let mut fixed = 0;
+        let mut targets = BTreeSet::new();
+
+        self.adj.update_all(|u, list| {
+            let before = list.len();
+            list.retain(|e| e.to != u);
+            fixed += usize::from(list.len() != before);
+
+            let mut merged: Vec<Edge<W>> = Vec::new();
+            let mut duplicated = BTreeSet::new();
+            for e in list.drain(..) {
+                match merged.iter_mut().find(|m| m.to == e.to) {
+                    Some(m) => {
+                        duplicated.insert(e.to);
+                        if e.weight.less_than(m.weight) {
+                            *m = e;
                         }
                     }
+                    None => merged.push(e),
+                }
+            }
+            fixed += duplicated.len();
+            *list = merged;
+            targets.extend(list.iter().map(|e| e.to));
+        });
+        self.unpair_orphans();
+
+        for v in targets {
+            if !self.adj.contains_key(&v) {
+                self.add_node(v);
+                fixed += 1;
+            }
+        }
+        fixed
+    }
+
+    // 随机游走的一步：按出边权重成比例地选下一个节点，r 为 [0, 1) 内的随机数。
+    // 非正权重视为 0，全部为 0 时等概率选择；没有出边返回 None
+    pub fn walk_step(&self, u: i64, r: f64) -> Option<i64> {
+        let edges: Vec<&Edge<W>> = self.out_edges(u).collect();
+        if edges.is_empty() {
+            return None;
+        }
+
+        let weights: Vec<f64> = edges.iter().map(|e| e.weight.to_f64().max(0.0)).collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            let i = ((r * edges.len() as f64) as usize).min(edges.len() - 1);
+            return Some(edges[i].to);
+        }
+
+        let mut acc = 0.0;
+        for (e, w) in edges.iter().zip(&weights) {
+            acc += w;
+            if r * total < acc {
+                return Some(e.to);
+            }
+        }
+        edges.last().map(|e| e.to)
+    }
+
+    // 从 s 出发沿出边可达的节点（含 s），按 ID 排序
+    pub fn reachable_from(&self, s: i64) -> Vec<i64> {
+        let (mut reached, _) = self.bfs(s);
+        reached.sort();
+        reached
+    }
+
+    // 沿出边能否从 u 到达 v（u == v 时总是可以），找到 v 后立即停止
+    pub fn is_reachable(&self, u: i64, v: i64) -> bool {
+        self.reach_path(u, v).is_some()
+    }
+
+    // 从 u 到 v 边数最少的一条路径（含两端），不可达时返回 None；BFS 找到 v 后立即停止
+    pub fn reach_path(&self, u: i64, v: i64) -> Option<Vec<i64>> {
+        if !self.contains_node(u) || !self.contains_node(v) {
+            return None;
+        }
+        let mut parent: HashMap<i64, i64> = HashMap::from([(u, u)]);
+        let mut queue = VecDeque::from([u]);
+        while let Some(x) = queue.pop_front() {
+            if x == v {
+                return path_from_parents(&parent, u, v);
+            }
+            for e in self.out_edges(x) {
+                if let Entry::Vacant(slot) = parent.entry(e.to) {
+                    slot.insert(x);
+                    queue.push_back(e.to);
                 }
-                _ => {}
             }
         }
+        None
+    }
 
-        (visited_nodes, visited_edges, dist, parent)
+    // 边数最少的环（围长），环上节点按经过顺序排列、首节点不重复；没有环时返回 None。
+    // 单向边只能顺着方向走，无向边不能原路返回，自环长度为 1，两条平行边构成长度为 2 的环
+    pub fn girth(&self) -> Option<Vec<i64>> {
+        self.shortest_cycle(|_| 1i64).map(|(_, cycle)| cycle)
     }
 
-    pub fn prim(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>, i64) {
-        let mut dist: HashMap<i64, i64> = HashMap::new();
-        let mut booked: HashSet<i64> = HashSet::new();
-        let mut visited_nodes: Vec<i64> = Vec::new();
-        let mut visited_edges: Vec<(i64, i64)> = Vec::new();
+    // 边权之和最小的环及其总权重，规则同 girth；用 Dijkstra 找回路，有负权边时不保证最小
+    pub fn min_weight_cycle(&self) -> Option<(Vec<i64>, W)> {
+        self.shortest_cycle(|e| e.weight)
+            .map(|(cost, cycle)| (cycle, cost))
+    }
 
-        let mut parent: HashMap<i64, i64> = HashMap::new();
-        let mut pq: BinaryHeap<State> = BinaryHeap::new();
-        let mut total_cost: i64 = 0;
+    // 对每条边 u -> v，求不经过这条边（无向边连同反向记录）时 v 回到 u 的最短路，
+    // 加上这条边即为经过它的最短环，取其中最短的；长度相同时取先找到的
+    fn shortest_cycle<C: Weight>(&self, cost: impl Fn(&Edge<W>) -> C) -> Option<(C, Vec<i64>)> {
+        let mut best: Option<(C, Vec<i64>)> = None;
+        for (&u, list) in &self.adj {
+            for (i, e) in list.iter().enumerate() {
+                let found = if e.to == u {
+                    Some((cost(e), vec![u]))
+                } else {
+                    self.return_path(u, i, &cost).map(|(d, path)| {
+                        let mut cycle = vec![u];
+                        cycle.extend(&path[..path.len() - 1]);
+                        (cost(e) + d, cycle)
+                    })
+                };
+                if let Some((c, cycle)) = found
+                    && best.as_ref().is_none_or(|(b, _)| c.less_than(*b))
+                {
+                    best = Some((c, cycle));
+                }
+            }
+        }
+        best
+    }
 
-        dist.insert(s, 0);
-        pq.push(State { cost: 0, node: s });
+    // u 的第 i 条出边为 u -> v，不经过这条记录（Both 时连同 v 中对应的反向记录）从 v 到 u 的最短路
+    fn return_path<C: Weight>(
+        &self,
+        u: i64,
+        i: usize,
+        cost: &impl Fn(&Edge<W>) -> C,
+    ) -> Option<(C, Vec<i64>)> {
+        let e = &self.adj.get(&u)?[i];
+        let v = e.to;
+        let twin = (e.edge_type == EdgeType::Both)
+            .then(|| {
+                self.out_edges(v).position(|b| {
+                    b.to == u && b.edge_type == EdgeType::Both && b.weight.approx_eq(e.weight)
+                })
+            })
+            .flatten();
 
-        while let Some(State { cost, node: u }) = pq.pop() {
-            if booked.contains(&u) {
+        let mut dist: HashMap<i64, C> = HashMap::from([(v, C::zero())]);
+        let mut parent: HashMap<i64, i64> = HashMap::new();
+        let mut pq = BinaryHeap::from([State {
+            cost: C::zero(),
+            node: v,
+        }]);
+        while let Some(State { cost: d, node: x }) = pq.pop() {
+            if dist.get(&x).is_some_and(|&best| best.less_than(d)) {
                 continue;
             }
-            if cost > *dist.get(&u).unwrap_or(&i64::MAX) {
-                continue;
+            if x == u {
+                return path_from_parents(&parent, v, u).map(|path| (d, path));
+            }
+            for (j, b) in self.out_edges(x).enumerate() {
+                if (x == u && j == i) || (x == v && Some(j) == twin) {
+                    continue;
+                }
+                let next = d.add_or_infinity(cost(b));
+                if dist.get(&b.to).is_none_or(|&old| next.less_than(old)) {
+                    dist.insert(b.to, next);
+                    parent.insert(b.to, x);
+                    pq.push(State {
+                        cost: next,
+                        node: b.to,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    // 从 s 出发无法到达的节点（含孤立节点），按 ID 升序
+    pub fn unreachable_from(&self, s: i64) -> Vec<i64> {
+        let reached: HashSet<i64> = self.reachable_from(s).into_iter().collect();
+        self.nodes()
+            .into_iter()
+            .filter(|u| !reached.contains(u))
+            .collect()
+    }
+
+    // 没有反向边的单向边 u -> v，按 (u, v) 排序去重
+    pub fn one_way_edges(&self) -> Vec<(i64, i64)> {
+        let mut result: Vec<(i64, i64)> = self
+            .adj
+            .iter()
+            .flat_map(|(&u, list)| list.iter().map(move |e| (u, e.to)))
+            .filter(|&(u, v)| !self.contains_edge(v, u))
+            .collect();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    // 所有边权都相同（包括没有边），此时 BFS 按边数得到的就是最短路径
+    pub fn has_uniform_weights(&self) -> bool {
+        let mut weights = self
+            .adj
+            .iter()
+            .flat_map(|(_, list)| list.iter().map(|e| e.weight));
+        match weights.next() {
+            Some(first) => weights.all(|w| w.approx_eq(first)),
+            None => true,
+        }
+    }
+
+    // 图中存在单向边时视为有向图；无向边在邻接表中总是成对出现
+    pub fn is_directed(&self) -> bool {
+        !self.one_way_edges().is_empty()
+    }
+
+    // 为每条单向边补上同权重的反向边（平行的单向边各补一条），返回补上的边数
+    pub fn symmetrize(&mut self) -> usize {
+        let mut added = 0;
+        for (u, v) in self.one_way_edges() {
+            let mut reversed = Vec::new();
+            self.adj.modify(u, |list| {
+                for record in list.iter_mut().filter(|e| e.to == v) {
+                    record.edge_type = EdgeType::Both;
+                    reversed.push(Edge {
+                        to: u,
+                        ..record.clone()
+                    });
+                }
+            });
+            added += reversed.len();
+            self.adj.modify(v, |list| list.extend(reversed));
+        }
+        added
+    }
+
+    // 把互为反向、权重相同的单向边两两配成无向边，用于从只有有向边的格式导入。返回配成的边数
+    pub fn pair_opposite_edges(&mut self) -> usize {
+        let mut singles: BTreeMap<(i64, i64), Vec<(usize, W)>> = BTreeMap::new();
+        for (&u, list) in &self.adj {
+            for (i, e) in list.iter().enumerate() {
+                if e.edge_type == EdgeType::Single && e.to != u {
+                    singles.entry((u, e.to)).or_default().push((i, e.weight));
+                }
             }
-            booked.insert(u);
-            visited_nodes.push(u);
+        }
 
-            if let Some(&p) = parent.get(&u) {
-                visited_edges.push((p, u));
-                total_cost += cost;
+        let mut marked: BTreeSet<(i64, usize)> = BTreeSet::new();
+        let mut paired = 0;
+        for (&(u, v), forward) in &singles {
+            let Some(backward) = singles.get(&(v, u)).filter(|_| u < v) else {
+                continue;
+            };
+            let mut used = vec![false; backward.len()];
+            for &(i, w) in forward {
+                let found = backward
+                    .iter()
+                    .enumerate()
+                    .position(|(k, &(_, b))| !used[k] && b.approx_eq(w));
+                if let Some(k) = found {
+                    used[k] = true;
+                    marked.insert((u, i));
+                    marked.insert((v, backward[k].0));
+                    paired += 1;
+                }
             }
+        }
 
-            if let Some(v_list) = self.adj.get(&u) {
-                for &(v, w) in v_list {
-                    if !booked.contains(&v) && w < *dist.get(&v).unwrap_or(&i64::MAX) {
-                        dist.insert(v, w);
-                        parent.insert(v, u);
-                        pq.push(State { cost: w, node: v });
+        if paired > 0 {
+            self.adj.update_all(|u, list| {
+                for (i, e) in list.iter_mut().enumerate() {
+                    if marked.contains(&(u, i)) {
+                        e.edge_type = EdgeType::Both;
                     }
                 }
+            });
+        }
+        paired
+    }
+
+    // 把每对相邻节点之间的所有边（不论方向）合并成一条，保留权重最小的那条的属性
+    fn merged_pairs(&self) -> BTreeMap<(i64, i64), Edge<W>> {
+        let mut pairs: BTreeMap<(i64, i64), Edge<W>> = BTreeMap::new();
+        for (&u, list) in &self.adj {
+            for e in list {
+                let key = (u.min(e.to), u.max(e.to));
+                if pairs.get(&key).is_none_or(|m| e.weight.less_than(m.weight)) {
+                    pairs.insert(key, e.clone());
+                }
+            }
+        }
+        pairs
+    }
+
+    // 节点、属性和标签不变，边换成给定的 (起点, 边)
+    fn with_edges(&self, edges: impl IntoIterator<Item = (i64, Edge<W>)>) -> Graph<W> {
+        let mut lists: BTreeMap<i64, Vec<Edge<W>>> =
+            self.adj.keys().map(|&u| (u, Vec::new())).collect();
+        for (u, e) in edges {
+            lists.entry(u).or_default().push(e);
+        }
+        Graph {
+            adj: Adjacency::from_lists(self.adj.repr(), lists),
+            meta: self.meta.clone(),
+            labels: self.labels.clone(),
+        }
+    }
+
+    // 由 nodes 导出的子图：只保留这些节点、它们的元数据和两端都在其中的边。
+    // 不在图中的节点被忽略
+    pub fn induced_subgraph(&self, nodes: &[i64]) -> Graph<W> {
+        let keep: BTreeSet<i64> = nodes
+            .iter()
+            .copied()
+            .filter(|u| self.contains_node(*u))
+            .collect();
+        let lists: BTreeMap<i64, Vec<Edge<W>>> = keep
+            .iter()
+            .map(|&u| {
+                let list = self
+                    .out_edges(u)
+                    .filter(|e| keep.contains(&e.to))
+                    .cloned()
+                    .collect();
+                (u, list)
+            })
+            .collect();
+        let meta: BTreeMap<i64, NodeMeta> = self
+            .meta
+            .iter()
+            .filter(|(u, _)| keep.contains(u))
+            .map(|(&u, m)| (u, m.clone()))
+            .collect();
+        let labels = self
+            .labels
+            .iter()
+            .filter(|(_, u)| keep.contains(u))
+            .map(|(l, &u)| (l.clone(), u))
+            .collect();
+        Graph {
+            adj: Adjacency::from_lists(self.adj.repr(), lists),
+            meta,
+            labels,
+        }
+    }
+
+    // 去掉方向：相邻节点之间只保留一条无向边，互为反向的边取较小的权重
+    pub fn to_undirected(&self) -> Graph<W> {
+        self.with_edges(self.merged_pairs().into_iter().flat_map(|((u, v), e)| {
+            let e = Edge {
+                edge_type: EdgeType::Both,
+                ..e
+            };
+            let back = (u != v).then(|| (v, Edge { to: u, ..e.clone() }));
+            std::iter::once((u, Edge { to: v, ..e })).chain(back)
+        }))
+    }
+
+    // 给每条边定向：先按 to_undirected 合并，再对每对 u < v 调用 keep(u, v)，
+    // 返回 true 保留 u -> v，否则保留 v -> u。自环保持不变
+    pub fn orient_by(&self, mut keep: impl FnMut(i64, i64) -> bool) -> Graph<W> {
+        self.with_edges(self.merged_pairs().into_iter().map(|((u, v), e)| {
+            let e = Edge {
+                edge_type: EdgeType::Single,
+                ..e
+            };
+            if u == v || keep(u, v) {
+                (u, Edge { to: v, ..e })
+            } else {
+                (v, Edge { to: u, ..e })
+            }
+        }))
+    }
+
+    // 随机定向，相同种子得到相同结果
+    pub fn orient_random(&self, seed: u64) -> Graph<W> {
+        let mut rng = SeededRng::new(seed);
+        self.orient_by(|_, _| rng.next_u64() >> 63 == 0)
+    }
+
+    // 所有边权乘以 factor，容量不变；有边溢出时返回 None。
+    // 乘以正数不改变各路径长度的大小关系，最短路径不变
+    pub fn scale_weights(&self, factor: W) -> Option<Graph<W>> {
+        self.map_weights(|w| w.checked_mul(factor))
+    }
+
+    // 所有边权加上 delta，有边溢出时返回 None。边数不同的路径增加的量不同，最短路径可能改变
+    pub fn shift_weights(&self, delta: W) -> Option<Graph<W>> {
+        self.map_weights(|w| w.checked_add(delta))
+    }
+
+    fn map_weights(&self, f: impl Fn(W) -> Option<W>) -> Option<Graph<W>> {
+        let mut edges = Vec::new();
+        for (&u, list) in &self.adj {
+            for e in list {
+                let weight = f(e.weight)?;
+                edges.push((
+                    u,
+                    Edge {
+                        weight,
+                        ..e.clone()
+                    },
+                ));
+            }
+        }
+        Some(self.with_edges(edges))
+    }
+
+    // 忽略方向的连通分量，排序方式同 strongly_connected_components
+    pub fn connected_components(&self) -> Vec<Vec<i64>> {
+        self.to_undirected().strongly_connected_components()
+    }
+
+    // 基于极大匹配的 2-近似顶点覆盖，忽略方向：按 edges() 的顺序考察每条边，
+    // 两端都未覆盖时把两端都加入。返回 (按加入顺序的覆盖节点, 选中的匹配边)；
+    // 匹配边两两不相交，最优覆盖至少要包含每条匹配边的一个端点，因此结果不超过最优解的 2 倍
+    pub fn vertex_cover_2approx(&self) -> (Vec<i64>, Vec<(i64, i64)>) {
+        let mut cover = Vec::new();
+        let mut covered = HashSet::new();
+        let mut matching = Vec::new();
+        for (u, v, _, _) in self.edges() {
+            if covered.contains(&u) || covered.contains(&v) {
+                continue;
+            }
+            matching.push((u, v));
+            for x in [u, v] {
+                if covered.insert(x) {
+                    cover.push(x);
+                }
+            }
+        }
+        (cover, matching)
+    }
+
+    // 贪心独立集，忽略方向：每次选剩余图中度数最小的节点（度数相同取编号小的），
+    // 删去它和它的邻居，直到图为空。有自环的节点不能入选。结果是极大独立集，但不保证最大
+    pub fn greedy_independent_set(&self) -> Vec<i64> {
+        let g = self.to_undirected();
+        let mut remaining: BTreeSet<i64> = g
+            .nodes()
+            .into_iter()
+            .filter(|&u| !g.contains_edge(u, u))
+            .collect();
+        let mut chosen = Vec::new();
+        while let Some(u) = remaining
+            .iter()
+            .copied()
+            .min_by_key(|&u| g.neighbors(u).filter(|v| remaining.contains(v)).count())
+        {
+            chosen.push(u);
+            remaining.remove(&u);
+            for v in g.neighbors(u) {
+                remaining.remove(&v);
             }
         }
+        chosen
+    }
 
-        (visited_nodes, visited_edges, total_cost)
+    // 贪心支配集，忽略方向：每轮选能新支配最多节点的节点（自身与邻居中尚未被支配的个数，
+    // 相同时取编号小的），直到所有节点都被支配。返回每轮的 (选中节点, 这一轮新被支配的节点)；
+    // 结果不超过最小支配集的 ln(n) + 1 倍
+    pub fn greedy_dominating_set(&self) -> Vec<(i64, Vec<i64>)> {
+        let g = self.to_undirected();
+        let closed = |u: i64| {
+            let mut around: BTreeSet<i64> = g.neighbors(u).collect();
+            around.insert(u);
+            around
+        };
+        let mut left: BTreeSet<i64> = g.nodes().into_iter().collect();
+        let mut rounds = Vec::new();
+        while !left.is_empty() {
+            let Some((u, gained)) = g
+                .nodes()
+                .into_iter()
+                .map(|u| {
+                    (
+                        u,
+                        closed(u).intersection(&left).copied().collect::<Vec<_>>(),
+                    )
+                })
+                // max_by_key 取最后一个最大值，倒序遍历使编号小的优先
+                .rev()
+                .max_by_key(|(_, gained)| gained.len())
+            else {
+                break;
+            };
+            for v in &gained {
+                left.remove(v);
+            }
+            rounds.push((u, gained));
+        }
+        rounds
     }
 
-    pub fn bfs(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
-        let mut visited: HashSet<i64> = HashSet::new();
-        let mut visited_nodes: Vec<i64> = Vec::new();
-        let mut visited_edges: Vec<(i64, i64)> = Vec::new();
-        let mut q: VecDeque<i64> = VecDeque::new();
-
-        q.push_back(s);
-        visited.insert(s);
-        visited_nodes.push(s);
-
-        while !q.is_empty() {
-            let u = q.pop_front();
-            match u {
-                Some(u) => {
-                    if let Some(v_list) = self.adj.get(&u) {
-                        for &(v, _) in v_list {
-                            if !visited.contains(&v) {
-                                q.push_back(v);
-                                visited.insert(v);
-                                visited_nodes.push(v);
-                                visited_edges.push((u, v));
-                            }
+    // 忽略方向做 BFS 二染色，相邻节点同色（含自环）时不是二分图
+    pub fn is_bipartite(&self) -> bool {
+        let g = self.to_undirected();
+        let mut side: HashMap<i64, bool> = HashMap::new();
+        for &s in g.adj.keys() {
+            if side.contains_key(&s) {
+                continue;
+            }
+            side.insert(s, false);
+            let mut q = VecDeque::from([s]);
+            while let Some(u) = q.pop_front() {
+                for v in g.neighbors(u) {
+                    match side.get(&v) {
+                        Some(&c) if c == side[&u] => return false,
+                        Some(_) => {}
+                        None => {
+                            side.insert(v, !side[&u]);
+                            q.push_back(v);
                         }
                     }
                 }
-                _ => println!("error"),
             }
         }
+        true
+    }
 
-        (visited_nodes, visited_edges)
+    // s 到 t 两两不共用边的路径，条数最多，即 s、t 之间的边连通度。
+    // 用单位容量的最大流求解：单向边是容量 1 的弧，无向边两个方向合计只能用一次
+    pub fn edge_disjoint_paths(&self, s: i64, t: i64) -> Vec<Vec<i64>> {
+        if s == t || !self.contains_node(s) || !self.contains_node(t) {
+            return Vec::new();
+        }
+        let (nodes, index, mut net) = self.edge_network();
+        net.max_flow(index[&s], index[&t]);
+        net.paths(index[&s], index[&t])
+            .into_iter()
+            .map(|path| path.into_iter().map(|i| nodes[i]).collect())
+            .collect()
     }
-    pub fn dfs(&self, s: i64) -> (Vec<i64>, Vec<(i64, i64)>) {
-        let mut visited: HashSet<i64> = HashSet::new();
-        let mut visited_nodes: Vec<i64> = Vec::new();
-        let mut visited_edges: Vec<(i64, i64)> = Vec::new();
-        
-        self.dfs_helper(s, &mut visited, &mut visited_nodes, &mut visited_edges);
-        
-        (visited_nodes, visited_edges)
+
+    // s 到 t 除两端外两两不共用节点的路径，条数最多，即 s、t 之间的点连通度（s、t 相邻时
+    // 每条直接相连的边各算一条路径）。把每个中间节点拆成入点和出点，中间连一条容量 1 的弧
+    pub fn vertex_disjoint_paths(&self, s: i64, t: i64) -> Vec<Vec<i64>> {
+        if s == t || !self.contains_node(s) || !self.contains_node(t) {
+            return Vec::new();
+        }
+        let (nodes, index, mut net) = self.split_network(1);
+        net.max_flow(2 * index[&s] + 1, 2 * index[&t]);
+        net.paths(2 * index[&s] + 1, 2 * index[&t])
+            .into_iter()
+            .map(|path| {
+                let mut path: Vec<i64> = path.into_iter().map(|i| nodes[i / 2]).collect();
+                path.dedup();
+                path
+            })
+            .collect()
     }
 
-    fn dfs_helper(
-        &self,
-        curr: i64,
-        visited: &mut HashSet<i64>,
-        visited_nodes: &mut Vec<i64>,
-        visited_edges: &mut Vec<(i64, i64)>,
-    ) -> bool {
-        visited.insert(curr);
-        visited_nodes.push(curr);
-        
-        if let Some(v_list) = self.adj.get(&curr) {
-            for &(v, _) in v_list {
-                if !visited.contains(&v) {
-                    visited_edges.push((curr, v));
-                    if self.dfs_helper(v, visited, visited_nodes, visited_edges) {
-                        return true;
-                    }
+    // 边连通度：至少删去几条边才能使某个节点到不了另一个节点（有单向边时按方向），
+    // 同时返回一个这样的最小边集。固定一个节点，对其余每个节点双向求最大流取最小，
+    // 只适合小图
+    pub fn edge_connectivity(&self) -> (usize, Vec<(i64, i64)>) {
+        let (nodes, _, mut net) = self.edge_network();
+        let mut best: Option<(usize, Vec<(i64, i64)>)> = None;
+        for t in 1..nodes.len() {
+            for (a, b) in [(0, t), (t, 0)] {
+                net.reset();
+                let flow = net.max_flow(a, b);
+                if best.as_ref().is_none_or(|(k, _)| flow < *k) {
+                    let cut = net.min_cut(a);
+                    best = Some((
+                        flow,
+                        cut.into_iter().map(|(u, v)| (nodes[u], nodes[v])).collect(),
+                    ));
                 }
             }
         }
-        false
+        best.unwrap_or_default()
     }
 
-    pub fn nodes(&self) -> Vec<i64> {
-        let mut set: HashSet<i64> = HashSet::new();
-        for (&u, v_list) in &self.adj {
-            set.insert(u);
-            for &(v, _) in v_list {
-                set.insert(v);
+    // 点连通度：至少删去几个节点才能使剩下的某个节点到不了另一个节点（有单向边时按方向），
+    // 同时返回一个这样的最小点集。对每对不直接相连的节点求拆点后的最大流取最小；
+    // 任意两点都直接相连时为节点数 - 1，点集为空。只适合小图
+    pub fn vertex_connectivity(&self) -> (usize, Vec<i64>) {
+        let n = self.adj.len();
+        // 边上的弧容量足够大，最小割只会落在节点拆出的弧上
+        let (nodes, _, mut net) = self.split_network(n);
+        let mut best = (n.saturating_sub(1), Vec::new());
+        for (s, &u) in nodes.iter().enumerate() {
+            for (t, &v) in nodes.iter().enumerate() {
+                if s == t || self.contains_edge(u, v) {
+                    continue;
+                }
+                net.reset();
+                let flow = net.max_flow(2 * s + 1, 2 * t);
+                if flow < best.0 {
+                    let cut = net.min_cut(2 * s + 1);
+                    best = (flow, cut.into_iter().map(|(i, _)| nodes[i / 2]).collect());
+                }
+            }
+        }
+        best
+    }
+
+    // 求边不相交路径和边连通度的流网络，返回 (节点列表, 节点 -> 下标, 网络)
+    fn edge_network(&self) -> (Vec<i64>, HashMap<i64, usize>, UnitFlow) {
+        let nodes = self.nodes();
+        let index: HashMap<i64, usize> = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let mut net = UnitFlow::new(nodes.len());
+        for (&u, list) in &self.adj {
+            for e in list {
+                match e.edge_type {
+                    EdgeType::Single => net.add(index[&u], index[&e.to], 1, 0),
+                    EdgeType::Both if u < e.to => net.add(index[&u], index[&e.to], 1, 1),
+                    EdgeType::Both => {}
+                }
+            }
+        }
+        (nodes, index, net)
+    }
+
+    // 拆点后的流网络：节点下标 i 拆成入点 2i 和出点 2i + 1，中间一条容量 1 的弧，
+    // 每条边 u -> v 是 u 的出点到 v 的入点、容量为 edge_cap 的弧。以出点为源、入点为汇时
+    // 两端自己拆出的弧用不到，所以同一个网络可以换源汇重复使用
+    fn split_network(&self, edge_cap: usize) -> (Vec<i64>, HashMap<i64, usize>, UnitFlow) {
+        let nodes = self.nodes();
+        let index: HashMap<i64, usize> = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let mut net = UnitFlow::new(2 * nodes.len());
+        for i in 0..nodes.len() {
+            net.add(2 * i, 2 * i + 1, 1, 0);
+        }
+        for (&u, list) in &self.adj {
+            for e in list.iter().filter(|e| e.to != u) {
+                net.add(2 * index[&u] + 1, 2 * index[&e.to], edge_cap, 0);
+            }
+        }
+        (nodes, index, net)
+    }
+
+    // 中国邮递员问题：奇度节点按最短路两两配对，配对的最短路上的边各多走一遍，
+    // 所有节点度数变为偶数后用 Hierholzer 算法求欧拉回路，从编号最小的有边节点出发。
+    // 有单向边、有边的部分不连通或奇度节点超过 POSTMAN_ODD_LIMIT 个时返回 None
+    pub fn chinese_postman(&self) -> Option<PostmanTour<W>> {
+        if self.is_directed() {
+            return None;
+        }
+        // 每条无向边一条记录，自环按记录计
+        let mut edges: Vec<(i64, i64, W)> = Vec::new();
+        for (&u, list) in &self.adj {
+            for e in list.iter().filter(|e| u <= e.to) {
+                edges.push((u, e.to, e.weight));
+            }
+        }
+        if edges.is_empty() {
+            return Some(PostmanTour {
+                tour: Vec::new(),
+                cost: W::zero(),
+                repeated: Vec::new(),
+            });
+        }
+        if self
+            .connected_components()
+            .iter()
+            .filter(|c| c.iter().any(|&u| self.degree(u) > 0))
+            .count()
+            > 1
+        {
+            return None;
+        }
+
+        let odd: Vec<i64> = self
+            .nodes()
+            .into_iter()
+            .filter(|&u| self.out_edges(u).filter(|e| e.to != u).count() % 2 == 1)
+            .collect();
+        if odd.len() > POSTMAN_ODD_LIMIT {
+            return None;
+        }
+        let paths: Vec<DijkstraResult<W>> = odd.iter().map(|&u| self.dijkstra(u)).collect();
+        let dist =
+            |i: usize, j: usize| paths[i].dist.get(&odd[j]).copied().unwrap_or(W::infinity());
+
+        // best[mask]：把 mask 中的奇度节点两两配对的最小费用，pick[mask] 为最低位节点的配对对象
+        let full = (1usize << odd.len()) - 1;
+        let mut best: Vec<Option<W>> = vec![None; full + 1];
+        let mut pick = vec![0; full + 1];
+        best[0] = Some(W::zero());
+        for mask in 1..=full {
+            if mask.count_ones() % 2 == 1 {
+                continue;
+            }
+            let i = mask.trailing_zeros() as usize;
+            for j in (i + 1..odd.len()).filter(|&j| mask & (1 << j) != 0) {
+                let Some(rest) = best[mask & !(1 << i) & !(1 << j)] else {
+                    continue;
+                };
+                let cost = rest.add_or_infinity(dist(i, j));
+                if best[mask].is_none_or(|b| cost.less_than(b)) {
+                    best[mask] = Some(cost);
+                    pick[mask] = j;
+                }
+            }
+        }
+
+        let mut repeated = Vec::new();
+        let mut mask = full;
+        while mask != 0 {
+            let (i, j) = (mask.trailing_zeros() as usize, pick[mask]);
+            let path = path_from_parents(&paths[i].parent, odd[i], odd[j])?;
+            for w in path.windows(2) {
+                let d = &paths[i].dist;
+                repeated.push((w[0], w[1], d[&w[1]] - d[&w[0]]));
+            }
+            mask &= !(1 << i) & !(1 << j);
+        }
+        edges.extend(&repeated);
+
+        // 邻接表按编号升序，第一条边的起点就是编号最小的有边节点
+        let start = edges[0].0;
+        let cost = edges
+            .iter()
+            .fold(W::zero(), |acc, &(_, _, w)| acc.add_or_infinity(w));
+        Some(PostmanTour {
+            tour: euler_circuit(&edges, start),
+            cost,
+            repeated,
+        })
+    }
+
+    // Kosaraju 求强连通分量。每个分量内按 ID 排序，分量之间按最小 ID 排序
+    pub fn strongly_connected_components(&self) -> Vec<Vec<i64>> {
+        let mut components = self.scc_topological();
+        components.sort();
+        components
+    }
+
+    // 与 strongly_connected_components 相同，但分量按缩点后的拓扑序排列：
+    // 若有边从分量 A 指向分量 B，A 排在 B 前面。先按完成时间排序，再在反向图上按逆序 DFS，
+    // 找到分量的顺序即为拓扑序
+    pub fn scc_topological(&self) -> Vec<Vec<i64>> {
+        let mut finished: Vec<i64> = Vec::new();
+        let mut seen: HashSet<i64> = HashSet::new();
+        for &s in self.adj.keys() {
+            if !seen.insert(s) {
+                continue;
+            }
+            // (节点, 下一个要检查的出边下标)
+            let mut stack: Vec<(i64, usize)> = vec![(s, 0)];
+            while let Some(&mut (u, ref mut i)) = stack.last_mut() {
+                match self.adj.get(&u).and_then(|list| list.get(*i)) {
+                    Some(e) => {
+                        *i += 1;
+                        if seen.insert(e.to) {
+                            stack.push((e.to, 0));
+                        }
+                    }
+                    None => {
+                        finished.push(u);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        let mut reverse: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (&u, list) in &self.adj {
+            for e in list {
+                reverse.entry(e.to).or_default().push(u);
+            }
+        }
+
+        let mut assigned: HashSet<i64> = HashSet::new();
+        let mut components: Vec<Vec<i64>> = Vec::new();
+        for &s in finished.iter().rev() {
+            if !assigned.insert(s) {
+                continue;
+            }
+            let mut component = vec![s];
+            let mut stack = vec![s];
+            while let Some(u) = stack.pop() {
+                for &v in reverse.get(&u).into_iter().flatten() {
+                    if assigned.insert(v) {
+                        component.push(v);
+                        stack.push(v);
+                    }
+                }
             }
+            component.sort();
+            components.push(component);
         }
-        set.into_iter().collect()
+        components
+    }
+
+    // 每条边一项 (u, v, 权重, 方向性)：无向边只列一次，单向边按 u -> v 列出，
+    // 互为反向的两条单向边各列一次。平行边只列第一条
+    pub fn edges(&self) -> Vec<(i64, i64, W, EdgeType)> {
+        self.edges_with(EdgeAttr::Weight)
     }
 
-    pub fn edges(&self) -> Vec<(i64, i64, i64)> {
+    // 与 edges() 相同，但返回指定的边属性
+    pub fn edges_with(&self, attr: EdgeAttr) -> Vec<(i64, i64, W, EdgeType)> {
         let mut result = Vec::new();
-        let mut seen = HashSet::new();
+        let mut seen = BTreeSet::new();
 
         for (&u, v_list) in &self.adj {
-            for &(v, w) in v_list {
-                let key = if u <= v { (u, v) } else { (v, u) };
+            for e in v_list {
+                let v = e.to;
+                let key = match e.edge_type {
+                    EdgeType::Both => (u.min(v), u.max(v), EdgeType::Both),
+                    EdgeType::Single => (u, v, EdgeType::Single),
+                };
                 if seen.insert(key) {
-                    result.push((u, v, w));
+                    result.push((u, v, e.get(attr), e.edge_type));
                 }
             }
         }
 
         result
     }
+
+    pub fn edge(&self, u: i64, v: i64) -> Option<&Edge<W>> {
+        self.adj.get(&u)?.iter().find(|e| e.to == v)
+    }
+
+    // u 的出边
+    pub fn out_edges(&self, u: i64) -> impl Iterator<Item = &Edge<W>> {
+        self.adj.get(&u).into_iter().flatten()
+    }
+
+    pub fn neighbors(&self, u: i64) -> impl Iterator<Item = i64> + '_ {
+        self.out_edges(u).map(|e| e.to)
+    }
+
+    // 出度，无向边在两端各计一次
+    pub fn degree(&self, u: i64) -> usize {
+        self.adj.get(&u).map_or(0, |l| l.len())
+    }
+
+    // 所有节点都在邻接表里有一项（add_edge 会为终点建立空表）
+    pub fn contains_node(&self, u: i64) -> bool {
+        self.adj.contains_key(&u)
+    }
+
+    pub fn contains_edge(&self, u: i64, v: i64) -> bool {
+        self.edge(u, v).is_some()
+    }
+
+    pub fn edge_weight(&self, u: i64, v: i64) -> Option<W> {
+        self.edge(u, v).map(|e| e.weight)
+    }
+
+    // u、v 之间所有方向的边记录，无向边在邻接表中存了两份
+    fn edges_between_mut(&mut self, u: i64, v: i64) -> impl Iterator<Item = &mut Edge<W>> {
+        self.adj.iter_mut().flat_map(move |(&a, list)| {
+            list.iter_mut()
+                .filter(move |e| (a == u && e.to == v) || (a == v && e.to == u))
+        })
+    }
+
+    // 按端点修改边：对 u、v 之间所有方向的边记录调用 f，无向边的两份记录一起改，
+    // 返回是否存在这样的边
+    pub fn edit_edge(&mut self, u: i64, v: i64, mut f: impl FnMut(&mut Edge<W>)) -> bool {
+        let mut found = false;
+        for e in self.edges_between_mut(u, v) {
+            f(e);
+            found = true;
+        }
+        found
+    }
+
+    // 修改 u、v 之间所有方向的边权，返回是否存在这样的边
+    pub fn set_edge_weight(&mut self, u: i64, v: i64, weight: W) -> bool {
+        self.edit_edge(u, v, |e| e.weight = weight)
+    }
+
+    pub fn set_capacity(&mut self, u: i64, v: i64, capacity: W) {
+        self.edit_edge(u, v, |e| e.capacity = capacity);
+    }
+
+    pub fn set_edge_label(&mut self, u: i64, v: i64, label: &str) {
+        self.edit_edge(u, v, |e| e.label = Some(label.to_string()));
+    }
 }
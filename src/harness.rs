@@ -0,0 +1,200 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::graph::{EdgeType, Graph, SeededRng};
+
+// 与外部"学生求解器"对拍。求解器从 stdin 读入:
+//   <算法> <节点数> <边数> <起点>
+//   u v w            （每行一条无向边，节点编号 1..=n）
+// 并向 stdout 输出:
+//   dijkstra: 每个节点一行 "<节点> <距离>"，按节点编号升序，不可达写 INF
+//   bfs:      一行访问顺序，以空格分隔，邻居按输入中边出现的顺序遍历
+//   prim:     一行最小生成树总长度（只统计起点所在连通分量）
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckAlgorithm {
+    Dijkstra,
+    Bfs,
+    Prim,
+}
+
+impl CheckAlgorithm {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "dijkstra" => Some(CheckAlgorithm::Dijkstra),
+            "bfs" => Some(CheckAlgorithm::Bfs),
+            "prim" => Some(CheckAlgorithm::Prim),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CheckAlgorithm::Dijkstra => "dijkstra",
+            CheckAlgorithm::Bfs => "bfs",
+            CheckAlgorithm::Prim => "prim",
+        }
+    }
+}
+
+// 一个测试用例：节点 1..=n 与按顺序给出的无向边
+#[derive(Clone, Debug)]
+pub struct Case {
+    pub n: i64,
+    pub edges: Vec<(i64, i64, i64)>,
+    pub source: i64,
+}
+
+impl Case {
+    // 用固定种子生成，相同种子得到相同的图，便于复现
+    pub fn random(seed: u64, n: i64, m: usize) -> Self {
+        let mut rng = SeededRng::new(seed);
+        let mut next = |bound: i64| rng.below(bound as u64) as i64;
+
+        let edges = (0..m)
+            .map(|_| (next(n) + 1, next(n) + 1, next(20) + 1))
+            .filter(|(u, v, _)| u != v)
+            .collect();
+        Case {
+            n,
+            edges,
+            source: 1,
+        }
+    }
+
+    pub fn graph(&self) -> Graph {
+        let mut g = Graph::new();
+        for u in 1..=self.n {
+            g.add_node(u);
+        }
+        for &(u, v, w) in &self.edges {
+            g.add_edge(u, v, w, EdgeType::Both);
+        }
+        g
+    }
+
+    pub fn input(&self, algorithm: CheckAlgorithm) -> String {
+        let mut s = format!(
+            "{} {} {} {}\n",
+            algorithm.name(),
+            self.n,
+            self.edges.len(),
+            self.source
+        );
+        for (u, v, w) in &self.edges {
+            s.push_str(&format!("{} {} {}\n", u, v, w));
+        }
+        s
+    }
+
+    // 本库算法给出的标准答案
+    pub fn expected(&self, algorithm: CheckAlgorithm) -> String {
+        let g = self.graph();
+        match algorithm {
+            CheckAlgorithm::Dijkstra => {
+                let dist = g.dijkstra(self.source).dist;
+                (1..=self.n)
+                    .map(|u| match dist.get(&u) {
+                        Some(d) => format!("{} {}\n", u, d),
+                        None => format!("{} INF\n", u),
+                    })
+                    .collect()
+            }
+            CheckAlgorithm::Bfs => {
+                let (order, _) = g.bfs(self.source);
+                let order: Vec<String> = order.iter().map(|u| u.to_string()).collect();
+                format!("{}\n", order.join(" "))
+            }
+            CheckAlgorithm::Prim => format!("{}\n", g.prim(self.source).total_weight),
+        }
+    }
+}
+
+// 逐行比较（忽略行尾空白和末尾空行），返回第一处不同
+pub fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let clean = |s: &str| -> Vec<String> {
+        let mut lines: Vec<String> = s.lines().map(|l| l.trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    let (expected, actual) = (clean(expected), clean(actual));
+
+    for i in 0..expected.len().max(actual.len()) {
+        let e = expected.get(i).map(String::as_str);
+        let a = actual.get(i).map(String::as_str);
+        if e != a {
+            return Some(format!(
+                "第 {} 行: 期望 {:?}，实际 {:?}",
+                i + 1,
+                e.unwrap_or("<缺失>"),
+                a.unwrap_or("<缺失>")
+            ));
+        }
+    }
+    None
+}
+
+pub fn run_solver(solver: &Path, input: &str) -> io::Result<String> {
+    let mut child = Command::new(solver)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin 已设置为 piped")
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// 第一个失败的用例
+#[derive(Debug)]
+pub struct Failure {
+    pub case: usize,
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+    pub diff: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "用例 {} 失败: {}", self.case, self.diff)?;
+        writeln!(f, "--- 输入 ---\n{}", self.input.trim_end())?;
+        writeln!(f, "--- 期望 ---\n{}", self.expected.trim_end())?;
+        write!(f, "--- 实际 ---\n{}", self.actual.trim_end())
+    }
+}
+
+// 在 cases 个随机图上对拍，全部通过返回 Ok(用例数)
+pub fn check(
+    solver: &Path,
+    algorithm: CheckAlgorithm,
+    cases: usize,
+    seed: u64,
+) -> io::Result<Result<usize, Failure>> {
+    for i in 0..cases {
+        // 图的规模随用例编号增长，先用小图暴露问题
+        let n = 2 + (i as i64 % 10);
+        let case = Case::random(seed + i as u64, n, (n as usize) * 2);
+        let input = case.input(algorithm);
+        let expected = case.expected(algorithm);
+        let actual = run_solver(solver, &input)?;
+
+        if let Some(diff) = first_difference(&expected, &actual) {
+            return Ok(Err(Failure {
+                case: i + 1,
+                input,
+                expected,
+                actual,
+                diff,
+            }));
+        }
+    }
+    Ok(Ok(cases))
+}
@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+
+use crate::algo::GraphLike;
+
+// 隐式图：不预先存储节点和边，只在需要时根据节点生成邻居，
+// 例如单词接龙（改一个字母）、棋盘上的骑士走法
+pub trait ImplicitGraph {
+    type Node: Clone + Eq + Hash + Display;
+
+    fn neighbors(&self, n: &Self::Node) -> Vec<Self::Node>;
+}
+
+// 单词接龙：同长度且只差一个字母的两个词相邻
+#[derive(Clone, Debug)]
+pub struct WordLadder {
+    words: Vec<String>,
+}
+
+impl WordLadder {
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Self {
+        Self {
+            words: words.iter().map(|w| w.as_ref().to_lowercase()).collect(),
+        }
+    }
+
+    // 内置的小词典，cold 可以接到 warm
+    pub fn builtin() -> Self {
+        Self::new(&[
+            "cold", "cord", "card", "ward", "warm", "word", "worm", "wore", "core", "care", "cart",
+            "wart", "worn", "corn", "born", "barn", "bard", "bold", "bolt", "boat", "coat", "cost",
+            "most", "mold", "gold", "golf", "wolf", "wood", "good", "goad", "load", "lord", "ford",
+            "form", "farm", "harm", "hard", "herd", "here", "were",
+        ])
+    }
+}
+
+impl ImplicitGraph for WordLadder {
+    type Node = String;
+
+    fn neighbors(&self, n: &String) -> Vec<String> {
+        self.words
+            .iter()
+            .filter(|w| {
+                w.len() == n.len() && w.chars().zip(n.chars()).filter(|(a, b)| a != b).count() == 1
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+// 棋盘格子，列 0 起、行 0 起，显示为 a1、c2 这样的记法
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Square {
+    pub col: i64,
+    pub row: i64,
+}
+
+impl Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.col as u8) as char, self.row + 1)
+    }
+}
+
+// size × size 棋盘上骑士的走法
+#[derive(Copy, Clone, Debug)]
+pub struct KnightMoves {
+    pub size: i64,
+}
+
+impl ImplicitGraph for KnightMoves {
+    type Node = Square;
+
+    fn neighbors(&self, n: &Square) -> Vec<Square> {
+        const MOVES: [(i64, i64); 8] = [
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ];
+        MOVES
+            .iter()
+            .map(|&(dc, dr)| Square {
+                col: n.col + dc,
+                row: n.row + dr,
+            })
+            .filter(|s| (0..self.size).contains(&s.col) && (0..self.size).contains(&s.row))
+            .collect()
+    }
+}
+
+// 把隐式图包装成 GraphLike，以便直接运行 algo 中的 DFS/BFS/Dijkstra/Prim。
+// 节点在第一次被生成时分配 ID（起点为 1），边权都为 1
+pub struct Indexed<G: ImplicitGraph> {
+    graph: G,
+    nodes: RefCell<Vec<G::Node>>,
+    ids: RefCell<HashMap<G::Node, i64>>,
+}
+
+impl<G: ImplicitGraph> Indexed<G> {
+    pub fn new(graph: G, start: G::Node) -> Self {
+        Self {
+            graph,
+            ids: RefCell::new(HashMap::from([(start.clone(), 1)])),
+            nodes: RefCell::new(vec![start]),
+        }
+    }
+
+    pub fn id_of(&self, n: &G::Node) -> Option<i64> {
+        self.ids.borrow().get(n).copied()
+    }
+
+    pub fn node(&self, id: i64) -> Option<G::Node> {
+        self.nodes.borrow().get((id - 1) as usize).cloned()
+    }
+
+    fn intern(&self, n: G::Node) -> i64 {
+        let mut ids = self.ids.borrow_mut();
+        if let Some(&id) = ids.get(&n) {
+            return id;
+        }
+        let mut nodes = self.nodes.borrow_mut();
+        let id = nodes.len() as i64 + 1;
+        ids.insert(n.clone(), id);
+        nodes.push(n);
+        id
+    }
+}
+
+impl<G: ImplicitGraph> GraphLike for Indexed<G> {
+    type Weight = i64;
+
+    // 目前已生成的节点
+    fn nodes(&self) -> Vec<i64> {
+        (1..=self.nodes.borrow().len() as i64).collect()
+    }
+
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, i64)> + '_ {
+        let next = match self.node(u) {
+            Some(n) => self.graph.neighbors(&n),
+            None => Vec::new(),
+        };
+        next.into_iter()
+            .map(|n| (self.intern(n), 1))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+// 一次展开：取出队首节点 u，生成它的全部邻居，其中 discovered 是第一次见到的（附带显示名）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expansion {
+    pub u: i64,
+    pub neighbors: Vec<i64>,
+    pub discovered: Vec<(i64, String)>,
+}
+
+// 供界面逐步驱动的惰性搜索，节点换成按发现顺序分配的 ID（起点为 1），隐藏具体的节点类型
+pub trait Explore {
+    // 展开下一个节点；找到目标或队列为空时返回 None
+    fn step(&mut self) -> Option<Expansion>;
+    // 目标已被发现时返回它的 ID
+    fn goal(&self) -> Option<i64>;
+    // 从起点沿 BFS 树到 id 的路径
+    fn path_to(&self, id: i64) -> Option<Vec<i64>>;
+}
+
+// 惰性 BFS：只有节点出队时才调用 neighbors，发现目标后立即停止，
+// 因此只会生成整张图中离起点不超过目标距离的那一部分
+pub struct LazyBfs<G: ImplicitGraph> {
+    graph: G,
+    goal: G::Node,
+    ids: HashMap<G::Node, i64>,
+    nodes: Vec<G::Node>,
+    parent: HashMap<i64, i64>,
+    queue: VecDeque<i64>,
+    found: bool,
+}
+
+impl<G: ImplicitGraph> LazyBfs<G> {
+    pub fn new(graph: G, start: G::Node, goal: G::Node) -> Self {
+        let found = start == goal;
+        Self {
+            graph,
+            goal,
+            ids: HashMap::from([(start.clone(), 1)]),
+            nodes: vec![start],
+            parent: HashMap::new(),
+            queue: VecDeque::from([1]),
+            found,
+        }
+    }
+
+    pub fn node(&self, id: i64) -> Option<&G::Node> {
+        self.nodes.get((id - 1) as usize)
+    }
+
+    // 目前生成过的节点数
+    pub fn generated(&self) -> usize {
+        self.nodes.len()
+    }
+
+    // 一直展开到结束，返回从起点到目标的节点序列
+    pub fn run(&mut self) -> Option<Vec<G::Node>> {
+        while self.step().is_some() {}
+        let path = self.path_to(self.goal()?)?;
+        Some(
+            path.iter()
+                .filter_map(|&id| self.node(id).cloned())
+                .collect(),
+        )
+    }
+}
+
+impl<G: ImplicitGraph> Explore for LazyBfs<G> {
+    fn step(&mut self) -> Option<Expansion> {
+        if self.found {
+            return None;
+        }
+        let u = self.queue.pop_front()?;
+        let mut expansion = Expansion {
+            u,
+            neighbors: Vec::new(),
+            discovered: Vec::new(),
+        };
+        for n in self.graph.neighbors(&self.nodes[(u - 1) as usize]) {
+            let id = match self.ids.get(&n) {
+                Some(&id) => id,
+                None => {
+                    let id = self.nodes.len() as i64 + 1;
+                    self.found |= n == self.goal;
+                    expansion.discovered.push((id, n.to_string()));
+                    self.ids.insert(n.clone(), id);
+                    self.nodes.push(n);
+                    self.parent.insert(id, u);
+                    self.queue.push_back(id);
+                    id
+                }
+            };
+            expansion.neighbors.push(id);
+        }
+        Some(expansion)
+    }
+
+    fn goal(&self) -> Option<i64> {
+        self.ids.get(&self.goal).copied()
+    }
+
+    fn path_to(&self, id: i64) -> Option<Vec<i64>> {
+        crate::graph::path_from_parents(&self.parent, 1, id)
+    }
+}
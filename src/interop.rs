@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use petgraph::visit::EdgeRef;
+
+use crate::graph::{Graph, GraphBuilder};
+use crate::weight::Weight;
+
+// 转为 petgraph 有向图：节点权为原节点 ID，无向边对应两条方向相反的边
+impl<W: Weight> From<&Graph<W>> for petgraph::Graph<i64, W> {
+    fn from(g: &Graph<W>) -> Self {
+        let mut pg = petgraph::Graph::new();
+        let mut index = HashMap::new();
+        for u in g.nodes() {
+            index.insert(u, pg.add_node(u));
+        }
+        for u in g.nodes() {
+            for e in g.out_edges(u) {
+                pg.add_edge(index[&u], index[&e.to], e.weight);
+            }
+        }
+        pg
+    }
+}
+
+// 从 petgraph 转回：节点权作为节点 ID，无向图的边按双向边加入；
+// 有向图中互为反向且权重相同的两条边还原为一条无向边
+impl<W: Weight, Ty: petgraph::EdgeType> From<&petgraph::Graph<i64, W, Ty>> for Graph<W> {
+    fn from(pg: &petgraph::Graph<i64, W, Ty>) -> Self {
+        let mut builder = pg
+            .node_weights()
+            .fold(GraphBuilder::default(), |b, &u| b.node(u));
+        for e in pg.edge_references() {
+            let (u, v) = (pg[e.source()], pg[e.target()]);
+            builder = if Ty::is_directed() {
+                builder.directed_edge(u, v, *e.weight())
+            } else {
+                builder.edge(u, v, *e.weight())
+            };
+        }
+        let mut g = builder.build();
+        if Ty::is_directed() {
+            g.pair_opposite_edges();
+        }
+        g
+    }
+}
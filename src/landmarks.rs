@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::algo::{self, GraphLike};
+use crate::weight::Weight;
+
+// 菜单中的地标 A* 选取的地标数
+pub const DEFAULT_LANDMARKS: usize = 4;
+
+// 把每条边反过来的图，用来求各节点到地标的距离
+struct Reversed<W> {
+    nodes: Vec<i64>,
+    edges: HashMap<i64, Vec<(i64, W)>>,
+}
+
+impl<W: Weight> Reversed<W> {
+    fn of<G: GraphLike<Weight = W>>(g: &G) -> Self {
+        let nodes = g.nodes();
+        let mut edges: HashMap<i64, Vec<(i64, W)>> = HashMap::new();
+        for &u in &nodes {
+            for (v, w) in g.neighbors(u) {
+                edges.entry(v).or_default().push((u, w));
+            }
+        }
+        Self { nodes, edges }
+    }
+}
+
+impl<W: Weight> GraphLike for Reversed<W> {
+    type Weight = W;
+
+    fn nodes(&self) -> Vec<i64> {
+        self.nodes.clone()
+    }
+
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, W)> + '_ {
+        self.edges.get(&u).into_iter().flatten().copied()
+    }
+}
+
+// ALT 预处理：选出若干地标，记下每个地标到各节点、各节点到地标的最短距离。
+// 查询时由三角不等式得到任意节点到终点距离的下界，作为 A* 的启发函数。要求边权非负
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Landmarks<W = i64> {
+    // 地标节点，按选取顺序
+    pub nodes: Vec<i64>,
+    // from[i][v] 为第 i 个地标到 v 的距离，to[i][v] 为 v 到第 i 个地标的距离，不可达的节点不出现
+    from: Vec<HashMap<i64, W>>,
+    to: Vec<HashMap<i64, W>>,
+}
+
+impl<W: Weight> Landmarks<W> {
+    // 最远点选取：第一个地标取离编号最小的节点最远的节点，之后每次取离已选地标最远的节点
+    // （到各地标距离的最小值最大，从地标出发到不了的节点算作无穷远，优先选中），距离相同取编号小的。
+    // 每个地标各做一次正向和反向 Dijkstra
+    pub fn select<G: GraphLike<Weight = W>>(g: &G, k: usize) -> Self {
+        let nodes = g.nodes();
+        let mut landmarks = Self::default();
+        let Some(&seed) = nodes.iter().min() else {
+            return landmarks;
+        };
+        let reversed = Reversed::of(g);
+        let mut nearest = algo::dijkstra(g, seed).dist;
+        let far = |nearest: &HashMap<i64, W>, u: i64| {
+            nearest.get(&u).copied().unwrap_or_else(W::infinity)
+        };
+
+        while landmarks.nodes.len() < k.min(nodes.len()) {
+            let Some(l) = nodes
+                .iter()
+                .copied()
+                .filter(|u| !landmarks.nodes.contains(u))
+                .max_by(|&a, &b| {
+                    far(&nearest, a)
+                        .total_cmp(&far(&nearest, b))
+                        .then(b.cmp(&a))
+                })
+            else {
+                break;
+            };
+            let from = algo::dijkstra(g, l).dist;
+            if landmarks.nodes.is_empty() {
+                nearest = from.clone();
+            } else {
+                for (&u, &d) in &from {
+                    if d.less_than(far(&nearest, u)) {
+                        nearest.insert(u, d);
+                    }
+                }
+            }
+            landmarks.nodes.push(l);
+            landmarks.from.push(from);
+            landmarks.to.push(algo::dijkstra(&reversed, l).dist);
+        }
+        landmarks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    // v 到 t 的距离下界：对每个地标 L 有 d(v,t) >= d(L,t) - d(L,v) 和 d(v,t) >= d(v,L) - d(t,L)，
+    // 取其中最大的一个，不小于 0。缺少距离的项跳过
+    pub fn lower_bound(&self, v: i64, t: i64) -> W {
+        let mut best = W::zero();
+        for (from, to) in self.from.iter().zip(&self.to) {
+            let bounds = [from.get(&t).zip(from.get(&v)), to.get(&v).zip(to.get(&t))];
+            for (&far, &near) in bounds.into_iter().flatten() {
+                if best.less_than(far - near) {
+                    best = far - near;
+                }
+            }
+        }
+        best
+    }
+
+    // 以 lower_bound 为启发函数的 A*
+    pub fn search<G: GraphLike<Weight = W>>(&self, g: &G, s: i64, t: i64) -> Search<W> {
+        astar(g, s, t, |v| self.lower_bound(v, t))
+    }
+}
+
+// A* 的开放表中的项：f 小的先出；f 相同时 g 大的先出，即优先沿已经走得远的方向继续，
+// 启发函数精确时只展开路径上的节点
+struct Open<W> {
+    f: W,
+    g: W,
+    node: i64,
+}
+
+impl<W: Weight> Ord for Open<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f.total_cmp(&self.f).then(self.g.total_cmp(&other.g))
+    }
+}
+
+impl<W: Weight> PartialOrd for Open<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Weight> PartialEq for Open<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for Open<W> {}
+
+// 一次点到点查询的结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct Search<W = i64> {
+    // 最短路径长度与路径，t 不可达时为 None
+    pub route: Option<(W, Vec<i64>)>,
+    // 按出堆顺序展开（确定距离）的节点，到 t 为止
+    pub expanded: Vec<i64>,
+}
+
+// 从 s 到 t 的 A*：按 已走距离 + h(节点) 从小到大展开，t 出堆时停止。h 恒为 0 时就是到 t 为止的
+// Dijkstra。h 需要是一致的（h(u) <= w(u,v) + h(v)），否则展开过的节点不会重新打开，结果可能偏长
+pub fn astar<G: GraphLike>(
+    g: &G,
+    s: i64,
+    t: i64,
+    h: impl Fn(i64) -> G::Weight,
+) -> Search<G::Weight> {
+    let mut dist: HashMap<i64, G::Weight> = HashMap::from([(s, G::Weight::zero())]);
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut closed: HashSet<i64> = HashSet::new();
+    let mut expanded = Vec::new();
+    let mut pq = BinaryHeap::from([Open {
+        f: h(s),
+        g: G::Weight::zero(),
+        node: s,
+    }]);
+
+    while let Some(Open { node: u, .. }) = pq.pop() {
+        if !closed.insert(u) {
+            continue;
+        }
+        expanded.push(u);
+        let d = dist[&u];
+        if u == t {
+            let mut path = vec![t];
+            let mut x = t;
+            while let Some(&p) = parent.get(&x) {
+                path.push(p);
+                x = p;
+            }
+            path.reverse();
+            return Search {
+                route: Some((d, path)),
+                expanded,
+            };
+        }
+        for (v, w) in g.neighbors(u) {
+            if closed.contains(&v) {
+                continue;
+            }
+            let c = d.add_or_infinity(w);
+            if c.total_cmp(&G::Weight::infinity()) == Ordering::Less
+                && dist.get(&v).is_none_or(|&current| c.less_than(current))
+            {
+                dist.insert(v, c);
+                parent.insert(v, u);
+                pq.push(Open {
+                    f: c.add_or_infinity(h(v)),
+                    g: c,
+                    node: v,
+                });
+            }
+        }
+    }
+    Search {
+        route: None,
+        expanded,
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+// 以 root 为圆心，按 BFS 深度把节点排到同心圆上
+// tree_edges 为 BFS 树边 (父, 子)，返回相对 root 的坐标
+pub fn radial_tree(
+    root: i64,
+    tree_edges: &[(i64, i64)],
+    ring_gap: f64,
+) -> HashMap<i64, (f64, f64)> {
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    for &(p, c) in tree_edges {
+        children.entry(p).or_default().push(c);
+    }
+
+    // 每棵子树的叶子数，决定它分到的角度
+    let mut leaves: HashMap<i64, usize> = HashMap::new();
+    count_leaves(root, &children, &mut leaves);
+
+    let mut positions: HashMap<i64, (f64, f64)> = HashMap::new();
+    positions.insert(root, (0.0, 0.0));
+
+    // (节点, 深度, 起始角, 角度范围)
+    let mut stack: Vec<(i64, usize, f64, f64)> = vec![(root, 0, 0.0, 2.0 * PI)];
+    while let Some((u, depth, start, span)) = stack.pop() {
+        let Some(kids) = children.get(&u) else {
+            continue;
+        };
+        let total = leaves[&u] as f64;
+        let mut angle = start;
+        for &c in kids {
+            let share = span * leaves[&c] as f64 / total;
+            let mid = angle + share / 2.0;
+            let radius = (depth + 1) as f64 * ring_gap;
+            positions.insert(c, (radius * mid.cos(), radius * mid.sin()));
+            stack.push((c, depth + 1, angle, share));
+            angle += share;
+        }
+    }
+
+    positions
+}
+
+// BFS 树的最大深度，用于根据画布大小计算环间距
+pub fn tree_depth(root: i64, tree_edges: &[(i64, i64)]) -> usize {
+    let mut depth: HashMap<i64, usize> = HashMap::new();
+    depth.insert(root, 0);
+    let mut max_depth = 0;
+    for &(p, c) in tree_edges {
+        let d = depth.get(&p).copied().unwrap_or(0) + 1;
+        depth.insert(c, d);
+        max_depth = max_depth.max(d);
+    }
+    max_depth
+}
+
+fn count_leaves(
+    u: i64,
+    children: &HashMap<i64, Vec<i64>>,
+    leaves: &mut HashMap<i64, usize>,
+) -> usize {
+    let n = match children.get(&u) {
+        Some(kids) => kids
+            .iter()
+            .map(|&c| count_leaves(c, children, leaves))
+            .sum(),
+        None => 1,
+    };
+    leaves.insert(u, n);
+    n
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutKind {
+    Force,
+    Circular,
+    Layered,
+    Tree,
+    Spectral,
+}
+
+// 所有节点按 ID 顺序均匀排在椭圆上
+pub fn circular(nodes: &[i64], rx: f64, ry: f64) -> HashMap<i64, (f64, f64)> {
+    let mut sorted = nodes.to_vec();
+    sorted.sort();
+
+    let n = sorted.len().max(1) as f64;
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let angle = PI / 2.0 - 2.0 * PI * i as f64 / n;
+            (id, (rx * angle.cos(), ry * angle.sin()))
+        })
+        .collect()
+}
+
+// 按 BFS 深度分层，root 在最上层，从 root 不可达的节点放在最底下一层
+pub fn layered(
+    root: i64,
+    tree_edges: &[(i64, i64)],
+    nodes: &[i64],
+    width: f64,
+    height: f64,
+) -> HashMap<i64, (f64, f64)> {
+    let mut depth: HashMap<i64, usize> = HashMap::new();
+    depth.insert(root, 0);
+    for &(p, c) in tree_edges {
+        let d = depth.get(&p).copied().unwrap_or(0) + 1;
+        depth.insert(c, d);
+    }
+
+    let max_depth = depth.values().copied().max().unwrap_or(0);
+    let mut layers: Vec<Vec<i64>> = vec![Vec::new(); max_depth + 2];
+    let mut sorted = nodes.to_vec();
+    sorted.sort();
+    for id in sorted {
+        let d = depth.get(&id).copied().unwrap_or(max_depth + 1);
+        layers[d].push(id);
+    }
+    if layers[max_depth + 1].is_empty() {
+        layers.pop();
+    }
+
+    let gap_y = if layers.len() > 1 {
+        height / (layers.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    let mut positions = HashMap::new();
+    for (d, layer) in layers.iter().enumerate() {
+        let y = height / 2.0 - d as f64 * gap_y;
+        let gap_x = width / layer.len() as f64;
+        for (i, &id) in layer.iter().enumerate() {
+            let x = -width / 2.0 + gap_x * (i as f64 + 0.5);
+            positions.insert(id, (x, y));
+        }
+    }
+    positions
+}
+
+// 谱布局：以拉普拉斯矩阵 L = D - A 第二、第三小特征值对应的特征向量作为 x、y 坐标。
+// 对 M = cI - L 做幂迭代（c 大于 L 的最大特征值），并与常向量及已求出的向量正交化以跳过平凡解。
+// 边按无向、无权处理，结果缩放到 width x height 范围内
+pub fn spectral(
+    nodes: &[i64],
+    edges: &[(i64, i64)],
+    width: f64,
+    height: f64,
+) -> HashMap<i64, (f64, f64)> {
+    let mut sorted = nodes.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    let n = sorted.len();
+    let index: HashMap<i64, usize> = sorted.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(&u), index.get(&v))
+            && a != b
+        {
+            if !neighbors[a].contains(&b) {
+                neighbors[a].push(b);
+            }
+            if !neighbors[b].contains(&a) {
+                neighbors[b].push(a);
+            }
+        }
+    }
+
+    let max_degree = neighbors.iter().map(|l| l.len()).max().unwrap_or(0);
+    let c = 2.0 * max_degree as f64 + 1.0;
+    // y = (cI - L) x = c*x - deg*x + sum(neighbors)
+    let apply = |x: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                let sum: f64 = neighbors[i].iter().map(|&j| x[j]).sum();
+                c * x[i] - neighbors[i].len() as f64 * x[i] + sum
+            })
+            .collect()
+    };
+
+    let mut found: Vec<Vec<f64>> = vec![vec![1.0 / (n.max(1) as f64).sqrt(); n]];
+    for k in 0..2 {
+        // 固定的伪随机初值，保证同一张图每次得到相同布局
+        let mut x: Vec<f64> = (0..n)
+            .map(|i| (((i * 7919 + k * 104729) % 1009) as f64 / 1009.0) - 0.5)
+            .collect();
+        for _ in 0..500 {
+            orthogonalize(&mut x, &found);
+            if !normalize(&mut x) {
+                break;
+            }
+            x = apply(&x);
+        }
+        orthogonalize(&mut x, &found);
+        normalize(&mut x);
+        found.push(x);
+    }
+
+    let fit = |v: &[f64], size: f64| -> Vec<f64> {
+        let max = v.iter().fold(0.0_f64, |m, a| m.max(a.abs()));
+        v.iter()
+            .map(|a| if max > 0.0 { a / max * size / 2.0 } else { 0.0 })
+            .collect()
+    };
+    let xs = fit(&found[1], width);
+    let ys = fit(&found[2], height);
+
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(i, u)| (u, (xs[i], ys[i])))
+        .collect()
+}
+
+fn orthogonalize(x: &mut [f64], basis: &[Vec<f64>]) {
+    for b in basis {
+        let dot: f64 = x.iter().zip(b).map(|(a, b)| a * b).sum();
+        for (a, b) in x.iter_mut().zip(b) {
+            *a -= dot * b;
+        }
+    }
+}
+
+fn normalize(x: &mut [f64]) -> bool {
+    let norm = x.iter().map(|a| a * a).sum::<f64>().sqrt();
+    if norm < 1e-12 {
+        return false;
+    }
+    for a in x.iter_mut() {
+        *a /= norm;
+    }
+    true
+}
+
+// 二次贝塞尔曲线近似为折线：控制点在线段中点沿法线方向偏移 bend * 线段长度，
+// bend 为正时向 from->to 方向的左侧弯曲。返回 segments + 1 个点，首尾分别为 from、to
+pub fn quad_bezier(
+    from: (f64, f64),
+    to: (f64, f64),
+    bend: f64,
+    segments: usize,
+) -> Vec<(f64, f64)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let control = (
+        (from.0 + to.0) / 2.0 - dy * bend,
+        (from.1 + to.1) / 2.0 + dx * bend,
+    );
+    let segments = segments.max(1);
+
+    (0..=segments)
+        .map(|i| {
+            let t = i as f64 / segments as f64;
+            let s = 1.0 - t;
+            (
+                s * s * from.0 + 2.0 * s * t * control.0 + t * t * to.0,
+                s * s * from.1 + 2.0 * s * t * control.1 + t * t * to.1,
+            )
+        })
+        .collect()
+}
+
+// 两套布局坐标之间的插值过渡，避免切换布局时节点瞬移
+pub struct Tween {
+    from: HashMap<i64, (f64, f64)>,
+    to: HashMap<i64, (f64, f64)>,
+    elapsed: f64,
+    duration: f64,
+}
+
+impl Tween {
+    pub fn new(
+        from: HashMap<i64, (f64, f64)>,
+        to: HashMap<i64, (f64, f64)>,
+        duration: f64,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    pub fn step(&mut self, dt: f64) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    // 当前时刻的插值坐标，使用 smoothstep 缓动
+    pub fn positions(&self) -> HashMap<i64, (f64, f64)> {
+        let t = if self.duration > 0.0 {
+            self.elapsed / self.duration
+        } else {
+            1.0
+        };
+        let t = t * t * (3.0 - 2.0 * t);
+
+        let mut result = self.from.clone();
+        for (&id, &(tx, ty)) in &self.to {
+            let (fx, fy) = self.from.get(&id).copied().unwrap_or((tx, ty));
+            result.insert(id, (fx + (tx - fx) * t, fy + (ty - fy) * t));
+        }
+        result
+    }
+}
+
+// 力导向布局的收敛判断：连续 calm_frames 帧内节点的最大位移都低于 threshold 时认为布局已稳定，
+// 停止模拟以免节点一直微微抖动；图或锚点改变后调用 wake 恢复模拟
+#[derive(Clone, Debug)]
+pub struct Cooling {
+    threshold: f64,
+    calm_frames: usize,
+    calm: usize,
+}
+
+impl Cooling {
+    pub fn new(threshold: f64, calm_frames: usize) -> Self {
+        Self {
+            threshold,
+            calm_frames,
+            calm: 0,
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.calm >= self.calm_frames
+    }
+
+    // 记录一帧中节点的最大位移，返回是否已稳定
+    pub fn observe(&mut self, max_move: f64) -> bool {
+        if max_move < self.threshold {
+            self.calm = (self.calm + 1).min(self.calm_frames);
+        } else {
+            self.calm = 0;
+        }
+        self.is_settled()
+    }
+
+    pub fn wake(&mut self) {
+        self.calm = 0;
+    }
+}
+
+// 碰撞处理：距离小于 min_dist 的两个节点沿连线各推开重叠量的一半，
+// fixed 的节点不动、由另一个节点承担全部位移。每帧做一遍，多帧后收敛到互不重叠
+pub fn separate(positions: &mut [(f64, f64)], fixed: &[bool], min_dist: f64) {
+    for i in 0..positions.len() {
+        for j in i + 1..positions.len() {
+            let (dx, dy) = (
+                positions[j].0 - positions[i].0,
+                positions[j].1 - positions[i].1,
+            );
+            let d = (dx * dx + dy * dy).sqrt();
+            if d >= min_dist || (fixed[i] && fixed[j]) {
+                continue;
+            }
+            // 完全重合时没有方向，按下标错开
+            let (ux, uy) = if d > 1e-9 {
+                (dx / d, dy / d)
+            } else {
+                let angle = (i * 7 + j) as f64;
+                (angle.cos(), angle.sin())
+            };
+            let overlap = min_dist - d;
+            let (si, sj) = match (fixed[i], fixed[j]) {
+                (true, _) => (0.0, overlap),
+                (_, true) => (overlap, 0.0),
+                _ => (overlap / 2.0, overlap / 2.0),
+            };
+            positions[i].0 -= ux * si;
+            positions[i].1 -= uy * si;
+            positions[j].0 += ux * sj;
+            positions[j].1 += uy * sj;
+        }
+    }
+}
+
+// 弹簧静止长度：把每条边 (i, j, rest) 两端的距离朝 rest 拉近 stiffness 比例（0~1），
+// 与 separate 一样直接修正坐标。force_graph 的弹簧静止长度为 0，叠加这一步后
+// 边长大致与 rest 成正比
+pub fn spring_lengths(
+    positions: &mut [(f64, f64)],
+    fixed: &[bool],
+    springs: &[(usize, usize, f64)],
+    stiffness: f64,
+) {
+    for &(i, j, rest) in springs {
+        if i == j || (fixed[i] && fixed[j]) {
+            continue;
+        }
+        let (dx, dy) = (
+            positions[j].0 - positions[i].0,
+            positions[j].1 - positions[i].1,
+        );
+        let d = (dx * dx + dy * dy).sqrt();
+        if d < 1e-9 {
+            continue;
+        }
+        let (ux, uy) = (dx / d, dy / d);
+        let delta = (d - rest) * stiffness;
+        let (si, sj) = match (fixed[i], fixed[j]) {
+            (true, _) => (0.0, delta),
+            (_, true) => (delta, 0.0),
+            _ => (delta / 2.0, delta / 2.0),
+        };
+        positions[i].0 += ux * si;
+        positions[i].1 += uy * si;
+        positions[j].0 -= ux * sj;
+        positions[j].1 -= uy * sj;
+    }
+}
+
+// 点 p 到线段 a-b 的距离
+pub fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (cx, cy) = (a.0 + dx * t, a.1 + dy * t);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
+
+// 鼠标点选边：返回离 p 最近、且距离不超过 max_dist 的折线下标。
+// 每条边给出画布上实际绘制的折线（弧线即贝塞尔折线）
+pub fn nearest_polyline(p: (f64, f64), lines: &[Vec<(f64, f64)>], max_dist: f64) -> Option<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, points)| {
+            let d = points
+                .windows(2)
+                .map(|w| segment_distance(p, w[0], w[1]))
+                .fold(f64::INFINITY, f64::min);
+            (d <= max_dist).then_some((i, d))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
@@ -1,2 +1,38 @@
+pub mod adjacency;
+pub mod algo;
+pub mod allpairs;
+pub mod animation;
+pub mod avoid;
+pub mod bell;
+pub mod command;
+pub mod config;
+pub mod crash;
+pub mod exact;
+pub mod export;
+pub mod flow;
+pub mod fuzz;
 pub mod graph;
+pub mod harness;
+pub mod implicit;
+#[cfg(feature = "petgraph")]
+pub mod interop;
+pub mod landmarks;
+pub mod layout;
 pub mod menu;
+pub mod metrics;
+pub mod monochrome;
+pub mod panel;
+pub mod quiz;
+pub mod registry;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod scenario;
+pub mod summary;
+pub mod temporal;
+pub mod text;
+pub mod trace;
+pub mod turns;
+pub mod tutorial;
+pub mod twosat;
+pub mod view;
+pub mod weight;
@@ -1,31 +1,525 @@
 use crossterm::event;
-use crossterm::event::{Event, KeyCode, KeyEventKind};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    MouseButton, MouseEventKind,
+};
 use force_graph::{DefaultNodeIdx, EdgeData, ForceGraph, NodeData, SimulationParameters};
-use graph_algorithm_tui::graph::EdgeType::Both;
-use graph_algorithm_tui::graph::Graph;
-use graph_algorithm_tui::menu::{Menu, MenuItem, MenuSignal, MenuState};
-use rand::Rng;
+use graph_algorithm_tui::algo;
+use graph_algorithm_tui::allpairs;
+use graph_algorithm_tui::animation::{AnimationPlayer, AnimationStep, Pacing, Replay};
+use graph_algorithm_tui::avoid::Forbidden;
+use graph_algorithm_tui::bell::{Bell, Cue};
+use graph_algorithm_tui::command::{self, Command, History, Setting};
+use graph_algorithm_tui::config::Config;
+use graph_algorithm_tui::crash;
+use graph_algorithm_tui::exact::{self, Budget, Exact};
+use graph_algorithm_tui::export;
+use graph_algorithm_tui::fuzz;
+use graph_algorithm_tui::graph::{
+    self, AddEdgePolicy, DijkstraResult, DistanceOrder, EdgeAttr, EdgeDirection, EdgeType, Graph,
+    KargerTrial, MstResult, NegativeCycle,
+};
+use graph_algorithm_tui::harness::{self, CheckAlgorithm};
+use graph_algorithm_tui::implicit::{Explore, KnightMoves, LazyBfs, Square, WordLadder};
+use graph_algorithm_tui::landmarks::{self, Landmarks};
+use graph_algorithm_tui::layout::{self, Cooling, LayoutKind, Tween};
+use graph_algorithm_tui::menu::{Menu, MenuSignal, MenuState};
+use graph_algorithm_tui::metrics::{self, MetricsHistory};
+use graph_algorithm_tui::monochrome;
+use graph_algorithm_tui::panel::Panel;
+use graph_algorithm_tui::quiz::{Quiz, QuizAnswer, QuizKind};
+use graph_algorithm_tui::registry::{Algorithm, AlgorithmRegistry};
+#[cfg(feature = "remote")]
+use graph_algorithm_tui::remote::{self, Response};
+use graph_algorithm_tui::scenario::{self, Comparison, Side};
+use graph_algorithm_tui::summary::{GraphFacts, GraphSummary};
+use graph_algorithm_tui::temporal::{EdgeEvent, Timeline};
+use graph_algorithm_tui::text;
+use graph_algorithm_tui::trace::{
+    self as trace, AlgorithmTrace, LogEvents, StateSnapshot, StepEvent, StepKind, TraceAlgorithm,
+};
+use graph_algorithm_tui::turns::{self, TurnRestrictions, TurnRule};
+use graph_algorithm_tui::tutorial::{StepMark, Tutorial};
+use graph_algorithm_tui::twosat::{TwoSat, TwoSatResult};
+use graph_algorithm_tui::view::{draw_arrowhead, draw_cross, draw_dashed, print_centered};
+use graph_algorithm_tui::weight::Weight;
+use rand::RngExt;
+use ratatui::layout::Rect;
 use ratatui::layout::{Constraint, Layout};
 use ratatui::prelude::{Color, Direction};
 use ratatui::style::Stylize;
-use ratatui::widgets::canvas::{Canvas, Circle, Context, Line as CanvaLine};
-use ratatui::widgets::{Block, Borders, Padding, Paragraph};
+use ratatui::widgets::canvas::{Canvas, Circle, Context, Line as CanvaLine, Points, Rectangle};
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, Gauge, LineGauge, Padding, Paragraph, Row, Sparkline, Table,
+    TableState, Tabs,
+};
 use ratatui::{DefaultTerminal, Frame};
-use std::collections::{HashMap, HashSet};
-use std::io;
-use std::time::Duration;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "tracing")]
+use tracing::{debug, error, info};
+
+// 未启用 tracing 特性时日志宏不输出，只检查参数
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        let _ = format_args!($($arg)*);
+    };
+}
+#[cfg(not(feature = "tracing"))]
+use debug as error;
+#[cfg(not(feature = "tracing"))]
+use debug as info;
+
+// 画布上一条边实际绘制的折线
+type Polyline = Vec<(f64, f64)>;
+
+// --log 写入的日志文件，位于当前目录
+const LOG_FILE: &str = "graph_algorithm_tui.log";
+
+const GROUP_COLORS: [Color; 6] = [
+    Color::Magenta,
+    Color::Green,
+    Color::Cyan,
+    Color::Red,
+    Color::LightMagenta,
+    Color::LightGreen,
+];
 
 fn main() -> io::Result<()> {
+    let mut app = App::new();
+    let args: Vec<String> = std::env::args().collect();
+
+    // --check <求解器>：不启动界面，与外部求解器对拍
+    if let Some(solver) = arg_value(&args, "--check") {
+        return run_check(&args, solver);
+    }
+
+    // --fuzz：不启动界面，在随机图上检查算法不变式
+    if args.iter().any(|a| a == "--fuzz") {
+        return run_fuzz(&args);
+    }
+
+    // --duplicate-edges <parallel|min|max|sum|last>：导入时重复出现的边如何合并
+    if let Some(policy) = arg_value(&args, "--duplicate-edges") {
+        match AddEdgePolicy::parse(policy) {
+            Some(policy) => app.edge_policy = policy,
+            None => {
+                eprintln!(
+                    "未知的重复边策略: {}，可选 parallel/min/max/sum/last",
+                    policy
+                );
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // --log <error|warn|info|debug|trace>：把计时、按键和错误写入日志文件，不显示在界面上
+    if let Some(level) = arg_value(&args, "--log") {
+        init_logging(level)?;
+    }
+
+    // --accessible：启动时开启无障碍模式
+    app.accessible = args.iter().any(|a| a == "--accessible");
+
+    // --no-color：不支持颜色的终端（如简陋的 SSH 会话），NO_COLOR 或 TERM=dumb 时自动开启
+    app.monochrome = args.iter().any(|a| a == "--no-color")
+        || monochrome::requested(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        );
+
+    // --exact-budget <秒>：精确求解（旅行商、最少着色、最小顶点覆盖）的时间预算
+    app.exact_budget = Duration::from_secs(number_arg(&args, "--exact-budget", 10).max(1));
+
+    // --karger-trials <次数>：Karger 最小割演示的试验次数
+    app.karger_trials = number_arg(&args, "--karger-trials", 20).max(1) as usize;
+
+    // --edge-length-scale <系数>：边长与边权成正比，系数越大边越长
+    if let Some(scale) = arg_value(&args, "--edge-length-scale") {
+        match scale.parse::<f64>() {
+            Ok(scale) if scale > 0.0 => app.edge_length_scale = Some(scale),
+            _ => {
+                eprintln!("--edge-length-scale 需要一个正数");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // --timeline <文件>：加载带时间戳的边事件，供"时序回放"使用
+    if let Some(path) = arg_value(&args, "--timeline") {
+        match Timeline::parse(&std::fs::read_to_string(path)?) {
+            Ok(timeline) => app.timeline = Some(timeline),
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // --two-sat <文件>：读入 2-SAT 子句，启动后直接显示蕴含图
+    if let Some(path) = arg_value(&args, "--two-sat") {
+        match TwoSat::parse(&std::fs::read_to_string(path)?) {
+            Ok(problem) => app.two_sat = Some(problem),
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    // --exec <文件>：进入界面前依次执行文件中的命令栏命令（建图、修改设置、运行算法），
+    // 用于课堂上复现同样的场景或自动截图；有无法解析的行时报错退出
+    if let Some(path) = arg_value(&args, "--exec") {
+        let text = std::fs::read_to_string(path)?;
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(no, line)| (no + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        for &(no, line) in &lines {
+            if let Err(e) = Command::<f64>::parse(line) {
+                eprintln!("{} 第 {} 行: {}", path, no, e);
+                std::process::exit(2);
+            }
+        }
+        app.startup_commands = lines.into_iter().map(|(_, l)| l.to_string()).collect();
+    }
+
+    // --watch <文件>：监视 DOT 或命令脚本文件，保存后画布随之更新
+    if let Some(path) = arg_value(&args, "--watch") {
+        if let Err(e) = read_graph_file(Path::new(path)) {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(2);
+        }
+        app.watch = Some((PathBuf::from(path), None));
+    }
+
+    // --replay <菜单项,菜单项,…>：启动后循环演示这些菜单项，用于无人值守的展示屏
+    if let Some(playlist) = arg_value(&args, "--replay") {
+        app.execute_command(&format!("playlist {}", playlist));
+    }
+
+    // --remote <端口>：在 127.0.0.1 上接受每行一个 JSON 的控制请求，端口为 0 时由系统分配
+    #[cfg(feature = "remote")]
+    if arg_value(&args, "--remote").is_some() {
+        let port = number_arg(&args, "--remote", 0);
+        let Ok(port) = u16::try_from(port) else {
+            eprintln!("--remote 端口超出范围: {}", port);
+            std::process::exit(2);
+        };
+        let server = remote::Server::start(port)?;
+        app.visit_log
+            .push(format!("远程控制监听于 {}", server.addr()));
+        app.remote = Some(server);
+    }
+
+    // 面板比例保存在配置文件中，启动时读取、正常退出时写回
+    let config = Config::load();
+    app.horizontal_split_ratio = config.canvas_ratio;
+    app.panel_split_ratio = config.panel_ratio;
+    app.bell = Bell::new(config.bell, config.bell_pattern);
+    app.command_history = History::load();
+
     let mut terminal = ratatui::init();
+    crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+    install_panic_hook();
 
-    let app_result = App::new().run(&mut terminal);
+    let app_result = panic::catch_unwind(AssertUnwindSafe(|| app.run(&mut terminal)));
 
+    let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
     ratatui::restore();
-    app_result
+    match app_result {
+        Ok(result) => {
+            let config = Config {
+                canvas_ratio: app.horizontal_split_ratio,
+                panel_ratio: app.panel_split_ratio,
+                bell: app.bell.enabled,
+                bell_pattern: app.bell.pattern,
+            };
+            if let Err(e) = config.save() {
+                eprintln!("无法保存配置: {}", e);
+            }
+            if let Err(e) = app.command_history.save() {
+                eprintln!("无法保存命令历史: {}", e);
+            }
+            result
+        }
+        Err(_) => {
+            let summary = PANIC_SUMMARY
+                .lock()
+                .ok()
+                .and_then(|mut s| s.take())
+                .unwrap_or_default();
+            match std::fs::write(
+                crash::CRASH_LOG,
+                crash::crash_log(&summary, &app.data_graph),
+            ) {
+                Ok(()) => eprintln!("崩溃时的图已保存到 {}，请一并附上", crash::CRASH_LOG),
+                Err(e) => eprintln!("无法写入 {}: {}", crash::CRASH_LOG, e),
+            }
+            std::process::exit(101);
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn init_logging(level: &str) -> io::Result<()> {
+    let Ok(level) = level.parse::<tracing::Level>() else {
+        eprintln!(
+            "未知的日志级别: {}，可选 error/warn/info/debug/trace",
+            level
+        );
+        std::process::exit(2);
+    };
+    let file = std::fs::File::create(LOG_FILE)?;
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .init();
+    info!("日志级别 {}", level);
+    Ok(())
+}
+
+#[cfg(not(feature = "tracing"))]
+fn init_logging(_level: &str) -> io::Result<()> {
+    eprintln!(
+        "--log 需要启用 tracing 特性编译: cargo run --features tracing -- --log debug（日志写入 {}）",
+        LOG_FILE
+    );
+    std::process::exit(2);
+}
+
+// panic 钩子记下的报告，主线程捕获 panic 后写入崩溃日志
+static PANIC_SUMMARY: Mutex<Option<String>> = Mutex::new(None);
+
+// 先恢复终端再打印报告，否则终端停留在原始模式/备用屏幕，panic 信息显示错乱。
+// 后台计算线程 panic 时界面仍在运行，不打印（会打乱画面），由 update_job 提示计算失败
+fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        if thread::current().name() != Some("main") {
+            error!("后台线程{}", crash::summary(info));
+            return;
+        }
+        let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
+        ratatui::restore();
+        let summary = crash::summary(info);
+        error!("{}", summary);
+        eprintln!("{}", summary);
+        eprintln!("这是程序的错误，欢迎把以上信息提交到项目的 issue");
+        if let Ok(mut s) = PANIC_SUMMARY.lock() {
+            *s = Some(summary);
+        }
+    }));
+}
+
+// 读入图文件：.dot/.gv 按 DOT 解析（没有转向限制），其余按项目文件（node/edge/arc/turn）解析
+fn read_graph_file(path: &Path) -> Result<(Graph<f64>, TurnRestrictions<f64>), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("dot" | "gv") => export::dot::parse(&text).map(|g| (g, TurnRestrictions::default())),
+        _ => command::load_project(&text),
+    }
+}
+
+// 取 "--name 值" 形式的参数，缺少值时直接退出
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let i = args.iter().position(|a| a == name)?;
+    match args.get(i + 1) {
+        Some(value) => Some(value),
+        None => {
+            eprintln!("{} 需要指定参数值", name);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn number_arg(args: &[String], name: &str, default: u64) -> u64 {
+    match arg_value(args, name) {
+        Some(s) => s.parse().unwrap_or_else(|_| {
+            eprintln!("{} 需要一个非负整数", name);
+            std::process::exit(2);
+        }),
+        None => default,
+    }
+}
+
+// 随机测试算法不变式，失败时把缩小后的最小反例保存到 counterexample.txt
+fn run_fuzz(args: &[String]) -> io::Result<()> {
+    let cases = number_arg(args, "--cases", 1000) as usize;
+    let seed = number_arg(args, "--seed", 1);
+
+    let Some(found) = fuzz::fuzz(cases, seed) else {
+        println!("{} 个随机图上所有不变式均成立", cases);
+        return Ok(());
+    };
+
+    let input = found.shrunk.input(CheckAlgorithm::Dijkstra);
+    std::fs::write("counterexample.txt", &input)?;
+    println!(
+        "不变式 {} 不成立: {}",
+        found.invariant.name(),
+        found.message
+    );
+    println!(
+        "原始图 {} 个节点 {} 条边，缩小为 {} 个节点 {} 条边:",
+        found.original.n,
+        found.original.edges.len(),
+        found.shrunk.n,
+        found.shrunk.edges.len()
+    );
+    println!("{}", input.trim_end());
+    println!("最小反例已保存到 counterexample.txt");
+    std::process::exit(1);
+}
+
+// 在随机图上运行外部求解器并与本库算法对比，失败时把输入保存到 failing_case.txt
+fn run_check(args: &[String], solver: &str) -> io::Result<()> {
+    let algorithm = arg_value(args, "--algorithm").unwrap_or("dijkstra");
+    let Some(algorithm) = CheckAlgorithm::parse(algorithm) else {
+        eprintln!("未知算法: {}（可选 dijkstra、bfs、prim）", algorithm);
+        std::process::exit(2);
+    };
+    let cases = number_arg(args, "--cases", 100) as usize;
+    let seed = number_arg(args, "--seed", 1);
+
+    match harness::check(std::path::Path::new(solver), algorithm, cases, seed)? {
+        Ok(passed) => {
+            println!("全部 {} 个用例通过", passed);
+            Ok(())
+        }
+        Err(failure) => {
+            std::fs::write("failing_case.txt", &failure.input)?;
+            println!("{}", failure);
+            println!("失败用例的输入已保存到 failing_case.txt");
+            std::process::exit(1);
+        }
+    }
+}
+
+// 时序回放状态，结束回放时恢复 original
+struct Playback {
+    timeline: Timeline<f64>,
+    times: Vec<i64>,
+    index: usize,
+    playing: bool,
+    timer: f64,
+    original: Graph<f64>,
+}
+
+// 正在进行的教程，退出时恢复 original
+struct TutorialState {
+    index: usize,
+    step: usize,
+    original: Graph<f64>,
+}
+
+// 标签页：每页一张独立的图及其固定位置与撤销记录。
+// 当前页的内容在 App 的对应字段中，切换时与 tabs 中保存的内容交换
+#[derive(Default)]
+struct Tab {
+    name: String,
+    graph: Graph<f64>,
+    pinned: HashMap<i64, (f64, f64)>,
+    undo_stack: Vec<Graph<f64>>,
+    metrics: MetricsHistory,
+    scenario: Option<(Side, Graph<f64>)>,
+    forbidden: Forbidden,
+    turns: TurnRestrictions<f64>,
+}
+
+// 合并节点的动画：merged 移向 keep，计时结束后把 merged 并入 keep
+struct Contraction {
+    keep: i64,
+    merged: i64,
+    remaining: f64,
+}
+
+// Karger 最小割演示：逐次试验、逐条收缩，best 为目前割边最少的一次
+struct KargerDemo {
+    trial: usize,
+    current: KargerTrial<f64>,
+    step: usize,
+    timer: f64,
+    best: Option<KargerTrial<f64>>,
+    seed: u64,
+}
+
+// 隐式图场景：每隔一段时间展开一个节点，把新生成的节点和边加到画布上。
+// 每次加节点都会重建力导向图，日志与已展开的节点保存在这里
+struct ImplicitScene {
+    title: String,
+    explorer: Box<dyn Explore>,
+    timer: f64,
+    log: Vec<String>,
+    expanded: Vec<i64>,
+}
+
+// 后台线程的计算结果，由 update_job 在界面线程中显示
+enum JobOutput {
+    // 被取消时为 None
+    Betweenness(Option<HashMap<i64, f64>>),
+    Tsp(Exact<(Vec<i64>, f64)>),
+    Coloring(Exact<BTreeMap<i64, usize>>),
+    VertexCover(Exact<Vec<i64>>),
+}
+
+// 进度条的度量方式
+enum JobMeter {
+    // 全源计算：共有多少个源点，progress 为已完成的源点数
+    Sources(usize),
+    // 精确求解：时间预算，progress 为已搜索的状态数
+    Budget(Duration),
+}
+
+// 在后台线程运行的计算；cancel 置位后精确求解尽快停止，返回目前找到的最好解
+struct Job {
+    name: String,
+    meter: JobMeter,
+    started: Instant,
+    progress: Arc<AtomicUsize>,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<JobOutput>,
+}
+
+// 等待用户按键确认的操作
+enum Prompt {
+    RepairGraph,
+    // 按 / 输入节点 ID 或标签
+    Search(String),
+    // 日志面板中按 / 输入筛选子串，留空显示全部
+    LogFilter(String),
+    // 算法需要无向图，确认后补全反向边再运行该菜单项
+    Symmetrize(String),
+    // 输入 "u v 权重" 修改边权
+    EdgeWeight(String),
+    // 依次输入注册表中该项的参数，values 为已输入的部分
+    Param {
+        name: String,
+        values: Vec<String>,
+        input: String,
+    },
+    // BFS 用在边权不全相同的图上，确认是否改为运行 Dijkstra
+    SuggestDijkstra,
+    // 边权修改后，确认是否增量更新 Dijkstra 结果
+    UpdateDistances {
+        u: i64,
+        v: i64,
+    },
+    // 按 : 打开的命令栏，Tab 补全
+    Command(String),
 }
 
 struct App {
-    data_graph: Graph,
+    data_graph: Graph<f64>,
 
     screen_max_x: f64,
     screen_max_y: f64,
@@ -34,37 +528,487 @@ struct App {
     anchor_y: f64,
     r: f64,
 
+    // 视口：中心点与缩放倍数，zoom > 1 时只显示画布的一部分
+    view_x: f64,
+    view_y: f64,
+    zoom: f64,
+
     dt: f64,
 
+    // 边权显示的小数位数
+    weight_precision: usize,
+    // 画布上显示的边属性，随当前算法切换
+    edge_attr: EdgeAttr,
+    // 边画成弧线，便于区分靠得很近的重叠边；关闭时退回直线
+    curved_edges: bool,
+    // 冻结力导向模拟：节点位置不再漂移，算法动画照常进行
+    frozen: bool,
+    // 循环演示（按 R 或 set replay 切换），用于无人值守的展示屏
+    replay: Option<Replay>,
+    // 上一次运行的菜单项，循环演示没有播放列表时重复它
+    last_action: Option<String>,
+    // 响铃提示（set bell on/off），设置保存在配置文件中
+    bell: Bell,
+    // 布局稳定后自动停止模拟，图改变时恢复
+    cooling: Cooling,
+    // 边的静止长度 = 系数 × 边权 / 平均边权 × 4，None 表示不按权重调整边长
+    edge_length_scale: Option<f64>,
+    // 背景网格与坐标刻度，手动摆放节点或讲解基于坐标的启发式时使用
+    show_grid: bool,
+    // 全屏（按 f 切换）：隐藏侧栏，只显示画布和右上角的算法进度
+    fullscreen: bool,
+    // 无障碍模式：已访问的节点画成实心并在标签前加 *，已访问的边画成虚线，不只靠颜色区分
+    accessible: bool,
+    // 无颜色显示（NO_COLOR、TERM=dumb 或 --no-color）：颜色换成粗体/反色，符号换成 ASCII
+    monochrome: bool,
+
     horizontal_split_ratio: u16,
+    // 侧栏中当前面板占的高度百分比，小于 100 时下方显示日志
+    panel_split_ratio: u16,
     anchor_idx: Option<DefaultNodeIdx>,
-    graph: ForceGraph<i64, i64>,
+    // 边数据为 (权重, 方向性)
+    graph: ForceGraph<i64, (f64, EdgeType)>,
 
     menu: MenuState,
+    // 菜单项及其运行方式，菜单由它生成
+    registry: AlgorithmRegistry<App>,
+    // data_graph 的版本号，每次修改图（重建画布或就地修改边权、加节点）时加一
+    graph_version: u64,
+    // 计算结构性质时的版本号及其性质，版本号变化后重新计算
+    facts: Option<(u64, GraphFacts)>,
+    // 右侧当前显示的面板
+    panel: Panel,
+    // 距离表的排序方式与滚动位置
+    distance_order: DistanceOrder,
+    distance_table: TableState,
+    // 日志面板的筛选：事件类型与子串
+    log_events: LogEvents,
+    log_query: String,
 
     exit: bool,
 
     visited_nodes: HashSet<i64>,
     visited_edges: HashSet<(i64, i64)>,
 
-    animation_nodes: Vec<i64>,
-    animation_edges: Vec<(i64, i64)>,
-    animation_index: usize,
-    animation_timer: f64,
-    animation_step_is_edge: bool,
+    animation: AnimationPlayer,
+    // 最近运行的菜单项的动画节奏，载入步骤记录时使用
+    pacing: Pacing,
 
     current_algorithm: String,
     visit_log: Vec<String>,
+    // 最近一次遍历/最短路径运行中从起点不可达的节点，灰色显示
+    unreachable: HashSet<i64>,
+
+    // 解释模式：日志附带每一步的判断依据
+    verbose_log: bool,
+    // 按动画节点/边分组的算法步骤，每组末尾是对应的节点或边，前面是附带的说明
+    node_steps: Vec<Vec<StepEvent<f64>>>,
+    edge_steps: Vec<Vec<StepEvent<f64>>>,
+    // 队列面板：每一步之后的算法状态，以及动画已经展示到的步数
+    frontier: Option<(TraceAlgorithm, Vec<StateSnapshot<f64>>)>,
+    shown_steps: usize,
+
+    // 节点选择器当前选中的节点
+    selected_node: Option<i64>,
+    // 多选（空格加入/移出），用于删除、固定、整体移动、提取子图、多源 BFS
+    multi_selected: BTreeSet<i64>,
+
+    tabs: Vec<Tab>,
+    active_tab: usize,
+    // y 复制的导出子图，p 粘贴到当前标签页
+    clipboard: Option<Graph<f64>>,
+
+    // 手动摆放模式（按 i 切换）：方向键移动十字光标，回车在光标处放置节点
+    placing: Option<(f64, f64)>,
+    // 固定位置的节点，不参与力导向模拟，重建图后仍保持
+    pinned: HashMap<i64, (f64, f64)>,
+
+    // 编辑模式（按 e 切换，或用鼠标点击边）：n/N 改为选择边，d 切换方向，w 改权重，x 删除
+    edit_mode: bool,
+    selected_edge: Option<(i64, i64)>,
+    // 上一帧画布所在的区域，把鼠标点击的位置换算成画布坐标
+    canvas_area: Rect,
+    // 时间轴进度条所在的一行（不含左侧文字），没有显示时为 None，用于鼠标点击跳转
+    timeline_area: Option<Rect>,
+
+    prompt: Option<Prompt>,
+
+    // 只显示边权在 [最小, 最大] 内的边，None 表示该侧不限
+    weight_filter: Option<(Option<f64>, Option<f64>)>,
+
+    // 聚焦模式：只突出显示从某节点可达的子图，其余灰显
+    focus: Option<HashSet<i64>>,
+
+    // 闪烁提示的节点（搜索跳转的目标、增量更新后距离变化的节点）及剩余时间
+    flash_nodes: Option<(HashSet<i64>, f64)>,
+
+    // 随机游走：令牌所在节点、各节点被访问次数
+    walk_token: Option<i64>,
+    walk_counts: BTreeMap<i64, u64>,
+    walk_timer: f64,
+
+    // 测验模式；Kruskal 测验选边时先记下第一个端点
+    quiz: Option<Quiz<f64>>,
+    quiz_pick: Option<i64>,
+
+    // 按 m 合并节点：先记下第一个节点，再选第二个
+    merge_pick: Option<i64>,
+    // 按 r 查询可达性：先记下起点，再选终点
+    reach_pick: Option<i64>,
+    contraction: Option<Contraction>,
+
+    // Karger 演示的试验次数、进行中的演示，以及最终找到的割边（红色显示）
+    karger_trials: usize,
+    karger: Option<KargerDemo>,
+    cut_edges: HashSet<(i64, i64)>,
+    // 不相交路径：边 -> 所在路径的序号，每条路径用一种颜色
+    path_colors: HashMap<(i64, i64), usize>,
+    // 支配集：选中的节点 -> 它那一轮新支配的节点，选中节点点亮后这些节点显示为绿色
+    dominated: HashMap<i64, Vec<i64>>,
+
+    implicit: Option<ImplicitScene>,
+
+    tutorials: Vec<Tutorial<f64>>,
+    tutorial: Option<TutorialState>,
+
+    // 命令行加载的时间线；没有时回放按当前图的边顺序逐条添加
+    timeline: Option<Timeline<f64>>,
+    // 命令行加载的 2-SAT 子句，启动后生成蕴含图
+    two_sat: Option<TwoSat>,
+    // --exec 读入的命令，建好默认图后依次执行
+    startup_commands: Vec<String>,
+    // --watch 监视的图文件及上次读入时的修改时间，文件变化时重新读入
+    watch: Option<(PathBuf, Option<SystemTime>)>,
+    watch_timer: f64,
+    // --remote 启动的远程控制服务
+    #[cfg(feature = "remote")]
+    remote: Option<remote::Server>,
+    // 时间线中重复添加同一条边时的合并方式
+    edge_policy: AddEdgePolicy,
+    playback: Option<Playback>,
+
+    // 正在后台进行的计算（介数中心性或精确求解），以及最近一次的介数中心性（导出 CSV 时一并输出）
+    job: Option<Job>,
+    centrality: Option<HashMap<i64, f64>>,
+    // 精确求解的时间预算，用完后显示目前找到的最好解
+    exact_budget: Duration,
+    // 单源算法（遍历、Prim、最短路径等）的起点，命令栏 :run <菜单项> <起点> 修改
+    source: i64,
+    // 命令栏的历史，启动时读取、正常退出时写回
+    command_history: History,
+
+    // 去掉方向/定向等整图变换前的图，按 u 撤销
+    undo_stack: Vec<Graph<f64>>,
+    // 当前标签页的图统计随时间的记录，趋势面板使用
+    metrics: MetricsHistory,
+    // A/B 权重方案（按 B 切换）：当前显示的是哪一套，以及另一套方案的图
+    scenario: Option<(Side, Graph<f64>)>,
+    // 路径查询中禁行的节点和边（按 X 标记），画成划掉的样子，图本身不变
+    forbidden: Forbidden,
+    // 转向限制（turn 命令或项目文件中的 turn 行），转向限制最短路径使用
+    turns: TurnRestrictions<f64>,
+
+    // 重新编号后 新ID -> 原ID，用于在节点上显示原编号
+    original_ids: HashMap<i64, i64>,
+    // 每次重新编号前的 original_ids 及其对应的撤销栈深度，撤销重新编号时一并恢复
+    original_ids_undo: Vec<(usize, HashMap<i64, i64>)>,
+
+    prim_result: MstResult<f64>,
+    dijkstra_result: DijkstraResult<f64>,
+    // 运行 Dijkstra 后修改过边权且没有增量更新，距离表已不可信
+    dijkstra_stale: bool,
+    // 上次 Bellman-Ford 发现的负环
+    negative_cycle: Option<NegativeCycle<f64>>,
+    // 地标 A* 的预处理结果，连同当时的图和禁行标记，两者都没变时直接沿用
+    landmarks: Option<(Graph<f64>, Forbidden, Landmarks<f64>)>,
+
+    // 非力导向布局下的目标坐标（相对锚点）及切换过渡
+    layout: LayoutKind,
+    layout_targets: HashMap<i64, (f64, f64)>,
+    layout_tween: Option<Tween>,
+}
 
-    prim_total_cost: i64,
-    dijkstra_dist: HashMap<i64, i64>,
-    dijkstra_parent: HashMap<i64, i64>,
+type Action = Algorithm<App>;
+
+// 菜单中的全部算法和操作；新增一项只需在这里登记
+fn algorithms(tutorials: &[Tutorial<f64>]) -> AlgorithmRegistry<App> {
+    let mut r = AlgorithmRegistry::new();
+    // 遍历在回溯、出队处稍作停顿；Prim 在加入生成树的边上多停一会儿；
+    // Dijkstra 确定节点（出堆）时停得最久，松弛时较快
+    let pause_at_notes = Pacing {
+        note: 0.5,
+        ..Pacing::default()
+    };
+    r.register(Action::new("遍历", "Dfs", |app, _| app.run_dfs()).pacing(pause_at_notes))
+        .register(
+            Action::new("遍历", "Bfs", |app, _| {
+                if app.check_bfs_weights() {
+                    app.run_bfs()
+                }
+            })
+            .pacing(pause_at_notes),
+        )
+        .register(Action::new("遍历", "中国邮递员", |app, _| app.run_postman()).undirected())
+        .register(
+            Action::new("遍历", "拓扑排序", |app, _| {
+                app.run_topological_sort()
+            })
+            .when(GraphFacts::is_dag),
+        )
+        .register(
+            Action::new("MST", "Prim", |app, _| app.run_prim())
+                .undirected()
+                .pacing(Pacing {
+                    node: 1.0,
+                    edge: 1.5,
+                    note: 0.25,
+                }),
+        )
+        .register(
+            Action::new("最短路径", "Dijkstra", |app, _| app.run_dijkstra()).pacing(Pacing {
+                node: 2.0,
+                edge: 0.5,
+                note: 0.25,
+            }),
+        )
+        .register(
+            Action::new("最短路径", "DAG 最短路径", |app, _| {
+                app.run_dag_shortest_paths()
+            })
+            .when(GraphFacts::is_dag),
+        )
+        .register(
+            Action::new("最短路径", "Bellman-Ford", |app, _| {
+                app.run_bellman_ford()
+            })
+            .pacing(Pacing {
+                node: 1.0,
+                edge: 0.75,
+                note: 0.1,
+            }),
+        );
+
+    r.register(Action::new("布局", "力导向", |app, _| {
+        app.set_layout(LayoutKind::Force)
+    }))
+    .register(Action::new("布局", "环形", |app, _| {
+        app.set_layout(LayoutKind::Circular)
+    }))
+    .register(Action::new("布局", "分层", |app, _| {
+        app.set_layout(LayoutKind::Layered)
+    }))
+    .register(Action::new("布局", "谱布局", |app, _| {
+        app.set_layout(LayoutKind::Spectral)
+    }))
+    .register(
+        Action::new("布局", "边长按权重", |app, args| {
+            app.set_edge_length_scale(&args[0])
+        })
+        .param("边长缩放系数(留空关闭)"),
+    );
+
+    for (i, t) in tutorials.iter().enumerate() {
+        r.register(Action::new("教程", &t.title, move |app, _| {
+            app.start_tutorial(i)
+        }));
+    }
+
+    r.register(Action::new("测验", "Dijkstra 测验", |app, _| {
+        app.start_quiz(QuizKind::DijkstraSettle)
+    }))
+    .register(
+        Action::new("测验", "Kruskal 测验", |app, _| {
+            app.start_quiz(QuizKind::KruskalPick)
+        })
+        .undirected(),
+    );
+
+    r.register(Action::new("模拟", "随机游走", |app, _| {
+        app.start_random_walk()
+    }))
+    .register(Action::new("模拟", "时序回放", |app, _| {
+        app.toggle_playback()
+    }))
+    .register(
+        Action::new("模拟", "Karger 最小割", |app, args| {
+            app.run_karger(&args[0])
+        })
+        .param("Karger 试验次数(回车用默认值)"),
+    )
+    .register(Action::new("模拟", "单词接龙", |app, _| {
+        app.start_implicit(
+            "单词接龙",
+            Box::new(LazyBfs::new(
+                WordLadder::builtin(),
+                "cold".to_string(),
+                "warm".to_string(),
+            )),
+            "cold",
+            "warm",
+        )
+    }))
+    .register(Action::new("模拟", "骑士走法", |app, _| {
+        let (from, to) = (Square { col: 0, row: 0 }, Square { col: 4, row: 4 });
+        app.start_implicit(
+            "骑士走法",
+            Box::new(LazyBfs::new(KnightMoves { size: 5 }, from, to)),
+            &from.to_string(),
+            &to.to_string(),
+        );
+    }));
+
+    r.register(Action::new("近似", "顶点覆盖", |app, _| {
+        app.run_vertex_cover()
+    }))
+    .register(Action::new("近似", "独立集", |app, _| {
+        app.run_independent_set()
+    }))
+    .register(Action::new("近似", "支配集", |app, _| {
+        app.run_dominating_set()
+    }));
+
+    r.register(Action::new("精确", "旅行商", |app, _| {
+        app.start_exact("旅行商", |g, budget| {
+            JobOutput::Tsp(exact::tsp(g, budget))
+        })
+    }))
+    .register(Action::new("精确", "最少着色", |app, _| {
+        app.start_exact("最少着色", |g, budget| {
+            JobOutput::Coloring(exact::coloring(g, budget))
+        })
+    }))
+    .register(Action::new("精确", "最小顶点覆盖", |app, _| {
+        app.start_exact("最小顶点覆盖", |g, budget| {
+            JobOutput::VertexCover(exact::vertex_cover(g, budget))
+        })
+    }));
+
+    r.register(Action::new("图", "全部运行", |app, _| {
+        app.run_summary()
+    }))
+    .register(Action::new("图", "介数中心性", |app, _| {
+        app.start_betweenness()
+    }))
+    .register(Action::new("图", "重新编号", |app, _| {
+        app.relabel_graph()
+    }))
+    .register(Action::new("图", "检查修复", |app, _| {
+        app.run_validate()
+    }))
+    .register(
+        Action::new("图", "按权重筛选", |app, args| {
+            app.apply_weight_filter(&args[0])
+        })
+        .param("边权范围(最小,最大)"),
+    )
+    .register(
+        Action::new("图", "修改边权", |app, args| {
+            app.apply_edge_weight(&args[0])
+        })
+        .param("修改边权(u v 权重)"),
+    )
+    .register(
+        Action::new("图", "边权乘以", |app, args| {
+            app.transform_weights(&args[0], true)
+        })
+        .param("所有边权乘以"),
+    )
+    .register(
+        Action::new("图", "边权加上", |app, args| {
+            app.transform_weights(&args[0], false)
+        })
+        .param("所有边权加上"),
+    )
+    .register(Action::new("图", "去掉方向", |app, _| {
+        let g = app.data_graph.to_undirected();
+        app.replace_graph(g, "已去掉方向，互为反向的边保留较小权重");
+    }))
+    .register(Action::new("图", "按编号定向", |app, _| {
+        let g = app.data_graph.orient_by(|_, _| true);
+        app.replace_graph(g, "已把每条边定向为小编号 -> 大编号");
+    }))
+    .register(Action::new("图", "随机定向", |app, _| {
+        let g = app.data_graph.orient_random(rand::rng().random());
+        app.replace_graph(g, "已随机给每条边定向");
+    }))
+    .register(Action::new("图", "最短环", |app, _| {
+        app.run_girth(false)
+    }))
+    .register(Action::new("图", "最小权环", |app, _| {
+        app.run_girth(true)
+    }))
+    .register(Action::new("图", "重复边策略", |app, _| {
+        app.cycle_edge_policy()
+    }));
+
+    r.register(Action::new("选择", "删除选中节点", |app, _| {
+        app.delete_selected()
+    }))
+    .register(Action::new("选择", "固定选中节点", |app, _| {
+        app.pin_selected()
+    }))
+    .register(Action::new("选择", "提取到新标签页", |app, _| {
+        app.extract_selected()
+    }))
+    .register(Action::new("选择", "多源 BFS", |app, _| {
+        app.run_multi_bfs()
+    }))
+    .register(Action::new("选择", "边不相交路径", |app, _| {
+        app.run_disjoint_paths(false)
+    }))
+    .register(Action::new("选择", "点不相交路径", |app, _| {
+        app.run_disjoint_paths(true)
+    }))
+    .register(Action::new("选择", "A/B 方案对比", |app, _| {
+        app.compare_scenarios()
+    }))
+    .register(Action::new(
+        "选择",
+        "转向限制最短路径",
+        |app, _| app.run_turn_route(),
+    ))
+    .register(Action::new("选择", "地标 A*", |app, _| {
+        app.run_landmark_astar()
+    }))
+    .register(Action::new("选择", "清空选择", |app, _| {
+        app.multi_selected.clear()
+    }));
+
+    r.register(Action::new("导出", "节点指标 CSV", |app, _| {
+        app.export_metrics()
+    }))
+    .register(Action::new("导出", "DOT 文件", |app, _| {
+        app.export_dot()
+    }));
+
+    r.register(Action::new("分组", "社区检测", |app, _| {
+        app.run_communities()
+    }))
+    .register(Action::new("分组", "强连通分量", |app, _| app.run_scc()).when(|f| f.directed))
+    .register(
+        Action::new("分组", "2-SAT", |app, args| {
+            match TwoSat::parse(&args[0]) {
+                Ok(problem) => app.load_two_sat(&problem),
+                Err(e) => app.visit_log.push(e),
+            }
+        })
+        .param("2-SAT 子句(如 1 -2, -1 3)"),
+    )
+    .register(Action::new("分组", "清除分组", |app, _| {
+        app.data_graph.clear_groups()
+    }));
+
+    r.register(Action::new("", "退出", |app, _| app.exit = true));
+    r
 }
 
 impl App {
     pub fn new() -> Self {
+        let tutorials = Tutorial::builtin();
+        let registry = algorithms(&tutorials);
+
         Self {
-            data_graph: Graph::new(),
+            data_graph: Graph::default(),
 
             screen_max_x: 20.0,
             screen_max_y: 10.0,
@@ -73,9 +1017,28 @@ impl App {
             anchor_y: 0.0,
             r: 0.6,
 
+            view_x: 0.0,
+            view_y: 0.0,
+            zoom: 1.0,
+
             dt: 0.005,
 
+            weight_precision: 2,
+            edge_attr: EdgeAttr::Weight,
+            curved_edges: true,
+            show_grid: false,
+            fullscreen: false,
+            accessible: false,
+            monochrome: false,
+            frozen: false,
+            replay: None,
+            last_action: None,
+            bell: Bell::default(),
+            cooling: Cooling::new(0.002, 100),
+            edge_length_scale: None,
+
             horizontal_split_ratio: 70,
+            panel_split_ratio: 100,
 
             anchor_idx: None,
             graph: ForceGraph::new(SimulationParameters {
@@ -86,471 +1049,5257 @@ impl App {
                 damping_factor: 0.85,
             }),
 
-            menu: MenuState::new(vec![
-                MenuItem::new("遍历", vec![MenuItem::leaf("Dfs"), MenuItem::leaf("Bfs")]),
-                MenuItem::new("MST", vec![MenuItem::leaf("Prim")]),
-                MenuItem::new("最短路径", vec![MenuItem::leaf("Dijkstra")]),
-                MenuItem::leaf("退出"),
-            ]),
+            menu: MenuState::new(registry.menu()),
+            registry,
+            graph_version: 0,
+            facts: None,
+            panel: Panel::Menu,
+            distance_order: DistanceOrder::Node,
+            distance_table: TableState::default(),
+            log_events: LogEvents::All,
+            log_query: String::new(),
             exit: false,
 
             visited_nodes: HashSet::new(),
             visited_edges: HashSet::new(),
 
-            animation_nodes: Vec::new(),
-            animation_edges: Vec::new(),
-            animation_index: 0,
-            animation_timer: 0.0,
-            animation_step_is_edge: false,
+            animation: AnimationPlayer::default(),
+            pacing: Pacing::default(),
 
             current_algorithm: String::new(),
             visit_log: Vec::new(),
+            unreachable: HashSet::new(),
 
-            prim_total_cost: 0,
-            dijkstra_dist: HashMap::new(),
-            dijkstra_parent: HashMap::new(),
-        }
-    }
-    pub fn init_graph(&mut self) {
-        let mut rng = rand::rng();
+            verbose_log: false,
+            node_steps: Vec::new(),
+            edge_steps: Vec::new(),
+            frontier: None,
+            shown_steps: 0,
 
-        let mut nodes = self.data_graph.nodes();
+            selected_node: None,
+            multi_selected: BTreeSet::new(),
 
-        if nodes.is_empty() {
-            let n1_idx = self.graph.add_node(NodeData {
-                x: self.anchor_x as f32,
-                y: self.anchor_y as f32,
-                is_anchor: true,
-                user_data: 1,
+            tabs: vec![Tab {
+                name: "主图".to_string(),
                 ..Default::default()
-            });
-            self.anchor_idx = Some(n1_idx);
-            return;
-        }
+            }],
+            active_tab: 0,
+            clipboard: None,
 
-        nodes.sort();
+            placing: None,
+            pinned: HashMap::new(),
 
-        let mut id_to_idx: HashMap<i64, DefaultNodeIdx> = HashMap::new();
-        let mut anchor_idx: Option<DefaultNodeIdx> = None;
+            edit_mode: false,
+            selected_edge: None,
+            canvas_area: Rect::default(),
+            timeline_area: None,
 
-        for node_id in nodes {
-            let is_anchor = node_id == 1;
-            let (x, y) = if is_anchor {
-                (self.anchor_x as f32, self.anchor_y as f32)
-            } else {
-                (rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0))
-            };
+            prompt: None,
 
-            let idx = self.graph.add_node(NodeData {
-                x,
-                y,
-                is_anchor,
-                user_data: node_id,
-                ..Default::default()
-            });
+            weight_filter: None,
 
-            if is_anchor {
-                anchor_idx = Some(idx);
-            }
+            focus: None,
 
-            id_to_idx.insert(node_id, idx);
-        }
+            flash_nodes: None,
 
-        self.anchor_idx = anchor_idx;
+            walk_token: None,
+            walk_counts: BTreeMap::new(),
+            walk_timer: 0.0,
 
-        for (u, v, w) in self.data_graph.edges() {
-            if let (Some(&u_idx), Some(&v_idx)) = (id_to_idx.get(&u), id_to_idx.get(&v)) {
-                self.graph.add_edge(u_idx, v_idx, EdgeData { user_data: w });
-            }
+            quiz: None,
+            quiz_pick: None,
+
+            merge_pick: None,
+            reach_pick: None,
+            contraction: None,
+
+            karger_trials: 20,
+            karger: None,
+            cut_edges: HashSet::new(),
+            path_colors: HashMap::new(),
+            dominated: HashMap::new(),
+
+            implicit: None,
+
+            tutorials,
+            tutorial: None,
+
+            timeline: None,
+            two_sat: None,
+            startup_commands: Vec::new(),
+            watch: None,
+            watch_timer: 0.0,
+            #[cfg(feature = "remote")]
+            remote: None,
+            edge_policy: AddEdgePolicy::KeepLast,
+            playback: None,
+
+            job: None,
+            centrality: None,
+            exact_budget: Duration::from_secs(10),
+            source: 1,
+            command_history: History::default(),
+
+            undo_stack: Vec::new(),
+            metrics: MetricsHistory::default(),
+            scenario: None,
+            forbidden: Forbidden::default(),
+            turns: TurnRestrictions::default(),
+
+            original_ids: HashMap::new(),
+            original_ids_undo: Vec::new(),
+
+            prim_result: MstResult::default(),
+            dijkstra_result: DijkstraResult::default(),
+            dijkstra_stale: false,
+            negative_cycle: None,
+            landmarks: None,
+
+            layout: LayoutKind::Force,
+            layout_targets: HashMap::new(),
+            layout_tween: None,
         }
     }
+    // 图结构变化后重建力导向图，并清空上一次的算法状态
+    fn reload_graph(&mut self) {
+        self.graph.clear();
+        self.anchor_idx = None;
+        self.cooling.wake();
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        self.data_graph.add_edge(5, 9, 650, Both);
-        self.data_graph.add_edge(7, 9, 1140, Both);
-        self.data_graph.add_edge(9, 10, 750, Both);
-        self.data_graph.add_edge(4, 8, 960, Both);
-        self.data_graph.add_edge(5, 8, 820, Both);
-        self.data_graph.add_edge(5, 4, 1200, Both);
-        self.data_graph.add_edge(8, 10, 680, Both);
-        self.data_graph.add_edge(9, 8, 800, Both);
-        self.data_graph.add_edge(6, 2, 340, Both);
-        self.data_graph.add_edge(6, 7, 840, Both);
-        self.data_graph.add_edge(1, 3, 1380, Both);
-        self.data_graph.add_edge(1, 2, 900, Both);
-        self.data_graph.add_edge(1, 4, 680, Both);
-        self.data_graph.add_edge(1, 5, 530, Both);
-        self.data_graph.add_edge(3, 2, 2500, Both);
-        self.data_graph.add_edge(3, 4, 2600, Both);
-        self.data_graph.add_edge(7, 5, 570, Both);
+        self.current_algorithm.clear();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
+        self.selected_node = None;
+        self.multi_selected
+            .retain(|&u| self.data_graph.contains_node(u));
+        self.focus = None;
+        self.quiz = None;
+        self.quiz_pick = None;
+        self.karger = None;
+        self.cut_edges.clear();
+        self.path_colors.clear();
+        self.dominated.clear();
+        // 图已改变，丢弃旧图上的计算结果，并通知后台线程停止
+        if let Some(job) = self.job.take() {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+        self.centrality = None;
+        self.set_layout(LayoutKind::Force);
 
         self.init_graph();
-        while !self.exit {
-            self.handle_events()?;
+    }
 
-            self.update_animation();
+    // 整图变换：记下原图以便撤销
+    fn replace_graph(&mut self, graph: Graph<f64>, message: &str) {
+        self.undo_stack
+            .push(std::mem::replace(&mut self.data_graph, graph));
+        self.reload_keep_positions();
+        self.current_algorithm = "图变换".to_string();
+        self.visit_log.push(message.to_string());
+        self.visit_log.push("按 u 撤销".to_string());
+    }
 
-            let limit_x = (self.screen_max_x - self.r) as f32;
-            let limit_y = (self.screen_max_y - self.r) as f32;
-            if let Some(idx) = self.anchor_idx {
-                let tx = self.anchor_x as f32;
-                let ty = self.anchor_y as f32;
+    fn cycle_edge_policy(&mut self) {
+        let all = AddEdgePolicy::ALL;
+        let i = all.iter().position(|&p| p == self.edge_policy).unwrap_or(0);
+        self.edge_policy = all[(i + 1) % all.len()];
+        self.load_snapshot();
 
-                self.graph.visit_nodes_mut(|node| {
-                    if node.index() == idx {
-                        node.data.x = tx;
-                        node.data.y = ty;
-                    } else {
-                        node.data.x = node.data.x.clamp(-limit_x, limit_x);
-                        node.data.y = node.data.y.clamp(-limit_y, limit_y);
-                    }
-                });
-            }
+        self.current_algorithm = "重复边策略".to_string();
+        self.visit_log.clear();
+        self.visit_log
+            .push(format!("重复边策略: {}", self.edge_policy.name()));
+        self.visit_log
+            .push("作用于时序回放中重复添加的边和合并节点后重复的边".to_string());
+    }
 
-            self.graph.update(self.dt as f32);
-            terminal.draw(|frame| self.draw(frame))?;
+    fn undo_graph(&mut self) {
+        let Some(graph) = self.undo_stack.pop() else {
+            self.visit_log.push("没有可撤销的变换".to_string());
+            return;
+        };
+        if self
+            .original_ids_undo
+            .last()
+            .is_some_and(|&(depth, _)| depth == self.undo_stack.len())
+            && let Some((_, ids)) = self.original_ids_undo.pop()
+        {
+            self.original_ids = ids;
         }
-        Ok(())
+        self.data_graph = graph;
+        self.reload_graph();
+        self.current_algorithm = "图变换".to_string();
+        self.visit_log.push("已撤销".to_string());
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(self.horizontal_split_ratio),
-                Constraint::Percentage(100 - self.horizontal_split_ratio),
-            ])
-            .split(frame.area());
+    // 把节点 ID 压缩为 1..=n（可撤销）。按 ID 记录的状态（固定位置、禁行、转向限制、起点、
+    // 选择、方案 B 和画布上的位置）跟着节点迁移到新编号；撤销只恢复图本身和显示的原编号
+    fn relabel_graph(&mut self) {
+        let (graph, originals) = self.data_graph.relabel();
+        let new_id: HashMap<i64, i64> = originals
+            .iter()
+            .enumerate()
+            .map(|(i, &old)| (old, i as i64 + 1))
+            .collect();
+        self.original_ids_undo
+            .push((self.undo_stack.len(), self.original_ids.clone()));
+        // 多次重新编号时始终追溯到最初的编号
+        self.original_ids = originals
+            .iter()
+            .enumerate()
+            .map(|(i, &old)| {
+                let first = self.original_ids.get(&old).copied().unwrap_or(old);
+                (i as i64 + 1, first)
+            })
+            .filter(|(new, old)| new != old)
+            .collect();
 
-        let canva = Canvas::default()
-            .block(Block::default().title("Graph").borders(Borders::ALL))
-            .x_bounds([-self.screen_max_x, self.screen_max_x])
-            .y_bounds([-self.screen_max_y, self.screen_max_y])
-            .paint(|ctx| self.render_ctx(ctx));
+        self.pinned = self
+            .pinned
+            .iter()
+            .filter_map(|(u, &p)| Some((*new_id.get(u)?, p)))
+            .collect();
+        self.forbidden = self.forbidden.relabel(&new_id);
+        self.turns = self.turns.relabel(&new_id);
+        self.source = new_id.get(&self.source).copied().unwrap_or(1);
+        let selected = self.selected_node.and_then(|u| new_id.get(&u).copied());
+        self.multi_selected = self
+            .multi_selected
+            .iter()
+            .filter_map(|u| new_id.get(u).copied())
+            .collect();
+        // 两套方案节点相同，重新编号的结果也相同
+        if let Some((_, other)) = &mut self.scenario {
+            *other = other.relabel().0;
+        }
+        self.graph.visit_nodes_mut(|node| {
+            if let Some(&u) = new_id.get(&node.data.user_data) {
+                node.data.user_data = u;
+            }
+        });
+
+        self.replace_graph(
+            graph,
+            &format!(
+                "已将 {} 个节点重新编号为 1..={}",
+                originals.len(),
+                originals.len()
+            ),
+        );
+        self.selected_node = selected;
+    }
+
+    // 不播放动画，一次性运行所有适用的算法并列出结果
+    fn run_summary(&mut self) {
+        self.current_algorithm = "全部运行".to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
 
-        frame.render_widget(canva, chunks[0]);
+        let summary = GraphSummary::new(&self.data_graph, self.source);
+        self.visit_log.extend(
+            summary
+                .rows(self.weight_precision)
+                .into_iter()
+                .map(|(name, value)| format!("{}: {}", name, value)),
+        );
+        // 标出最小点割和最小边割
+        if let Some((_, cut)) = &summary.vertex_connectivity {
+            self.visited_nodes.extend(cut);
+        }
+        if let Some((_, cut)) = &summary.edge_connectivity {
+            self.visited_edges.extend(cut);
+        }
+    }
 
-        let right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-            .split(chunks[1]);
+    // 从当前起点 source 出发计算各节点指标，写入当前目录的 metrics.csv
+    fn export_metrics(&mut self) {
+        self.current_algorithm = "导出".to_string();
+        self.visit_log.clear();
 
-        let title = "Menu";
+        let csv = export::csv::node_metrics(
+            &self.data_graph,
+            self.source,
+            self.centrality.as_ref(),
+            self.weight_precision,
+        );
+        match std::fs::write("metrics.csv", csv) {
+            Ok(()) => self.visit_log.push(format!(
+                "已导出 {} 个节点的指标到 metrics.csv",
+                self.data_graph.nodes().len()
+            )),
+            Err(e) => {
+                error!("导出 metrics.csv 失败: {}", e);
+                self.visit_log.push(format!("导出失败: {}", e));
+            }
+        }
+    }
+
+    fn export_dot(&mut self) {
+        self.current_algorithm = "导出".to_string();
+        self.visit_log.clear();
+
+        let dot = export::dot::to_dot(&self.data_graph, self.weight_precision);
+        match std::fs::write("graph.dot", dot) {
+            Ok(()) => self.visit_log.push(format!(
+                "已导出 {} 条边到 graph.dot，可用 dot -Tpng graph.dot -o graph.png 渲染",
+                self.data_graph.edges().len()
+            )),
+            Err(e) => {
+                error!("导出 graph.dot 失败: {}", e);
+                self.visit_log.push(format!("导出失败: {}", e));
+            }
+        }
+    }
+
+    fn run_validate(&mut self) {
+        self.current_algorithm = "图检查".to_string();
+        self.visit_log.clear();
+
+        let issues = self.data_graph.validate();
+        if issues.is_empty() {
+            self.visit_log.push("未发现问题".to_string());
+            return;
+        }
+
+        self.visit_log
+            .extend(issues.iter().map(|issue| format!("- {}", issue)));
+        self.visit_log.push(String::new());
+        self.visit_log
+            .push("按 y 修复（合并重复边保留最小权重、删除自环），其他键取消".to_string());
+        self.prompt = Some(Prompt::RepairGraph);
+    }
+
+    // 处理确认提示的按键，返回 true 表示按键已被提示消费
+    fn handle_prompt(&mut self, code: KeyCode) -> bool {
+        let Some(prompt) = self.prompt.take() else {
+            return false;
+        };
+
+        let accepted = code == KeyCode::Char('y');
+        match prompt {
+            Prompt::Search(query) => {
+                if let Some(query) = self.edit_text(code, query, Prompt::Search) {
+                    self.jump_to(&query);
+                }
+            }
+            Prompt::LogFilter(query) => {
+                if let Some(query) = self.edit_text(code, query, Prompt::LogFilter) {
+                    self.log_query = query.trim().to_string();
+                }
+            }
+            Prompt::Param {
+                name,
+                mut values,
+                input,
+            } => {
+                let edit = |input| Prompt::Param {
+                    name: name.clone(),
+                    values: values.clone(),
+                    input,
+                };
+                if let Some(input) = self.edit_text(code, input, edit) {
+                    values.push(input);
+                    let Some(algorithm) = self.registry.get(&name).cloned() else {
+                        return true;
+                    };
+                    if values.len() < algorithm.params.len() {
+                        self.prompt = Some(Prompt::Param {
+                            name,
+                            values,
+                            input: String::new(),
+                        });
+                    } else {
+                        self.pacing = algorithm.pacing;
+                        (algorithm.run)(self, &values);
+                    }
+                }
+            }
+            Prompt::RepairGraph if accepted => {
+                let fixed = self.data_graph.repair();
+                self.reload_graph();
+                self.current_algorithm = "图检查".to_string();
+                self.visit_log.push(format!("已修复 {} 处问题", fixed));
+            }
+            Prompt::RepairGraph => self.visit_log.push("已取消修复".to_string()),
+            Prompt::Symmetrize(name) if accepted => {
+                let mut graph = self.data_graph.clone();
+                let added = graph.symmetrize();
+                self.replace_graph(graph, "");
+                self.run_action(&name);
+                self.visit_log
+                    .insert(0, format!("已补上 {} 条反向边，按 u 撤销", added));
+            }
+            Prompt::Symmetrize(_) => self.visit_log.push("已取消".to_string()),
+            Prompt::SuggestDijkstra if accepted => self.run_dijkstra(),
+            Prompt::SuggestDijkstra => self.run_bfs(),
+            Prompt::EdgeWeight(input) => {
+                if let Some(input) = self.edit_text(code, input, Prompt::EdgeWeight) {
+                    self.apply_edge_weight(&input);
+                }
+            }
+            Prompt::Command(input) if code == KeyCode::Tab => {
+                let names: Vec<&str> = self.registry.iter().map(|a| a.name.as_str()).collect();
+                let completed = command::complete_line(&input, &names, &self.data_graph.nodes());
+                self.prompt = Some(Prompt::Command(completed));
+            }
+            Prompt::Command(input) if matches!(code, KeyCode::Up | KeyCode::Down) => {
+                let recalled = if code == KeyCode::Up {
+                    self.command_history.older(&input)
+                } else {
+                    self.command_history.newer()
+                };
+                let line = recalled.map_or(input, str::to_string);
+                self.prompt = Some(Prompt::Command(line));
+            }
+            Prompt::Command(input) => {
+                if let Some(input) = self.edit_text(code, input, Prompt::Command) {
+                    self.command_history.push(&input);
+                    self.execute_command(&input);
+                }
+            }
+            Prompt::UpdateDistances { u, v } if accepted => self.update_distances(u, v),
+            Prompt::UpdateDistances { .. } => {
+                self.dijkstra_stale = true;
+                self.visit_log
+                    .push("未更新，距离表已过期，重新运行 Dijkstra 以刷新".to_string());
+            }
+        }
+        true
+    }
+
+    // 文本输入提示的编辑：回车返回输入内容，Esc 取消，其余按键继续编辑
+    fn edit_text(
+        &mut self,
+        code: KeyCode,
+        mut text: String,
+        prompt: impl FnOnce(String) -> Prompt,
+    ) -> Option<String> {
+        match code {
+            KeyCode::Enter => return Some(text),
+            KeyCode::Esc => return None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            _ => {}
+        }
+        self.prompt = Some(prompt(text));
+        None
+    }
+
+    // 执行命令栏中的一条命令；改动图的命令经 replace_graph 进行，可按 u 撤销
+    fn execute_command(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        match Command::<f64>::parse(line) {
+            Ok(command) => {
+                info!("命令: {}", line.trim());
+                self.apply_command(command);
+            }
+            Err(e) => self.visit_log.push(format!(":{} - {}", line.trim(), e)),
+        }
+    }
+
+    fn apply_command(&mut self, command: Command<f64>) {
+        let mut g = self.data_graph.clone();
+        match command {
+            Command::Edge { u, v, w, directed } => {
+                let edge_type = if directed {
+                    EdgeType::Single
+                } else {
+                    EdgeType::Both
+                };
+                g.add_edge(u, v, w, edge_type);
+                let arrow = if directed { "->" } else { "-" };
+                let w = w.format(self.weight_precision);
+                self.replace_graph(g, &format!("已添加边 {} {} {} ({})", u, arrow, v, w));
+            }
+            Command::Node(u) if g.contains_node(u) => {
+                self.visit_log.push(format!("节点 {} 已存在", u));
+            }
+            Command::Node(u) => {
+                g.add_node(u);
+                self.replace_graph(g, &format!("已添加节点 {}", u));
+            }
+            Command::DelEdge(u, v) => {
+                if g.remove_edge(u, v, EdgeType::Both) {
+                    self.replace_graph(g, &format!("已删除边 {}-{}", u, v));
+                } else {
+                    self.visit_log.push(format!("边 {}-{} 不存在", u, v));
+                }
+            }
+            Command::DelNode(u) => {
+                if g.remove_node(u) {
+                    self.replace_graph(g, &format!("已删除节点 {}", u));
+                } else {
+                    self.visit_log.push(format!("节点 {} 不存在", u));
+                }
+            }
+            Command::Run { algorithm, source } => {
+                let name = self
+                    .registry
+                    .iter()
+                    .find(|a| a.name.to_lowercase() == algorithm.to_lowercase())
+                    .map(|a| a.name.clone());
+                let Some(name) = name else {
+                    self.visit_log
+                        .push(format!("没有名为 {} 的菜单项", algorithm));
+                    return;
+                };
+                if let Some(s) = source {
+                    if !g.contains_node(s) {
+                        self.visit_log.push(format!("节点 {} 不存在", s));
+                        return;
+                    }
+                    self.source = s;
+                }
+                self.run_action(&name);
+            }
+            Command::Save(path) => match std::fs::write(
+                &path,
+                command::script(&g) + &command::turn_script(&self.turns),
+            ) {
+                Ok(()) => {
+                    self.visit_log.push(format!(
+                        "已把 {} 个节点、{} 条边、{} 条转向限制保存到 {}",
+                        g.nodes().len(),
+                        g.edges().len(),
+                        self.turns.len(),
+                        path
+                    ));
+                    let unsaved = command::unsaved_fields(&g);
+                    if !unsaved.is_empty() {
+                        self.visit_log
+                            .push(format!("命令脚本不支持，未保存: {}", unsaved.join("、")));
+                    }
+                }
+                Err(e) => {
+                    error!("保存 {} 失败: {}", path, e);
+                    self.visit_log.push(format!("保存失败: {}", e));
+                }
+            },
+            Command::Clear => self.replace_graph(Graph::default(), "已清空图"),
+            Command::ForbidNode(u) => self.forbid_node(u),
+            Command::ForbidEdge(u, v) => self.forbid_edge(u, v),
+            Command::ClearForbidden => {
+                self.forbidden.clear();
+                self.visit_log.push("已解除全部禁行".to_string());
+            }
+            Command::Turn {
+                from,
+                via,
+                to,
+                penalty,
+            } => self.set_turn(from, via, to, penalty),
+            Command::ClearTurns => {
+                self.turns.clear();
+                self.visit_log.push("已清除全部转向限制".to_string());
+            }
+            Command::Group { name, nodes } => self.assign_group(&name, nodes),
+            Command::ClearGroups => {
+                self.data_graph.clear_groups();
+                self.visit_log.push("已清除全部分组".to_string());
+            }
+            Command::Set(setting) => {
+                let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+                let (name, value) = match setting {
+                    Setting::Color(on) => {
+                        self.monochrome = !on;
+                        ("color", on_off(on))
+                    }
+                    Setting::Accessible(on) => {
+                        self.accessible = on;
+                        ("accessible", on_off(on))
+                    }
+                    Setting::Grid(on) => {
+                        self.show_grid = on;
+                        ("grid", on_off(on))
+                    }
+                    Setting::Curved(on) => {
+                        self.curved_edges = on;
+                        ("curved", on_off(on))
+                    }
+                    Setting::Precision(n) => {
+                        self.weight_precision = n;
+                        ("precision", n.to_string())
+                    }
+                    Setting::Bell(on) => {
+                        self.bell.enabled = on;
+                        ("bell", on_off(on))
+                    }
+                    Setting::Replay(on) => {
+                        self.replay = on.then(|| self.replay.take().unwrap_or_default());
+                        ("replay", on_off(on))
+                    }
+                };
+                self.visit_log.push(format!("已设置 {} {}", name, value));
+            }
+            Command::Playlist(names) => {
+                let mut playlist = Vec::new();
+                for name in names {
+                    let found = self
+                        .registry
+                        .iter()
+                        .find(|a| a.name.to_lowercase() == name.to_lowercase());
+                    let Some(algorithm) = found else {
+                        self.visit_log.push(format!("没有名为 {} 的菜单项", name));
+                        return;
+                    };
+                    playlist.push(algorithm.name.clone());
+                }
+                self.visit_log.push(if playlist.is_empty() {
+                    "循环演示：重复上一次运行的菜单项".to_string()
+                } else {
+                    format!("循环演示：{}", playlist.join(" → "))
+                });
+                self.replay = Some(Replay::new(playlist));
+            }
+            Command::Load(path) => match read_graph_file(Path::new(&path)) {
+                Ok((g, turns)) => {
+                    self.turns = turns;
+                    self.replace_graph(g, &format!("已从 {} 读入图", path));
+                }
+                Err(e) => self.visit_log.push(format!("读入 {} 失败: {}", path, e)),
+            },
+        }
+    }
+
+    // 解析 "最小,最大"，任一侧留空表示不限；输入为空时清除筛选
+    fn apply_weight_filter(&mut self, input: &str) {
+        self.current_algorithm = "权重筛选".to_string();
+        self.visit_log.clear();
+
+        if input.trim().is_empty() {
+            self.weight_filter = None;
+            self.visit_log.push("已清除筛选，显示全部边".to_string());
+            return;
+        }
+
+        let bound = |s: &str| -> Result<Option<f64>, ()> {
+            if s.trim().is_empty() {
+                Ok(None)
+            } else {
+                f64::parse(s).map(Some).ok_or(())
+            }
+        };
+        let (min, max) = input.split_once(',').unwrap_or((input, ""));
+        let (Ok(min), Ok(max)) = (bound(min), bound(max)) else {
+            self.visit_log
+                .push(format!("无法解析范围: {}，格式为 最小,最大", input));
+            return;
+        };
+
+        self.weight_filter = Some((min, max));
+        let shown = self
+            .data_graph
+            .edges()
+            .into_iter()
+            .filter(|&(_, _, w, _)| self.edge_passes_filter(w))
+            .count();
+        self.visit_log.push(format!(
+            "显示边权在 [{}, {}] 内的 {} 条边",
+            min.map_or("-∞".to_string(), |w| w.format(self.weight_precision)),
+            max.map_or("∞".to_string(), |w| w.format(self.weight_precision)),
+            shown
+        ));
+        self.visit_log
+            .push("孤立节点以灰色显示，输入空范围清除筛选".to_string());
+    }
+
+    // 解析 "u v 权重" 并修改 u、v 之间的边；刚运行过 Dijkstra 时询问是否增量更新距离
+    fn apply_edge_weight(&mut self, input: &str) {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let parsed = match parts[..] {
+            [u, v, w] => u
+                .parse::<i64>()
+                .ok()
+                .zip(v.parse::<i64>().ok())
+                .zip(f64::parse(w)),
+            _ => None,
+        };
+        let Some(((u, v), w)) = parsed else {
+            self.visit_log
+                .push(format!("无法解析: {}，格式为 u v 权重", input));
+            return;
+        };
+        if !self.data_graph.set_edge_weight(u, v, w) {
+            self.visit_log.push(format!("边 {}-{} 不存在", u, v));
+            return;
+        }
+        self.graph_version += 1;
+        self.visit_log.push(format!(
+            "边 {}-{} 的权重改为 {}",
+            u,
+            v,
+            w.format(self.weight_precision)
+        ));
+
+        if self.current_algorithm != "Dijkstra" || self.dijkstra_stale {
+            return;
+        }
+        if w < 0.0 {
+            // 与 +/- 相同：Dijkstra 不支持负权，不再提示增量更新
+            self.dijkstra_stale = true;
+            self.visit_log
+                .push("出现负权边，最短距离已过期".to_string());
+        } else {
+            self.visit_log
+                .push("按 y 增量更新最短距离，其他键保留旧结果".to_string());
+            self.prompt = Some(Prompt::UpdateDistances { u, v });
+        }
+    }
+
+    // 所有边权乘以或加上输入的数（可撤销）。刚运行过 Dijkstra 时比较变换前后从同一起点出发的
+    // 最短路径树：乘以正数时不变，加常数时边数多的路径吃亏，树可能改变
+    fn transform_weights(&mut self, input: &str, scale: bool) {
+        let Some(k) = f64::parse(input) else {
+            self.visit_log.push(format!("无法解析: {}", input.trim()));
+            return;
+        };
+        let transformed = if scale {
+            self.data_graph.scale_weights(k)
+        } else {
+            self.data_graph.shift_weights(k)
+        };
+        let Some(g) = transformed else {
+            self.visit_log.push("边权溢出，图保持不变".to_string());
+            return;
+        };
+
+        let source = (self.current_algorithm == "Dijkstra" && !self.dijkstra_stale)
+            .then_some(self.dijkstra_result.source);
+        let changed = source.map(|s| {
+            let (before, after) = (self.data_graph.dijkstra(s), g.dijkstra(s));
+            g.nodes()
+                .into_iter()
+                .filter(|x| before.path_to(*x) != after.path_to(*x))
+                .collect::<Vec<_>>()
+        });
+        let k = k.format(self.weight_precision);
+        self.replace_graph(
+            g,
+            &if scale {
+                format!("已把所有边权乘以 {}", k)
+            } else {
+                format!("已把所有边权加上 {}", k)
+            },
+        );
+
+        let (Some(s), Some(changed)) = (source, changed) else {
+            return;
+        };
+        if changed.is_empty() {
+            self.visit_log
+                .push(format!("从 {} 出发的最短路径都没有改变", s));
+        } else {
+            let list = changed.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            self.visit_log.push(format!(
+                "从 {} 出发到 {} 个节点的最短路径改变了: {}",
+                s,
+                changed.len(),
+                list.join(", ")
+            ));
+            self.flash_nodes = Some((changed.into_iter().collect(), 2.0));
+        }
+    }
+
+    // 只重新计算受这条边影响的节点，距离变化的节点在画布上闪烁
+    fn update_distances(&mut self, u: i64, v: i64) {
+        let changed = if self.forbidden.is_empty() {
+            let Some(changed) = self.dijkstra_result.update_edge(&self.data_graph, u, v) else {
+                self.dijkstra_stale = true;
+                self.visit_log
+                    .push("出现负权边，最短距离已过期".to_string());
+                return;
+            };
+            changed
+        } else {
+            // 增量更新不知道禁行标记，有标记时整个重新计算再比较
+            let view = self.forbidden.view(&self.data_graph);
+            let after = algo::dijkstra(&view, self.dijkstra_result.source);
+            let changed = self
+                .data_graph
+                .nodes()
+                .into_iter()
+                .filter(|x| self.dijkstra_result.dist.get(x) != after.dist.get(x))
+                .collect();
+            self.dijkstra_result = after;
+            changed
+        };
+        self.unreachable = self
+            .data_graph
+            .nodes()
+            .into_iter()
+            .filter(|x| !self.dijkstra_result.dist.contains_key(x) && !self.forbidden.has_node(*x))
+            .collect();
+
+        if changed.is_empty() {
+            self.visit_log.push("最短距离没有变化".to_string());
+            return;
+        }
+        let list = changed.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        self.visit_log.push(format!(
+            "{} 个节点的距离发生变化: {}",
+            changed.len(),
+            list.join(", ")
+        ));
+        self.flash_nodes = Some((changed.into_iter().collect(), 2.0));
+    }
+
+    fn edge_passes_filter(&self, w: f64) -> bool {
+        match self.weight_filter {
+            Some((min, max)) => {
+                min.is_none_or(|m| !w.less_than(m)) && max.is_none_or(|m| !m.less_than(w))
+            }
+            None => true,
+        }
+    }
+
+    // 画布上的边 u-v 是否通过筛选：按 data_graph 中的当前边权判断，力导向图里记下的
+    // 边权（stale）在 +/-、w 修改边权后不会更新，只在边已不存在时使用
+    fn canvas_edge_passes_filter(&self, u: i64, v: i64, stale: f64) -> bool {
+        let w = self
+            .data_graph
+            .edge_weight(u, v)
+            .or_else(|| self.data_graph.edge_weight(v, u))
+            .unwrap_or(stale);
+        self.edge_passes_filter(w)
+    }
+
+    // 以选中节点为起点 BFS，灰显不可达的部分
+    fn toggle_focus(&mut self) {
+        if self.focus.take().is_some() {
+            return;
+        }
+        let Some(u) = self.selected_node else {
+            self.visit_log
+                .push("聚焦模式需要先用 n/N 选择一个节点".to_string());
+            return;
+        };
+
+        let reachable = self.data_graph.reachable_from(u);
+        self.visit_log.push(format!(
+            "聚焦节点 {}: 可达 {}/{} 个节点，Esc 恢复",
+            u,
+            reachable.len(),
+            self.data_graph.nodes().len()
+        ));
+        self.focus = Some(reachable.into_iter().collect());
+    }
+
+    // 把视口中心移到查找到的节点上并让它闪烁
+    fn jump_to(&mut self, query: &str) {
+        let Some(u) = self.data_graph.find_node(query) else {
+            self.visit_log.push(format!("未找到节点: {}", query.trim()));
+            return;
+        };
+
+        let mut pos = None;
+        self.graph.visit_nodes(|node| {
+            if node.data.user_data == u {
+                pos = Some((node.x() as f64, node.y() as f64));
+            }
+        });
+        let Some((x, y)) = pos else {
+            return;
+        };
+
+        self.zoom = self.zoom.max(2.0);
+        self.view_x = x;
+        self.view_y = y;
+        self.pan(0.0, 0.0);
+        self.selected_node = Some(u);
+        self.flash_nodes = Some((HashSet::from([u]), 1.5));
+    }
+
+    // 介数中心性需要从每个节点各跑一次最短路，放到后台线程，界面显示进度
+    fn start_betweenness(&mut self) {
+        if self.job.is_some() {
+            return;
+        }
+        self.current_algorithm = "介数中心性".to_string();
+        self.visit_log.clear();
+
+        let graph = self.data_graph.clone();
+        let progress = Arc::new(AtomicUsize::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (counter, stop) = (Arc::clone(&progress), Arc::clone(&cancel));
+        self.job = Some(Job {
+            name: self.current_algorithm.clone(),
+            meter: JobMeter::Sources(graph.nodes().len()),
+            started: Instant::now(),
+            progress,
+            cancel,
+            handle: thread::spawn(move || {
+                JobOutput::Betweenness(allpairs::betweenness(&graph, &counter, &stop))
+            }),
+        });
+    }
+
+    // NP 难问题的精确求解放到后台线程，界面显示已搜索的状态数和剩余时间，
+    // 预算用完或按 Esc 取消时显示目前找到的最好解
+    fn start_exact(
+        &mut self,
+        name: &str,
+        solve: impl FnOnce(&Graph<f64>, &Budget) -> JobOutput + Send + 'static,
+    ) {
+        if let Some(job) = &self.job {
+            self.visit_log
+                .push(format!("{} 仍在计算，按 Esc 取消后再试", job.name));
+            return;
+        }
+        self.current_algorithm = name.to_string();
+        self.reset_selection_animation();
+        self.data_graph.clear_groups();
+
+        let graph = self.data_graph.clone();
+        let budget = self.exact_budget;
+        self.visit_log.push(format!(
+            "在后台搜索最优解，最多 {} 秒，按 Esc 取消",
+            budget.as_secs()
+        ));
+        let progress = Arc::new(AtomicUsize::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (explored, stop) = (Arc::clone(&progress), Arc::clone(&cancel));
+        self.job = Some(Job {
+            name: name.to_string(),
+            meter: JobMeter::Budget(budget),
+            started: Instant::now(),
+            progress,
+            cancel,
+            handle: thread::spawn(move || solve(&graph, &Budget::new(budget, &stop, &explored))),
+        });
+    }
+
+    fn update_job(&mut self) {
+        if !self
+            .job
+            .as_ref()
+            .is_some_and(|job| job.handle.is_finished())
+        {
+            return;
+        }
+        let job = self.job.take().unwrap();
+        self.bell.cue(Cue::Complete);
+        let cancelled = job.cancel.load(Ordering::Relaxed);
+        let explored = job.progress.load(Ordering::Relaxed);
+        let elapsed = job.started.elapsed();
+        let Ok(output) = job.handle.join() else {
+            self.visit_log.push("计算失败".to_string());
+            return;
+        };
+
+        let finished = match &output {
+            JobOutput::Betweenness(_) => true,
+            JobOutput::Tsp(r) => r.finished,
+            JobOutput::Coloring(r) => r.finished,
+            JobOutput::VertexCover(r) => r.finished,
+        };
+        if !matches!(output, JobOutput::Betweenness(_)) {
+            self.visit_log.push(if finished {
+                format!(
+                    "已求得最优解：搜索了 {} 个状态，用时 {:.1} 秒",
+                    explored,
+                    elapsed.as_secs_f64()
+                )
+            } else {
+                format!(
+                    "{}，以下是搜索 {} 个状态后找到的最好解，不一定最优",
+                    if cancelled {
+                        "已取消"
+                    } else {
+                        "时间预算用完"
+                    },
+                    explored
+                )
+            });
+        }
+
+        match output {
+            JobOutput::Betweenness(Some(centrality)) => self.show_betweenness(centrality),
+            JobOutput::Betweenness(None) => self
+                .visit_log
+                .push(format!("已取消，完成了 {} 个源点", explored)),
+            JobOutput::Tsp(r) => match r.best {
+                Some((tour, cost)) => {
+                    let mut text: Vec<String> = tour.iter().map(|u| u.to_string()).collect();
+                    text.push(tour[0].to_string());
+                    self.visit_log.push(format!(
+                        "回路总权重 {}: {}",
+                        cost.format(self.weight_precision),
+                        text.join(" → ")
+                    ));
+                    self.visited_edges.extend(
+                        tour.iter()
+                            .zip(tour.iter().cycle().skip(1))
+                            .map(|(&u, &v)| (u, v)),
+                    );
+                    self.visited_nodes.extend(tour);
+                }
+                None => self.visit_log.push(if finished {
+                    "不存在经过每个节点恰好一次的回路".to_string()
+                } else {
+                    "还没有找到经过每个节点恰好一次的回路".to_string()
+                }),
+            },
+            JobOutput::Coloring(r) => match r.best {
+                Some(colors) => {
+                    let count = colors.values().map(|&c| c + 1).max().unwrap_or(0);
+                    self.visit_log
+                        .push(format!("用 {} 种颜色，相邻节点颜色不同:", count));
+                    let mut members: BTreeMap<usize, Vec<i64>> = BTreeMap::new();
+                    for (&u, &c) in &colors {
+                        members.entry(c).or_default().push(u);
+                        self.data_graph.set_group(u, &format!("颜色{}", c + 1));
+                    }
+                    for (c, nodes) in members {
+                        let list: Vec<String> = nodes.iter().map(|u| u.to_string()).collect();
+                        self.visit_log
+                            .push(format!("  颜色{}: {}", c + 1, list.join(", ")));
+                    }
+                }
+                None => self
+                    .visit_log
+                    .push("有自环的节点与自己相邻，无法着色".to_string()),
+            },
+            JobOutput::VertexCover(r) => {
+                let cover = r.best.unwrap_or_default();
+                let list: Vec<String> = cover.iter().map(|u| u.to_string()).collect();
+                self.visit_log.push(format!(
+                    "顶点覆盖共 {} 个节点: {}",
+                    cover.len(),
+                    list.join(", ")
+                ));
+                self.visited_nodes.extend(cover);
+            }
+        }
+    }
+
+    fn show_betweenness(&mut self, centrality: HashMap<i64, f64>) {
+        info!("介数中心性计算完成，共 {} 个节点", centrality.len());
+
+        let mut ranked: Vec<(i64, f64)> = centrality.iter().map(|(&u, &c)| (u, c)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.visit_log.push("介数中心性（从高到低）:".to_string());
+        for (u, c) in ranked.into_iter().take(10) {
+            self.visit_log
+                .push(format!("  节点{}: {}", u, c.format(self.weight_precision)));
+        }
+        self.visit_log
+            .push("导出节点指标 CSV 时会包含这一列".to_string());
+        self.centrality = Some(centrality);
+    }
+
+    // 每 0.5 秒检查一次监视的文件，修改时间变化时重新读入；
+    // 仍存在的节点保持原来的位置和固定状态。文件正写到一半无法解析时保留当前图，等下次修改
+    fn update_watch(&mut self) {
+        let Some((path, seen)) = &self.watch else {
+            return;
+        };
+        self.watch_timer += self.dt;
+        if seen.is_some() && self.watch_timer < 0.5 {
+            return;
+        }
+        self.watch_timer = 0.0;
+        let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            return;
+        };
+        if *seen == Some(modified) {
+            return;
+        }
+        let path = path.clone();
+        self.watch = Some((path.clone(), Some(modified)));
+
+        match read_graph_file(&path) {
+            Ok((g, turns)) => {
+                self.pinned.retain(|u, _| g.contains_node(*u));
+                self.data_graph = g;
+                self.turns = turns;
+                self.reload_keep_positions();
+                self.current_algorithm = "监视文件".to_string();
+                self.visit_log.push(format!(
+                    "已重新读入 {}: {} 个节点、{} 条边",
+                    path.display(),
+                    self.data_graph.nodes().len(),
+                    self.data_graph.edges().len()
+                ));
+            }
+            Err(e) => {
+                self.visit_log
+                    .push(format!("读入 {} 失败，保留当前图: {}", path.display(), e))
+            }
+        }
+    }
+
+    // 是否为"导出"分组中写文件的菜单项
+    fn is_export_item(&self, name: &str) -> bool {
+        self.registry
+            .iter()
+            .any(|a| a.category == "导出" && a.name.to_lowercase() == name.to_lowercase())
+    }
+
+    // 命令是否会运行"导出"分组中写文件的菜单项
+    #[cfg(feature = "remote")]
+    fn writes_files(&self, command: &Command<f64>) -> bool {
+        let names = match command {
+            Command::Run { algorithm, .. } => std::slice::from_ref(algorithm),
+            Command::Playlist(names) => names.as_slice(),
+            _ => return false,
+        };
+        names.iter().any(|name| self.is_export_item(name))
+    }
+
+    // 处理远程控制收到的请求：改图和运行算法与命令栏走同一条路径，
+    // 应答附上执行期间新增的日志行
+    #[cfg(feature = "remote")]
+    fn update_remote(&mut self) {
+        while let Some(pending) = self.remote.as_ref().and_then(|s| s.poll()) {
+            let request = match pending.request {
+                Ok(ref request) => request.clone(),
+                Err(ref e) => {
+                    let e = e.clone();
+                    pending.reply(Response::error(e));
+                    continue;
+                }
+            };
+            let response = match request.to_command() {
+                None => Response::state(self.remote_state()),
+                Some(Err(e)) => Response::error(e),
+                Some(Ok(command)) if self.writes_files(&command) => {
+                    Response::error("远程请求不能运行导出类菜单项".to_string())
+                }
+                Some(Ok(command)) => {
+                    info!("远程请求: {:?}", request);
+                    // 改图会先清空日志，这时整段日志都是新的
+                    let before = self.visit_log.clone();
+                    self.apply_command(command);
+                    let new = if self.visit_log.starts_with(&before) {
+                        before.len()
+                    } else {
+                        0
+                    };
+                    Response::ok(self.visit_log[new..].to_vec())
+                }
+            };
+            pending.reply(response);
+        }
+    }
+
+    #[cfg(feature = "remote")]
+    fn remote_state(&self) -> remote::State {
+        let mut visited: Vec<i64> = self.visited_nodes.iter().copied().collect();
+        visited.sort_unstable();
+        let skip = self.visit_log.len().saturating_sub(20);
+        remote::State {
+            nodes: self.data_graph.nodes(),
+            edges: self
+                .data_graph
+                .edges()
+                .into_iter()
+                .map(|(u, v, w, t)| (u, v, w, t == EdgeType::Single))
+                .collect(),
+            algorithm: self.current_algorithm.clone(),
+            source: self.source,
+            visited,
+            log: self.visit_log[skip..].to_vec(),
+        }
+    }
+
+    fn update_flash(&mut self) {
+        if let Some((_, remaining)) = &mut self.flash_nodes {
+            *remaining -= self.dt;
+            if *remaining <= 0.0 {
+                self.flash_nodes = None;
+            }
+        }
+    }
+
+    pub fn init_graph(&mut self) {
+        self.graph_version += 1;
+        self.metrics.record(&self.data_graph);
+        self.forbidden.retain(&self.data_graph);
+        self.turns.retain(&self.data_graph);
+        // 有方案 B 时增删节点或边对两套方案同时生效
+        if let Some((_, other)) = &mut self.scenario {
+            *other = scenario::follow_topology(&self.data_graph, other);
+        }
+        let mut rng = rand::rng();
+
+        let mut nodes = self.data_graph.nodes();
+
+        if nodes.is_empty() {
+            let n1_idx = self.graph.add_node(NodeData {
+                x: self.anchor_x as f32,
+                y: self.anchor_y as f32,
+                is_anchor: true,
+                user_data: 1,
+                ..Default::default()
+            });
+            self.anchor_idx = Some(n1_idx);
+            return;
+        }
+
+        nodes.sort();
+
+        let mut id_to_idx: HashMap<i64, DefaultNodeIdx> = HashMap::new();
+        let mut anchor_idx: Option<DefaultNodeIdx> = None;
+
+        for node_id in nodes {
+            let is_anchor = node_id == 1;
+            let pin = self.pinned.get(&node_id);
+            let (x, y) = if let Some(&(px, py)) = pin {
+                (px as f32, py as f32)
+            } else if is_anchor {
+                (self.anchor_x as f32, self.anchor_y as f32)
+            } else {
+                (rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0))
+            };
+
+            let idx = self.graph.add_node(NodeData {
+                x,
+                y,
+                is_anchor: is_anchor || pin.is_some(),
+                user_data: node_id,
+                ..Default::default()
+            });
+
+            if is_anchor {
+                anchor_idx = Some(idx);
+            }
+
+            id_to_idx.insert(node_id, idx);
+        }
+
+        self.anchor_idx = anchor_idx;
+
+        for (u, v, w, edge_type) in self.data_graph.edges() {
+            if let (Some(&u_idx), Some(&v_idx)) = (id_to_idx.get(&u), id_to_idx.get(&v)) {
+                let user_data = (w, edge_type);
+                self.graph.add_edge(u_idx, v_idx, EdgeData { user_data });
+            }
+        }
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        self.data_graph = [
+            (5, 9, 650.0),
+            (7, 9, 1140.0),
+            (9, 10, 750.0),
+            (4, 8, 960.0),
+            (5, 8, 820.0),
+            (5, 4, 1200.0),
+            (8, 10, 680.0),
+            (9, 8, 800.0),
+            (6, 2, 340.0),
+            (6, 7, 840.0),
+            (1, 3, 1380.0),
+            (1, 2, 900.0),
+            (1, 4, 680.0),
+            (1, 5, 530.0),
+            (3, 2, 2500.0),
+            (3, 4, 2600.0),
+            (7, 5, 570.0),
+        ]
+        .into_iter()
+        .collect();
+        self.data_graph.add_node(11);
+
+        self.init_graph();
+        if let Some(problem) = self.two_sat.take() {
+            self.load_two_sat(&problem);
+        }
+        for line in std::mem::take(&mut self.startup_commands) {
+            self.execute_command(&line);
+        }
+        while !self.exit {
+            self.handle_events()?;
+
+            self.update_animation();
+            self.update_replay();
+            self.update_bell();
+            self.update_random_walk();
+            self.update_playback();
+            self.update_flash();
+            self.update_job();
+            self.update_watch();
+            #[cfg(feature = "remote")]
+            self.update_remote();
+            self.update_contraction();
+            self.update_karger();
+            self.update_implicit();
+            self.update_facts();
+
+            if self.layout != LayoutKind::Force {
+                self.step_layout();
+                terminal.draw(|frame| self.draw(frame))?;
+                continue;
+            }
+
+            let limit_x = (self.screen_max_x - self.r) as f32;
+            let limit_y = (self.screen_max_y - self.r) as f32;
+            if let Some(idx) = self.anchor_idx {
+                let tx = self.anchor_x as f32;
+                let ty = self.anchor_y as f32;
+
+                self.graph.visit_nodes_mut(|node| {
+                    if node.index() == idx {
+                        node.data.x = tx;
+                        node.data.y = ty;
+                    } else {
+                        node.data.x = node.data.x.clamp(-limit_x, limit_x);
+                        node.data.y = node.data.y.clamp(-limit_y, limit_y);
+                    }
+                });
+            }
+
+            if !self.frozen && !self.cooling.is_settled() {
+                let mut before = Vec::new();
+                self.graph
+                    .visit_nodes(|node| before.push((node.data.x, node.data.y)));
+                self.graph.update(self.dt as f32);
+
+                // 碰撞处理：相邻节点的圆之间至少留出一个半径的空隙，标签不互相遮挡
+                let mut ids = Vec::new();
+                let mut positions = Vec::new();
+                let mut fixed = Vec::new();
+                self.graph.visit_nodes(|node| {
+                    ids.push(node.data.user_data);
+                    positions.push((node.data.x as f64, node.data.y as f64));
+                    fixed.push(node.data.is_anchor);
+                });
+                if let Some(scale) = self.edge_length_scale {
+                    let springs = self.weighted_springs(&ids, scale);
+                    layout::spring_lengths(&mut positions, &fixed, &springs, 0.05);
+                }
+                layout::separate(&mut positions, &fixed, self.r * 3.0);
+
+                // 本帧节点的最大位移，持续很小时停止模拟
+                let mut max_move: f64 = 0.0;
+                let mut i = 0;
+                self.graph.visit_nodes_mut(|node| {
+                    let (x, y) = positions[i];
+                    let (bx, by) = before[i];
+                    i += 1;
+                    node.data.x = x as f32;
+                    node.data.y = y as f32;
+                    max_move = max_move.max((x - bx as f64).hypot(y - by as f64));
+                });
+                self.cooling.observe(max_move);
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(self.horizontal_split_ratio),
+                Constraint::Percentage(100 - self.horizontal_split_ratio),
+            ])
+            .split(frame.area());
+
+        let (half_w, half_h) = self.view_half_size();
+        let graph_title = match &self.prompt {
+            Some(Prompt::Search(query)) => format!("Graph  查找节点(ID/标签): {}_", query),
+            Some(Prompt::EdgeWeight(input)) => format!("Graph  修改边权(u v 权重): {}_", input),
+            Some(Prompt::Command(input)) => {
+                format!("Graph  :{}_  {}", input, command::hint::<f64>(input))
+            }
+            Some(Prompt::Param {
+                name,
+                values,
+                input,
+            }) => {
+                let label = self
+                    .registry
+                    .get(name)
+                    .and_then(|a| a.params.get(values.len()))
+                    .map_or("", |p| p.label);
+                format!("Graph  {}: {}_", label, input)
+            }
+            _ if self.fullscreen => String::new(),
+            _ if self.frozen => "Graph  [物理已冻结，按 s 恢复]".to_string(),
+            _ if self.replay.is_some() => "Graph  [循环演示，按 R 关闭]".to_string(),
+            _ if let Some((side, _)) = &self.scenario => {
+                format!("Graph  [权重方案 {}，按 B 切换]", side.name())
+            }
+            _ if self.tabs.len() > 1 => format!("Graph  {}", self.tab_bar()),
+            _ => "Graph".to_string(),
+        };
+        // 全屏时画布占满整个窗口，不显示侧栏
+        let canvas_area = if self.fullscreen {
+            frame.area()
+        } else {
+            chunks[0]
+        };
+        // 一列字符对应的画布宽度，用于按显示宽度居中标签
+        let cell_w = half_w * 2.0 / canvas_area.width.saturating_sub(2).max(1) as f64;
+        let canva = Canvas::default()
+            .block(Block::default().title(graph_title).borders(Borders::ALL))
+            .x_bounds([self.view_x - half_w, self.view_x + half_w])
+            .y_bounds([self.view_y - half_h, self.view_y + half_h])
+            .paint(|ctx| self.render_ctx(ctx, cell_w));
+
+        frame.render_widget(canva, canvas_area);
+        self.canvas_area = canvas_area;
+        self.timeline_area = None;
+        if self.timeline_visible() {
+            self.render_timeline(frame, canvas_area);
+        }
+
+        if self.zoom > 1.0 {
+            self.render_minimap(frame, canvas_area);
+        }
+
+        if self.fullscreen {
+            self.render_overlay(frame, canvas_area);
+        } else {
+            self.draw_side_panel(frame, chunks[1]);
+        }
+
+        if self.monochrome {
+            monochrome::apply(frame.buffer_mut());
+        }
+    }
+
+    // 画布底边内侧的时间轴：已点亮步数/总步数、最后点亮的一步和进度条
+    fn render_timeline(&mut self, frame: &mut Frame, area: Rect) {
+        if area.height < 4 || area.width < 20 {
+            return;
+        }
+        let (shown, total) = self.animation.progress();
+        let current = match self.animation.current() {
+            Some(AnimationStep::Node { node, .. }) => format!("节点 {}", node),
+            Some(AnimationStep::Edge { edge: (u, v), .. }) => format!("边 {}→{}", u, v),
+            None => "开始".to_string(),
+        };
+        let label = format!(" {}/{} {} ", shown, total, current);
+        let row = Rect {
+            x: area.x + 1,
+            y: area.y + area.height - 2,
+            width: area.width - 2,
+            height: 1,
+        };
+        let gauge = LineGauge::default()
+            .ratio(shown as f64 / total.max(1) as f64)
+            .label(label.clone().fg(Color::Black).bg(Color::Gray))
+            .filled_style(ratatui::style::Style::default().fg(Color::Yellow))
+            .unfilled_style(ratatui::style::Style::default().fg(Color::DarkGray))
+            .line_set(ratatui::symbols::line::THICK);
+        frame.render_widget(Clear, row);
+        frame.render_widget(gauge, row);
+
+        // LineGauge 在文字后空一列再画进度条
+        let start = (text::width(&label) as u16 + 1).min(row.width);
+        self.timeline_area = Some(Rect {
+            x: row.x + start,
+            width: row.width - start,
+            ..row
+        });
+    }
+
+    // 全屏时在画布右上角显示当前算法和动画进度，不遮挡图的主体
+    fn render_overlay(&self, frame: &mut Frame, area: Rect) {
+        if self.current_algorithm.is_empty() {
+            return;
+        }
+        let mut text = self.current_algorithm.clone();
+        if !self.animation.is_empty() {
+            let (shown, total) = self.animation.progress();
+            text = format!("{}  {}/{}", text, shown, total);
+        }
+        let width = (text::width(&text) as u16 + 2).min(area.width.saturating_sub(2));
+        if width == 0 || area.height < 3 {
+            return;
+        }
+        let overlay = Rect {
+            x: area.x + area.width - width - 1,
+            y: area.y + 1,
+            width,
+            height: 1,
+        };
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(
+            Paragraph::new(format!(" {}", text)).style(
+                ratatui::style::Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Gray),
+            ),
+            overlay,
+        );
+    }
+
+    // 当前结果是否为单源最短路径（dijkstra_result 有效）
+    fn shows_distances(&self) -> bool {
+        matches!(
+            self.current_algorithm.as_str(),
+            "Dijkstra" | "DAG 最短路径" | "Bellman-Ford"
+        )
+    }
+
+    // 趋势面板：边数、连通分量数和最小生成森林权重随图的改变而变化的迷你折线，最新的在右
+    fn render_trend(&self, frame: &mut Frame, area: Rect) {
+        let Some(last) = self.metrics.last() else {
+            return;
+        };
+        let precision = self.weight_precision;
+        let series = [
+            ("边数", self.metrics.edges(), last.edges.to_string()),
+            (
+                "连通分量",
+                self.metrics.components(),
+                last.components.to_string(),
+            ),
+            (
+                "最小生成森林权重",
+                self.metrics.mst_weights(),
+                last.mst_weight.format(precision),
+            ),
+        ];
+        let rows = Layout::vertical([Constraint::Ratio(1, 3); 3]).split(area);
+        for ((name, values, current), row) in series.into_iter().zip(rows.iter()) {
+            let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let title = format!(
+                "{} {}（最小 {}，最大 {}）",
+                name,
+                current,
+                min.format(precision),
+                max.format(precision)
+            );
+            // 只显示放得下的最近若干次
+            let width = row.width.saturating_sub(2) as usize;
+            let levels = metrics::sparkline_levels(&values, 8);
+            let levels = &levels[levels.len().saturating_sub(width)..];
+            let sparkline = Sparkline::default()
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .data(levels)
+                .max(8)
+                .style(ratatui::style::Style::default().fg(Color::Cyan));
+            frame.render_widget(sparkline, *row);
+        }
+    }
+
+    // 最短路径结果的距离表：节点 | 距离 | 前驱 | 路径，不可达节点排在最后
+    fn render_distance_table(&mut self, frame: &mut Frame, area: Rect) {
+        if !self.shows_distances() || self.dijkstra_result.dist.is_empty() {
+            let hint = Paragraph::new("运行 Dijkstra、Bellman-Ford 或 DAG 最短路径后显示距离表")
+                .block(
+                    Block::default()
+                        .title("距离")
+                        .borders(Borders::ALL)
+                        .padding(Padding::uniform(1)),
+                );
+            frame.render_widget(hint, area);
+            return;
+        }
+
+        let precision = self.weight_precision;
+        let mut rows: Vec<Row> = self
+            .dijkstra_result
+            .rows(self.distance_order)
+            .into_iter()
+            .map(|row| {
+                let path: Vec<String> = row.path.iter().map(|u| u.to_string()).collect();
+                Row::new([
+                    Cell::from(row.node.to_string()),
+                    Cell::from(row.dist.format(precision)),
+                    Cell::from(row.parent.map_or("-".to_string(), |p| p.to_string())),
+                    Cell::from(path.join(" -> ")),
+                ])
+            })
+            .collect();
+        let mut unreachable: Vec<i64> = self.unreachable.iter().copied().collect();
+        unreachable.sort();
+        rows.extend(unreachable.into_iter().map(|u| {
+            Row::new([
+                u.to_string(),
+                "∞".to_string(),
+                "-".to_string(),
+                "不可达".to_string(),
+            ])
+            .style(ratatui::style::Style::default().fg(Color::DarkGray))
+        }));
+
+        let order = match self.distance_order {
+            DistanceOrder::Node => "按节点",
+            DistanceOrder::Distance => "按距离",
+        };
+        let stale = if self.dijkstra_stale {
+            "，已过期"
+        } else {
+            ""
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(6),
+                Constraint::Min(8),
+            ],
+        )
+        .header(
+            Row::new(["节点", "距离", "前驱", "路径"])
+                .style(ratatui::style::Style::default().fg(Color::Yellow)),
+        )
+        .block(
+            Block::default()
+                .title(format!("距离（{}{}，o 切换排序，j/k 滚动）", order, stale))
+                .borders(Borders::ALL),
+        )
+        .row_highlight_style(
+            ratatui::style::Style::default()
+                .bg(Color::Blue)
+                .fg(Color::White),
+        );
+        frame.render_stateful_widget(table, area, &mut self.distance_table);
+    }
+
+    // 右侧侧栏：面板标签与当前面板
+    fn draw_side_panel(&mut self, frame: &mut Frame, area: Rect) {
+        // 侧栏顶部一行是面板标签，下面整块显示当前面板
+        let [tabs_area, panel_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+        let tabs = Tabs::new(Panel::ALL.map(Panel::title))
+            .select(self.panel.index())
+            .highlight_style(
+                ratatui::style::Style::default()
+                    .bg(Color::Blue)
+                    .fg(Color::White),
+            );
+        frame.render_widget(tabs, tabs_area);
+
+        let mut log_lines = self.playback_lines();
+        log_lines.extend(self.edit_lines());
+        log_lines.extend(self.selection_lines());
+        log_lines.extend(self.quiz_lines());
+        // 输入筛选时即时预览
+        let query = match &self.prompt {
+            Some(Prompt::LogFilter(query)) => query.trim(),
+            _ => self.log_query.as_str(),
+        };
+        let filtering = self.log_events != LogEvents::All || !query.is_empty();
+        let shown = trace::filter_log(&self.visit_log, self.log_events, query);
+        let filter_note = filtering.then(|| {
+            let mut note = format!(
+                "  [{} {}/{}",
+                self.log_events.title(),
+                shown.len(),
+                self.visit_log.len()
+            );
+            if !query.is_empty() {
+                note.push_str(&format!(" \"{}\"", query));
+            }
+            note.push(']');
+            note
+        });
+        log_lines.extend(shown.into_iter().map(str::to_string));
+
+        let animation_complete = !self.animation.is_playing();
+
+        if animation_complete
+            && !self.current_algorithm.is_empty()
+            && self.walk_token.is_none()
+            && self.karger.is_none()
+            && self.implicit.is_none()
+            && self.quiz.is_none()
+            && self.tutorial.is_none()
+        {
+            log_lines.push("".to_string());
+            log_lines.push("---- 结束 ----\n".to_string());
+
+            match self.current_algorithm.as_str() {
+                "DFS" => {
+                    log_lines.extend(self.unreachable_lines());
+                }
+                "BFS" => {
+                    log_lines.extend(self.unreachable_lines());
+                    log_lines.push(match self.layout {
+                        LayoutKind::Tree => "按 t 恢复力导向布局".to_string(),
+                        _ => "按 t 切换树形布局".to_string(),
+                    });
+                }
+                "Prim" => {
+                    log_lines.push("最小生成树的边:".to_string());
+                    for &(u, v, w) in &self.prim_result.edges {
+                        log_lines.push(format!(
+                            "  {} - {}: {}",
+                            u,
+                            v,
+                            w.format(self.weight_precision)
+                        ));
+                    }
+                    log_lines.push(format!(
+                        "最小生成树总长度: {}",
+                        self.prim_result.total_weight.format(self.weight_precision)
+                    ));
+                    if !self.prim_result.is_spanning {
+                        log_lines.push(format!(
+                            "图不连通，只覆盖了节点 {} 所在的连通分量",
+                            self.source
+                        ));
+                    }
+                }
+                "拓扑排序" => {
+                    if let Some(order) = self.data_graph.topological_sort() {
+                        let order: Vec<String> = order.iter().map(|u| u.to_string()).collect();
+                        log_lines.push(format!("拓扑序: {}", order.join(" ")));
+                    }
+                }
+                "Bellman-Ford" if self.negative_cycle.is_some() => {
+                    if let Some(cycle) = &self.negative_cycle {
+                        let nodes: Vec<String> =
+                            cycle.nodes.iter().map(|u| u.to_string()).collect();
+                        log_lines.push(format!(
+                            "负环: {} -> {}，总权重 {}",
+                            nodes.join(" -> "),
+                            cycle.nodes[0],
+                            cycle.weight.format(self.weight_precision)
+                        ));
+                        log_lines.push("沿负环绕圈距离可以无限减小，最短距离没有意义".to_string());
+                    }
+                }
+                "Dijkstra" | "DAG 最短路径" | "Bellman-Ford" => {
+                    if self.dijkstra_stale {
+                        log_lines.push("边权已修改，以下结果已过期".to_string());
+                    }
+                    log_lines.push(format!(
+                        "{} 个节点可达，{} 个不可达",
+                        self.dijkstra_result.dist.len(),
+                        self.unreachable.len()
+                    ));
+                    log_lines.push("最短距离、前驱与路径见\"距离\"面板（Tab 切换）".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        let log_text = log_lines.join("\n");
+        let mut info_title = if self.current_algorithm.is_empty() {
+            "请选择算法".to_string()
+        } else {
+            self.current_algorithm.clone()
+        };
+        if let Some(Prompt::LogFilter(query)) = &self.prompt {
+            info_title.push_str(&format!("  筛选: {}_", query));
+        } else if let Some(note) = filter_note {
+            info_title.push_str(&note);
+        }
+
+        let info_widget = Paragraph::new(log_text).block(
+            Block::default()
+                .title(info_title)
+                .borders(Borders::ALL)
+                .padding(Padding::uniform(1)),
+        );
+
+        let mut info_area = panel_area;
+        if let Some(job) = &self.job {
+            let [gauge_area, rest] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(info_area);
+            let done = job.progress.load(Ordering::Relaxed);
+            let (ratio, label) = match job.meter {
+                JobMeter::Sources(total) => (
+                    done as f64 / total.max(1) as f64,
+                    format!("{}/{} 个源点", done, total),
+                ),
+                JobMeter::Budget(budget) => {
+                    let elapsed = job.started.elapsed().as_secs_f64();
+                    (
+                        (elapsed / budget.as_secs_f64()).min(1.0),
+                        format!(
+                            "已搜索 {} 个状态，{:.0}/{} 秒，Esc 取消",
+                            done,
+                            elapsed,
+                            budget.as_secs()
+                        ),
+                    )
+                }
+            };
+            let gauge = Gauge::default()
+                .block(
+                    Block::default()
+                        .title(format!("{}计算中", job.name))
+                        .borders(Borders::ALL),
+                )
+                .gauge_style(Color::Cyan)
+                .ratio(ratio)
+                .label(label);
+            frame.render_widget(gauge, gauge_area);
+            info_area = rest;
+        }
+
+        // 面板比例小于 100 时，日志以外的面板下方再显示日志
+        let mut log_area = None;
+        if self.panel_split_ratio < 100 && self.panel != Panel::Log {
+            let [top, bottom] = Layout::vertical([
+                Constraint::Percentage(self.panel_split_ratio),
+                Constraint::Percentage(100 - self.panel_split_ratio),
+            ])
+            .areas(info_area);
+            info_area = top;
+            log_area = Some(bottom);
+        }
+
+        let panel_widget = |title: &str, lines: Vec<String>| {
+            Paragraph::new(lines.join("\n")).block(
+                Block::default()
+                    .title(title.to_string())
+                    .borders(Borders::ALL)
+                    .padding(Padding::uniform(1)),
+            )
+        };
+        match self.panel {
+            Panel::Menu => {
+                let menu_widget = Menu::new()
+                    .block(Block::default().title("Menu").borders(Borders::ALL))
+                    .highlight_style(
+                        ratatui::style::Style::default()
+                            .bg(Color::Blue)
+                            .fg(Color::White),
+                    ); // 设置高亮样式
+                frame.render_stateful_widget(menu_widget, info_area, &mut self.menu);
+            }
+            Panel::Inspector => {
+                let mut lines = self.inspector_lines();
+                if lines.is_empty() {
+                    lines.push("按 n/N 选择节点".to_string());
+                }
+                frame.render_widget(panel_widget("节点", lines), info_area);
+            }
+            Panel::Frontier => {
+                frame.render_widget(panel_widget("队列", self.frontier_lines()), info_area)
+            }
+            Panel::Distances => self.render_distance_table(frame, info_area),
+            Panel::Trend => self.render_trend(frame, info_area),
+            Panel::Log => frame.render_widget(info_widget.clone(), info_area),
+        }
+        if let Some(area) = log_area {
+            frame.render_widget(info_widget, area);
+        }
+    }
+
+    fn render_ctx(&self, ctx: &mut Context, cell_w: f64) {
+        if self.show_grid {
+            self.render_grid(ctx);
+        }
+        if let Some((x, y)) = self.placing {
+            let size = self.r * 3.0;
+            for (x1, y1, x2, y2) in [(x - size, y, x + size, y), (x, y - size, x, y + size)] {
+                ctx.draw(&CanvaLine {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color: Color::Red,
+                });
+            }
+        }
+        self.render_groups(ctx, cell_w);
+
+        // 筛选后仍有可见边的节点，其余节点灰显
+        let mut connected: HashSet<i64> = HashSet::new();
+        self.graph.visit_edges(|node1, node2, edge_data| {
+            let (u, v) = (node1.data.user_data, node2.data.user_data);
+            if self.canvas_edge_passes_filter(u, v, edge_data.user_data.0) {
+                connected.insert(u);
+                connected.insert(v);
+            }
+        });
+
+        self.graph.visit_edges(|node1, node2, edge_data| {
+            let u = node1.data.user_data;
+            let v = node2.data.user_data;
+            if !self.canvas_edge_passes_filter(u, v, edge_data.user_data.0) {
+                return;
+            }
+
+            let is_visited =
+                self.visited_edges.contains(&(u, v)) || self.visited_edges.contains(&(v, u));
+
+            let from = (node1.x() as f64, node1.y() as f64);
+            let to = (node2.x() as f64, node2.y() as f64);
+            let points = if self.curved_edges {
+                layout::quad_bezier(from, to, 0.15, 12)
+            } else {
+                vec![from, to]
+            };
+            let dimmed = self
+                .focus
+                .as_ref()
+                .is_some_and(|set| !set.contains(&u) || !set.contains(&v));
+            let is_cut = self.current_algorithm == "Karger 最小割"
+                && (self.cut_edges.contains(&(u, v)) || self.cut_edges.contains(&(v, u)));
+            let path_color = if self.current_algorithm.ends_with("不相交路径")
+                || self.current_algorithm == "A/B 方案对比"
+                || self.current_algorithm == "转向限制最短路径"
+                || self.current_algorithm == "地标 A*"
+            {
+                self.path_colors
+                    .get(&(u, v))
+                    .or_else(|| self.path_colors.get(&(v, u)))
+                    .map(|&i| GROUP_COLORS[i % GROUP_COLORS.len()])
+            } else {
+                None
+            };
+            let is_selected = self.edit_mode
+                && self
+                    .selected_edge
+                    .is_some_and(|e| e == (u, v) || e == (v, u));
+            let is_forbidden = self.forbidden.has_edge(u, v);
+            let color = if is_selected {
+                Color::White
+            } else if dimmed || is_forbidden {
+                Color::DarkGray
+            } else if is_cut {
+                Color::Red
+            } else if let Some(c) = path_color {
+                c
+            } else if is_visited {
+                Color::Yellow
+            } else {
+                Color::LightBlue
+            };
+
+            // 选中的边在两侧各多画一条，显得更粗
+            if is_selected {
+                for side in [-0.5, 0.5] {
+                    for pair in points.windows(2) {
+                        let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+                        let len = (dx * dx + dy * dy).sqrt().max(1e-9);
+                        let (ox, oy) = (-dy / len * cell_w * side, dx / len * cell_w * side);
+                        ctx.draw(&CanvaLine {
+                            x1: pair[0].0 + ox,
+                            y1: pair[0].1 + oy,
+                            x2: pair[1].0 + ox,
+                            y2: pair[1].1 + oy,
+                            color,
+                        });
+                    }
+                }
+            }
+            if self.accessible && is_visited && !self.curved_edges {
+                draw_dashed(ctx, from.0, from.1, to.0, to.1, color);
+            } else {
+                // 无障碍模式下已访问的弧线隔一段画一段，形成虚线
+                let dashed = self.accessible && is_visited;
+                for (i, pair) in points.windows(2).enumerate() {
+                    if dashed && i % 2 == 1 {
+                        continue;
+                    }
+                    ctx.draw(&CanvaLine {
+                        x1: pair[0].0,
+                        y1: pair[0].1,
+                        x2: pair[1].0,
+                        y2: pair[1].1,
+                        color,
+                    });
+                }
+            }
+
+            // 单向边在终点一侧画箭头；画布上的边按 u -> v 加入，终点即 v
+            if edge_data.user_data.1 == EdgeType::Single {
+                let last = points.len() - 1;
+                draw_arrowhead(ctx, points[last - 1], points[last], self.r, color);
+            }
+
+            // 标签放在曲线中点
+            let (mid_x, mid_y) = if self.curved_edges {
+                points[points.len() / 2]
+            } else {
+                ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0)
+            };
+            // 禁行的边在中点画一个红叉
+            if is_forbidden {
+                draw_cross(ctx, mid_x, mid_y, self.r, Color::Red);
+            }
+            let record = self
+                .data_graph
+                .edge(u, v)
+                .or_else(|| self.data_graph.edge(v, u));
+            let value = match record {
+                Some(e) => e.get(self.edge_attr),
+                None => edge_data.user_data.0,
+            };
+            let text = match record.and_then(|e| e.label.as_deref()) {
+                Some(label) => format!(
+                    "{}:{}",
+                    text::truncate(label, 8),
+                    value.abbreviated(self.weight_precision)
+                ),
+                None => value.abbreviated(self.weight_precision),
+            };
+            print_centered(ctx, mid_x, mid_y, &text, cell_w, Color::White);
+        });
+
+        self.graph.visit_nodes(|node| {
+            let node_id = node.data.user_data;
+            let is_visited = self.visited_nodes.contains(&node_id);
+
+            let color = if self.walk_token == Some(node_id) {
+                Color::Red
+            } else if self.weight_filter.is_some() && !connected.contains(&node_id)
+                || self
+                    .focus
+                    .as_ref()
+                    .is_some_and(|set| !set.contains(&node_id))
+            {
+                Color::DarkGray
+            } else if is_visited {
+                Color::Yellow
+            } else if self.current_algorithm == "支配集"
+                && self
+                    .dominated
+                    .iter()
+                    .any(|(u, gained)| self.visited_nodes.contains(u) && gained.contains(&node_id))
+            {
+                Color::Green
+            } else if self.unreachable.contains(&node_id) || self.forbidden.has_node(node_id) {
+                Color::DarkGray
+            } else {
+                Color::LightBlue
+            };
+            // 无障碍模式下已访问的节点用同心圆填满
+            let rings = if self.accessible && is_visited { 4 } else { 1 };
+            for i in 0..rings {
+                ctx.draw(&Circle {
+                    x: node.x() as f64,
+                    y: node.y() as f64,
+                    radius: self.r * (rings - i) as f64 / rings as f64,
+                    color,
+                });
+            }
+            let mut label = match self.original_ids.get(&node_id) {
+                Some(original) => format!("{}({})", node_id, original),
+                None => node_id.to_string(),
+            };
+            if let Some(name) = self.data_graph.node_label(node_id) {
+                label = format!("{} {}", label, text::truncate(name, 12));
+            }
+            if self.accessible && is_visited {
+                label = format!("*{}", label);
+            }
+            print_centered(
+                ctx,
+                node.x() as f64,
+                node.y() as f64,
+                &label,
+                cell_w,
+                Color::Yellow,
+            );
+
+            // 禁行的节点用红叉划掉
+            if self.forbidden.has_node(node_id) {
+                draw_cross(
+                    ctx,
+                    node.x() as f64,
+                    node.y() as f64,
+                    self.r * 1.2,
+                    Color::Red,
+                );
+            }
+
+            // 闪烁：每 0.25 秒切换一次外圈
+            if let Some((nodes, remaining)) = &self.flash_nodes
+                && nodes.contains(&node_id)
+                && (remaining / 0.25) as i64 % 2 == 0
+            {
+                ctx.draw(&Circle {
+                    x: node.x() as f64,
+                    y: node.y() as f64,
+                    radius: self.r * 2.0,
+                    color: Color::Red,
+                });
+            }
+
+            if self.selected_node == Some(node_id) {
+                ctx.draw(&Circle {
+                    x: node.x() as f64,
+                    y: node.y() as f64,
+                    radius: self.r * 1.4,
+                    color: Color::White,
+                });
+            }
+            if self.multi_selected.contains(&node_id) {
+                ctx.draw(&Circle {
+                    x: node.x() as f64,
+                    y: node.y() as f64,
+                    radius: self.r * 1.2,
+                    color: Color::Magenta,
+                });
+            }
+        });
+    }
+
+    // 动画当前进度对应的算法状态：BFS 队列、DFS 栈、Dijkstra/Prim 的候选节点及暂定值
+    fn frontier_lines(&self) -> Vec<String> {
+        let Some((algorithm, snapshots)) = &self.frontier else {
+            return vec!["运行 DFS/BFS/Dijkstra/Prim 后显示队列".to_string()];
+        };
+        let Some(snapshot) = self
+            .shown_steps
+            .checked_sub(1)
+            .and_then(|i| snapshots.get(i.min(snapshots.len().saturating_sub(1))))
+        else {
+            return vec!["算法还没有开始".to_string()];
+        };
+
+        let mut lines = vec![
+            format!("第 {}/{} 步", snapshot.step + 1, snapshots.len()),
+            format!("  {}", snapshot.event.explain(self.weight_precision)),
+            String::new(),
+        ];
+        let name = match algorithm {
+            TraceAlgorithm::Dfs => "栈（栈底在前）",
+            TraceAlgorithm::Bfs => "队列（队首在前）",
+            TraceAlgorithm::Dijkstra | TraceAlgorithm::Prim => "候选（按暂定值从小到大）",
+        };
+        lines.push(format!("{}:", name));
+        if snapshot.frontier.is_empty() {
+            lines.push("  （空）".to_string());
+        }
+        for u in &snapshot.frontier {
+            match snapshot.dist.get(u) {
+                Some(d) => lines.push(format!("  节点{}: {}", u, d.format(self.weight_precision))),
+                None => lines.push(format!("  节点{}", u)),
+            }
+        }
+        let visited: Vec<String> = snapshot.visited.iter().map(|u| u.to_string()).collect();
+        lines.push(String::new());
+        lines.push(format!("已访问: {}", visited.join(", ")));
+        lines
+    }
+
+    fn unreachable_lines(&self) -> Vec<String> {
+        let mut unreachable: Vec<_> = self.unreachable.iter().collect();
+        if unreachable.is_empty() {
+            return Vec::new();
+        }
+        unreachable.sort();
+        let list = unreachable
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>();
+        vec![format!("不可达节点: {}", list.join(", "))]
+    }
+
+    // 选中节点时，在信息面板顶部显示节点的分组与属性
+    fn inspector_lines(&self) -> Vec<String> {
+        let Some(u) = self.selected_node else {
+            return Vec::new();
+        };
+
+        let mut lines = vec![
+            format!("[节点 {}]", u),
+            format!("  度: {}", self.data_graph.degree(u)),
+        ];
+        if let Some(group) = self.data_graph.group_of(u) {
+            lines.push(format!("  分组: {}", group));
+        }
+        if let Some(&(x, y)) = self.pinned.get(&u) {
+            lines.push(format!("  固定于: ({:.2}, {:.2})", x, y));
+        }
+        for (key, value) in self.data_graph.node_attrs(u) {
+            lines.push(format!("  {}: {}", key, value));
+        }
+        lines.push(String::new());
+        lines
+    }
+
+    fn edit_lines(&self) -> Vec<String> {
+        if let Some((x, y)) = self.placing {
+            return vec![
+                "[摆放模式] 方向键移动光标，回车放置新节点；".to_string(),
+                "  先用 n/N 选中节点再回车则把它固定到光标处，Esc 退出".to_string(),
+                format!("  光标: ({:.2}, {:.2})", x, y),
+                String::new(),
+            ];
+        }
+        if !self.edit_mode {
+            return Vec::new();
+        }
+        let mut lines = vec![
+            "[编辑模式] n/N 或鼠标点击选择边，d 切换方向，w 修改权重，x 删除，Esc 退出".to_string(),
+        ];
+        if let Some((u, v)) = self.selected_edge {
+            let dir = match self.data_graph.direction(u, v) {
+                Some(EdgeDirection::Forward) => format!("{} → {}", u, v),
+                Some(EdgeDirection::Backward) => format!("{} → {}", v, u),
+                Some(EdgeDirection::Both) => format!("{} ↔ {}", u, v),
+                None => "已删除".to_string(),
+            };
+            lines.push(format!("  选中边: {}", dir));
+        }
+        lines.push(String::new());
+        lines
+    }
+
+    fn selection_lines(&self) -> Vec<String> {
+        if self.multi_selected.is_empty() {
+            return Vec::new();
+        }
+        let list: Vec<String> = self.multi_selected.iter().map(|u| u.to_string()).collect();
+        vec![
+            format!("[多选 {} 个] {}", list.len(), list.join(", ")),
+            "  方向键整体移动，菜单\"选择\"中执行批量操作".to_string(),
+            String::new(),
+        ]
+    }
+
+    fn toggle_multi_select(&mut self) {
+        let Some(u) = self.selected_node else {
+            return;
+        };
+        if !self.multi_selected.remove(&u) {
+            self.multi_selected.insert(u);
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        if self.multi_selected.is_empty() {
+            self.visit_log
+                .push("没有多选的节点（空格加入选择）".to_string());
+            return;
+        }
+        let mut g = self.data_graph.clone();
+        for &u in &self.multi_selected {
+            g.remove_node(u);
+            self.pinned.remove(&u);
+        }
+        let count = self.multi_selected.len();
+        self.multi_selected.clear();
+        self.replace_graph(g, &format!("已删除 {} 个节点及其关联边", count));
+    }
+
+    // 把选中的节点固定在当前位置
+    fn pin_selected(&mut self) {
+        let selected = &self.multi_selected;
+        let mut positions = Vec::new();
+        self.graph.visit_nodes_mut(|node| {
+            if selected.contains(&node.data.user_data) {
+                node.data.is_anchor = true;
+                positions.push((node.data.user_data, node.data.x as f64, node.data.y as f64));
+            }
+        });
+        for &(u, x, y) in &positions {
+            self.pinned.insert(u, (x, y));
+        }
+        self.visit_log
+            .push(format!("已固定 {} 个节点", positions.len()));
+    }
+
+    // 多选的节点整体平移；固定的节点同时更新固定位置
+    fn move_selected(&mut self, dx: f64, dy: f64) {
+        let (limit_x, limit_y) = (self.screen_max_x - self.r, self.screen_max_y - self.r);
+        let selected = &self.multi_selected;
+        let pinned = &mut self.pinned;
+        self.graph.visit_nodes_mut(|node| {
+            let u = node.data.user_data;
+            if !selected.contains(&u) {
+                return;
+            }
+            let x = (node.data.x as f64 + dx).clamp(-limit_x, limit_x);
+            let y = (node.data.y as f64 + dy).clamp(-limit_y, limit_y);
+            node.data.x = x as f32;
+            node.data.y = y as f32;
+            if let Some(pin) = pinned.get_mut(&u) {
+                *pin = (x, y);
+            }
+        });
+        // 节点 1 每帧都会被拉回锚点
+        if self.multi_selected.contains(&1) {
+            self.move_anchor(dx, dy);
+        }
+        self.cooling.wake();
+    }
+
+    fn extract_selected(&mut self) {
+        if self.multi_selected.is_empty() {
+            self.visit_log
+                .push("没有多选的节点（空格加入选择）".to_string());
+            return;
+        }
+        let nodes: Vec<i64> = self.multi_selected.iter().copied().collect();
+        let sub = self.data_graph.induced_subgraph(&nodes);
+        let name = format!("子图{}", self.tabs.len());
+        self.open_tab(&name, sub);
+        self.current_algorithm = "提取子图".to_string();
+        self.visit_log.push(format!(
+            "{} 个节点的导出子图已放入新标签页\"{}\"，< / > 切换标签页",
+            nodes.len(),
+            name
+        ));
+    }
+
+    // 多选的两个节点作为查询的 (起点, 终点)：起点为其中当前选中的节点，都没选中时取编号小的。
+    // 没有恰好多选两个节点时在日志中提示并返回 None
+    fn picked_pair(&mut self) -> Option<(i64, i64)> {
+        let picked: Vec<i64> = self.multi_selected.iter().copied().collect();
+        let [a, b] = picked[..] else {
+            self.visit_log
+                .push("需要恰好多选两个节点（空格加入选择）".to_string());
+            return None;
+        };
+        Some(if self.selected_node == Some(b) {
+            (b, a)
+        } else {
+            (a, b)
+        })
+    }
+
+    // 两点查询出结果前的准备：换成名为 name 的算法，清掉上一次的日志、高亮、动画和路径配色
+    fn begin_query(&mut self, name: &str) {
+        self.current_algorithm = name.to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+        self.path_colors.clear();
+    }
+
+    // 多选的两个节点之间两两不相交的路径，每条路径一种颜色，起点取法见 picked_pair。
+    // 路径条数即两点之间的边（点）连通度
+    fn run_disjoint_paths(&mut self, vertex: bool) {
+        let Some((s, t)) = self.picked_pair() else {
+            return;
+        };
+        let (name, paths) = if vertex {
+            ("点不相交路径", self.data_graph.vertex_disjoint_paths(s, t))
+        } else {
+            ("边不相交路径", self.data_graph.edge_disjoint_paths(s, t))
+        };
+        self.begin_query(name);
+
+        self.visit_log.push(format!(
+            "{} 到 {} 的{}连通度为 {}",
+            s,
+            t,
+            if vertex { "点" } else { "边" },
+            paths.len()
+        ));
+        for (i, path) in paths.iter().enumerate() {
+            let text: Vec<String> = path.iter().map(|u| u.to_string()).collect();
+            self.visit_log
+                .push(format!("  路径{}: {}", i + 1, text.join(" → ")));
+            for w in path.windows(2) {
+                self.path_colors.insert((w[0], w[1]), i);
+            }
+        }
+        self.visited_nodes.extend([s, t]);
+    }
+
+    // 从 s 出发绕开禁行标记到不了的节点，禁行节点本身不算在内
+    fn unreachable_avoiding(&self, s: i64) -> HashSet<i64> {
+        let (reached, _) = algo::bfs(&self.forbidden.view(&self.data_graph), s);
+        let reached: HashSet<i64> = reached.into_iter().collect();
+        self.data_graph
+            .nodes()
+            .into_iter()
+            .filter(|u| !reached.contains(u) && !self.forbidden.has_node(*u))
+            .collect()
+    }
+
+    // 编辑模式下切换选中边的禁行标记，否则切换选中节点的
+    fn toggle_forbidden(&mut self) {
+        match (self.edit_mode, self.selected_edge, self.selected_node) {
+            (true, Some((u, v)), _) => self.forbid_edge(u, v),
+            (_, _, Some(u)) => self.forbid_node(u),
+            _ => self
+                .visit_log
+                .push("禁行需要先用 n/N 选择节点，或在编辑模式下选择边".to_string()),
+        }
+    }
+
+    fn forbid_node(&mut self, u: i64) {
+        if !self.data_graph.contains_node(u) {
+            self.visit_log.push(format!("节点 {} 不存在", u));
+            return;
+        }
+        let on = self.forbidden.toggle_node(u);
+        self.forbidden_changed(&format!("节点 {}", u), on);
+    }
+
+    fn forbid_edge(&mut self, u: i64, v: i64) {
+        if self.data_graph.edge(u, v).is_none() && self.data_graph.edge(v, u).is_none() {
+            self.visit_log.push(format!("边 {}-{} 不存在", u, v));
+            return;
+        }
+        let on = self.forbidden.toggle_edge(u, v);
+        self.forbidden_changed(&format!("边 {}-{}", u, v), on);
+    }
+
+    // 禁行标记改变后，已显示的最短距离不再对应当前的限制
+    fn forbidden_changed(&mut self, what: &str, on: bool) {
+        self.visit_log.push(if on {
+            format!("{} 已禁行，最短路径查询会绕开它", what)
+        } else {
+            format!("{} 已解除禁行", what)
+        });
+        if self.current_algorithm == "Dijkstra" && !self.dijkstra_stale {
+            self.dijkstra_stale = true;
+            self.visit_log
+                .push("最短距离已过期，重新运行 Dijkstra 以绕开禁行标记".to_string());
+        }
+    }
+
+    // 把路径上的边标成第 color 种颜色，已被另一条路径标过的边改为共用的第 2 种
+    fn paint_path(&mut self, path: &[i64], color: usize) {
+        for w in path.windows(2) {
+            let key = if self.path_colors.contains_key(&(w[1], w[0])) {
+                (w[1], w[0])
+            } else {
+                (w[0], w[1])
+            };
+            let shared = self.path_colors.get(&key).is_some_and(|&c| c != color);
+            self.path_colors.insert(key, if shared { 2 } else { color });
+        }
+    }
+
+    fn set_turn(&mut self, from: i64, via: i64, to: i64, penalty: Option<f64>) {
+        for (u, v) in [(from, via), (via, to)] {
+            if !self.data_graph.contains_edge(u, v) {
+                self.visit_log.push(format!("边 {} -> {} 不存在", u, v));
+                return;
+            }
+        }
+        let rule = penalty.map_or(TurnRule::Ban, TurnRule::Penalty);
+        self.turns.set((from, via, to), rule);
+        let turn = format!("{} → {} → {}", from, via, to);
+        self.visit_log.push(match rule {
+            TurnRule::Ban => format!("已禁止转向 {}", turn),
+            TurnRule::Penalty(0.0) => format!("已取消转向 {} 的限制", turn),
+            TurnRule::Penalty(p) => {
+                format!("转向 {} 罚时 {}", turn, p.format(self.weight_precision))
+            }
+        });
+    }
+
+    // 多选的两个节点之间考虑转向限制的最短路径（紫色）与不考虑限制的最短路径（绿色）对比，
+    // 两者共用的路段青色。两者都绕开禁行标记
+    fn run_turn_route(&mut self) {
+        let Some((s, t)) = self.picked_pair() else {
+            return;
+        };
+        let view = self.forbidden.view(&self.data_graph);
+        let route = turns::shortest_path(&view, &self.turns, s, t);
+        let plain = scenario::shortest_path(&view, s, t);
+
+        self.begin_query("转向限制最短路径");
+
+        let precision = self.weight_precision;
+        if self.turns.is_empty() {
+            self.visit_log.push(
+                "还没有转向限制：用 turn <from> <via> <to> [罚时] 添加，或写在项目文件中"
+                    .to_string(),
+            );
+        } else {
+            self.visit_log
+                .push(format!("共 {} 条转向限制", self.turns.len()));
+        }
+        match &route {
+            Some(route) => {
+                let text: Vec<String> = route.path.iter().map(|u| u.to_string()).collect();
+                self.visit_log.push(format!(
+                    "考虑转向限制: {}，长度 {}（搜索确定了 {} 个边状态）",
+                    text.join(" → "),
+                    route.dist.format(precision),
+                    route.settled
+                ));
+                for ((from, via, to), p) in &route.penalties {
+                    self.visit_log.push(format!(
+                        "  转向 {} → {} → {} 罚时 {}",
+                        from,
+                        via,
+                        to,
+                        p.format(precision)
+                    ));
+                }
+            }
+            None => self
+                .visit_log
+                .push(format!("考虑转向限制时 {} 不可达 {}", s, t)),
+        }
+        match &plain {
+            Some((dist, path)) => {
+                let text: Vec<String> = path.iter().map(|u| u.to_string()).collect();
+                self.visit_log.push(format!(
+                    "不考虑转向限制: {}，长度 {}",
+                    text.join(" → "),
+                    dist.format(precision)
+                ));
+            }
+            None => self.visit_log.push(format!("{} 不可达 {}", s, t)),
+        }
+        if let (Some(route), Some((_, path))) = (&route, &plain)
+            && route.path == *path
+        {
+            self.visit_log.push("转向限制没有改变路线".to_string());
+        }
+
+        if let Some(route) = route {
+            self.paint_path(&route.path, 0);
+        }
+        if let Some((_, path)) = plain {
+            self.paint_path(&path, 1);
+        }
+        self.visited_nodes.extend([s, t]);
+    }
+
+    // 在多选的两个节点之间用地标（ALT）启发函数做 A*，与同一查询上到终点为止的 Dijkstra 比较
+    // 展开的节点数。展开过的节点高亮，路径标色
+    fn run_landmark_astar(&mut self) {
+        let Some((s, t)) = self.picked_pair() else {
+            return;
+        };
+        if self.data_graph.edges().iter().any(|&(_, _, w, _)| w < 0.0) {
+            self.visit_log.push("地标 A* 要求边权非负".to_string());
+            return;
+        }
+
+        let view = self.forbidden.view(&self.data_graph);
+        let reused = self
+            .landmarks
+            .as_ref()
+            .is_some_and(|(g, f, _)| *g == self.data_graph && *f == self.forbidden);
+        if !reused {
+            let chosen = Landmarks::select(&view, landmarks::DEFAULT_LANDMARKS);
+            self.landmarks = Some((self.data_graph.clone(), self.forbidden.clone(), chosen));
+        }
+        let Some((_, _, chosen)) = &self.landmarks else {
+            return;
+        };
+        let alt = chosen.search(&view, s, t);
+        let plain = landmarks::astar(&view, s, t, |_| 0.0);
+        let nodes: Vec<String> = chosen.nodes.iter().map(|u| u.to_string()).collect();
+        let count = chosen.len();
+
+        self.begin_query("地标 A*");
+
+        self.visit_log.push(if reused {
+            format!("地标: {}（沿用上次的预处理）", nodes.join(" "))
+        } else {
+            format!(
+                "地标: {}（预处理做了 {} 次 Dijkstra）",
+                nodes.join(" "),
+                count * 2
+            )
+        });
+        match &alt.route {
+            Some((dist, path)) => {
+                let text: Vec<String> = path.iter().map(|u| u.to_string()).collect();
+                self.visit_log.push(format!(
+                    "最短路径: {}，长度 {}",
+                    text.join(" → "),
+                    dist.format(self.weight_precision)
+                ));
+            }
+            None => self.visit_log.push(format!("{} 不可达 {}", s, t)),
+        }
+        let (ours, theirs) = (alt.expanded.len(), plain.expanded.len());
+        self.visit_log.push(format!(
+            "地标 A* 展开 {} 个节点，Dijkstra 展开 {} 个",
+            ours, theirs
+        ));
+        if ours < theirs {
+            self.visit_log.push(format!(
+                "少展开 {} 个（{:.0}%）",
+                theirs - ours,
+                (theirs - ours) as f64 / theirs as f64 * 100.0
+            ));
+        }
+
+        if let Some((_, path)) = &alt.route {
+            self.paint_path(path, 0);
+        }
+        self.visited_nodes.extend(alt.expanded);
+    }
+
+    // 在两套权重方案之间切换；第一次按时把当前边权复制一份作为方案 B。
+    // 两套方案共用节点位置和拓扑（增删节点或边两边同步），各自用编辑模式的 w/+/- 修改边权
+    fn toggle_scenario(&mut self) {
+        let created = self.scenario.is_none();
+        let (side, other) = match self.scenario.take() {
+            Some((side, other)) => (side.other(), other),
+            None => (Side::B, self.data_graph.clone()),
+        };
+        let previous = std::mem::replace(&mut self.data_graph, other);
+        self.scenario = Some((side, previous));
+        self.reload_keep_positions();
+        self.current_algorithm = "权重方案".to_string();
+        if created {
+            self.visit_log
+                .push("已复制当前边权作为方案 B，修改边权后按 B 切回方案 A".to_string());
+        }
+        self.visit_log
+            .push(format!("当前为权重方案 {}", side.name()));
+    }
+
+    // 多选的两个节点在方案 A、B 下的最短路径：A 的路径紫色、B 的绿色、两者共用的青色，
+    // 只是边权不同的边红色
+    fn compare_scenarios(&mut self) {
+        if self.scenario.is_none() {
+            self.visit_log
+                .push("还没有方案 B：按 B 复制当前边权作为方案 B，修改后再对比".to_string());
+            return;
+        }
+        let Some((s, t)) = self.picked_pair() else {
+            return;
+        };
+        let Some((side, other)) = &self.scenario else {
+            return;
+        };
+        let (graph_a, graph_b) = match side {
+            Side::A => (&self.data_graph, other),
+            Side::B => (other, &self.data_graph),
+        };
+        let comparison = Comparison::avoiding(graph_a, graph_b, s, t, &self.forbidden);
+
+        self.begin_query("A/B 方案对比");
+
+        let precision = self.weight_precision;
+        for (name, route, color) in [("A", &comparison.a, 0), ("B", &comparison.b, 1)] {
+            let Some((dist, path)) = route else {
+                self.visit_log
+                    .push(format!("方案 {}: {} 不可达 {}", name, s, t));
+                continue;
+            };
+            let text: Vec<String> = path.iter().map(|u| u.to_string()).collect();
+            self.visit_log.push(format!(
+                "方案 {}: {}，长度 {}",
+                name,
+                text.join(" → "),
+                dist.format(precision)
+            ));
+            self.paint_path(path, color);
+        }
+        if comparison.same_route() {
+            self.visit_log.push("两套方案走同一条路径".to_string());
+        }
+        self.visit_log
+            .push(format!("边权不同的边 {} 条:", comparison.changed.len()));
+        for (u, v, wa, wb) in comparison.changed {
+            self.visit_log.push(format!(
+                "  {}-{}: {} → {}",
+                u,
+                v,
+                wa.format(precision),
+                wb.format(precision)
+            ));
+            if !self.path_colors.contains_key(&(v, u)) {
+                self.path_colors.entry((u, v)).or_insert(3);
+            }
+        }
+        self.visited_nodes.extend([s, t]);
+    }
+
+    fn run_multi_bfs(&mut self) {
+        if self.multi_selected.is_empty() {
+            self.visit_log
+                .push("没有多选的节点（空格加入选择）".to_string());
+            return;
+        }
+        let sources: Vec<i64> = self.multi_selected.iter().copied().collect();
+        self.current_algorithm = "多源 BFS".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
+        self.visit_log.clear();
+
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
+
+        let (nodes, _) = self.data_graph.bfs_multi(&sources);
+        self.unreachable = self
+            .data_graph
+            .nodes()
+            .into_iter()
+            .filter(|u| !nodes.contains(u))
+            .collect();
+        self.load_steps(self.data_graph.bfs_multi_steps(&sources));
+
+        // 所有起点一开始就已访问，动画从起点之后的第一条边开始
+        for _ in 0..sources.len().min(nodes.len()) {
+            self.start_animation();
+        }
+    }
+
+    fn copy_selected(&mut self) {
+        if self.multi_selected.is_empty() {
+            self.visit_log
+                .push("没有多选的节点（空格加入选择）".to_string());
+            return;
+        }
+        let nodes: Vec<i64> = self.multi_selected.iter().copied().collect();
+        let piece = self.data_graph.induced_subgraph(&nodes);
+        self.visit_log.push(format!(
+            "已复制 {} 个节点，切换标签页后按 p 粘贴",
+            nodes.len()
+        ));
+        self.clipboard = Some(piece);
+    }
+
+    // 粘贴后新节点成为多选，可直接用方向键挪到合适的位置
+    fn paste_clipboard(&mut self) {
+        let Some(piece) = &self.clipboard else {
+            self.visit_log
+                .push("剪贴板为空，先多选节点按 y 复制".to_string());
+            return;
+        };
+        let mut g = self.data_graph.clone();
+        let mapping = g.paste(piece);
+        let renamed: Vec<String> = mapping
+            .iter()
+            .filter(|(old, new)| old != new)
+            .map(|(old, new)| format!("{}→{}", old, new))
+            .collect();
+
+        self.replace_graph(g, &format!("已粘贴 {} 个节点", mapping.len()));
+        if !renamed.is_empty() {
+            self.visit_log
+                .push(format!("编号冲突，已重新编号: {}", renamed.join(", ")));
+        }
+        self.multi_selected = mapping.into_values().collect();
+    }
+
+    fn tab_bar(&self) -> String {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                if i == self.active_tab {
+                    format!("[{}:{}]", i + 1, tab.name)
+                } else {
+                    format!(" {}:{} ", i + 1, tab.name)
+                }
+            })
+            .collect()
+    }
+
+    // 当前页的内容存回 tabs，换入第 i 页
+    fn switch_tab(&mut self, i: usize) {
+        if i == self.active_tab || i >= self.tabs.len() {
+            return;
+        }
+        let current = &mut self.tabs[self.active_tab];
+        std::mem::swap(&mut current.graph, &mut self.data_graph);
+        std::mem::swap(&mut current.pinned, &mut self.pinned);
+        std::mem::swap(&mut current.undo_stack, &mut self.undo_stack);
+        std::mem::swap(&mut current.metrics, &mut self.metrics);
+        std::mem::swap(&mut current.scenario, &mut self.scenario);
+        std::mem::swap(&mut current.forbidden, &mut self.forbidden);
+        std::mem::swap(&mut current.turns, &mut self.turns);
+
+        let next = &mut self.tabs[i];
+        std::mem::swap(&mut next.graph, &mut self.data_graph);
+        std::mem::swap(&mut next.pinned, &mut self.pinned);
+        std::mem::swap(&mut next.undo_stack, &mut self.undo_stack);
+        std::mem::swap(&mut next.metrics, &mut self.metrics);
+        std::mem::swap(&mut next.scenario, &mut self.scenario);
+        std::mem::swap(&mut next.forbidden, &mut self.forbidden);
+        std::mem::swap(&mut next.turns, &mut self.turns);
+        self.active_tab = i;
+
+        self.multi_selected.clear();
+        self.original_ids.clear();
+        self.original_ids_undo.clear();
+        self.dijkstra_stale = false;
+        self.reload_graph();
+    }
+
+    fn open_tab(&mut self, name: &str, graph: Graph<f64>) {
+        self.tabs.push(Tab {
+            name: name.to_string(),
+            graph,
+            ..Default::default()
+        });
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    fn cycle_tab(&mut self, forward: bool) {
+        let n = self.tabs.len();
+        let next = if forward {
+            (self.active_tab + 1) % n
+        } else {
+            (self.active_tab + n - 1) % n
+        };
+        self.switch_tab(next);
+    }
+
+    fn toggle_placing(&mut self) {
+        self.placing = match self.placing {
+            Some(_) => None,
+            None => Some((self.view_x, self.view_y)),
+        };
+    }
+
+    fn move_cursor(&mut self, dx: f64, dy: f64) {
+        let Some((x, y)) = &mut self.placing else {
+            return;
+        };
+        let step = 0.2 / self.zoom;
+        *x = (*x + dx * step).clamp(-self.screen_max_x, self.screen_max_x);
+        *y = (*y + dy * step).clamp(-self.screen_max_y, self.screen_max_y);
+    }
+
+    // 选中了节点时把它固定到光标处，否则在光标处新建一个固定的节点（编号为最大编号 + 1）
+    fn place_node(&mut self) {
+        let Some((x, y)) = self.placing else {
+            return;
+        };
+
+        let id = match self.selected_node.take() {
+            Some(u) => {
+                self.visit_log
+                    .push(format!("节点 {} 固定在 ({:.2}, {:.2})", u, x, y));
+                u
+            }
+            None => {
+                let u = self.data_graph.nodes().last().map_or(1, |&max| max + 1);
+                self.data_graph.add_node(u);
+                self.graph_version += 1;
+                let idx = self.graph.add_node(NodeData {
+                    x: x as f32,
+                    y: y as f32,
+                    is_anchor: true,
+                    user_data: u,
+                    ..Default::default()
+                });
+                if u == 1 {
+                    self.anchor_idx = Some(idx);
+                }
+                self.visit_log
+                    .push(format!("在 ({:.2}, {:.2}) 放置节点 {}", x, y, u));
+                u
+            }
+        };
+
+        self.pinned.insert(id, (x, y));
+        self.cooling.wake();
+        // 节点 1 每帧都会被拉回锚点，固定它就是移动锚点
+        if id == 1 {
+            self.anchor_x = x;
+            self.anchor_y = y;
+        }
+        self.graph.visit_nodes_mut(|node| {
+            if node.data.user_data == id {
+                node.data.x = x as f32;
+                node.data.y = y as f32;
+                node.data.is_anchor = true;
+            }
+        });
+    }
+
+    fn toggle_edit_mode(&mut self) {
+        self.edit_mode = !self.edit_mode;
+        self.selected_edge = None;
+        if self.edit_mode {
+            self.select_next_edge(true);
+        }
+    }
+
+    // 按 (小编号, 大编号) 排序后循环选择，两个方向的边算同一条
+    fn select_next_edge(&mut self, forward: bool) {
+        let mut edges: Vec<(i64, i64)> = self
+            .data_graph
+            .edges()
+            .into_iter()
+            .filter(|(u, v, _, _)| u != v)
+            .map(|(u, v, _, _)| (u.min(v), u.max(v)))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        if edges.is_empty() {
+            self.selected_edge = None;
+            return;
+        }
+
+        let next = match self
+            .selected_edge
+            .and_then(|e| edges.iter().position(|&x| x == e))
+        {
+            Some(i) if forward => (i + 1) % edges.len(),
+            Some(i) => (i + edges.len() - 1) % edges.len(),
+            None if forward => 0,
+            None => edges.len() - 1,
+        };
+        self.selected_edge = Some(edges[next]);
+    }
+
+    // 鼠标点击画布：选中离点击位置最近的边（不超过两列字符宽），并进入编辑模式
+    fn click_canvas(&mut self, column: u16, row: u16) {
+        let Some(p) = self.screen_to_canvas(column, row) else {
+            return;
+        };
+        let (half_w, _) = self.view_half_size();
+        let cell_w = half_w * 2.0 / self.canvas_area.width.saturating_sub(2).max(1) as f64;
+        let (edges, lines): (Vec<(i64, i64)>, Vec<Polyline>) =
+            self.edge_polylines().into_iter().unzip();
+        let Some(i) = layout::nearest_polyline(p, &lines, cell_w * 2.0) else {
+            return;
+        };
+        let (u, v) = edges[i];
+        self.edit_mode = true;
+        self.selected_edge = Some((u.min(v), u.max(v)));
+    }
+
+    // 终端的行列换算成画布坐标，点在画布边框外时返回 None
+    fn screen_to_canvas(&self, column: u16, row: u16) -> Option<(f64, f64)> {
+        let area = self.canvas_area;
+        let (left, top) = (area.x + 1, area.y + 1);
+        let (width, height) = (area.width.saturating_sub(2), area.height.saturating_sub(2));
+        if column < left || row < top || column >= left + width || row >= top + height {
+            return None;
+        }
+        let (half_w, half_h) = self.view_half_size();
+        let fx = (column - left) as f64 + 0.5;
+        let fy = (row - top) as f64 + 0.5;
+        Some((
+            self.view_x - half_w + fx / width as f64 * half_w * 2.0,
+            self.view_y + half_h - fy / height as f64 * half_h * 2.0,
+        ))
+    }
+
+    // 画布上每条可见边的折线，与 render_ctx 画出的一致
+    fn edge_polylines(&self) -> Vec<((i64, i64), Polyline)> {
+        let mut lines = Vec::new();
+        self.graph.visit_edges(|node1, node2, edge_data| {
+            let (u, v) = (node1.data.user_data, node2.data.user_data);
+            if !self.canvas_edge_passes_filter(u, v, edge_data.user_data.0) {
+                return;
+            }
+            let from = (node1.x() as f64, node1.y() as f64);
+            let to = (node2.x() as f64, node2.y() as f64);
+            let points = if self.curved_edges {
+                layout::quad_bezier(from, to, 0.15, 12)
+            } else {
+                vec![from, to]
+            };
+            lines.push(((u, v), points));
+        });
+        lines
+    }
+
+    fn edit_selected_weight(&mut self) {
+        if let Some((u, v)) = self.selected_edge {
+            self.prompt = Some(Prompt::EdgeWeight(format!("{} {} ", u, v)));
+        }
+    }
+
+    // 编辑模式下 +/- 把选中边的权重加减 1，标签即时更新；刚运行过 Dijkstra 时直接增量更新距离
+    fn nudge_selected_weight(&mut self, delta: f64) {
+        let Some((u, v)) = self.selected_edge else {
+            return;
+        };
+        let mut weight = None;
+        self.data_graph.edit_edge(u, v, |e| {
+            e.weight += delta;
+            weight = Some(e.weight);
+        });
+        let Some(w) = weight else {
+            return;
+        };
+        self.graph_version += 1;
+        self.visit_log.push(format!(
+            "边 {}-{} 的权重改为 {}",
+            u,
+            v,
+            w.format(self.weight_precision)
+        ));
+
+        if self.current_algorithm != "Dijkstra" || self.dijkstra_stale {
+            return;
+        }
+        if w < 0.0 {
+            // Dijkstra 不支持负权，不再增量更新
+            self.dijkstra_stale = true;
+            self.visit_log
+                .push("出现负权边，最短距离已过期".to_string());
+        } else {
+            self.update_distances(u, v);
+        }
+    }
+
+    fn delete_selected_edge(&mut self) {
+        let Some((u, v)) = self.selected_edge.take() else {
+            return;
+        };
+        let mut g = self.data_graph.clone();
+        if !g.remove_edge(u, v, EdgeType::Both) {
+            return;
+        }
+        self.replace_graph(g, &format!("已删除边 {}-{}", u, v));
+        self.edit_mode = true;
+        self.select_next_edge(true);
+    }
+
+    // 选中边的方向按 u→v、v→u、双向 循环切换（可撤销），重建画布以更新箭头
+    fn cycle_edge_direction(&mut self) {
+        let Some((u, v)) = self.selected_edge else {
+            return;
+        };
+        let Some(dir) = self.data_graph.direction(u, v) else {
+            return;
+        };
+        let next = dir.next();
+        let mut g = self.data_graph.clone();
+        g.set_direction(u, v, next);
+        let message = match next {
+            EdgeDirection::Forward => format!("边 {}-{} 改为 {} → {}", u, v, u, v),
+            EdgeDirection::Backward => format!("边 {}-{} 改为 {} → {}", u, v, v, u),
+            EdgeDirection::Both => format!("边 {}-{} 改为双向", u, v),
+        };
+        self.replace_graph(g, &message);
+    }
+
+    // 按节点 ID 顺序循环选择节点
+    fn select_next_node(&mut self, forward: bool) {
+        let mut nodes = Vec::new();
+        self.graph
+            .visit_nodes(|node| nodes.push(node.data.user_data));
+        nodes.sort();
+        if nodes.is_empty() {
+            return;
+        }
+
+        let next = match self
+            .selected_node
+            .and_then(|u| nodes.iter().position(|&n| n == u))
+        {
+            Some(i) if forward => (i + 1) % nodes.len(),
+            Some(i) => (i + nodes.len() - 1) % nodes.len(),
+            None if forward => 0,
+            None => nodes.len() - 1,
+        };
+        self.selected_node = Some(nodes[next]);
+    }
+
+    // 边长按权重调整的缩放系数，留空时恢复等长的弹簧
+    fn set_edge_length_scale(&mut self, input: &str) {
+        if input.trim().is_empty() {
+            self.edge_length_scale = None;
+            self.visit_log.push("边长不再按权重调整".to_string());
+        } else {
+            match input.trim().parse::<f64>() {
+                Ok(scale) if scale > 0.0 => {
+                    self.edge_length_scale = Some(scale);
+                    self.visit_log
+                        .push(format!("边长按权重调整，缩放系数 {}", scale));
+                }
+                _ => {
+                    self.visit_log
+                        .push(format!("缩放系数需要是正数: {}", input));
+                    return;
+                }
+            }
+        }
+        self.cooling.wake();
+    }
+
+    // 按 ids 中的下标给出每条边的静止长度，权重按平均边权归一化；
+    // 太短的边不小于碰撞距离，非正的边权按最短处理
+    fn weighted_springs(&self, ids: &[i64], scale: f64) -> Vec<(usize, usize, f64)> {
+        let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let edges: Vec<(i64, i64, f64)> = self
+            .data_graph
+            .edges()
+            .into_iter()
+            .filter(|(u, v, _, _)| u != v)
+            .map(|(u, v, w, _)| (u, v, w))
+            .collect();
+        let positive: Vec<f64> = edges.iter().map(|e| e.2).filter(|&w| w > 0.0).collect();
+        if positive.is_empty() {
+            return Vec::new();
+        }
+        let mean = positive.iter().sum::<f64>() / positive.len() as f64;
+
+        edges
+            .into_iter()
+            .filter_map(|(u, v, w)| {
+                let rest = (scale * w / mean * 4.0).max(self.r * 3.0);
+                Some((*index.get(&u)?, *index.get(&v)?, rest))
+            })
+            .collect()
+    }
+
+    // 移动中心节点，其余节点要跟着重新受力，恢复模拟
+    fn move_anchor(&mut self, dx: f64, dy: f64) {
+        let limit_x = self.screen_max_x - self.r;
+        let limit_y = self.screen_max_y - self.r;
+        self.anchor_x = (self.anchor_x + dx).clamp(-limit_x, limit_x);
+        self.anchor_y = (self.anchor_y + dy).clamp(-limit_y, limit_y);
+        self.cooling.wake();
+    }
+
+    // 调整画布与侧栏的宽度比例
+    fn resize_canvas(&mut self, delta: i16) {
+        let (min, max) = Config::CANVAS_RANGE;
+        self.horizontal_split_ratio =
+            (self.horizontal_split_ratio as i16 + delta).clamp(min as i16, max as i16) as u16;
+    }
+
+    // 调整侧栏中当前面板与下方日志的高度比例，到 100 时不再分割
+    fn resize_panel(&mut self, delta: i16) {
+        let (min, max) = Config::PANEL_RANGE;
+        self.panel_split_ratio =
+            (self.panel_split_ratio as i16 + delta).clamp(min as i16, max as i16) as u16;
+    }
+
+    // 视口半宽、半高（画布坐标）
+    fn view_half_size(&self) -> (f64, f64) {
+        (self.screen_max_x / self.zoom, self.screen_max_y / self.zoom)
+    }
+
+    fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(1.0, 8.0);
+        self.pan(0.0, 0.0);
+    }
+
+    // 平移视口，保证视口不超出整张画布
+    fn pan(&mut self, dx: f64, dy: f64) {
+        let (half_w, half_h) = self.view_half_size();
+        let limit_x = self.screen_max_x - half_w;
+        let limit_y = self.screen_max_y - half_h;
+        self.view_x = (self.view_x + dx).clamp(-limit_x, limit_x);
+        self.view_y = (self.view_y + dy).clamp(-limit_y, limit_y);
+    }
+
+    // 放大时在画布右下角显示整张图的缩略图，白框为当前视口
+    fn render_minimap(&self, frame: &mut Frame, area: Rect) {
+        if area.width < 16 || area.height < 10 {
+            return;
+        }
+        let width = (area.width / 4).max(12);
+        let height = (area.height / 3).max(6);
+        let mini_area = Rect {
+            x: area.x + area.width - width - 1,
+            y: area.y + area.height - height - 1,
+            width,
+            height,
+        };
+
+        let (half_w, half_h) = self.view_half_size();
+        let minimap = Canvas::default()
+            .block(Block::default().borders(Borders::ALL))
+            .x_bounds([-self.screen_max_x, self.screen_max_x])
+            .y_bounds([-self.screen_max_y, self.screen_max_y])
+            .paint(|ctx| {
+                self.graph.visit_edges(|node1, node2, _| {
+                    ctx.draw(&CanvaLine {
+                        x1: node1.x() as f64,
+                        y1: node1.y() as f64,
+                        x2: node2.x() as f64,
+                        y2: node2.y() as f64,
+                        color: Color::DarkGray,
+                    });
+                });
+                let mut coords = Vec::new();
+                self.graph
+                    .visit_nodes(|node| coords.push((node.x() as f64, node.y() as f64)));
+                ctx.draw(&Points {
+                    coords: &coords,
+                    color: Color::LightBlue,
+                });
+                ctx.draw(&Rectangle {
+                    x: self.view_x - half_w,
+                    y: self.view_y - half_h,
+                    width: half_w * 2.0,
+                    height: half_h * 2.0,
+                    color: Color::White,
+                });
+            });
+
+        frame.render_widget(Clear, mini_area);
+        frame.render_widget(minimap, mini_area);
+    }
+
+    // 每个分组画一个虚线包围框，放在最底层
+    // 网格间距取 1、2、5 乘以 10 的幂，视口内约 8 格；坐标轴颜色较亮，刻度标在轴旁
+    fn render_grid(&self, ctx: &mut Context) {
+        let (half_w, half_h) = self.view_half_size();
+        let raw = half_w.max(half_h) / 4.0;
+        let magnitude = 10f64.powf(raw.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .map(|m| m * magnitude)
+            .find(|&s| s >= raw)
+            .unwrap_or(raw);
+
+        let (x1, x2) = (self.view_x - half_w, self.view_x + half_w);
+        let (y1, y2) = (self.view_y - half_h, self.view_y + half_h);
+        // 刻度贴着坐标轴；轴不在视口内时贴着视口边缘
+        let tick_x = 0f64.clamp(x1, x2);
+        let tick_y = 0f64.clamp(y1, y2);
+        // 加 0.0 把 -0 变成 0
+        let label = |v: f64| format!("{}", (v * 100.0).round() / 100.0 + 0.0);
+
+        let mut x = (x1 / step).ceil() * step;
+        while x <= x2 {
+            let color = if x.abs() < step / 2.0 {
+                Color::Gray
+            } else {
+                Color::DarkGray
+            };
+            ctx.draw(&CanvaLine {
+                x1: x,
+                y1,
+                x2: x,
+                y2,
+                color,
+            });
+            ctx.print(x, tick_y, label(x).dark_gray());
+            x += step;
+        }
+        let mut y = (y1 / step).ceil() * step;
+        while y <= y2 {
+            let color = if y.abs() < step / 2.0 {
+                Color::Gray
+            } else {
+                Color::DarkGray
+            };
+            ctx.draw(&CanvaLine {
+                x1,
+                y1: y,
+                x2,
+                y2: y,
+                color,
+            });
+            if y.abs() >= step / 2.0 {
+                ctx.print(tick_x, y, label(y).dark_gray());
+            }
+            y += step;
+        }
+        ctx.layer();
+    }
+
+    fn render_groups(&self, ctx: &mut Context, cell_w: f64) {
+        let mut boxes: BTreeMap<&str, (f64, f64, f64, f64)> = BTreeMap::new();
+        self.graph.visit_nodes(|node| {
+            if let Some(group) = self.data_graph.group_of(node.data.user_data) {
+                let (x, y) = (node.x() as f64, node.y() as f64);
+                let b = boxes.entry(group).or_insert((x, y, x, y));
+                *b = (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y));
+            }
+        });
+
+        let pad = self.r * 1.5;
+        for (i, (name, (x1, y1, x2, y2))) in boxes.into_iter().enumerate() {
+            let color = GROUP_COLORS[i % GROUP_COLORS.len()];
+            let (x1, y1, x2, y2) = (x1 - pad, y1 - pad, x2 + pad, y2 + pad);
+
+            draw_dashed(ctx, x1, y1, x2, y1, color);
+            draw_dashed(ctx, x2, y1, x2, y2, color);
+            draw_dashed(ctx, x2, y2, x1, y2, color);
+            draw_dashed(ctx, x1, y2, x1, y1, color);
+            // 分组名从框的左上角开始，不超过框的宽度
+            let max = ((x2 - x1) / cell_w).max(1.0) as usize;
+            ctx.print(x1, y2, text::truncate(name, max).fg(color));
+        }
+        ctx.layer();
+    }
+
+    // 手动分组：把给出的节点（不给时用多选的节点）放进 name 分组，画布上同样画出分组框
+    fn assign_group(&mut self, name: &str, nodes: Vec<i64>) {
+        let nodes = if nodes.is_empty() {
+            self.multi_selected.iter().copied().collect()
+        } else {
+            nodes
+        };
+        if nodes.is_empty() {
+            self.visit_log
+                .push("没有给出节点，也没有多选节点（空格加入选择）".to_string());
+            return;
+        }
+        if let Some(u) = nodes.iter().find(|&&u| !self.data_graph.contains_node(u)) {
+            self.visit_log.push(format!("节点 {} 不存在", u));
+            return;
+        }
+        for &u in &nodes {
+            self.data_graph.set_group(u, name);
+        }
+        let list: Vec<String> = nodes.iter().map(|u| u.to_string()).collect();
+        self.visit_log
+            .push(format!("{}: {}", name, list.join(", ")));
+    }
+
+    fn run_communities(&mut self) {
+        self.current_algorithm = "社区检测".to_string();
+        self.visit_log.clear();
+
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+
+        let labels = self.data_graph.communities();
+        let mut members: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+        for (&u, &label) in &labels {
+            members.entry(label).or_default().push(u);
+        }
+
+        self.data_graph.clear_groups();
+        for (i, nodes) in members.values_mut().enumerate() {
+            nodes.sort();
+            let name = format!("社区{}", i + 1);
+            for &u in nodes.iter() {
+                self.data_graph.set_group(u, &name);
+            }
+            let list = nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            self.visit_log
+                .push(format!("{}: {}", name, list.join(", ")));
+        }
+    }
+
+    // 强连通分量按分组显示；无向图中它就是连通分量，照常计算但给出说明
+    fn run_scc(&mut self) {
+        self.current_algorithm = "强连通分量".to_string();
+        self.visit_log.clear();
+
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+
+        if !self.data_graph.is_directed() {
+            self.visit_log
+                .push("图中没有单向边，强连通分量即连通分量".to_string());
+        }
+
+        self.data_graph.clear_groups();
+        for (i, nodes) in self
+            .data_graph
+            .strongly_connected_components()
+            .iter()
+            .enumerate()
+        {
+            let name = format!("分量{}", i + 1);
+            for &u in nodes {
+                self.data_graph.set_group(u, &name);
+            }
+            let list = nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            self.visit_log
+                .push(format!("{}: {}", name, list.join(", ")));
+        }
+    }
+
+    // 把当前图换成子句的蕴含图（按 u 撤销），强连通分量按分组显示，
+    // 有解时标黄取值为真的文字，无解时标出 x 与 ¬x 互相蕴含的两条路径
+    fn load_two_sat(&mut self, problem: &TwoSat) {
+        self.replace_graph(problem.implication_graph(), "");
+        self.run_scc();
+        self.current_algorithm = "2-SAT".to_string();
+        self.visit_log.insert(
+            0,
+            format!(
+                "{} 个变量、{} 个子句，子句 a∨b 对应 ¬a→b 与 ¬b→a，按 u 撤销",
+                problem.variables().len(),
+                problem.clauses.len()
+            ),
+        );
+
+        match problem.solve() {
+            TwoSatResult::Satisfiable(assignment) => {
+                self.visit_log.push("有解（黄色为取真的文字）:".to_string());
+                for (&k, &value) in &assignment {
+                    let lit = if value { k } else { -k };
+                    self.visited_nodes.insert(lit);
+                    self.visit_log
+                        .push(format!("  x{} = {}", k, if value { "真" } else { "假" }));
+                }
+            }
+            TwoSatResult::Conflict { var, component } => {
+                self.visit_log
+                    .push(format!("无解：x{} 与 ¬x{} 在同一个强连通分量中", var, var));
+                for (from, to) in [(var, -var), (-var, var)] {
+                    let (_, tree) = self.data_graph.bfs(from);
+                    let Some(path) = graph::tree_path(from, &tree, to) else {
+                        continue;
+                    };
+                    let names = path
+                        .iter()
+                        .map(|&u| self.data_graph.node_label(u).unwrap_or_default())
+                        .collect::<Vec<_>>();
+                    self.visit_log.push(format!("  {}", names.join(" → ")));
+                    self.visited_edges
+                        .extend(path.windows(2).map(|w| (w[0], w[1])));
+                }
+                self.visited_nodes.extend(component);
+                self.flash_nodes = Some((HashSet::from([var, -var]), 2.0));
+            }
+        }
+    }
+
+    // 按边数（围长）或边权找最短环，在画布上标出环上的节点和边
+    fn run_girth(&mut self, weighted: bool) {
+        self.current_algorithm = if weighted {
+            "最小权环"
+        } else {
+            "最短环"
+        }
+        .to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+
+        let found = if weighted {
+            self.data_graph
+                .min_weight_cycle()
+                .map(|(cycle, w)| (cycle, format!("总权重 {}", w.format(self.weight_precision))))
+        } else {
+            self.data_graph.girth().map(|cycle| {
+                let len = cycle.len();
+                (cycle, format!("围长 {}", len))
+            })
+        };
+        let Some((cycle, length)) = found else {
+            self.visit_log.push("图中没有环".to_string());
+            return;
+        };
+
+        let mut text: Vec<String> = cycle.iter().map(|u| u.to_string()).collect();
+        text.push(cycle[0].to_string());
+        self.visit_log.push(format!(
+            "{} 条边，{}: {}",
+            cycle.len(),
+            length,
+            text.join(" → ")
+        ));
+        self.visited_edges.extend(
+            cycle
+                .iter()
+                .zip(cycle.iter().cycle().skip(1))
+                .map(|(&u, &v)| (u, v)),
+        );
+        self.visited_nodes.extend(cycle);
+    }
+
+    // 当前图换成只有起点的图（按 u 撤销），之后由 update_implicit 逐步生成
+    fn start_implicit(&mut self, title: &str, explorer: Box<dyn Explore>, start: &str, goal: &str) {
+        let mut g = Graph::default();
+        g.add_node(1);
+        g.set_node_label(1, start);
+        self.replace_graph(g, "");
+
+        self.implicit = Some(ImplicitScene {
+            title: title.to_string(),
+            explorer,
+            timer: 0.0,
+            log: vec![
+                format!("从 {} 出发寻找 {}", start, goal),
+                "节点和边都按需生成：BFS 取出队首节点时才计算它的邻居".to_string(),
+            ],
+            expanded: Vec::new(),
+        });
+        self.show_implicit();
+    }
+
+    // 重建画布后恢复场景的日志与高亮
+    fn show_implicit(&mut self) {
+        let Some(scene) = &self.implicit else {
+            return;
+        };
+        self.current_algorithm = scene.title.clone();
+        self.visit_log = scene.log.clone();
+        self.visited_nodes = scene.expanded.iter().copied().collect();
+    }
+
+    fn update_implicit(&mut self) {
+        let Some(mut scene) = self.implicit.take() else {
+            return;
+        };
+        if self.current_algorithm != scene.title {
+            return;
+        }
+        scene.timer += self.dt;
+        if scene.timer < 0.3 {
+            self.implicit = Some(scene);
+            return;
+        }
+        scene.timer = 0.0;
+
+        let name = |g: &Graph<f64>, u: i64| g.node_label(u).unwrap_or_default().to_string();
+        let Some(expansion) = scene.explorer.step() else {
+            let generated = self.data_graph.nodes().len();
+            let path = scene
+                .explorer
+                .goal()
+                .and_then(|g| scene.explorer.path_to(g));
+            self.current_algorithm = scene.title;
+            self.visit_log = scene.log;
+            self.visited_nodes.clear();
+            match path {
+                Some(path) => {
+                    let names = path
+                        .iter()
+                        .map(|&u| name(&self.data_graph, u))
+                        .collect::<Vec<_>>();
+                    self.visit_log.push(format!(
+                        "找到目标，共 {} 步: {}",
+                        path.len() - 1,
+                        names.join(" → ")
+                    ));
+                    self.visited_nodes.extend(&path);
+                    self.visited_edges
+                        .extend(path.windows(2).map(|w| (w[0], w[1])));
+                }
+                None => self.visit_log.push("目标不可达".to_string()),
+            }
+            self.visit_log
+                .push(format!("只生成了 {} 个节点，从未构造完整的图", generated));
+            return;
+        };
+
+        let u = expansion.u;
+        for (id, label) in &expansion.discovered {
+            self.data_graph.add_node(*id);
+            self.data_graph.set_node_label(*id, label);
+        }
+        for &v in &expansion.neighbors {
+            if self.data_graph.edge(u, v).is_none() {
+                self.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+            }
+        }
+        scene.expanded.push(u);
+        scene.log.push(format!(
+            "展开 {}: {} 个邻居，新发现 {} 个",
+            name(&self.data_graph, u),
+            expansion.neighbors.len(),
+            expansion.discovered.len()
+        ));
+
+        self.implicit = Some(scene);
+        self.reload_keep_positions();
+        self.show_implicit();
+    }
+
+    fn start_random_walk(&mut self) {
+        self.current_algorithm = "随机游走".to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+
+        let start = self.selected_node.unwrap_or(1);
+        self.walk_token = Some(start);
+        self.walk_counts.clear();
+        self.walk_counts.insert(start, 1);
+        self.walk_timer = 0.0;
+    }
+
+    fn start_karger(&mut self) {
+        self.current_algorithm = "Karger 最小割".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.cut_edges.clear();
+        self.animation = AnimationPlayer::default();
+        self.unreachable.clear();
+
+        let seed = rand::rng().random();
+        self.karger = Some(KargerDemo {
+            trial: 0,
+            current: self.data_graph.karger_trial(seed),
+            step: 0,
+            timer: 0.0,
+            best: None,
+            seed,
+        });
+        self.visit_log.push(format!(
+            "共 {} 次试验，每次随机收缩边直到剩两个超级节点",
+            self.karger_trials
+        ));
+    }
+
+    // 每 0.1 秒收缩一条边，已收缩的边标黄；一次试验结束后记录割的大小并开始下一次
+    fn update_karger(&mut self) {
+        let Some(demo) = &mut self.karger else {
+            return;
+        };
+        if self.current_algorithm != "Karger 最小割" {
+            self.karger = None;
+            return;
+        }
+
+        demo.timer += self.dt;
+        if demo.timer < 0.1 {
+            return;
+        }
+        demo.timer = 0.0;
+
+        if let Some(&(u, v)) = demo.current.contractions.get(demo.step) {
+            demo.step += 1;
+            self.visited_edges.insert((u, v));
+            self.visited_nodes.insert(u);
+            self.visited_nodes.insert(v);
+            if self.verbose_log {
+                self.visit_log
+                    .push(format!("收缩 {}-{}：两端所在的超级节点合并为一个", u, v));
+            }
+            return;
+        }
+
+        let cut = demo.current.cut_edges.len();
+        let improved = demo.best.as_ref().is_none_or(|b| cut < b.cut_edges.len());
+        self.visit_log.push(format!(
+            "试验 {}: 割 {} 条边，权重 {}{}",
+            demo.trial + 1,
+            cut,
+            demo.current.cut_weight().format(self.weight_precision),
+            if improved { "  ← 目前最好" } else { "" }
+        ));
+        if improved {
+            demo.best = Some(demo.current.clone());
+        }
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+
+        demo.trial += 1;
+        if demo.trial < self.karger_trials {
+            demo.current = self
+                .data_graph
+                .karger_trial(demo.seed.wrapping_add(demo.trial as u64));
+            demo.step = 0;
+            return;
+        }
+
+        let best = demo.best.take().unwrap_or_default();
+        self.karger = None;
+        self.cut_edges = best.cut_edges.iter().map(|&(u, v, _)| (u, v)).collect();
+        self.visited_nodes = best.side.iter().copied().collect();
+
+        let side = best.side.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        self.visit_log.push(String::new());
+        self.visit_log.push(format!(
+            "最小割: {} 条边（红色），黄色一侧: {}",
+            best.cut_edges.len(),
+            side.join(", ")
+        ));
+        for &(u, v, w) in &best.cut_edges {
+            self.visit_log.push(format!(
+                "  {} - {}: {}",
+                u,
+                v,
+                w.format(self.weight_precision)
+            ));
+        }
+        if best.cut_edges.is_empty() {
+            self.visit_log.push("图不连通，不需要割任何边".to_string());
+        }
+    }
+
+    fn start_quiz(&mut self, kind: QuizKind) {
+        self.current_algorithm = "测验".to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
+        self.quiz_pick = None;
+
+        let quiz = match kind {
+            QuizKind::DijkstraSettle => {
+                self.visited_nodes.insert(self.source);
+                self.visit_log
+                    .push(format!("从节点 {} 出发运行 Dijkstra", self.source));
+                Quiz::dijkstra(&self.data_graph, self.source)
+            }
+            QuizKind::KruskalPick => Quiz::kruskal(&self.data_graph),
+        };
+        self.visit_log.push(match kind {
+            QuizKind::DijkstraSettle => "用 n/N 选择节点，按 x 作答".to_string(),
+            QuizKind::KruskalPick => "用 n/N 依次选择边的两个端点，各按一次 x 作答".to_string(),
+        });
+        self.quiz = Some(quiz);
+    }
+
+    // 把选中的节点作为答案提交
+    fn submit_quiz_answer(&mut self) {
+        let Some(quiz) = &mut self.quiz else {
+            return;
+        };
+        let Some(u) = self.selected_node else {
+            self.visit_log.push("请先用 n/N 选择节点".to_string());
+            return;
+        };
+
+        let answer = match quiz.kind() {
+            QuizKind::DijkstraSettle => QuizAnswer::Node(u),
+            QuizKind::KruskalPick => match self.quiz_pick.take() {
+                Some(first) => QuizAnswer::edge(first, u),
+                None => {
+                    self.quiz_pick = Some(u);
+                    self.visit_log.push(format!("第一个端点: {}", u));
+                    return;
+                }
+            },
+        };
+
+        let Some((correct, expected)) = quiz.answer(answer) else {
+            return;
+        };
+        self.visit_log.push(if correct {
+            format!("✓ {} 正确", answer)
+        } else {
+            format!("✗ {} 错误，应为 {}", answer, expected)
+        });
+        match expected {
+            QuizAnswer::Node(v) => {
+                self.visited_nodes.insert(v);
+            }
+            QuizAnswer::Edge(a, b) => {
+                self.visited_edges.insert((a, b));
+                self.visited_nodes.insert(a);
+                self.visited_nodes.insert(b);
+            }
+        }
+
+        if quiz.is_finished() {
+            let (right, _, total) = quiz.score();
+            self.visit_log
+                .push(format!("测验结束，得分 {}/{}", right, total));
+        }
+    }
+
+    fn quiz_lines(&self) -> Vec<String> {
+        let Some(quiz) = &self.quiz else {
+            return Vec::new();
+        };
+        let (right, answered, _) = quiz.score();
+        let mut lines = vec![format!("[测验] 得分 {}/{}", right, answered)];
+        if let Some(question) = quiz.question() {
+            lines.push(question);
+        }
+        lines.push(String::new());
+        lines
+    }
+
+    // 载入教程的示例图，从第一步开始讲解
+    fn start_tutorial(&mut self, index: usize) {
+        let original = match self.tutorial.take() {
+            Some(state) => state.original,
+            None => self.data_graph.clone(),
+        };
+        self.data_graph = self.tutorials[index].graph.clone();
+        self.reload_graph();
+        self.tutorial = Some(TutorialState {
+            index,
+            step: 0,
+            original,
+        });
+        self.show_tutorial_step();
+    }
+
+    fn end_tutorial(&mut self) {
+        if let Some(state) = self.tutorial.take() {
+            self.data_graph = state.original;
+            self.reload_graph();
+        }
+    }
+
+    fn step_tutorial(&mut self, forward: bool) {
+        let Some(state) = &mut self.tutorial else {
+            return;
+        };
+        let last = self.tutorials[state.index].steps.len().saturating_sub(1);
+        state.step = if forward {
+            (state.step + 1).min(last)
+        } else {
+            state.step.saturating_sub(1)
+        };
+        self.show_tutorial_step();
+    }
+
+    // 按当前步骤重建高亮和讲解文字，前进后退都从头累积
+    fn show_tutorial_step(&mut self) {
+        let Some(state) = &self.tutorial else {
+            return;
+        };
+        let tutorial = &self.tutorials[state.index];
+
+        self.current_algorithm = tutorial.title.clone();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.visit_log.push(format!(
+            "步骤 {}/{}  [ ] 切换步骤，Esc 退出教程",
+            state.step + 1,
+            tutorial.steps.len()
+        ));
+        self.visit_log.push(String::new());
+
+        for (i, step) in tutorial.steps.iter().take(state.step + 1).enumerate() {
+            match step.mark {
+                Some(StepMark::Node(u)) => {
+                    self.visited_nodes.insert(u);
+                }
+                Some(StepMark::Edge(u, v)) => {
+                    self.visited_edges.insert((u, v));
+                    self.visited_nodes.insert(u);
+                    self.visited_nodes.insert(v);
+                }
+                None => {}
+            }
+            let marker = if i == state.step { ">" } else { " " };
+            self.visit_log.push(format!("{} {}", marker, step.text));
+        }
+    }
+
+    // 开始或结束时序回放，结束时恢复回放前的图
+    fn toggle_playback(&mut self) {
+        if let Some(playback) = self.playback.take() {
+            self.data_graph = playback.original;
+            self.reload_graph();
+            self.visit_log.push("已结束时序回放".to_string());
+            return;
+        }
+
+        let timeline = self.timeline.clone().unwrap_or_else(|| {
+            let mut demo = Timeline::default();
+            for (t, (u, v, w, _)) in self.data_graph.edges().into_iter().enumerate() {
+                demo.push(t as i64, EdgeEvent::Add { u, v, w });
+            }
+            demo
+        });
+        if timeline.is_empty() {
+            self.visit_log.push("时间线为空".to_string());
+            return;
+        }
+
+        self.playback = Some(Playback {
+            times: timeline.times(),
+            timeline,
+            index: 0,
+            playing: true,
+            timer: 0.0,
+            original: self.data_graph.clone(),
+        });
+        self.load_snapshot();
+    }
+
+    // 用当前时刻的快照替换图，已有节点保留原来的位置
+    fn load_snapshot(&mut self) {
+        let Some(playback) = &self.playback else {
+            return;
+        };
+        let snapshot = playback
+            .timeline
+            .snapshot_with(playback.times[playback.index], self.edge_policy);
+
+        let mut positions: HashMap<i64, (f32, f32)> = HashMap::new();
+        self.graph.visit_nodes(|node| {
+            positions.insert(node.data.user_data, (node.data.x, node.data.y));
+        });
+
+        self.data_graph = snapshot;
+        self.reload_keep_positions();
+    }
+
+    // 重建力导向图，已有节点保留原来的位置
+    fn reload_keep_positions(&mut self) {
+        let mut positions: HashMap<i64, (f32, f32)> = HashMap::new();
+        self.graph.visit_nodes(|node| {
+            positions.insert(node.data.user_data, (node.data.x, node.data.y));
+        });
+
+        self.reload_graph();
+        self.graph.visit_nodes_mut(|node| {
+            if !node.data.is_anchor
+                && let Some(&(x, y)) = positions.get(&node.data.user_data)
+            {
+                node.data.x = x;
+                node.data.y = y;
+            }
+        });
+    }
+
+    // 第一次按 m 记下选中的节点，第二次把新选中的节点合并进去
+    // 选中起点按 r，再选终点按 r：沿边的方向查询可达性，可达时闪烁找到的路径
+    fn pick_reach_node(&mut self) {
+        let Some(v) = self.selected_node else {
+            self.visit_log
+                .push("可达性查询需要先用 n/N 选择一个节点".to_string());
+            return;
+        };
+        let Some(u) = self.reach_pick.take() else {
+            self.reach_pick = Some(v);
+            self.visit_log
+                .push(format!("已选择起点 {}，再选择终点按 r 查询是否可达", v));
+            return;
+        };
+
+        let path = if self.forbidden.is_empty() {
+            self.data_graph.reach_path(u, v)
+        } else {
+            // 有禁行标记时沿绕开它们的 BFS 树往回找
+            let (_, tree) = algo::bfs(&self.forbidden.view(&self.data_graph), u);
+            let parent: HashMap<i64, i64> = tree.into_iter().map(|(a, b)| (b, a)).collect();
+            let mut path = vec![v];
+            while let Some(&p) = path.last().and_then(|x| parent.get(x)) {
+                path.push(p);
+            }
+            path.reverse();
+            (path[0] == u && !self.forbidden.has_node(v)).then_some(path)
+        };
+        match path {
+            Some(path) => {
+                let text: Vec<String> = path.iter().map(|x| x.to_string()).collect();
+                self.visit_log.push(format!(
+                    "{} 可达 {}，经过 {} 条边: {}",
+                    u,
+                    v,
+                    path.len() - 1,
+                    text.join(" → ")
+                ));
+                self.flash_nodes = Some((path.into_iter().collect(), 2.0));
+            }
+            None => {
+                self.visit_log.push(format!("{} 不可达 {}", u, v));
+                self.flash_nodes = Some((HashSet::from([u, v]), 1.0));
+            }
+        }
+    }
+
+    fn pick_merge_node(&mut self) {
+        let Some(u) = self.selected_node else {
+            self.visit_log
+                .push("合并节点需要先用 n/N 选择一个节点".to_string());
+            return;
+        };
+        match self.merge_pick.take() {
+            Some(keep) if keep != u => {
+                self.contraction = Some(Contraction {
+                    keep,
+                    merged: u,
+                    remaining: 0.6,
+                });
+            }
+            _ => {
+                self.merge_pick = Some(u);
+                self.visit_log.push(format!(
+                    "已选择节点 {}，再选择另一个节点按 m 把它合并进来",
+                    u
+                ));
+            }
+        }
+    }
+
+    fn update_contraction(&mut self) {
+        let Some(c) = &mut self.contraction else {
+            return;
+        };
+        c.remaining -= self.dt;
+        let (keep, merged, done) = (c.keep, c.merged, c.remaining <= 0.0);
+
+        let mut target = None;
+        self.graph.visit_nodes(|node| {
+            if node.data.user_data == keep {
+                target = Some((node.data.x, node.data.y));
+            }
+        });
+        let t = (self.dt * 10.0).min(1.0) as f32;
+        if let Some((tx, ty)) = target {
+            self.graph.visit_nodes_mut(|node| {
+                if node.data.user_data == merged {
+                    node.data.x += (tx - node.data.x) * t;
+                    node.data.y += (ty - node.data.y) * t;
+                }
+            });
+        }
+        if !done {
+            return;
+        }
+
+        self.contraction = None;
+        let mut graph = self.data_graph.clone();
+        graph.contract(keep, merged, self.edge_policy);
+        self.replace_graph(
+            graph,
+            &format!(
+                "已把节点 {} 合并进节点 {}，重复的边按\"{}\"处理",
+                merged,
+                keep,
+                self.edge_policy.name()
+            ),
+        );
+        self.selected_node = Some(keep);
+    }
+
+    // 按步移动时间轴，到两端时停止播放
+    fn step_playback(&mut self, forward: bool) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        let last = playback.times.len() - 1;
+        let next = if forward {
+            (playback.index + 1).min(last)
+        } else {
+            playback.index.saturating_sub(1)
+        };
+        if next == last || next == 0 {
+            playback.playing = false;
+        }
+        if next != playback.index {
+            playback.index = next;
+            self.load_snapshot();
+        }
+    }
+
+    fn update_playback(&mut self) {
+        let Some(playback) = &mut self.playback else {
+            return;
+        };
+        if !playback.playing {
+            return;
+        }
+        playback.timer += self.dt;
+        if playback.timer >= 0.5 {
+            playback.timer = 0.0;
+            self.step_playback(true);
+        }
+    }
+
+    // 信息面板顶部的时间轴
+    fn playback_lines(&self) -> Vec<String> {
+        let Some(playback) = &self.playback else {
+            return Vec::new();
+        };
+        let width = 20;
+        let filled = if playback.times.len() > 1 {
+            playback.index * width / (playback.times.len() - 1)
+        } else {
+            width
+        };
+        vec![
+            format!(
+                "[时间 t={}] {}{} {}/{}",
+                playback.times[playback.index],
+                "#".repeat(filled),
+                "-".repeat(width - filled),
+                playback.index + 1,
+                playback.times.len()
+            ),
+            format!(
+                "  {}  空格 播放/暂停，[ ] 单步",
+                if playback.playing {
+                    "播放中"
+                } else {
+                    "已暂停"
+                }
+            ),
+            String::new(),
+        ]
+    }
+
+    fn update_random_walk(&mut self) {
+        let Some(u) = self.walk_token else {
+            return;
+        };
+        if self.current_algorithm != "随机游走" {
+            self.walk_token = None;
+            return;
+        }
+
+        self.walk_timer += self.dt;
+        if self.walk_timer < 0.1 {
+            return;
+        }
+        self.walk_timer = 0.0;
+
+        let r: f64 = rand::rng().random_range(0.0..1.0);
+        match self.data_graph.walk_step(u, r) {
+            Some(v) => {
+                self.walk_token = Some(v);
+                *self.walk_counts.entry(v).or_default() += 1;
+                self.visited_edges.clear();
+                self.visited_edges.insert((u, v));
+                self.visit_log = self.walk_histogram();
+            }
+            None => {
+                self.visit_log = self.walk_histogram();
+                self.visit_log
+                    .push(format!("节点 {} 没有出边，游走结束", u));
+                self.walk_token = None;
+            }
+        }
+    }
+
+    // 访问次数直方图，并给出按加权度计算的平稳分布作参考（无向图时准确）
+    fn walk_histogram(&self) -> Vec<String> {
+        let steps: u64 = self.walk_counts.values().sum();
+        let max = self.walk_counts.values().copied().max().unwrap_or(1);
+
+        let strength = |u: i64| -> f64 {
+            self.data_graph
+                .out_edges(u)
+                .map(|e| e.weight.to_f64().max(0.0))
+                .sum()
+        };
+        let total_strength: f64 = self.data_graph.nodes().into_iter().map(strength).sum();
+
+        let mut lines = vec![
+            format!("步数: {}", steps),
+            format!(
+                "{} {} {} {} {}",
+                text::pad_left("节点", 3),
+                text::pad_right("", 12),
+                text::pad_left("次数", 4),
+                text::pad_left("实际%", 5),
+                text::pad_left("理论%", 5)
+            ),
+        ];
+        for u in self.data_graph.nodes() {
+            let count = self.walk_counts.get(&u).copied().unwrap_or(0);
+            let bar = "█".repeat((count * 12 / max) as usize);
+            let expected = if total_strength > 0.0 {
+                strength(u) / total_strength * 100.0
+            } else {
+                0.0
+            };
+            lines.push(format!(
+                "{:>3} {:<12} {:>4} {:>5.1} {:>5.1}",
+                u,
+                bar,
+                count,
+                count as f64 / steps as f64 * 100.0,
+                expected
+            ));
+        }
+        lines
+    }
+
+    // 把步骤按动画的节点/边分组，说明类步骤归到其后的节点或边
+    fn load_steps(&mut self, steps: Vec<StepEvent<f64>>) {
+        self.animation = AnimationPlayer::from_events_paced(&steps, self.pacing);
+        self.node_steps.clear();
+        self.edge_steps.clear();
+        self.frontier = None;
+        self.shown_steps = 0;
+
+        let mut pending = Vec::new();
+        let mut last_kind = StepKind::Note;
+        for step in steps {
+            let kind = step.kind();
+            pending.push(step);
+            match kind {
+                StepKind::Node => self.node_steps.push(std::mem::take(&mut pending)),
+                StepKind::Edge => self.edge_steps.push(std::mem::take(&mut pending)),
+                StepKind::Note => continue,
+            }
+            last_kind = kind;
+        }
+        // 最后一个节点/边之后的说明（如 Bellman-Ford 最后一轮没有更新）并入最后一步
+        let last = match last_kind {
+            StepKind::Node => self.node_steps.last_mut(),
+            StepKind::Edge => self.edge_steps.last_mut(),
+            StepKind::Note => None,
+        };
+        if let Some(last) = last {
+            last.append(&mut pending);
+        }
+    }
+
+    // 从当前起点 source 运行算法并记录步骤，队列面板据此显示每一步的状态
+    fn load_trace(&mut self, algorithm: TraceAlgorithm) {
+        // 最短路径类的查询绕开禁行的节点和边
+        let trace = match algorithm {
+            TraceAlgorithm::Bfs | TraceAlgorithm::Dijkstra => AlgorithmTrace::new(
+                &self.forbidden.view(&self.data_graph),
+                algorithm,
+                self.source,
+            ),
+            TraceAlgorithm::Dfs | TraceAlgorithm::Prim => {
+                AlgorithmTrace::new(&self.data_graph, algorithm, self.source)
+            }
+        };
+        self.load_steps(trace.steps.clone());
+        self.frontier = Some((algorithm, trace.snapshots()));
+    }
+
+    // 输出动画第 index 个节点/边对应的日志
+    fn log_step(&mut self, kind: StepKind, index: usize) {
+        let groups = match kind {
+            StepKind::Edge => &self.edge_steps,
+            _ => &self.node_steps,
+        };
+        let Some(group) = groups.get(index) else {
+            return;
+        };
+        self.shown_steps += group.len();
+
+        if self.verbose_log {
+            let lines: Vec<String> = group
+                .iter()
+                .map(|step| step.explain(self.weight_precision))
+                .collect();
+            self.visit_log.extend(lines);
+        } else if let Some(line) = group.last().and_then(|step| step.terse()) {
+            self.visit_log.push(line);
+        }
+    }
+
+    // 中止正在进行的后台计算或动画：通知后台线程尽快停止（结果由 update_job 显示），
+    // 停止动画、Karger 演示、随机游走和隐式图搜索并清除高亮。没有可中止的返回 false
+    fn abort_running(&mut self) -> bool {
+        if let Some(job) = &self.job {
+            job.cancel.store(true, Ordering::Relaxed);
+            self.visit_log.push(format!("正在取消{}…", job.name));
+            return true;
+        }
+        if !self.animation.is_playing()
+            && self.karger.is_none()
+            && self.walk_token.is_none()
+            && self.implicit.is_none()
+        {
+            return false;
+        }
+        self.karger = None;
+        self.walk_token = None;
+        self.implicit = None;
+        self.animation = AnimationPlayer::default();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.unreachable.clear();
+        self.cut_edges.clear();
+        self.path_colors.clear();
+        self.dominated.clear();
+        self.visit_log
+            .push(format!("已中止{}，高亮已清除", self.current_algorithm));
+        // 动画已清空，不再按中止的算法处理按键（如 BFS 后的 t）
+        self.current_algorithm.clear();
+        self.edge_attr = EdgeAttr::Weight;
+        true
+    }
+
+    fn update_animation(&mut self) {
+        if !self.animation.is_playing() {
+            return;
+        }
+
+        for step in self.animation.tick(self.dt) {
+            self.show_animation_step(step);
+        }
+        if !self.animation.is_running() {
+            self.bell.cue(Cue::Complete);
+        }
+    }
+
+    // 每帧最多响一声，输出在画面之外，不影响界面绘制
+    fn update_bell(&mut self) {
+        if self.bell.tick(self.dt) {
+            let mut out = io::stdout();
+            let _ = out.write_all(b"\x07").and_then(|_| out.flush());
+        }
+    }
+
+    // 循环演示：没有动画、计算或输入在进行时，停留片刻后运行下一个菜单项。
+    // 在时间轴上停住时不重新开始。"导出"菜单项不重复运行，远程开启的循环演示也就不会写文件
+    fn update_replay(&mut self) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        let idle = !self.animation.is_playing()
+            && !self.animation.is_paused()
+            && (!self.animation.is_empty() || !replay.playlist().is_empty())
+            && self.job.is_none()
+            && self.prompt.is_none()
+            && self.karger.is_none()
+            && self.walk_token.is_none()
+            && self.implicit.is_none();
+        if let Some(name) = replay.tick(self.dt, idle, self.last_action.as_deref())
+            && !self.is_export_item(&name)
+        {
+            self.run_action(&name);
+        }
+    }
+
+    // 动画播完或暂停在某一步时，画布底部显示时间轴，可用 ←/→ 或鼠标跳到任意一步
+    fn timeline_visible(&self) -> bool {
+        !self.animation.is_empty() && !self.animation.is_playing()
+    }
+
+    // 跳到点亮前 shown 步之后的状态：按动画步骤重新得到已访问的节点和边，
+    // 队列面板随之对应到这一步
+    fn scrub_to(&mut self, shown: usize) {
+        self.animation.seek(shown);
+        let nodes = self.animation.visited_nodes();
+        let edges = self.animation.visited_edges();
+        self.shown_steps = self
+            .node_steps
+            .iter()
+            .take(nodes.len())
+            .map(Vec::len)
+            .sum::<usize>()
+            + self
+                .edge_steps
+                .iter()
+                .take(edges.len())
+                .map(Vec::len)
+                .sum::<usize>();
+        self.visited_nodes = nodes.into_iter().collect();
+        self.visited_edges = edges.into_iter().collect();
+    }
+
+    fn scrub_by(&mut self, delta: isize) {
+        let (shown, _) = self.animation.progress();
+        self.scrub_to(shown.saturating_add_signed(delta));
+    }
+
+    // 点击时间轴的进度条时跳到对应的一步，返回是否点在进度条上
+    fn click_timeline(&mut self, column: u16, row: u16) -> bool {
+        let Some(bar) = self.timeline_area else {
+            return false;
+        };
+        if row != bar.y || column < bar.x || column >= bar.x + bar.width {
+            return false;
+        }
+        let (_, total) = self.animation.progress();
+        let offset = (column - bar.x) as usize + 1;
+        self.scrub_to(offset * total / bar.width as usize);
+        true
+    }
+
+    // 不等计时立即点亮动画的下一步，用于开始时点亮起点
+    fn start_animation(&mut self) {
+        if let Some(step) = self.animation.step() {
+            self.show_animation_step(step);
+        }
+    }
+
+    fn show_animation_step(&mut self, step: AnimationStep) {
+        match step {
+            AnimationStep::Edge { index, edge } => {
+                debug!(
+                    "{} 动画第 {} 步: 边 {:?}",
+                    self.current_algorithm, index, edge
+                );
+                self.visited_edges.insert(edge);
+                self.log_step(StepKind::Edge, index);
+                let negative_cycle = self.edge_steps.get(index).is_some_and(|group| {
+                    group
+                        .iter()
+                        .any(|step| matches!(step, StepEvent::NegativeCycle { .. }))
+                });
+                if negative_cycle {
+                    self.bell.cue(Cue::NegativeCycle);
+                }
+            }
+            AnimationStep::Node { index, node } => {
+                debug!(
+                    "{} 动画第 {} 步: 节点 {}",
+                    self.current_algorithm, index, node
+                );
+                self.visited_nodes.insert(node);
+                self.log_step(StepKind::Node, index);
+                let settled = self.node_steps.get(index).is_some_and(|group| {
+                    group
+                        .iter()
+                        .any(|step| matches!(step, StepEvent::Settle { .. }))
+                });
+                if settled {
+                    self.bell.cue(Cue::Settle);
+                }
+            }
+        }
+    }
 
-        let menu_widget = Menu::new()
-            .block(Block::default().title(title).borders(Borders::ALL))
-            .highlight_style(
-                ratatui::style::Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White),
-            ); // 设置高亮样式
+    fn toggle_tree_layout(&mut self) {
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+            return;
+        }
 
-        frame.render_stateful_widget(menu_widget, right_chunks[0], &mut self.menu);
+        let animation_complete = !self.animation.is_playing();
+        if self.current_algorithm == "BFS" && animation_complete {
+            self.set_layout(LayoutKind::Tree);
+        }
+    }
 
-        let mut log_lines = self.visit_log.clone();
+    fn set_layout(&mut self, kind: LayoutKind) {
+        self.layout = kind;
+        self.cooling.wake();
+        self.layout_tween = None;
 
-        let animation_complete = self.animation_index >= self.animation_nodes.len();
+        let max_x = self.screen_max_x - self.r;
+        let max_y = self.screen_max_y - self.r;
+        let mut nodes = Vec::new();
+        self.graph
+            .visit_nodes(|node| nodes.push(node.data.user_data));
 
-        if animation_complete && !self.current_algorithm.is_empty() {
-            log_lines.push("".to_string());
-            log_lines.push("---- 结束 ----\n".to_string());
+        let targets = match kind {
+            LayoutKind::Force => return,
+            LayoutKind::Circular => layout::circular(&nodes, max_x * 0.9, max_y * 0.9),
+            LayoutKind::Layered => {
+                let (_, edges) = self.data_graph.bfs(self.source);
+                layout::layered(self.source, &edges, &nodes, max_x * 1.8, max_y * 1.8)
+            }
+            LayoutKind::Spectral => {
+                let edges: Vec<(i64, i64)> = self
+                    .data_graph
+                    .edges()
+                    .into_iter()
+                    .map(|(u, v, _, _)| (u, v))
+                    .collect();
+                layout::spectral(&nodes, &edges, max_x * 1.8, max_y * 1.8)
+            }
+            LayoutKind::Tree => {
+                // 没有播放过的 BFS 时没有树根，保持力导向布局
+                let Some(&root) = self.animation.nodes().first() else {
+                    self.layout = LayoutKind::Force;
+                    return;
+                };
+                let edges = self.animation.edges();
+                let depth = layout::tree_depth(root, &edges).max(1);
+                layout::radial_tree(root, &edges, max_y / depth as f64)
+            }
+        };
 
-            match self.current_algorithm.as_str() {
-                "Prim" => {
-                    log_lines.push(format!("最小生成树总长度: {}", self.prim_total_cost));
-                }
-                "Dijkstra" => {
-                    log_lines.push("最短距离:".to_string());
-                    let mut sorted_nodes: Vec<_> = self.dijkstra_dist.iter().collect();
-                    sorted_nodes.sort_by_key(|(k, _)| *k);
-
-                    for (&node, &dist) in sorted_nodes {
-                        // Build path
-                        let mut path = vec![node];
-                        let mut current = node;
-                        while let Some(&prev) = self.dijkstra_parent.get(&current) {
-                            path.push(prev);
-                            current = prev;
-                        }
-                        path.reverse();
+        let (ax, ay) = (self.anchor_x, self.anchor_y);
+        let mut from = HashMap::new();
+        self.graph.visit_nodes(|node| {
+            from.insert(
+                node.data.user_data,
+                (node.x() as f64 - ax, node.y() as f64 - ay),
+            );
+        });
 
-                        let path_str = path
-                            .iter()
-                            .map(|n| n.to_string())
-                            .collect::<Vec<_>>()
-                            .join(" -> ");
+        self.layout_tween = Some(Tween::new(from, targets.clone(), 1.0));
+        self.layout_targets = targets;
+    }
 
-                        log_lines.push(format!(
-                            "  到节点{}: 距离={}, 路径={}",
-                            node, dist, path_str
-                        ));
-                    }
+    // 固定布局下不跑物理模拟，节点位置由过渡动画或目标坐标决定
+    fn step_layout(&mut self) {
+        let positions = match &mut self.layout_tween {
+            Some(tween) => {
+                tween.step(self.dt);
+                let positions = tween.positions();
+                if tween.is_finished() {
+                    self.layout_tween = None;
                 }
-                _ => {}
+                positions
             }
-        }
-
-        let log_text = log_lines.join("\n");
-        let info_title = if self.current_algorithm.is_empty() {
-            "请选择算法".to_string()
-        } else {
-            format!("{}", self.current_algorithm)
+            None => self.layout_targets.clone(),
         };
 
-        let info_widget = Paragraph::new(log_text).block(
-            Block::default()
-                .title(info_title)
-                .borders(Borders::ALL)
-                .padding(Padding::uniform(1)),
-        );
-
-        frame.render_widget(info_widget, right_chunks[1]);
+        let (ax, ay) = (self.anchor_x, self.anchor_y);
+        self.graph.visit_nodes_mut(|node| {
+            if let Some(&(x, y)) = positions.get(&node.data.user_data) {
+                node.data.x = (ax + x) as f32;
+                node.data.y = (ay + y) as f32;
+            }
+        });
     }
 
-    fn render_ctx(&self, ctx: &mut Context) {
-        self.graph.visit_edges(|node1, node2, edge_data| {
-            let u = node1.data.user_data;
-            let v = node2.data.user_data;
+    fn run_dfs(&mut self) {
+        self.current_algorithm = "DFS".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
+        self.visit_log.clear();
 
-            let is_visited =
-                self.visited_edges.contains(&(u, v)) || self.visited_edges.contains(&(v, u));
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
 
-            let x1 = node1.x() as f64;
-            let y1 = node1.y() as f64;
-            let x2 = node2.x() as f64;
-            let y2 = node2.y() as f64;
+        self.load_trace(TraceAlgorithm::Dfs);
+        self.unreachable = self
+            .data_graph
+            .unreachable_from(self.source)
+            .into_iter()
+            .collect();
 
-            ctx.draw(&CanvaLine {
-                x1,
-                y1,
-                x2,
-                y2,
-                color: if is_visited {
-                    Color::Yellow
-                } else {
-                    Color::LightBlue
-                },
-            });
+        self.start_animation();
+    }
 
-            let mid_x = (x1 + x2) / 2.0;
-            let mid_y = (y1 + y2) / 2.0;
-            ctx.print(mid_x, mid_y, format!("{}", edge_data.user_data).white());
-        });
+    fn run_bfs(&mut self) {
+        // Set algorithm name and clear log
+        self.current_algorithm = "BFS".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
+        self.visit_log.clear();
 
-        self.graph.visit_nodes(|node| {
-            let node_id = node.data.user_data;
-            let is_visited = self.visited_nodes.contains(&node_id);
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
 
-            ctx.draw(&Circle {
-                x: node.x() as f64,
-                y: node.y() as f64,
-                radius: self.r,
-                color: if is_visited {
-                    Color::Yellow
-                } else {
-                    Color::LightBlue
-                },
-            });
-            ctx.print(
-                node.x() as f64,
-                node.y() as f64,
-                format!("{}", node.data.user_data).yellow(),
-            );
-        });
+        self.load_trace(TraceAlgorithm::Bfs);
+        self.unreachable = self.unreachable_avoiding(self.source);
+
+        self.start_animation();
     }
 
-    fn update_animation(&mut self) {
-        if self.animation_nodes.is_empty() {
+    // 经过每条边至少一次的最短闭合回路，逐条边播放并在日志中累计费用
+    fn run_postman(&mut self) {
+        self.current_algorithm = "中国邮递员".to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
+
+        let Some(result) = self.data_graph.chinese_postman() else {
+            self.visit_log.push(format!(
+                "有边的部分不连通，或奇度节点超过 {} 个，无法求邮递员回路",
+                graph::POSTMAN_ODD_LIMIT
+            ));
             return;
+        };
+        if result.tour.is_empty() {
+            self.visit_log.push("图中没有边".to_string());
+            return;
+        }
+        let precision = self.weight_precision;
+        self.visit_log.push(format!(
+            "回路共 {} 条边，总费用 {}，其中重复走 {} 条边:",
+            result.tour.len(),
+            result.cost.format(precision),
+            result.repeated.len()
+        ));
+        for &(u, v, w) in &result.repeated {
+            self.visit_log
+                .push(format!("  {} - {}: {}", u, v, w.format(precision)));
         }
 
-        let nodes_done = self.animation_index >= self.animation_nodes.len();
-        let edges_done = self.animation_index >= self.animation_edges.len();
+        let mut total = 0.0;
+        let mut steps = Vec::new();
+        for &(u, v, w) in &result.tour {
+            total += w;
+            steps.push(StepEvent::TourEdge { u, v, w, total });
+        }
+        self.load_steps(steps);
+        self.start_animation();
+    }
 
-        if nodes_done && (edges_done || !self.animation_step_is_edge) {
+    // 逐条播放选中的匹配边及其两端，最后黄色的节点即为覆盖
+    fn run_vertex_cover(&mut self) {
+        self.current_algorithm = "顶点覆盖".to_string();
+        self.reset_selection_animation();
+
+        let (cover, matching) = self.data_graph.vertex_cover_2approx();
+        if matching.is_empty() {
+            self.visit_log
+                .push("图中没有边，空集即为顶点覆盖".to_string());
             return;
         }
+        self.visit_log.push(format!(
+            "极大匹配有 {} 条边，最优覆盖至少 {} 个节点；近似覆盖 {} 个节点，不超过最优的 2 倍",
+            matching.len(),
+            matching.len(),
+            cover.len()
+        ));
+        for &(u, v) in &matching {
+            self.visit_log
+                .push(format!("  边 {}-{} 两端都未覆盖，加入两端", u, v));
+        }
 
-        self.animation_timer += self.dt;
+        // 匹配边两两不相交，每个覆盖节点恰好属于一条匹配边，在该节点之前播放
+        let edges = cover[1..]
+            .iter()
+            .filter_map(|&x| matching.iter().copied().find(|&(u, v)| u == x || v == x))
+            .collect();
+        self.start_selection_animation(cover, edges);
+    }
 
-        if self.animation_timer >= 0.2 {
-            self.animation_timer = 0.0;
+    fn run_independent_set(&mut self) {
+        self.current_algorithm = "独立集".to_string();
+        self.reset_selection_animation();
 
-            if self.animation_step_is_edge {
-                if self.animation_index < self.animation_edges.len() {
-                    let edge = self.animation_edges[self.animation_index];
-                    self.visited_edges.insert(edge);
-                    self.visit_log
-                        .push(format!("访问边: {} -> {}", edge.0, edge.1));
-                }
-                self.animation_index += 1;
-                self.animation_step_is_edge = false;
-            } else {
-                if self.animation_index < self.animation_nodes.len() {
-                    let node = self.animation_nodes[self.animation_index];
-                    self.visited_nodes.insert(node);
-                    self.visit_log.push(format!("访问节点: {}", node));
-                }
-                if self.animation_index < self.animation_edges.len() {
-                    self.animation_step_is_edge = true;
-                } else {
-                    self.animation_index += 1;
-                }
-            }
+        let chosen = self.data_graph.greedy_independent_set();
+        let text: Vec<String> = chosen.iter().map(|u| u.to_string()).collect();
+        self.visit_log.push(format!(
+            "贪心选出 {} 个两两不相邻的节点（每次取剩余度数最小的节点）: {}",
+            chosen.len(),
+            text.join(", ")
+        ));
+        self.start_selection_animation(chosen, Vec::new());
+    }
+
+    // 每轮点亮一个选中的节点（黄色），它新支配的节点变为绿色，未被支配的保持蓝色
+    fn run_dominating_set(&mut self) {
+        self.current_algorithm = "支配集".to_string();
+        self.reset_selection_animation();
+
+        let rounds = self.data_graph.greedy_dominating_set();
+        for (i, (u, gained)) in rounds.iter().enumerate() {
+            let text: Vec<String> = gained.iter().map(|v| v.to_string()).collect();
+            self.visit_log.push(format!(
+                "第 {} 轮选 {}，新支配 {} 个节点: {}",
+                i + 1,
+                u,
+                gained.len(),
+                text.join(", ")
+            ));
         }
+        self.visit_log.push(format!(
+            "支配集共 {} 个节点（贪心，不超过最小支配集的 ln(n)+1 倍）",
+            rounds.len()
+        ));
+
+        let picked = rounds.iter().map(|&(u, _)| u).collect();
+        self.dominated = rounds.into_iter().collect();
+        self.start_selection_animation(picked, Vec::new());
     }
 
-    fn run_dfs(&mut self) {
-        self.current_algorithm = "DFS".to_string();
+    fn reset_selection_animation(&mut self) {
         self.visit_log.clear();
-
         self.visited_nodes.clear();
         self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
+        self.load_steps(Vec::new());
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
+    }
+
+    // 依次点亮选中的节点；edges[i] 在第 i + 1 个节点之前播放
+    fn start_selection_animation(&mut self, nodes: Vec<i64>, edges: Vec<(i64, i64)>) {
+        self.animation = AnimationPlayer::new(nodes, edges);
+        self.start_animation();
+    }
 
-        let (nodes, edges) = self.data_graph.dfs(1);
-        self.animation_nodes = nodes;
-        self.animation_edges = edges;
+    fn run_prim(&mut self) {
+        self.current_algorithm = "Prim".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
+        self.visit_log.clear();
 
-        if !self.animation_nodes.is_empty() {
-            self.visited_nodes.insert(self.animation_nodes[0]);
-            self.visit_log
-                .push(format!("访问节点: {}", self.animation_nodes[0]));
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
         }
 
-        self.animation_index = 0;
-        self.animation_timer = 0.0;
-        self.animation_step_is_edge = true;
+        let result = self.data_graph.prim(self.source);
+        self.prim_result = result;
+        self.load_trace(TraceAlgorithm::Prim);
+        self.unreachable.clear();
+
+        self.start_animation();
     }
 
-    fn run_bfs(&mut self) {
-        // Set algorithm name and clear log
-        self.current_algorithm = "BFS".to_string();
+    fn run_dijkstra(&mut self) {
+        self.current_algorithm = "Dijkstra".to_string();
+        self.edge_attr = algorithm_edge_attr(&self.current_algorithm);
         self.visit_log.clear();
 
         self.visited_nodes.clear();
         self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
 
-        let (nodes, edges) = self.data_graph.bfs(1);
-        self.animation_nodes = nodes;
-        self.animation_edges = edges;
-
-        if !self.animation_nodes.is_empty() {
-            self.visited_nodes.insert(self.animation_nodes[0]);
+        let result = algo::dijkstra(&self.forbidden.view(&self.data_graph), self.source);
+        self.dijkstra_stale = false;
+        self.dijkstra_result = result;
+        self.load_trace(TraceAlgorithm::Dijkstra);
+        self.unreachable = self.unreachable_avoiding(self.source);
+        if self.data_graph.edges().iter().any(|&(_, _, w, _)| w < 0.0) {
             self.visit_log
-                .push(format!("访问节点: {}", self.animation_nodes[0]));
+                .push("图中有负权边，Dijkstra 的结果可能不正确，请改用 Bellman-Ford".to_string());
         }
 
-        self.animation_index = 0;
-        self.animation_timer = 0.0;
-        self.animation_step_is_edge = true;
+        self.start_animation();
     }
 
-    fn run_prim(&mut self) {
-        self.current_algorithm = "Prim".to_string();
-        self.visit_log.clear();
+    // 图的版本号变化后重新计算结构性质；性质改变时按适用性重新生成菜单
+    fn update_facts(&mut self) {
+        if self
+            .facts
+            .as_ref()
+            .is_some_and(|&(version, _)| version == self.graph_version)
+        {
+            return;
+        }
+        let facts = GraphFacts::new(&self.data_graph);
+        if self.facts.as_ref().is_none_or(|(_, old)| *old != facts) {
+            self.menu.set_items(self.registry.menu_for(&facts));
+        }
+        self.facts = Some((self.graph_version, facts));
+    }
 
+    fn run_topological_sort(&mut self) {
+        self.current_algorithm = "拓扑排序".to_string();
+        self.visit_log.clear();
         self.visited_nodes.clear();
         self.visited_edges.clear();
+        self.unreachable.clear();
+        self.animation = AnimationPlayer::default();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
 
-        let (nodes, edges, total_cost) = self.data_graph.prim(1);
-        self.animation_nodes = nodes;
-        self.animation_edges = edges;
-        self.prim_total_cost = total_cost;
+        let Some(order) = self.data_graph.topological_sort() else {
+            self.visit_log.push("图中有环，无法拓扑排序".to_string());
+            return;
+        };
+        self.load_steps(order.iter().map(|&u| StepEvent::Visit { u }).collect());
 
-        if !self.animation_nodes.is_empty() {
-            self.visited_nodes.insert(self.animation_nodes[0]);
-            self.visit_log
-                .push(format!("访问节点: {}", self.animation_nodes[0]));
+        self.start_animation();
+    }
+
+    // 按拓扑序松弛，和 Dijkstra 一样在距离面板中显示结果
+    fn run_dag_shortest_paths(&mut self) {
+        self.current_algorithm = "DAG 最短路径".to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
         }
 
-        self.animation_index = 0;
-        self.animation_timer = 0.0;
-        self.animation_step_is_edge = true;
+        let view = self.forbidden.view(&self.data_graph);
+        let (Some(result), Some(steps)) = (
+            algo::dag_shortest_paths(&view, self.source),
+            algo::dag_shortest_paths_steps(&view, self.source),
+        ) else {
+            self.animation = AnimationPlayer::default();
+            self.visit_log
+                .push("图中有环，DAG 最短路径不适用".to_string());
+            return;
+        };
+        self.dijkstra_stale = false;
+        self.unreachable = self
+            .data_graph
+            .nodes()
+            .into_iter()
+            .filter(|u| !result.dist.contains_key(u))
+            .collect();
+        self.dijkstra_result = result;
+        self.load_steps(steps);
+
+        self.start_animation();
     }
 
-    fn run_dijkstra(&mut self) {
-        self.current_algorithm = "Dijkstra".to_string();
+    // 允许负权的最短路径，逐轮松弛所有边；发现负环时不显示距离表，改为在日志中列出负环
+    fn run_bellman_ford(&mut self) {
+        self.current_algorithm = "Bellman-Ford".to_string();
         self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        if self.layout == LayoutKind::Tree {
+            self.set_layout(LayoutKind::Force);
+        }
+
+        let result = algo::bellman_ford(&self.forbidden.view(&self.data_graph), self.source);
+        self.load_steps(result.steps());
+        self.dijkstra_stale = false;
+        self.negative_cycle = result.negative_cycle;
+        if self.negative_cycle.is_some() {
+            self.dijkstra_result = DijkstraResult::default();
+            self.unreachable.clear();
+        } else {
+            self.dijkstra_result = result.paths;
+            self.unreachable = self.unreachable_avoiding(self.source);
+        }
+
+        self.start_animation();
+    }
+
+    // 执行菜单项
+    fn run_action(&mut self, name: &str) {
+        let Some(algorithm) = self.registry.get(name).cloned() else {
+            return;
+        };
+        self.last_action = Some(name.to_string());
+        self.pacing = algorithm.pacing;
+        if algorithm.undirected && !self.require_undirected(name) {
+            return;
+        }
+        if algorithm.params.is_empty() {
+            (algorithm.run)(self, &[]);
+        } else {
+            self.prompt = Some(Prompt::Param {
+                name: name.to_string(),
+                values: Vec::new(),
+                input: String::new(),
+            });
+        }
+    }
+
+    // Karger 的试验次数，留空用当前值
+    fn run_karger(&mut self, input: &str) {
+        match input.trim() {
+            "" => self.start_karger(),
+            s => match s.parse::<usize>() {
+                Ok(n) if n > 0 => {
+                    self.karger_trials = n;
+                    self.start_karger();
+                }
+                _ => self.visit_log.push(format!("试验次数需要是正整数: {}", s)),
+            },
+        }
+    }
 
+    // BFS 按边数求最短路径：边权不全相同时提示改用 Dijkstra，返回 true 表示直接运行 BFS
+    fn check_bfs_weights(&mut self) -> bool {
+        if self.data_graph.has_uniform_weights() {
+            return true;
+        }
+
+        let weights: Vec<f64> = self.data_graph.edges().iter().map(|e| e.2).collect();
+        let min = weights.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = weights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        self.current_algorithm = "BFS".to_string();
+        self.visit_log.clear();
         self.visited_nodes.clear();
         self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
 
-        let (nodes, edges, dist, parent) = self.data_graph.dijkstra(1);
-        self.animation_nodes = nodes;
-        self.animation_edges = edges;
-        self.dijkstra_dist = dist;
-        self.dijkstra_parent = parent;
+        self.visit_log.push(format!(
+            "边权不全相同（{} ~ {}），BFS 只按边数计算路径长度，得到的不是加权最短路径",
+            min.format(self.weight_precision),
+            max.format(self.weight_precision)
+        ));
+        self.visit_log.push(String::new());
+        self.visit_log
+            .push("按 y 改为运行 Dijkstra，其他键仍运行 BFS".to_string());
+        self.prompt = Some(Prompt::SuggestDijkstra);
+        false
+    }
 
-        if !self.animation_nodes.is_empty() {
-            self.visited_nodes.insert(self.animation_nodes[0]);
-            self.visit_log
-                .push(format!("访问节点: {}", self.animation_nodes[0]));
+    // 最小生成树只对无向图有意义：图中有单向边时不运行，列出这些边并提示补全反向边
+    fn require_undirected(&mut self, name: &str) -> bool {
+        let one_way = self.data_graph.one_way_edges();
+        if one_way.is_empty() {
+            return true;
         }
 
-        self.animation_index = 0;
-        self.animation_timer = 0.0;
-        self.animation_step_is_edge = true;
+        self.current_algorithm = name.to_string();
+        self.visit_log.clear();
+        self.visited_nodes.clear();
+        self.visited_edges.clear();
+        self.animation = AnimationPlayer::default();
+
+        self.visit_log.push(format!(
+            "{} 需要无向图，但图中有 {} 条单向边:",
+            name,
+            one_way.len()
+        ));
+        for &(u, v) in one_way.iter().take(10) {
+            self.visit_log.push(format!("  {} -> {}", u, v));
+        }
+        if one_way.len() > 10 {
+            self.visit_log.push("  ...".to_string());
+        }
+        self.visit_log.push(String::new());
+        self.visit_log
+            .push("按 y 为单向边补上同权重的反向边后运行，其他键取消".to_string());
+        self.prompt = Some(Prompt::Symmetrize(name.to_string()));
+        false
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        match event::poll(Duration::from_secs_f32(self.dt as f32))? {
-            true => match event::read()? {
+        if event::poll(Duration::from_secs_f32(self.dt as f32))? {
+            match event::read()? {
                 Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                    let limit_x = self.screen_max_x - self.r;
-                    let limit_y = self.screen_max_y - self.r;
+                    debug!("按键 {:?} {:?}", key_event.code, key_event.modifiers);
+                    if self.handle_prompt(key_event.code) {
+                        return Ok(());
+                    }
+
+                    let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
+                    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+                    let pan_step = 1.0 / self.zoom;
 
                     match key_event.code {
-                        KeyCode::Right => {
-                            self.anchor_x = (self.anchor_x + 0.2).clamp(-limit_x, limit_x)
+                        KeyCode::Left if ctrl => self.resize_canvas(-5),
+                        KeyCode::Right if ctrl => self.resize_canvas(5),
+                        KeyCode::Up if ctrl => self.resize_panel(-5),
+                        KeyCode::Down if ctrl => self.resize_panel(5),
+                        KeyCode::Right if shift => self.pan(pan_step, 0.0),
+                        KeyCode::Left if shift => self.pan(-pan_step, 0.0),
+                        KeyCode::Up if shift => self.pan(0.0, pan_step),
+                        KeyCode::Down if shift => self.pan(0.0, -pan_step),
+                        KeyCode::Right if self.placing.is_some() => self.move_cursor(1.0, 0.0),
+                        KeyCode::Left if self.placing.is_some() => self.move_cursor(-1.0, 0.0),
+                        KeyCode::Up if self.placing.is_some() => self.move_cursor(0.0, 1.0),
+                        KeyCode::Down if self.placing.is_some() => self.move_cursor(0.0, -1.0),
+                        KeyCode::Enter if self.placing.is_some() => self.place_node(),
+                        KeyCode::Char(' ') if self.playback.is_some() => {
+                            if let Some(playback) = &mut self.playback {
+                                playback.playing = !playback.playing;
+                            }
+                        }
+                        // 在时间轴上停在中途时，空格从这一步继续播放
+                        KeyCode::Char(' ')
+                            if self.animation.is_paused() && self.animation.is_running() =>
+                        {
+                            self.animation.resume()
+                        }
+                        KeyCode::Char(' ') => self.toggle_multi_select(),
+                        KeyCode::Tab => self.panel = self.panel.next(),
+                        KeyCode::BackTab => self.panel = self.panel.prev(),
+                        KeyCode::Char('>') => self.cycle_tab(true),
+                        KeyCode::Char('<') => self.cycle_tab(false),
+                        KeyCode::Char('[') if self.tutorial.is_some() => self.step_tutorial(false),
+                        KeyCode::Char(']') if self.tutorial.is_some() => self.step_tutorial(true),
+                        KeyCode::Char('[') => self.step_playback(false),
+                        KeyCode::Char(']') => self.step_playback(true),
+                        KeyCode::Char('/') if self.panel == Panel::Log => {
+                            self.prompt = Some(Prompt::LogFilter(self.log_query.clone()))
+                        }
+                        KeyCode::Char('/') => self.prompt = Some(Prompt::Search(String::new())),
+                        KeyCode::Char(':') => {
+                            self.command_history.rewind();
+                            self.prompt = Some(Prompt::Command(String::new()));
+                        }
+                        KeyCode::Char('z') => self.zoom_by(1.25),
+                        KeyCode::Char('Z') => self.zoom_by(0.8),
+
+                        KeyCode::Right if !self.multi_selected.is_empty() => {
+                            self.move_selected(0.2, 0.0)
                         }
-                        KeyCode::Up => {
-                            self.anchor_y = (self.anchor_y + 0.2).clamp(-limit_y, limit_y)
+                        KeyCode::Left if !self.multi_selected.is_empty() => {
+                            self.move_selected(-0.2, 0.0)
                         }
-                        KeyCode::Down => {
-                            self.anchor_y = (self.anchor_y - 0.2).clamp(-limit_y, limit_y)
+                        KeyCode::Up if !self.multi_selected.is_empty() => {
+                            self.move_selected(0.0, 0.2)
                         }
-                        KeyCode::Left => {
-                            self.anchor_x = (self.anchor_x - 0.2).clamp(-limit_x, limit_x)
+                        KeyCode::Down if !self.multi_selected.is_empty() => {
+                            self.move_selected(0.0, -0.2)
                         }
+                        KeyCode::Right if self.timeline_visible() => self.scrub_by(1),
+                        KeyCode::Left if self.timeline_visible() => self.scrub_by(-1),
+                        KeyCode::Home if self.timeline_visible() => self.scrub_to(0),
+                        KeyCode::End if self.timeline_visible() => self.scrub_to(usize::MAX),
+                        KeyCode::Right => self.move_anchor(0.2, 0.0),
+                        KeyCode::Up => self.move_anchor(0.0, 0.2),
+                        KeyCode::Down => self.move_anchor(0.0, -0.2),
+                        KeyCode::Left => self.move_anchor(-0.2, 0.0),
 
+                        KeyCode::Char('+') if self.edit_mode => self.nudge_selected_weight(1.0),
+                        KeyCode::Char('-') if self.edit_mode => self.nudge_selected_weight(-1.0),
                         KeyCode::Char('+') => self.r += 0.1,
                         KeyCode::Char('-') => self.r -= 0.1,
 
-                        KeyCode::Char('d') => match self.horizontal_split_ratio < 100 {
-                            true => self.horizontal_split_ratio += 1,
-                            false => (),
-                        },
+                        KeyCode::Char('e') if self.panel == Panel::Log => {
+                            self.log_events = self.log_events.next()
+                        }
+                        KeyCode::Char('e') => self.toggle_edit_mode(),
+                        KeyCode::Char('i') => self.toggle_placing(),
+                        KeyCode::Char('d') if self.edit_mode => self.cycle_edge_direction(),
+                        KeyCode::Char('w') if self.edit_mode => self.edit_selected_weight(),
+                        KeyCode::Char('x') | KeyCode::Delete if self.edit_mode => {
+                            self.delete_selected_edge()
+                        }
+                        KeyCode::Char('n') if self.edit_mode => self.select_next_edge(true),
+                        KeyCode::Char('N') if self.edit_mode => self.select_next_edge(false),
+                        KeyCode::Char('d') if self.horizontal_split_ratio < 100 => {
+                            self.horizontal_split_ratio += 1
+                        }
 
-                        KeyCode::Char('a') => match self.horizontal_split_ratio > 0 {
-                            true => self.horizontal_split_ratio -= 1,
-                            false => (),
-                        },
+                        KeyCode::Char('a') if self.horizontal_split_ratio > 0 => {
+                            self.horizontal_split_ratio -= 1
+                        }
 
-                        // menu
+                        KeyCode::Char('j') if self.panel == Panel::Distances => {
+                            self.distance_table.select_next()
+                        }
+                        KeyCode::Char('k') if self.panel == Panel::Distances => {
+                            self.distance_table.select_previous()
+                        }
+                        KeyCode::Char('o') if self.panel == Panel::Distances => {
+                            self.distance_order = match self.distance_order {
+                                DistanceOrder::Node => DistanceOrder::Distance,
+                                DistanceOrder::Distance => DistanceOrder::Node,
+                            };
+                            self.distance_table.select_first();
+                        }
+                        // menu：菜单不在显示时先切换到菜单面板
+                        KeyCode::Char('j' | 'k' | 'l' | 'h') | KeyCode::Enter
+                            if self.panel != Panel::Menu =>
+                        {
+                            self.panel = Panel::Menu
+                        }
                         KeyCode::Char('j') => self.menu.down(),
                         KeyCode::Char('k') => self.menu.up(),
                         KeyCode::Char('l') | KeyCode::Enter => match self.menu.enter() {
-                            MenuSignal::Selected(name) => match name.as_str() {
-                                "Bfs" => self.run_bfs(),
-                                "Dfs" => self.run_dfs(),
-                                "Prim" => self.run_prim(),
-                                "Dijkstra" => self.run_dijkstra(),
-                                "退出" => self.exit = true,
-                                _ => {}
-                            },
+                            MenuSignal::Selected(name) => {
+                                let start = Instant::now();
+                                self.run_action(&name);
+                                info!("{} 耗时 {:?}", name, start.elapsed());
+                                self.panel = Panel::Log;
+                            }
                             MenuSignal::None => {}
                         },
                         KeyCode::Char('h') => self.menu.back(),
+                        KeyCode::Char('t') => self.toggle_tree_layout(),
+                        KeyCode::Char('c') => {
+                            self.edge_attr = match self.edge_attr {
+                                EdgeAttr::Weight => EdgeAttr::Capacity,
+                                EdgeAttr::Capacity => EdgeAttr::Weight,
+                            }
+                        }
+                        KeyCode::Char('b') => self.curved_edges = !self.curved_edges,
+                        KeyCode::Char('g') => self.show_grid = !self.show_grid,
+                        KeyCode::Char('A') => self.accessible = !self.accessible,
+                        KeyCode::Char('s') => self.frozen = !self.frozen,
+                        KeyCode::Char('P') => {
+                            self.weight_precision = (self.weight_precision + 1) % 4
+                        }
+                        KeyCode::Char('y') => self.copy_selected(),
+                        KeyCode::Char('p') => self.paste_clipboard(),
+
+                        KeyCode::Char('n') => {
+                            self.select_next_node(true);
+                            self.panel = Panel::Inspector;
+                        }
+                        KeyCode::Char('N') => {
+                            self.select_next_node(false);
+                            self.panel = Panel::Inspector;
+                        }
+                        KeyCode::Char('f') => self.fullscreen = !self.fullscreen,
+                        KeyCode::Char('F') => self.toggle_focus(),
+                        KeyCode::Char('B') => self.toggle_scenario(),
+                        KeyCode::Char('X') => self.toggle_forbidden(),
+                        KeyCode::Char('R') => {
+                            self.apply_command(Command::Set(Setting::Replay(self.replay.is_none())))
+                        }
+                        KeyCode::Char('x') => self.submit_quiz_answer(),
+                        KeyCode::Char('u') => self.undo_graph(),
+                        KeyCode::Char('m') => self.pick_merge_node(),
+                        KeyCode::Char('r') => self.pick_reach_node(),
+                        KeyCode::Char('v') => self.verbose_log = !self.verbose_log,
+                        // 有正在进行的计算或动画时 Esc 先中止它，否则退出各种模式
+                        KeyCode::Esc if self.abort_running() => {}
+                        // 在时间轴上停在中途时 Esc 回到最终结果
+                        KeyCode::Esc
+                            if self.animation.is_paused() && self.animation.is_running() =>
+                        {
+                            self.scrub_to(usize::MAX)
+                        }
+                        KeyCode::Esc => {
+                            self.end_tutorial();
+                            self.edit_mode = false;
+                            self.selected_edge = None;
+                            self.placing = None;
+                            self.selected_node = None;
+                            self.focus = None;
+                        }
 
                         KeyCode::Char('q') => self.exit = true,
                         _ => {}
                     }
                 }
+                Event::Mouse(mouse)
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left)
+                        && self.click_timeline(mouse.column, mouse.row) => {}
+                Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                    self.click_canvas(mouse.column, mouse.row)
+                }
                 _ => {}
-            },
-            false => (),
+            }
         }
 
         Ok(())
     }
 }
+
+// 各算法使用的边属性：流类算法看容量，其余看权重
+fn algorithm_edge_attr(algorithm: &str) -> EdgeAttr {
+    match algorithm {
+        "MaxFlow" => EdgeAttr::Capacity,
+        _ => EdgeAttr::Weight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_layout_after_aborted_bfs_does_nothing() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3), (1, 4)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.run_bfs();
+        assert!(app.abort_running());
+        assert!(app.current_algorithm.is_empty());
+        app.toggle_tree_layout();
+        assert_eq!(app.layout, LayoutKind::Force);
+
+        // 没有动画时直接切到树形布局也不会取第一个节点
+        app.set_layout(LayoutKind::Tree);
+        assert_eq!(app.layout, LayoutKind::Force);
+    }
+
+    #[test]
+    fn group_command_uses_given_or_multi_selected_nodes() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3), (3, 4)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.apply_command(Command::parse("group 甲 1 2").unwrap());
+        app.multi_selected.extend([3, 4]);
+        app.apply_command(Command::parse("group 乙").unwrap());
+        assert_eq!(app.data_graph.group_of(2), Some("甲"));
+        assert_eq!(app.data_graph.group_of(4), Some("乙"));
+
+        // 不存在的节点不分组
+        app.apply_command(Command::parse("group 丙 1 9").unwrap());
+        assert_eq!(app.data_graph.group_of(1), Some("甲"));
+        app.apply_command(Command::ClearGroups);
+        assert!(app.data_graph.groups().is_empty());
+    }
+
+    #[test]
+    fn negative_weight_marks_distances_stale_without_prompt() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.run_dijkstra();
+        app.apply_edge_weight("2 3 4");
+        assert!(matches!(app.prompt, Some(Prompt::UpdateDistances { .. })));
+        app.prompt = None;
+        app.apply_edge_weight("2 3 -1");
+        assert!(app.prompt.is_none());
+        assert!(app.dijkstra_stale);
+    }
+
+    #[test]
+    fn cycling_edge_direction_redraws_arrows_and_can_be_undone() {
+        let mut app = App::new();
+        app.data_graph.add_edge(1, 2, 1.0, EdgeType::Both);
+        app.init_graph();
+        let canvas_edge_types = |app: &App| {
+            let mut types = Vec::new();
+            app.graph
+                .visit_edges(|_, _, edge_data| types.push(edge_data.user_data.1));
+            types
+        };
+
+        app.selected_edge = Some((1, 2));
+        app.cycle_edge_direction();
+        assert_eq!(app.data_graph.direction(1, 2), Some(EdgeDirection::Forward));
+        assert_eq!(canvas_edge_types(&app), vec![EdgeType::Single]);
+
+        app.undo_graph();
+        assert_eq!(app.data_graph.direction(1, 2), Some(EdgeDirection::Both));
+        assert_eq!(canvas_edge_types(&app), vec![EdgeType::Both]);
+    }
+
+    #[test]
+    fn relabel_moves_id_keyed_state_and_can_be_undone() {
+        let mut app = App::new();
+        for (u, v) in [(2, 5), (5, 9)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.toggle_scenario();
+        app.data_graph.set_edge_weight(5, 9, 7.0);
+        app.toggle_scenario();
+        app.forbidden.toggle_node(9);
+        app.pinned.insert(5, (1.0, 2.0));
+        app.turns.set((2, 5, 9), TurnRule::Ban);
+        app.source = 5;
+        app.multi_selected.extend([2, 9]);
+
+        app.relabel_graph();
+        assert_eq!(app.data_graph.nodes(), vec![1, 2, 3]);
+        assert_eq!(app.forbidden.nodes(), vec![3]);
+        assert_eq!(app.pinned.keys().collect::<Vec<_>>(), vec![&2]);
+        assert_eq!(app.turns.get((1, 2, 3)), Some(TurnRule::Ban));
+        assert_eq!(app.source, 2);
+        assert_eq!(app.multi_selected, BTreeSet::from([1, 3]));
+        let other = &app.scenario.as_ref().unwrap().1;
+        assert_eq!(other.edge(2, 3).map(|e| e.weight), Some(7.0));
+        assert_eq!(app.original_ids.get(&3), Some(&9));
+
+        app.undo_graph();
+        assert_eq!(app.data_graph.nodes(), vec![2, 5, 9]);
+        assert!(app.original_ids.is_empty());
+    }
+
+    #[test]
+    fn weight_filter_follows_edited_weights() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.weight_filter = Some((Some(5.0), None));
+        assert!(app.edge_polylines().is_empty());
+
+        app.apply_edge_weight("1 2 8");
+        let shown: Vec<_> = app.edge_polylines().into_iter().map(|(e, _)| e).collect();
+        assert_eq!(shown.len(), 1);
+        assert!(shown[0] == (1, 2) || shown[0] == (2, 1));
+    }
+
+    #[test]
+    fn facts_are_recomputed_only_after_the_graph_changes() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.update_facts();
+        let version = app.facts.as_ref().unwrap().0;
+        assert!(!app.facts.as_ref().unwrap().1.weighted);
+
+        app.update_facts();
+        assert_eq!(app.facts.as_ref().unwrap().0, version);
+
+        app.apply_edge_weight("1 2 4");
+        app.update_facts();
+        assert!(app.facts.as_ref().unwrap().0 > version);
+        assert!(app.facts.as_ref().unwrap().1.weighted);
+    }
+
+    #[test]
+    fn replay_never_reruns_export_items() {
+        let mut app = App::new();
+        app.data_graph.add_edge(1, 2, 1.0, EdgeType::Both);
+        app.init_graph();
+        app.replay = Some(Replay::new(vec!["DOT 文件".to_string()]));
+        app.dt = graph_algorithm_tui::animation::REPLAY_HOLD_SECONDS + 1.0;
+        app.update_replay();
+        assert_ne!(app.current_algorithm, "导出");
+        assert!(app.last_action.is_none());
+    }
+
+    #[test]
+    fn topology_edits_apply_to_both_scenarios() {
+        let mut app = App::new();
+        for (u, v) in [(1, 2), (2, 3)] {
+            app.data_graph.add_edge(u, v, 1.0, EdgeType::Both);
+        }
+        app.init_graph();
+        app.toggle_scenario();
+        app.data_graph.set_edge_weight(1, 2, 5.0);
+        app.apply_command(Command::parse("edge 3 4 2").unwrap());
+        app.apply_command(Command::parse("deledge 2 3").unwrap());
+
+        let other = &app.scenario.as_ref().unwrap().1;
+        assert_eq!(other.edge(3, 4).map(|e| e.weight), Some(2.0));
+        assert!(other.edge(2, 3).is_none());
+        // 两边都有的边各自保留自己的权重
+        assert_eq!(other.edge(1, 2).map(|e| e.weight), Some(1.0));
+        assert_eq!(
+            scenario::changed_weights(other, &app.data_graph),
+            vec![(1, 2, 1.0, 5.0)]
+        );
+
+        app.toggle_scenario();
+        assert_eq!(app.data_graph.edge(3, 4).map(|e| e.weight), Some(2.0));
+        assert!(app.data_graph.edge(2, 3).is_none());
+    }
+}
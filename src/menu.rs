@@ -49,6 +49,48 @@ impl MenuState {
         }
     }
 
+    // 替换菜单内容（如图变化后按适用性重新生成），尽量停留在原来的子菜单和选中项，
+    // 原路径已不存在时回到顶层
+    pub fn set_items(&mut self, items: Vec<MenuItem>) {
+        let path: Vec<String> = {
+            let mut names = Vec::new();
+            let mut list = &self.root_items;
+            for &i in &self.nav_stack {
+                let Some(item) = list.get(i) else { break };
+                names.push(item.name.clone());
+                list = &item.children;
+            }
+            names
+        };
+        let selected = self
+            .list_state
+            .selected()
+            .and_then(|i| self.get_current_items().get(i))
+            .map(|item| item.name.clone());
+
+        self.root_items = items;
+        self.nav_stack.clear();
+        for name in path {
+            let list = self.get_current_items();
+            match list
+                .iter()
+                .position(|item| item.name == name && !item.children.is_empty())
+            {
+                Some(i) => self.nav_stack.push(i),
+                None => {
+                    self.nav_stack.clear();
+                    break;
+                }
+            }
+        }
+        let list = self.get_current_items();
+        let index = selected
+            .and_then(|name| list.iter().position(|item| item.name == name))
+            .unwrap_or(0);
+        self.list_state
+            .select(Some(index.min(list.len().saturating_sub(1))));
+    }
+
     // 获取当前层级的数据
     pub fn get_current_items(&self) -> &[MenuItem] {
         let mut current_list = &self.root_items;
@@ -135,6 +177,12 @@ pub struct Menu<'a> {
     highlight_style: Style,
 }
 
+impl Default for Menu<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Menu<'a> {
     pub fn new() -> Self {
         Self {
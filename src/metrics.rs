@@ -0,0 +1,86 @@
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// 趋势面板最多保留的记录数，更早的丢弃
+pub const HISTORY_LIMIT: usize = 200;
+
+// 某一时刻图的统计。连通分量和最小生成森林都忽略边的方向
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GraphMetrics {
+    pub edges: usize,
+    pub components: usize,
+    pub mst_weight: f64,
+}
+
+impl GraphMetrics {
+    pub fn of<W: Weight>(g: &Graph<W>) -> Self {
+        Self {
+            edges: g.edges().len(),
+            components: g.connected_components().len(),
+            mst_weight: g.kruskal().total_weight.to_f64(),
+        }
+    }
+}
+
+// 图的统计随时间的记录：图每次改变（命令栏、编辑、撤销、时序回放等）后记录一次，
+// 与上一次相同时不重复记录。趋势面板把每一项画成迷你折线
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricsHistory {
+    samples: Vec<GraphMetrics>,
+}
+
+impl MetricsHistory {
+    // 返回是否新增了一条记录
+    pub fn record<W: Weight>(&mut self, g: &Graph<W>) -> bool {
+        let metrics = GraphMetrics::of(g);
+        if self.samples.last() == Some(&metrics) {
+            return false;
+        }
+        self.samples.push(metrics);
+        if self.samples.len() > HISTORY_LIMIT {
+            self.samples.remove(0);
+        }
+        true
+    }
+
+    pub fn samples(&self) -> &[GraphMetrics] {
+        &self.samples
+    }
+
+    pub fn last(&self) -> Option<&GraphMetrics> {
+        self.samples.last()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn edges(&self) -> Vec<f64> {
+        self.samples.iter().map(|m| m.edges as f64).collect()
+    }
+
+    pub fn components(&self) -> Vec<f64> {
+        self.samples.iter().map(|m| m.components as f64).collect()
+    }
+
+    pub fn mst_weights(&self) -> Vec<f64> {
+        self.samples.iter().map(|m| m.mst_weight).collect()
+    }
+}
+
+// 把一列数值换成迷你折线的高度：最小值对应 1、最大值对应 scale，全部相同时都为 1。
+// 权重可能为负或带小数，不能直接交给只接受非负整数的 Sparkline
+pub fn sparkline_levels(values: &[f64], scale: u64) -> Vec<u64> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if max > min && v.is_finite() {
+                1 + ((v - min) / (max - min) * scale.saturating_sub(1) as f64).round() as u64
+            } else {
+                1
+            }
+        })
+        .collect()
+}
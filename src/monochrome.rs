@@ -0,0 +1,69 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+
+// 是否改用无颜色显示：设置了非空的 NO_COLOR（见 no-color.org），或 TERM=dumb
+pub fn requested(no_color: Option<&str>, term: Option<&str>) -> bool {
+    no_color.is_some_and(|v| !v.is_empty()) || term == Some("dumb")
+}
+
+// 把画好的一帧改为无颜色样式：醒目的前景色（已访问、告警等）改为粗体，
+// 暗灰改为暗淡，有背景色的（菜单高亮）改为反色；画布点阵、边框、箭头等符号换成 ASCII
+pub fn apply(buf: &mut Buffer) {
+    for cell in &mut buf.content {
+        let emphasis = emphasis(cell.fg);
+        if cell.bg != Color::Reset {
+            cell.modifier |= Modifier::REVERSED;
+        }
+        cell.modifier |= emphasis;
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+
+        let mut chars = cell.symbol().chars();
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && let Some(ascii) = ascii_marker(c, emphasis)
+        {
+            cell.set_char(ascii);
+        }
+    }
+}
+
+fn emphasis(color: Color) -> Modifier {
+    match color {
+        Color::Reset | Color::White | Color::Gray | Color::Blue | Color::LightBlue => {
+            Modifier::empty()
+        }
+        Color::DarkGray | Color::Black => Modifier::DIM,
+        _ => Modifier::BOLD,
+    }
+}
+
+// 非 ASCII 的标记符号对应的 ASCII 字符，文字（如中文）保持不变。
+// 点阵按强调程度区分，没有颜色时仍能看出哪些边/节点已访问
+fn ascii_marker(c: char, emphasis: Modifier) -> Option<char> {
+    let ascii = match c {
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28ff}' | '•' | '·' => {
+            if emphasis.contains(Modifier::BOLD) {
+                '#'
+            } else if emphasis.contains(Modifier::DIM) {
+                '.'
+            } else {
+                '*'
+            }
+        }
+        '─' | '━' | '═' => '-',
+        '│' | '┃' | '║' => '|',
+        '┌' | '┐' | '└' | '┘' | '╭' | '╮' | '╰' | '╯' | '├' | '┤' | '┬' | '┴' | '┼' => {
+            '+'
+        }
+        '█' | '▀' | '▄' | '▌' | '▐' | '▁'..='▇' | '░' | '▒' | '▓' => '#',
+        '→' => '>',
+        '←' => '<',
+        '↑' => '^',
+        '↓' => 'v',
+        '↔' => '=',
+        '…' => '.',
+        _ => return None,
+    };
+    Some(ascii)
+}
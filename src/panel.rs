@@ -0,0 +1,46 @@
+// 右侧面板：同一时间只显示一个，占满整个侧栏高度。按 Tab 循环切换，
+// 进入相应模式时自动切换（选择节点显示检查器，运行算法显示日志）
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Panel {
+    #[default]
+    Menu,
+    Inspector,
+    Frontier,
+    Distances,
+    Trend,
+    Log,
+}
+
+impl Panel {
+    pub const ALL: [Panel; 6] = [
+        Panel::Menu,
+        Panel::Inspector,
+        Panel::Frontier,
+        Panel::Distances,
+        Panel::Trend,
+        Panel::Log,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Panel::Menu => "菜单",
+            Panel::Inspector => "节点",
+            Panel::Frontier => "队列",
+            Panel::Distances => "距离",
+            Panel::Trend => "趋势",
+            Panel::Log => "日志",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&p| p == self).unwrap_or(0)
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
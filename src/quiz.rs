@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuizAnswer {
+    Node(i64),
+    // 无向边，端点按从小到大保存
+    Edge(i64, i64),
+}
+
+impl QuizAnswer {
+    pub fn edge(u: i64, v: i64) -> Self {
+        QuizAnswer::Edge(u.min(v), u.max(v))
+    }
+}
+
+impl fmt::Display for QuizAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuizAnswer::Node(u) => write!(f, "节点 {}", u),
+            QuizAnswer::Edge(u, v) => write!(f, "边 {}-{}", u, v),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QuizKind {
+    // 下一个被 Dijkstra 确定最短距离的节点
+    DijkstraSettle,
+    // Kruskal 下一条选入生成树的边
+    KruskalPick,
+}
+
+// 按算法的实际执行顺序逐步提问并计分
+// 每步附带一个排序键（Dijkstra 为距离、Kruskal 为边权），键相同的答案视为同样正确
+pub struct Quiz<W = i64> {
+    kind: QuizKind,
+    steps: Vec<(QuizAnswer, W)>,
+    index: usize,
+    correct: usize,
+}
+
+impl<W: Weight> Quiz<W> {
+    pub fn dijkstra(g: &Graph<W>, s: i64) -> Self {
+        let result = g.dijkstra(s);
+        let dist = result.dist;
+        // 起点总是第一个确定，不作为题目
+        let steps = result
+            .visited_nodes
+            .into_iter()
+            .filter(|&u| u != s)
+            .map(|u| (QuizAnswer::Node(u), dist[&u]))
+            .collect();
+        Self::with_steps(QuizKind::DijkstraSettle, steps)
+    }
+
+    pub fn kruskal(g: &Graph<W>) -> Self {
+        let steps = g
+            .kruskal()
+            .edges
+            .into_iter()
+            .map(|(u, v, w)| (QuizAnswer::edge(u, v), w))
+            .collect();
+        Self::with_steps(QuizKind::KruskalPick, steps)
+    }
+
+    fn with_steps(kind: QuizKind, steps: Vec<(QuizAnswer, W)>) -> Self {
+        Self {
+            kind,
+            steps,
+            index: 0,
+            correct: 0,
+        }
+    }
+
+    pub fn kind(&self) -> QuizKind {
+        self.kind
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.steps.len()
+    }
+
+    pub fn question(&self) -> Option<String> {
+        if self.is_finished() {
+            return None;
+        }
+        Some(match self.kind {
+            QuizKind::DijkstraSettle => {
+                format!("第 {} 步: Dijkstra 下一个确定的节点是?", self.index + 1)
+            }
+            QuizKind::KruskalPick => format!("第 {} 步: Kruskal 下一条选入的边是?", self.index + 1),
+        })
+    }
+
+    // 已答过的步骤（含答错时揭晓的正确答案），用于在画布上显示进度
+    pub fn answered(&self) -> impl Iterator<Item = QuizAnswer> + '_ {
+        self.steps[..self.index].iter().map(|(a, _)| *a)
+    }
+
+    // 提交答案并进入下一步，返回 (是否正确, 本步的标准答案)
+    pub fn answer(&mut self, answer: QuizAnswer) -> Option<(bool, QuizAnswer)> {
+        let (expected, key) = *self.steps.get(self.index)?;
+
+        // 与标准答案键相同且尚未答过的答案同样正确，把它换到当前步
+        let tied = self.steps[self.index..]
+            .iter()
+            .position(|&(a, k)| a == answer && k.approx_eq(key));
+        let result = match tied {
+            Some(j) => {
+                self.steps.swap(self.index, self.index + j);
+                self.correct += 1;
+                (true, answer)
+            }
+            None => (false, expected),
+        };
+
+        self.index += 1;
+        Some(result)
+    }
+
+    // (答对题数, 已答题数, 总题数)
+    pub fn score(&self) -> (usize, usize, usize) {
+        (self.correct, self.index, self.steps.len())
+    }
+}
@@ -0,0 +1,159 @@
+use std::rc::Rc;
+
+use crate::animation::Pacing;
+use crate::menu::MenuItem;
+use crate::summary::GraphFacts;
+
+// 运行算法的闭包：上下文（界面状态）与按参数说明收集到的输入
+pub type Runner<C> = Rc<dyn Fn(&mut C, &[String])>;
+
+// 运行前需要用户依次输入的一个参数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+    // 输入提示，如 "边权范围(最小,最大)"
+    pub label: &'static str,
+}
+
+// 一个可从菜单运行的算法或操作
+pub struct Algorithm<C> {
+    pub name: String,
+    // 所在的菜单分组，为空时直接放在顶层
+    pub category: &'static str,
+    pub params: Vec<Param>,
+    // 只对无向图有意义，有单向边时先询问是否补全反向边
+    pub undirected: bool,
+    // 适用条件，不满足时不在菜单中显示；None 表示总是适用
+    pub applies: Option<fn(&GraphFacts) -> bool>,
+    // 动画节奏，默认每步停留相同的时间
+    pub pacing: Pacing,
+    pub run: Runner<C>,
+}
+
+impl<C> Clone for Algorithm<C> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            category: self.category,
+            params: self.params.clone(),
+            undirected: self.undirected,
+            applies: self.applies,
+            pacing: self.pacing,
+            run: Rc::clone(&self.run),
+        }
+    }
+}
+
+impl<C> Algorithm<C> {
+    pub fn new(
+        category: &'static str,
+        name: &str,
+        run: impl Fn(&mut C, &[String]) + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            category,
+            params: Vec::new(),
+            undirected: false,
+            applies: None,
+            pacing: Pacing::default(),
+            run: Rc::new(run),
+        }
+    }
+
+    pub fn param(mut self, label: &'static str) -> Self {
+        self.params.push(Param { label });
+        self
+    }
+
+    pub fn undirected(mut self) -> Self {
+        self.undirected = true;
+        self
+    }
+
+    // 只在图满足条件时显示，如 .when(GraphFacts::is_dag)
+    pub fn when(mut self, applies: fn(&GraphFacts) -> bool) -> Self {
+        self.applies = Some(applies);
+        self
+    }
+
+    pub fn pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    pub fn applies_to(&self, facts: &GraphFacts) -> bool {
+        self.applies.is_none_or(|f| f(facts))
+    }
+}
+
+// 算法注册表：每个算法登记名称、分组、参数说明和运行闭包，菜单由注册表生成，
+// 新增算法只需在注册处加一项，不必同时修改菜单和按名称分发的代码
+pub struct AlgorithmRegistry<C> {
+    algorithms: Vec<Algorithm<C>>,
+}
+
+impl<C> Default for AlgorithmRegistry<C> {
+    fn default() -> Self {
+        Self {
+            algorithms: Vec::new(),
+        }
+    }
+}
+
+impl<C> AlgorithmRegistry<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 同名算法后登记的覆盖先登记的，保留原来的登记顺序
+    pub fn register(&mut self, algorithm: Algorithm<C>) -> &mut Self {
+        match self
+            .algorithms
+            .iter_mut()
+            .find(|a| a.name == algorithm.name)
+        {
+            Some(existing) => *existing = algorithm,
+            None => self.algorithms.push(algorithm),
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Algorithm<C>> {
+        self.algorithms.iter().find(|a| a.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Algorithm<C>> {
+        self.algorithms.iter()
+    }
+
+    // 按分组第一次出现的顺序生成菜单，组内按登记顺序
+    pub fn menu(&self) -> Vec<MenuItem> {
+        Self::build_menu(self.algorithms.iter())
+    }
+
+    // 只包含适用于当前图的项，分组中没有适用项时整组不显示
+    pub fn menu_for(&self, facts: &GraphFacts) -> Vec<MenuItem> {
+        Self::build_menu(self.algorithms.iter().filter(|a| a.applies_to(facts)))
+    }
+
+    fn build_menu<'a>(algorithms: impl Iterator<Item = &'a Algorithm<C>>) -> Vec<MenuItem>
+    where
+        C: 'a,
+    {
+        let mut items: Vec<MenuItem> = Vec::new();
+        for a in algorithms {
+            if a.category.is_empty() {
+                items.push(MenuItem::leaf(&a.name));
+                continue;
+            }
+            let group = items
+                .iter()
+                .position(|item| item.name == a.category && !item.children.is_empty());
+            match group {
+                Some(i) => items[i].children.push(MenuItem::leaf(&a.name)),
+                None => items.push(MenuItem::new(a.category, vec![MenuItem::leaf(&a.name)])),
+            }
+        }
+        items
+    }
+}
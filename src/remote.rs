@@ -0,0 +1,223 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+
+// 远程控制：外部程序（如 Jupyter 笔记本、评分脚本）连到本机 TCP 端口，每行发送一个 JSON 请求，
+// 每个请求得到一行 JSON 应答。请求由界面线程在每一帧中处理，与按键操作走同一套逻辑
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    // {"cmd": "add_edge", "u": 1, "v": 2, "w": 3.5, "directed": false}，w 默认 1
+    AddEdge {
+        u: i64,
+        v: i64,
+        #[serde(default = "one")]
+        w: f64,
+        #[serde(default)]
+        directed: bool,
+    },
+    // {"cmd": "run_algorithm", "name": "dijkstra", "source": 3}，名称同命令栏的 run
+    RunAlgorithm {
+        name: String,
+        #[serde(default)]
+        source: Option<i64>,
+    },
+    // {"cmd": "get_state"}
+    GetState,
+    // {"cmd": "command", "line": "delnode 4"}，执行一条命令栏命令，读写文件的命令除外（见 allowed）
+    Command {
+        line: String,
+    },
+}
+
+fn one() -> f64 {
+    1.0
+}
+
+impl Request {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        serde_json::from_str(line).map_err(|e| format!("无法解析请求: {}", e))
+    }
+
+    // 改图和运行算法的请求对应的命令栏命令；get_state 返回 None
+    pub fn to_command(&self) -> Option<Result<Command<f64>, String>> {
+        match self {
+            Request::AddEdge { u, v, w, directed } => Some(Ok(Command::Edge {
+                u: *u,
+                v: *v,
+                w: *w,
+                directed: *directed,
+            })),
+            Request::RunAlgorithm { name, source } => Some(Ok(Command::Run {
+                algorithm: name.clone(),
+                source: *source,
+            })),
+            Request::Command { line } => Some(Command::parse(line).and_then(|command| {
+                if allowed(&command) {
+                    Ok(command)
+                } else {
+                    Err(format!("远程请求不能读写文件: {}", line.trim()))
+                }
+            })),
+            Request::GetState => None,
+        }
+    }
+}
+
+// 远程请求可以执行的命令：改图、运行算法和调整显示。端口没有认证，本机任何进程都能连上，
+// save/load 能读写任意路径，只能在命令栏中使用。新增命令时在这里决定是否开放
+pub fn allowed<W>(command: &Command<W>) -> bool {
+    match command {
+        Command::Save(_) | Command::Load(_) => false,
+        Command::Edge { .. }
+        | Command::Node(_)
+        | Command::DelEdge(..)
+        | Command::DelNode(_)
+        | Command::Run { .. }
+        | Command::Set(_)
+        | Command::Playlist(_)
+        | Command::Clear
+        | Command::ForbidNode(_)
+        | Command::ForbidEdge(..)
+        | Command::ClearForbidden
+        | Command::Turn { .. }
+        | Command::ClearTurns
+        | Command::Group { .. }
+        | Command::ClearGroups => true,
+    }
+}
+
+// get_state 的应答内容
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub nodes: Vec<i64>,
+    // (u, v, 权重, 是否单向)
+    pub edges: Vec<(i64, i64, f64, bool)>,
+    pub algorithm: String,
+    pub source: i64,
+    // 动画中已访问的节点，升序
+    pub visited: Vec<i64>,
+    // 日志的最后几行
+    pub log: Vec<String>,
+}
+
+// 一行应答：ok 为 false 时 error 给出原因；get_state 时 state 有值；
+// log 为执行请求期间新增的日志行（例如“节点 4 不存在”）
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub log: Vec<String>,
+}
+
+impl Response {
+    pub fn ok(log: Vec<String>) -> Self {
+        Self {
+            ok: true,
+            log,
+            ..Self::default()
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(message.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn state(state: State) -> Self {
+        Self {
+            ok: true,
+            state: Some(state),
+            ..Self::default()
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| r#"{"ok":false}"#.to_string())
+    }
+}
+
+// 等待界面线程处理的请求，处理完后用 reply 发回应答
+pub struct Pending {
+    pub request: Result<Request, String>,
+    reply: Sender<Response>,
+}
+
+impl Pending {
+    pub fn reply(self, response: Response) {
+        let _ = self.reply.send(response);
+    }
+}
+
+// 监听本机端口的服务：后台线程接受连接，每个连接一个线程逐行读请求，
+// 转交界面线程后等待应答再写回
+pub struct Server {
+    addr: SocketAddr,
+    requests: Receiver<Pending>,
+}
+
+impl Server {
+    // 只监听 127.0.0.1；port 为 0 时由系统分配
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let addr = listener.local_addr()?;
+        let (tx, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                thread::spawn(move || serve(stream, tx));
+            }
+        });
+        Ok(Self { addr, requests })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // 取一个待处理的请求，没有时立即返回 None
+    pub fn poll(&self) -> Option<Pending> {
+        self.requests.try_recv().ok()
+    }
+}
+
+fn serve(stream: TcpStream, tx: Sender<Pending>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (reply, response) = mpsc::channel();
+        let pending = Pending {
+            request: Request::parse(&line),
+            reply,
+        };
+        // 界面已退出时断开连接
+        if tx.send(pending).is_err() {
+            return;
+        }
+        let Ok(response) = response.recv() else {
+            return;
+        };
+        if writeln!(writer, "{}", response.to_line()).is_err() {
+            return;
+        }
+    }
+}
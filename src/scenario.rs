@@ -0,0 +1,88 @@
+use crate::algo::{self, GraphLike};
+use crate::avoid::Forbidden;
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// A/B 权重方案：同一拓扑的两套边权，如平时与高峰期的通行时间
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Side {
+    #[default]
+    A,
+    B,
+}
+
+impl Side {
+    pub fn other(self) -> Self {
+        match self {
+            Side::A => Side::B,
+            Side::B => Side::A,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Side::A => "A",
+            Side::B => "B",
+        }
+    }
+}
+
+// 两套方案下都存在、权重不同的边 (u, v, A 中的权重, B 中的权重)，按 A 的 edges() 顺序。
+// 只在一方存在的边不列出
+pub fn changed_weights<W: Weight>(a: &Graph<W>, b: &Graph<W>) -> Vec<(i64, i64, W, W)> {
+    a.edges()
+        .into_iter()
+        .filter_map(|(u, v, wa, _)| {
+            let wb = b.edge(u, v)?.weight;
+            (wa.total_cmp(&wb) != std::cmp::Ordering::Equal).then_some((u, v, wa, wb))
+        })
+        .collect()
+}
+
+// 让另一套方案跟上当前方案的拓扑：节点、边与方向取自 current，两套方案都有的边保留 other 的权重，
+// 新加的边在两套方案里权重相同。在当前方案上增删节点或边后调用，changed_weights 才不会漏掉边
+pub fn follow_topology<W: Weight>(current: &Graph<W>, other: &Graph<W>) -> Graph<W> {
+    let mut synced = current.clone();
+    for (u, v, _, _) in current.edges() {
+        if let Some(e) = other.edge(u, v) {
+            synced.set_edge_weight(u, v, e.weight);
+        }
+    }
+    synced
+}
+
+// s 到 t 的最短路径及其长度，不可达时为 None
+pub fn shortest_path<G: GraphLike>(g: &G, s: i64, t: i64) -> Option<(G::Weight, Vec<i64>)> {
+    let result = algo::dijkstra(g, s);
+    let dist = *result.dist.get(&t)?;
+    Some((dist, result.path_to(t)?))
+}
+
+// 同一对节点在两套方案下的最短路径对比
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison<W = i64> {
+    pub a: Option<(W, Vec<i64>)>,
+    pub b: Option<(W, Vec<i64>)>,
+    pub changed: Vec<(i64, i64, W, W)>,
+}
+
+impl<W: Weight> Comparison<W> {
+    pub fn new(a: &Graph<W>, b: &Graph<W>, s: i64, t: i64) -> Self {
+        Self::avoiding(a, b, s, t, &Forbidden::default())
+    }
+
+    // 两套方案下的路径都绕开禁行的节点和边
+    pub fn avoiding(a: &Graph<W>, b: &Graph<W>, s: i64, t: i64, forbidden: &Forbidden) -> Self {
+        Self {
+            a: shortest_path(&forbidden.view(a), s, t),
+            b: shortest_path(&forbidden.view(b), s, t),
+            changed: changed_weights(a, b),
+        }
+    }
+
+    // 两套方案走的是不是同一条路径
+    pub fn same_route(&self) -> bool {
+        let route = |p: &Option<(W, Vec<i64>)>| p.as_ref().map(|(_, path)| path.clone());
+        route(&self.a) == route(&self.b)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::graph::Graph;
+use crate::weight::Weight;
+
+// 节点数不超过这个值时才计算连通度，需要对每对节点求一次最大流
+pub const CONNECTIVITY_LIMIT: usize = 50;
+
+// 图的基本结构性质，图变化后计算一次，用于决定菜单中显示哪些算法
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphFacts {
+    // 有单向边
+    pub directed: bool,
+    // 没有环（无向边算作环），即 DAG
+    pub acyclic: bool,
+    // 忽略方向时连通
+    pub connected: bool,
+    pub bipartite: bool,
+    // 边权不全相同
+    pub weighted: bool,
+}
+
+impl GraphFacts {
+    pub fn new<W: Weight>(g: &Graph<W>) -> Self {
+        GraphFacts {
+            directed: g.is_directed(),
+            acyclic: g.is_acyclic(),
+            connected: g.connected_components().len() <= 1,
+            bipartite: g.is_bipartite(),
+            weighted: !g.has_uniform_weights(),
+        }
+    }
+
+    // 有边的 DAG，拓扑排序和 DAG 最短路径只在这时显示
+    pub fn is_dag(&self) -> bool {
+        self.directed && self.acyclic
+    }
+}
+
+// 不带动画地运行所有适用的算法，汇总成一张表，用于快速了解导入的图
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphSummary<W = i64> {
+    pub nodes: usize,
+    pub edges: usize,
+    pub directed: bool,
+    // 忽略方向的连通分量个数
+    pub components: usize,
+    pub bipartite: bool,
+    // Kruskal 最小生成森林的总权重，有单向边时不适用
+    pub mst_weight: Option<W>,
+    // 所有可达点对中最短距离的最大值，没有边时为 None
+    pub diameter: Option<W>,
+    // 点连通度与一个最小点割、边连通度与一个最小边割；节点过多时为 None
+    pub vertex_connectivity: Option<(usize, Vec<i64>)>,
+    pub edge_connectivity: Option<(usize, Vec<(i64, i64)>)>,
+    pub source: i64,
+    // 从 source 出发到每个节点的最短距离，不可达为 None
+    pub distances: Vec<(i64, Option<W>)>,
+}
+
+impl<W: Weight> GraphSummary<W> {
+    pub fn new(g: &Graph<W>, source: i64) -> Self {
+        let directed = g.is_directed();
+        let nodes = g.nodes();
+
+        let mut diameter: Option<W> = None;
+        for &u in &nodes {
+            for (v, d) in g.dijkstra(u).dist {
+                if v != u && d != W::infinity() && diameter.is_none_or(|m| m.less_than(d)) {
+                    diameter = Some(d);
+                }
+            }
+        }
+
+        let dist = g.dijkstra(source).dist;
+        let distances = nodes
+            .iter()
+            .map(|u| (*u, dist.get(u).copied().filter(|&d| d != W::infinity())))
+            .collect();
+
+        let small = nodes.len() <= CONNECTIVITY_LIMIT;
+        GraphSummary {
+            nodes: nodes.len(),
+            edges: g.edges().len(),
+            directed,
+            components: g.connected_components().len(),
+            bipartite: g.is_bipartite(),
+            mst_weight: (!directed).then(|| g.kruskal().total_weight),
+            diameter,
+            vertex_connectivity: small.then(|| g.vertex_connectivity()),
+            edge_connectivity: small.then(|| g.edge_connectivity()),
+            source,
+            distances,
+        }
+    }
+
+    // (指标, 值) 形式的表格行
+    pub fn rows(&self, precision: usize) -> Vec<(String, String)> {
+        let yes_no = |b: bool| if b { "是" } else { "否" }.to_string();
+        let weight = |w: Option<W>| w.map_or("-".to_string(), |w| w.format(precision));
+
+        let mut rows = vec![
+            ("节点数".to_string(), self.nodes.to_string()),
+            ("边数".to_string(), self.edges.to_string()),
+            ("有向".to_string(), yes_no(self.directed)),
+            ("连通分量数".to_string(), self.components.to_string()),
+            ("二分图".to_string(), yes_no(self.bipartite)),
+            (
+                if self.components > 1 {
+                    "最小生成森林权重"
+                } else {
+                    "最小生成树权重"
+                }
+                .to_string(),
+                match self.mst_weight {
+                    Some(w) => w.format(precision),
+                    None => "不适用（有单向边）".to_string(),
+                },
+            ),
+            ("直径".to_string(), weight(self.diameter)),
+        ];
+        let skipped = || format!("未计算（节点数超过 {}）", CONNECTIVITY_LIMIT);
+        let with_cut = |k: usize, cut: Vec<String>| {
+            if cut.is_empty() {
+                k.to_string()
+            } else {
+                format!("{}（删去 {}）", k, cut.join(", "))
+            }
+        };
+        rows.push((
+            "点连通度".to_string(),
+            self.vertex_connectivity
+                .as_ref()
+                .map_or_else(skipped, |(k, cut)| {
+                    with_cut(*k, cut.iter().map(|u| u.to_string()).collect())
+                }),
+        ));
+        rows.push((
+            "边连通度".to_string(),
+            self.edge_connectivity
+                .as_ref()
+                .map_or_else(skipped, |(k, cut)| {
+                    with_cut(
+                        *k,
+                        cut.iter().map(|(u, v)| format!("{}-{}", u, v)).collect(),
+                    )
+                }),
+        ));
+        rows.extend(self.distances.iter().map(|&(u, d)| {
+            (
+                format!("dist({} -> {})", self.source, u),
+                d.map_or("∞".to_string(), |d| d.format(precision)),
+            )
+        }));
+        rows
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+
+use crate::graph::{AddEdgePolicy, EdgeType, Graph};
+use crate::weight::Weight;
+
+// 带时间戳的边事件，边按无向处理
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeEvent<W = i64> {
+    Add { u: i64, v: i64, w: W },
+    Remove { u: i64, v: i64 },
+}
+
+// 按时间排序的边事件序列，可以取任意时刻的图快照
+#[derive(Clone, Debug, Default)]
+pub struct Timeline<W = i64> {
+    events: Vec<(i64, EdgeEvent<W>)>,
+}
+
+impl<W: Weight> Timeline<W> {
+    // 同一时刻的事件保持插入顺序
+    pub fn push(&mut self, time: i64, event: EdgeEvent<W>) {
+        let at = self.events.partition_point(|(t, _)| *t <= time);
+        self.events.insert(at, (time, event));
+    }
+
+    // 每行一个事件："<时间> + u v w" 添加边，"<时间> - u v" 删除边；# 开头为注释
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut timeline = Self::default();
+
+        for (no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = || format!("第 {} 行格式错误: {}", no + 1, line);
+            let id = |s: &str| s.parse::<i64>().map_err(|_| err());
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let event = match parts.as_slice() {
+                [_, "+", u, v, w] => EdgeEvent::Add {
+                    u: id(u)?,
+                    v: id(v)?,
+                    w: W::parse(w).ok_or_else(err)?,
+                },
+                [_, "-", u, v] => EdgeEvent::Remove {
+                    u: id(u)?,
+                    v: id(v)?,
+                },
+                _ => return Err(err()),
+            };
+            timeline.push(id(parts[0])?, event);
+        }
+
+        Ok(timeline)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // 所有出现过事件的时刻，升序
+    pub fn times(&self) -> Vec<i64> {
+        self.events
+            .iter()
+            .map(|(t, _)| *t)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    // 依次应用时间戳 <= time 的事件得到的图；出现过的节点在边删除后仍保留。
+    // 再次添加已有的边时替换其权重
+    pub fn snapshot_at(&self, time: i64) -> Graph<W> {
+        self.snapshot_with(time, AddEdgePolicy::KeepLast)
+    }
+
+    // 与 snapshot_at 相同，但再次添加已有的边时按 policy 合并权重
+    pub fn snapshot_with(&self, time: i64, policy: AddEdgePolicy) -> Graph<W> {
+        let mut g = Graph::default();
+        for (t, event) in &self.events {
+            if *t > time {
+                break;
+            }
+            match *event {
+                EdgeEvent::Add { u, v, w } => {
+                    g.add_edge_with(u, v, w, EdgeType::Both, policy);
+                }
+                EdgeEvent::Remove { u, v } => {
+                    g.remove_edge(u, v, EdgeType::Both);
+                }
+            }
+        }
+        g
+    }
+}
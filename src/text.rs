@@ -0,0 +1,37 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// 终端中的显示宽度（列数），中文等全角字符占 2 列
+pub fn width(s: &str) -> usize {
+    s.width()
+}
+
+// 截断到最多 max 列，截断时末尾加 "…"（占 1 列，计入 max）
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    out
+}
+
+// 右侧补空格到 w 列；format! 的 {:<w} 按字符数补齐，遇到全角字符会错位
+pub fn pad_right(s: &str, w: usize) -> String {
+    format!("{}{}", s, " ".repeat(w.saturating_sub(s.width())))
+}
+
+// 左侧补空格到 w 列
+pub fn pad_left(s: &str, w: usize) -> String {
+    format!("{}{}", " ".repeat(w.saturating_sub(s.width())), s)
+}
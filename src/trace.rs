@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::algo::{self, GraphLike};
+use crate::weight::Weight;
+
+// 算法执行中的一步。Visit/Settle 对应动画中的节点，TreeEdge/Relax/MstEdge/TourEdge/NegativeCycle 对应边，
+// 其余为只在解释模式下显示的说明
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StepEvent<W = i64> {
+    // DFS/BFS/Prim 访问节点
+    Visit {
+        u: i64,
+    },
+    // DFS/BFS 沿 u -> v 发现新节点
+    TreeEdge {
+        u: i64,
+        v: i64,
+    },
+    // DFS/BFS 的邻居 v 已访问
+    Skip {
+        u: i64,
+        v: i64,
+    },
+    // BFS 取出队首节点
+    Dequeue {
+        u: i64,
+    },
+    // DFS 处理完 u 的所有邻居，出栈回溯
+    Backtrack {
+        u: i64,
+    },
+    // Dijkstra 确定节点的最短距离
+    Settle {
+        u: i64,
+        dist: W,
+    },
+    // Dijkstra 松弛成功：base + w < old
+    Relax {
+        u: i64,
+        v: i64,
+        base: W,
+        w: W,
+        old: W,
+    },
+    // Dijkstra 松弛失败：base + w >= current
+    NoRelax {
+        u: i64,
+        v: i64,
+        base: W,
+        w: W,
+        current: W,
+    },
+    // Prim 发现更轻的候选边，更新 key[v]
+    Candidate {
+        u: i64,
+        v: i64,
+        w: W,
+        old: W,
+    },
+    // Prim 把 u-v 加入生成树
+    MstEdge {
+        u: i64,
+        v: i64,
+        w: W,
+    },
+    // 邮递员回路走过 u-v，total 为到这一步为止的累计费用
+    TourEdge {
+        u: i64,
+        v: i64,
+        w: W,
+        total: W,
+    },
+    // Bellman-Ford 开始第 round 轮（从 1 开始），依次松弛所有边
+    Round {
+        round: usize,
+    },
+    // Bellman-Ford 松弛完 |V| - 1 轮后 u -> v 仍能松弛：存在从起点可达的负环
+    NegativeCycle {
+        u: i64,
+        v: i64,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    Node,
+    Edge,
+    Note,
+}
+
+impl<W: Weight> StepEvent<W> {
+    pub fn kind(&self) -> StepKind {
+        match self {
+            StepEvent::Visit { .. } | StepEvent::Settle { .. } => StepKind::Node,
+            StepEvent::TreeEdge { .. }
+            | StepEvent::Relax { .. }
+            | StepEvent::MstEdge { .. }
+            | StepEvent::TourEdge { .. }
+            | StepEvent::NegativeCycle { .. } => StepKind::Edge,
+            _ => StepKind::Note,
+        }
+    }
+
+    // 简洁模式：只描述访问了什么，说明类步骤返回 None
+    pub fn terse(&self) -> Option<String> {
+        match *self {
+            StepEvent::Visit { u } | StepEvent::Settle { u, .. } => {
+                Some(format!("访问节点: {}", u))
+            }
+            StepEvent::TreeEdge { u, v }
+            | StepEvent::Relax { u, v, .. }
+            | StepEvent::MstEdge { u, v, .. } => Some(format!("访问边: {} -> {}", u, v)),
+            StepEvent::TourEdge { u, v, total, .. } => {
+                Some(format!("访问边: {} -> {}，累计费用 {}", u, v, total))
+            }
+            StepEvent::NegativeCycle { u, v } => Some(format!("发现负环: {} -> {} 仍能松弛", u, v)),
+            _ => None,
+        }
+    }
+
+    // 解释模式：带上判断依据，例如 "松弛 1→3: 0+3 < ∞，更新 dist[3]=3"
+    pub fn explain(&self, precision: usize) -> String {
+        let f = |w: W| {
+            if w == W::infinity() {
+                "∞".to_string()
+            } else {
+                w.format(precision)
+            }
+        };
+        match *self {
+            StepEvent::Visit { u } => format!("访问节点 {}", u),
+            StepEvent::TreeEdge { u, v } => {
+                format!("{} 的邻居 {} 未访问，沿 {}→{} 前进", u, v, u, v)
+            }
+            StepEvent::Skip { u, v } => format!("{} 的邻居 {} 已访问，跳过", u, v),
+            StepEvent::Dequeue { u } => format!("取出队首节点 {}", u),
+            StepEvent::Backtrack { u } => format!("{} 的邻居都已处理，回溯", u),
+            StepEvent::Settle { u, dist } => {
+                format!(
+                    "确定节点 {}: dist[{}]={} 是未确定节点中最小的",
+                    u,
+                    u,
+                    f(dist)
+                )
+            }
+            StepEvent::Relax { u, v, base, w, old } => format!(
+                "松弛 {}→{}: {}+{} < {}，更新 dist[{}]={}",
+                u,
+                v,
+                f(base),
+                f(w),
+                f(old),
+                v,
+                f(base.add_or_infinity(w))
+            ),
+            StepEvent::NoRelax {
+                u,
+                v,
+                base,
+                w,
+                current,
+            } => format!(
+                "松弛 {}→{}: {}+{} ≥ {}，不更新",
+                u,
+                v,
+                f(base),
+                f(w),
+                f(current)
+            ),
+            StepEvent::Candidate { u, v, w, old } => format!(
+                "候选边 {}-{}: {} < {}，更新 key[{}]={}",
+                u,
+                v,
+                f(w),
+                f(old),
+                v,
+                f(w)
+            ),
+            StepEvent::MstEdge { u, v, w } => {
+                format!("加入边 {}-{} ({})：连接树内外的最轻边", u, v, f(w))
+            }
+            StepEvent::TourEdge { u, v, w, total } => {
+                format!("走过边 {}-{} ({})，累计费用 {}", u, v, f(w), f(total))
+            }
+            StepEvent::Round { round } => format!("第 {} 轮：依次松弛所有边", round),
+            StepEvent::NegativeCycle { u, v } => format!(
+                "所有轮结束后 {}→{} 仍能松弛：存在从起点可达的负环，最短距离没有意义",
+                u, v
+            ),
+        }
+    }
+}
+
+// 日志面板按事件类型筛选，依据 terse/explain 生成的行首文字识别
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogEvents {
+    #[default]
+    All,
+    // 访问或确定节点
+    Visits,
+    // Dijkstra 的松弛与 Prim 的 key 更新，只在解释模式下出现
+    Relaxations,
+}
+
+impl LogEvents {
+    pub fn next(self) -> Self {
+        match self {
+            LogEvents::All => LogEvents::Visits,
+            LogEvents::Visits => LogEvents::Relaxations,
+            LogEvents::Relaxations => LogEvents::All,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            LogEvents::All => "全部",
+            LogEvents::Visits => "节点访问",
+            LogEvents::Relaxations => "松弛",
+        }
+    }
+
+    pub fn matches(self, line: &str) -> bool {
+        match self {
+            LogEvents::All => true,
+            LogEvents::Visits => ["访问节点", "确定节点"].iter().any(|p| line.starts_with(p)),
+            LogEvents::Relaxations => ["松弛", "候选边"].iter().any(|p| line.starts_with(p)),
+        }
+    }
+}
+
+// 保留同时满足事件类型和子串（不区分大小写）的日志行
+pub fn filter_log<'a>(lines: &'a [String], events: LogEvents, query: &str) -> Vec<&'a str> {
+    let query = query.to_lowercase();
+    lines
+        .iter()
+        .map(String::as_str)
+        .filter(|line| events.matches(line) && line.to_lowercase().contains(&query))
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraceAlgorithm {
+    Dfs,
+    Bfs,
+    Dijkstra,
+    Prim,
+}
+
+// 某一步执行后的完整算法状态
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateSnapshot<W = i64> {
+    pub step: usize,
+    pub event: StepEvent<W>,
+    // Dijkstra 的暂定距离 / Prim 的 key，遍历算法为空
+    pub dist: BTreeMap<i64, W>,
+    pub visited: BTreeSet<i64>,
+    // BFS 的队列（队首在前）、DFS 的递归栈（栈底在前）、
+    // Dijkstra/Prim 中已有暂定值但未确定的节点（按值从小到大）
+    pub frontier: Vec<i64>,
+}
+
+// 一次算法运行的完整步骤记录，可逐步重放出每一步的状态，供自动评分对照
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlgorithmTrace<W = i64> {
+    pub algorithm: TraceAlgorithm,
+    pub source: i64,
+    pub steps: Vec<StepEvent<W>>,
+}
+
+impl<W: Weight> AlgorithmTrace<W> {
+    // g 可以是 Graph，也可以是任何 GraphLike（如绕开禁行节点的视图）
+    pub fn new<G: GraphLike<Weight = W>>(g: &G, algorithm: TraceAlgorithm, source: i64) -> Self {
+        let steps = match algorithm {
+            TraceAlgorithm::Dfs => algo::dfs_steps(g, source),
+            TraceAlgorithm::Bfs => algo::bfs_steps(g, source),
+            TraceAlgorithm::Dijkstra => algo::dijkstra_steps(g, source),
+            TraceAlgorithm::Prim => algo::prim_steps(g, source),
+        };
+        Self {
+            algorithm,
+            source,
+            steps,
+        }
+    }
+
+    // 依次应用每个步骤，返回每一步之后的状态
+    pub fn snapshots(&self) -> Vec<StateSnapshot<W>> {
+        let mut dist: BTreeMap<i64, W> = BTreeMap::new();
+        let mut visited: BTreeSet<i64> = BTreeSet::new();
+        let mut order: Vec<i64> = Vec::new();
+        let weighted = matches!(
+            self.algorithm,
+            TraceAlgorithm::Dijkstra | TraceAlgorithm::Prim
+        );
+        if weighted {
+            dist.insert(self.source, W::zero());
+        }
+
+        let mut snapshots = Vec::with_capacity(self.steps.len());
+        for (step, event) in self.steps.iter().enumerate() {
+            match *event {
+                StepEvent::Visit { u } => {
+                    visited.insert(u);
+                    if !weighted {
+                        order.push(u);
+                    }
+                }
+                StepEvent::Settle { u, dist: d } => {
+                    visited.insert(u);
+                    dist.insert(u, d);
+                }
+                StepEvent::Relax { v, base, w, .. } => {
+                    dist.insert(v, base.add_or_infinity(w));
+                }
+                StepEvent::Candidate { v, w, .. } => {
+                    dist.insert(v, w);
+                }
+                StepEvent::Dequeue { u } | StepEvent::Backtrack { u } => {
+                    order.retain(|&x| x != u);
+                }
+                _ => {}
+            }
+
+            let frontier = if weighted {
+                let mut pending: Vec<(W, i64)> = dist
+                    .iter()
+                    .filter(|(u, _)| !visited.contains(u))
+                    .map(|(&u, &d)| (d, u))
+                    .collect();
+                pending.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+                pending.into_iter().map(|(_, u)| u).collect()
+            } else {
+                order.clone()
+            };
+
+            snapshots.push(StateSnapshot {
+                step,
+                event: event.clone(),
+                dist: dist.clone(),
+                visited: visited.clone(),
+                frontier,
+            });
+        }
+        snapshots
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+
+use crate::algo::GraphLike;
+use crate::graph::State;
+use crate::weight::Weight;
+
+// 一次转向 (from, via, to)：沿边 from -> via 到达 via，再沿 via -> to 离开。to == from 即掉头
+pub type Turn = (i64, i64, i64);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TurnRule<W = i64> {
+    Ban,
+    // 经过这个转向要额外加上的代价，如左转等红灯的时间
+    Penalty(W),
+}
+
+// 路网中的转向限制，例如路口禁止左转、掉头加罚时间。没有列出的转向（包括掉头）都可以走。
+// 在命令脚本中写成 turn <from> <via> <to> [罚时]，不写罚时表示禁止
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TurnRestrictions<W = i64> {
+    rules: BTreeMap<Turn, TurnRule<W>>,
+}
+
+impl<W: Weight> TurnRestrictions<W> {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn get(&self, turn: Turn) -> Option<TurnRule<W>> {
+        self.rules.get(&turn).copied()
+    }
+
+    // 罚时为 0 时去掉这个转向的限制
+    pub fn set(&mut self, turn: Turn, rule: TurnRule<W>) {
+        if rule == TurnRule::Penalty(W::zero()) {
+            self.rules.remove(&turn);
+        } else {
+            self.rules.insert(turn, rule);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rules.clear();
+    }
+
+    // 按 (from, via, to) 从小到大
+    pub fn iter(&self) -> impl Iterator<Item = (Turn, TurnRule<W>)> + '_ {
+        self.rules.iter().map(|(&turn, &rule)| (turn, rule))
+    }
+
+    // 经过这个转向的额外代价，不受限制时为 0，禁止时为 None
+    pub fn extra(&self, turn: Turn) -> Option<W> {
+        match self.get(turn) {
+            None => Some(W::zero()),
+            Some(TurnRule::Penalty(p)) => Some(p),
+            Some(TurnRule::Ban) => None,
+        }
+    }
+
+    // 去掉涉及图中已不存在的边的限制，图被编辑后调用；返回是否有限制被去掉
+    pub fn retain<G: GraphLike<Weight = W>>(&mut self, g: &G) -> bool {
+        let before = self.rules.len();
+        self.rules
+            .retain(|&(a, b, c), _| g.weight(a, b).is_some() && g.weight(b, c).is_some());
+        before != self.rules.len()
+    }
+
+    // 图的节点重新编号后按 new_id（原 ID -> 新 ID）迁移限制，涉及不在 new_id 中的节点的限制去掉
+    pub fn relabel(&self, new_id: &HashMap<i64, i64>) -> Self {
+        let rules = self
+            .rules
+            .iter()
+            .filter_map(|(&(a, b, c), &rule)| {
+                Some(((*new_id.get(&a)?, *new_id.get(&b)?, *new_id.get(&c)?), rule))
+            })
+            .collect();
+        TurnRestrictions { rules }
+    }
+}
+
+// 带转向限制的最短路径
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnRoute<W = i64> {
+    pub dist: W,
+    pub path: Vec<i64>,
+    // 途中经过的加罚转向及罚时，按经过顺序
+    pub penalties: Vec<(Turn, W)>,
+    // 搜索中确定的状态（来边, 节点）数
+    pub settled: usize,
+}
+
+// 在边状态空间（线图）上做 Dijkstra：状态是"沿哪条边到达了哪个节点"，从到达 v 的状态 u -> v
+// 沿 v -> w 转移时加上边权和转向 (u, v, w) 的罚时，禁止的转向不转移。同一节点从不同方向到达
+// 是不同的状态，所以绕一圈再从另一个方向进入路口的路线也能找到。起点的初始状态没有来边，
+// 第一次确定到达 t 的状态时停止。t 不可达时返回 None
+pub fn shortest_path<G: GraphLike>(
+    g: &G,
+    turns: &TurnRestrictions<G::Weight>,
+    s: i64,
+    t: i64,
+) -> Option<TurnRoute<G::Weight>> {
+    // 状态编号 -> (来边的起点, 所在节点)
+    let mut states: Vec<(Option<i64>, i64)> = vec![(None, s)];
+    let mut ids: HashMap<(Option<i64>, i64), i64> = HashMap::from([((None, s), 0)]);
+    let mut dist: HashMap<i64, G::Weight> = HashMap::from([(0, G::Weight::zero())]);
+    let mut parent: HashMap<i64, i64> = HashMap::new();
+    let mut settled: HashSet<i64> = HashSet::new();
+    let mut pq = BinaryHeap::from([State {
+        cost: G::Weight::zero(),
+        node: 0,
+    }]);
+
+    while let Some(State { cost, node: id }) = pq.pop() {
+        if !settled.insert(id) {
+            continue;
+        }
+        let (from, at) = states[id as usize];
+        if at == t {
+            let mut path = vec![at];
+            let mut x = id;
+            while let Some(&p) = parent.get(&x) {
+                path.push(states[p as usize].1);
+                x = p;
+            }
+            path.reverse();
+            let penalties = path
+                .windows(3)
+                .filter_map(|w| match turns.get((w[0], w[1], w[2])) {
+                    Some(TurnRule::Penalty(p)) => Some(((w[0], w[1], w[2]), p)),
+                    _ => None,
+                })
+                .collect();
+            return Some(TurnRoute {
+                dist: cost,
+                path,
+                penalties,
+                settled: settled.len(),
+            });
+        }
+
+        for (next, w) in g.neighbors(at) {
+            let extra = match from {
+                Some(f) => match turns.extra((f, at, next)) {
+                    Some(extra) => extra,
+                    None => continue,
+                },
+                None => G::Weight::zero(),
+            };
+            let key = (Some(at), next);
+            let next_id = *ids.entry(key).or_insert_with(|| {
+                states.push(key);
+                states.len() as i64 - 1
+            });
+            let c = cost.add_or_infinity(w).add_or_infinity(extra);
+            if dist
+                .get(&next_id)
+                .is_none_or(|&current| c.less_than(current))
+            {
+                dist.insert(next_id, c);
+                parent.insert(next_id, id);
+                pq.push(State {
+                    cost: c,
+                    node: next_id,
+                });
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,105 @@
+use crate::graph::{EdgeType, Graph};
+use crate::weight::Weight;
+
+// 教程每一步在画布上高亮的元素
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StepMark {
+    Node(i64),
+    Edge(i64, i64),
+}
+
+#[derive(Clone, Debug)]
+pub struct TutorialStep {
+    pub mark: Option<StepMark>,
+    pub text: String,
+}
+
+// 一份教程：固定的示例图加上逐步讲解，由 tutorials/ 下的数据文件描述
+#[derive(Clone, Debug)]
+pub struct Tutorial<W = i64> {
+    pub title: String,
+    pub algorithm: String,
+    pub start: i64,
+    pub graph: Graph<W>,
+    pub steps: Vec<TutorialStep>,
+}
+
+// 内置教程，数据文件在编译时嵌入
+const BUILTIN: [&str; 3] = [
+    include_str!("../tutorials/dijkstra.txt"),
+    include_str!("../tutorials/bfs.txt"),
+    include_str!("../tutorials/prim.txt"),
+];
+
+impl<W: Weight> Tutorial<W> {
+    pub fn builtin() -> Vec<Self> {
+        BUILTIN
+            .iter()
+            .map(|text| Self::parse(text).expect("内置教程格式错误"))
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut tutorial = Tutorial {
+            title: String::new(),
+            algorithm: String::new(),
+            start: 1,
+            graph: Graph::default(),
+            steps: Vec::new(),
+        };
+
+        for (no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = || format!("第 {} 行格式错误: {}", no + 1, line);
+            let id = |s: &str| s.parse::<i64>().map_err(|_| err());
+
+            let (keyword, rest) = line.split_once(' ').ok_or_else(err)?;
+            let rest = rest.trim();
+            match keyword {
+                "title" => tutorial.title = rest.to_string(),
+                "algorithm" => tutorial.algorithm = rest.to_string(),
+                "start" => tutorial.start = id(rest)?,
+                "edge" => {
+                    let parts: Vec<&str> = rest.split_whitespace().collect();
+                    let [u, v, w] = parts.as_slice() else {
+                        return Err(err());
+                    };
+                    let w = W::parse(w).ok_or_else(err)?;
+                    tutorial.graph.add_edge(id(u)?, id(v)?, w, EdgeType::Both);
+                }
+                "note" => tutorial.steps.push(TutorialStep {
+                    mark: None,
+                    text: rest.to_string(),
+                }),
+                "step" => {
+                    let (kind, rest) = rest.split_once(' ').ok_or_else(err)?;
+                    let (mark, text) = match kind {
+                        "node" => {
+                            let (u, text) = rest.split_once(' ').unwrap_or((rest, ""));
+                            (StepMark::Node(id(u)?), text)
+                        }
+                        "edge" => {
+                            let (u, rest) = rest.split_once(' ').ok_or_else(err)?;
+                            let (v, text) = rest.split_once(' ').unwrap_or((rest, ""));
+                            (StepMark::Edge(id(u)?, id(v)?), text)
+                        }
+                        _ => return Err(err()),
+                    };
+                    tutorial.steps.push(TutorialStep {
+                        mark: Some(mark),
+                        text: text.to_string(),
+                    });
+                }
+                _ => return Err(err()),
+            }
+        }
+
+        if tutorial.title.is_empty() {
+            return Err("缺少 title".to_string());
+        }
+        Ok(tutorial)
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::graph::{EdgeType, Graph};
+use crate::weight::Weight;
+
+// 2-SAT 问题：每个子句是两个文字的析取。文字用非零整数表示（同 DIMACS）：
+// k 为变量 x_k，-k 为 ¬x_k。蕴含图中文字直接作为节点 ID
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TwoSat {
+    pub clauses: Vec<(i64, i64)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TwoSatResult {
+    // 变量 -> 取值
+    Satisfiable(BTreeMap<i64, bool>),
+    // x_var 与 ¬x_var 在同一个强连通分量里，互相蕴含，无解；component 为该分量
+    Conflict { var: i64, component: Vec<i64> },
+}
+
+impl TwoSat {
+    // 子句之间用逗号、分号或换行分隔，子句内的文字用空格分隔，如 "1 -2, -1 3"。
+    // 只有一个文字 a 的子句视为 a ∨ a；兼容 DIMACS：跳过 c/p 开头的行，忽略行尾的 0
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut clauses = Vec::new();
+        for line in input.lines() {
+            let line = line.trim();
+            if line.starts_with('c') || line.starts_with('p') {
+                continue;
+            }
+            for clause in line.split([',', ';']) {
+                let mut literals = Vec::new();
+                for token in clause.split_whitespace() {
+                    match token.parse::<i64>() {
+                        Ok(0) => {}
+                        Ok(lit) => literals.push(lit),
+                        Err(_) => return Err(format!("无法解析文字: {}", token)),
+                    }
+                }
+                match literals[..] {
+                    [] => {}
+                    [a] => clauses.push((a, a)),
+                    [a, b] => clauses.push((a, b)),
+                    _ => {
+                        return Err(format!(
+                            "子句 \"{}\" 有 {} 个文字，2-SAT 每个子句最多 2 个",
+                            clause.trim(),
+                            literals.len()
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(TwoSat { clauses })
+    }
+
+    // 出现过的变量编号，升序
+    pub fn variables(&self) -> Vec<i64> {
+        let vars: BTreeSet<i64> = self
+            .clauses
+            .iter()
+            .flat_map(|&(a, b)| [a.abs(), b.abs()])
+            .collect();
+        vars.into_iter().collect()
+    }
+
+    // 子句 a ∨ b 等价于 ¬a → b 与 ¬b → a。每个变量的两个文字都是节点，
+    // 标签为 x1 / ¬x1；边权没有意义，取零
+    pub fn implication_graph<W: Weight>(&self) -> Graph<W> {
+        let mut g = Graph::default();
+        for k in self.variables() {
+            g.add_node(k);
+            g.add_node(-k);
+            g.set_node_label(k, &format!("x{}", k));
+            g.set_node_label(-k, &format!("¬x{}", k));
+        }
+        for &(a, b) in &self.clauses {
+            g.add_edge(-a, b, W::zero(), EdgeType::Single);
+            if a != b {
+                g.add_edge(-b, a, W::zero(), EdgeType::Single);
+            }
+        }
+        g
+    }
+
+    // 在蕴含图上求强连通分量：x 与 ¬x 同属一个分量时无解；
+    // 否则按拓扑序，x 的分量排在 ¬x 之后时取 x 为真
+    pub fn solve(&self) -> TwoSatResult {
+        let components = self.implication_graph::<i64>().scc_topological();
+        let mut order: HashMap<i64, usize> = HashMap::new();
+        for (i, component) in components.iter().enumerate() {
+            for &u in component {
+                order.insert(u, i);
+            }
+        }
+
+        let mut assignment = BTreeMap::new();
+        for k in self.variables() {
+            let (pos, neg) = (order[&k], order[&-k]);
+            if pos == neg {
+                return TwoSatResult::Conflict {
+                    var: k,
+                    component: components[pos].clone(),
+                };
+            }
+            assignment.insert(k, pos > neg);
+        }
+        TwoSatResult::Satisfiable(assignment)
+    }
+
+    pub fn is_satisfied_by(&self, assignment: &BTreeMap<i64, bool>) -> bool {
+        let value = |lit: i64| assignment.get(&lit.abs()).copied() == Some(lit > 0);
+        self.clauses.iter().all(|&(a, b)| value(a) || value(b))
+    }
+}
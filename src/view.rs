@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Stylize};
+use ratatui::widgets::canvas::{Canvas, Circle, Context, Line};
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+
+use crate::graph::{EdgeType, Graph};
+use crate::layout;
+use crate::text;
+use crate::weight::Weight;
+
+// 可嵌入其他 ratatui 程序的图画布：传入图和 GraphViewState（坐标、高亮），
+// 用 frame.render_stateful_widget 画出与本程序相同风格的节点、边、权重和箭头。
+// 不包含事件循环和力导向模拟，布局由调用方决定，没有坐标的节点按圆形布局补上
+pub struct GraphView<'a, W: Weight = i64> {
+    graph: &'a Graph<W>,
+    block: Option<Block<'a>>,
+    radius: f64,
+    curved: bool,
+    precision: usize,
+}
+
+// 画布的可变状态：节点坐标、已访问的节点和边（黄色）、选中的节点（白色外圈）。
+// bounds 为显示的坐标范围 ([x_min, x_max], [y_min, y_max])，None 时按节点坐标自动适配
+#[derive(Clone, Debug, Default)]
+pub struct GraphViewState {
+    pub positions: HashMap<i64, (f64, f64)>,
+    pub visited_nodes: HashSet<i64>,
+    pub visited_edges: HashSet<(i64, i64)>,
+    pub selected: Option<i64>,
+    pub bounds: Option<([f64; 2], [f64; 2])>,
+}
+
+impl GraphViewState {
+    pub fn new(positions: HashMap<i64, (f64, f64)>) -> Self {
+        Self {
+            positions,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_edge_visited(&self, u: i64, v: i64) -> bool {
+        self.visited_edges.contains(&(u, v)) || self.visited_edges.contains(&(v, u))
+    }
+
+    // 包含所有节点的坐标范围，四周留出半径的几倍作为边距
+    fn fit(&self, radius: f64) -> ([f64; 2], [f64; 2]) {
+        let mut xs = [f64::INFINITY, f64::NEG_INFINITY];
+        let mut ys = [f64::INFINITY, f64::NEG_INFINITY];
+        for &(x, y) in self.positions.values() {
+            xs = [xs[0].min(x), xs[1].max(x)];
+            ys = [ys[0].min(y), ys[1].max(y)];
+        }
+        if xs[0] > xs[1] {
+            return ([-1.0, 1.0], [-1.0, 1.0]);
+        }
+        let margin = radius * 3.0 + 1.0;
+        (
+            [xs[0] - margin, xs[1] + margin],
+            [ys[0] - margin, ys[1] + margin],
+        )
+    }
+}
+
+impl<'a, W: Weight> GraphView<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> Self {
+        Self {
+            graph,
+            block: None,
+            radius: 0.6,
+            curved: false,
+            precision: 2,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    // 节点圆的半径，单位与坐标相同
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    // 边画成二次贝塞尔曲线，平行的往返边不再重叠
+    pub fn curved(mut self, curved: bool) -> Self {
+        self.curved = curved;
+        self
+    }
+
+    // 边权显示的小数位数
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    fn paint(&self, ctx: &mut Context, state: &GraphViewState, cell_w: f64) {
+        for (u, v, w, edge_type) in self.graph.edges() {
+            let (Some(&from), Some(&to)) = (state.positions.get(&u), state.positions.get(&v))
+            else {
+                continue;
+            };
+            let color = if state.is_edge_visited(u, v) {
+                Color::Yellow
+            } else {
+                Color::LightBlue
+            };
+            let points = if self.curved {
+                layout::quad_bezier(from, to, 0.15, 12)
+            } else {
+                vec![from, to]
+            };
+            for pair in points.windows(2) {
+                ctx.draw(&Line {
+                    x1: pair[0].0,
+                    y1: pair[0].1,
+                    x2: pair[1].0,
+                    y2: pair[1].1,
+                    color,
+                });
+            }
+            if edge_type == EdgeType::Single {
+                let last = points.len() - 1;
+                draw_arrowhead(ctx, points[last - 1], points[last], self.radius, color);
+            }
+
+            // 标签放在曲线中点
+            let (mid_x, mid_y) = if self.curved {
+                points[points.len() / 2]
+            } else {
+                ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0)
+            };
+            let value = w.abbreviated(self.precision);
+            let label = self
+                .graph
+                .edge(u, v)
+                .or_else(|| self.graph.edge(v, u))
+                .and_then(|e| e.label.as_deref());
+            let text = match label {
+                Some(label) => format!("{}:{}", text::truncate(label, 8), value),
+                None => value,
+            };
+            print_centered(ctx, mid_x, mid_y, &text, cell_w, Color::White);
+        }
+
+        for u in self.graph.nodes() {
+            let Some(&(x, y)) = state.positions.get(&u) else {
+                continue;
+            };
+            let color = if state.visited_nodes.contains(&u) {
+                Color::Yellow
+            } else {
+                Color::LightBlue
+            };
+            ctx.draw(&Circle {
+                x,
+                y,
+                radius: self.radius,
+                color,
+            });
+            let label = match self.graph.node_label(u) {
+                Some(name) => format!("{} {}", u, text::truncate(name, 12)),
+                None => u.to_string(),
+            };
+            print_centered(ctx, x, y, &label, cell_w, Color::Yellow);
+            if state.selected == Some(u) {
+                ctx.draw(&Circle {
+                    x,
+                    y,
+                    radius: self.radius * 1.4,
+                    color: Color::White,
+                });
+            }
+        }
+    }
+}
+
+impl<W: Weight> StatefulWidget for GraphView<'_, W> {
+    type State = GraphViewState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let missing: Vec<i64> = self
+            .graph
+            .nodes()
+            .into_iter()
+            .filter(|u| !state.positions.contains_key(u))
+            .collect();
+        if !missing.is_empty() {
+            let n = self.graph.nodes().len() as f64;
+            let r = (n.sqrt() * 4.0).max(5.0);
+            let placed = layout::circular(&self.graph.nodes(), r, r);
+            for u in missing {
+                state.positions.insert(u, placed[&u]);
+            }
+        }
+
+        let (x_bounds, y_bounds) = state.bounds.unwrap_or_else(|| state.fit(self.radius));
+        let inner_w = match &self.block {
+            Some(block) => block.inner(area).width,
+            None => area.width,
+        };
+        // 一列字符对应的画布宽度，用于按显示宽度居中标签
+        let cell_w = (x_bounds[1] - x_bounds[0]) / inner_w.max(1) as f64;
+
+        let state = &*state;
+        let mut canvas = Canvas::default()
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
+            .paint(|ctx| self.paint(ctx, state, cell_w));
+        if let Some(block) = self.block.clone() {
+            canvas = canvas.block(block);
+        }
+        canvas.render(area, buf);
+    }
+}
+
+// 在 tip 处画指向它的箭头，from 为线段的另一端；箭头尖端退到节点圆周上
+pub fn draw_arrowhead(ctx: &mut Context, from: (f64, f64), tip: (f64, f64), r: f64, color: Color) {
+    let (dx, dy) = (tip.0 - from.0, tip.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len <= r {
+        return;
+    }
+    let (ux, uy) = (dx / len, dy / len);
+    let (px, py) = (tip.0 - ux * r, tip.1 - uy * r);
+    let size = r * 1.2;
+    for side in [-1.0, 1.0] {
+        ctx.draw(&Line {
+            x1: px,
+            y1: py,
+            x2: px - ux * size - uy * size * 0.5 * side,
+            y2: py - uy * size + ux * size * 0.5 * side,
+            color,
+        });
+    }
+}
+
+// 以 (x, y) 为中心打印文字，按显示宽度（中文占 2 列）左移半个文字宽度
+pub fn print_centered(ctx: &mut Context, x: f64, y: f64, s: &str, cell_w: f64, color: Color) {
+    let x = x - text::width(s) as f64 / 2.0 * cell_w;
+    ctx.print(x, y, s.to_string().fg(color));
+}
+
+// 用短线段拼出虚线
+pub fn draw_dashed(ctx: &mut Context, x1: f64, y1: f64, x2: f64, y2: f64, color: Color) {
+    let len = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    let dash = 0.6;
+    let steps = (len / dash).ceil() as usize;
+
+    for i in (0..steps).step_by(2) {
+        let t1 = i as f64 / steps as f64;
+        let t2 = ((i + 1) as f64 / steps as f64).min(1.0);
+        ctx.draw(&Line {
+            x1: x1 + (x2 - x1) * t1,
+            y1: y1 + (y2 - y1) * t1,
+            x2: x1 + (x2 - x1) * t2,
+            y2: y1 + (y2 - y1) * t2,
+            color,
+        });
+    }
+}
+
+// 以 (x, y) 为中心、半臂长 size 的叉，用来划掉禁行的节点和边
+pub fn draw_cross(ctx: &mut Context, x: f64, y: f64, size: f64, color: Color) {
+    for (dx, dy) in [(size, size), (size, -size)] {
+        ctx.draw(&Line {
+            x1: x - dx,
+            y1: y - dy,
+            x2: x + dx,
+            y2: y + dy,
+            color,
+        });
+    }
+}
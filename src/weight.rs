@@ -0,0 +1,181 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub};
+
+// 浮点比较的相对误差容限
+pub const EPSILON: f64 = 1e-9;
+
+// 图算法对边权的最小要求：整数权与浮点权共用同一套算法实现
+pub trait Weight:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Send
+    + Sync
+    + 'static
+{
+    fn zero() -> Self;
+    fn infinity() -> Self;
+
+    // 全序比较，浮点数按 f64::total_cmp 处理，可放进 BinaryHeap
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    // 考虑误差的严格小于，避免浮点累加误差导致反复松弛
+    fn less_than(self, other: Self) -> bool;
+
+    fn approx_eq(self, other: Self) -> bool {
+        !self.less_than(other) && !other.less_than(self)
+    }
+
+    fn parse(s: &str) -> Option<Self>;
+
+    // 按给定小数位数格式化，整数权忽略精度
+    fn format(self, precision: usize) -> String;
+
+    fn to_f64(self) -> f64;
+
+    // 溢出时返回 None，浮点数以结果不再有限为溢出
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    // 路径长度的加法：infinity() 加任何数仍为 infinity()，向上溢出时也取 infinity()，
+    // 越界的路径按不可达处理，不会回绕成负数
+    fn add_or_infinity(self, other: Self) -> Self;
+
+    // 画布标签等空间有限的地方使用：整数部分超过 ABBREVIATE_DIGITS 位时改用科学计数法，
+    // 如 1.23e30；导出文件仍用 format 保留完整数值
+    fn abbreviated(self, precision: usize) -> String {
+        let s = self.format(precision);
+        let digits = s
+            .trim_start_matches('-')
+            .split('.')
+            .next()
+            .map_or(0, str::len);
+        if digits <= ABBREVIATE_DIGITS {
+            return s;
+        }
+        format!("{:.2e}", self.to_f64())
+    }
+}
+
+pub const ABBREVIATE_DIGITS: usize = 12;
+
+// 整数权：infinity 为最大值，比较不需要误差容限
+macro_rules! int_weight {
+    ($t:ty) => {
+        impl Weight for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn infinity() -> Self {
+                <$t>::MAX
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+
+            fn less_than(self, other: Self) -> bool {
+                self < other
+            }
+
+            fn parse(s: &str) -> Option<Self> {
+                s.trim().parse().ok()
+            }
+
+            fn format(self, _precision: usize) -> String {
+                self.to_string()
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            // 向下溢出时取最小值
+            fn add_or_infinity(self, other: Self) -> Self {
+                if self == <$t>::MAX || other == <$t>::MAX {
+                    return <$t>::MAX;
+                }
+                self.saturating_add(other)
+            }
+        }
+    };
+}
+
+int_weight!(i64);
+// 128 位整数权，用于构造让 i64 溢出的极端输入
+#[cfg(feature = "i128")]
+int_weight!(i128);
+
+impl Weight for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn infinity() -> Self {
+        f64::INFINITY
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+
+    fn less_than(self, other: Self) -> bool {
+        if other.is_infinite() || self.is_infinite() {
+            return self < other;
+        }
+        self < other - EPSILON * other.abs().max(1.0)
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        s.trim().parse().ok().filter(|w: &f64| w.is_finite())
+    }
+
+    // 四舍五入到指定位数并去掉多余的 0，例如 650.00 -> 650
+    fn format(self, precision: usize) -> String {
+        if self.is_infinite() {
+            return "∞".to_string();
+        }
+        let s = format!("{:.*}", precision, self);
+        if s.contains('.') {
+            let s = s.trim_end_matches('0').trim_end_matches('.');
+            if s == "-0" {
+                "0".to_string()
+            } else {
+                s.to_string()
+            }
+        } else {
+            s
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|w| w.is_finite())
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        Some(self * other).filter(|w| w.is_finite())
+    }
+
+    // 浮点数溢出本来就得到无穷大
+    fn add_or_infinity(self, other: Self) -> Self {
+        self + other
+    }
+}
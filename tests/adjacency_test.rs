@@ -0,0 +1,60 @@
+use graph_algorithm_tui::adjacency::Repr;
+use graph_algorithm_tui::graph::{EdgeType::Both, EdgeType::Single, Graph};
+
+fn build(repr: Repr) -> Graph {
+    let mut g = Graph::with_representation(repr);
+    g.add_edge(5, 9, 1, Both);
+    g.add_edge(1, 5, 2, Both);
+    g.add_edge(1, 3, 4, Both);
+    g.add_edge(3, 9, 1, Single);
+    g.add_edge(1, 2, 7, Both);
+    g.add_edge(1, 2, 3, Both);
+    g.add_node(8);
+    g.set_capacity(1, 3, 6);
+    g
+}
+
+#[test]
+fn csr_matches_list_representation() {
+    let list = build(Repr::List);
+    let mut csr = build(Repr::Csr);
+    assert_eq!(csr.representation(), Repr::Csr);
+
+    assert_eq!(csr.nodes(), list.nodes());
+    assert_eq!(csr.edges(), list.edges());
+    assert_eq!(csr.bfs(1), list.bfs(1));
+    assert_eq!(csr.dfs(1), list.dfs(1));
+    assert_eq!(csr.dijkstra(1).dist, list.dijkstra(1).dist);
+    assert_eq!(csr.kruskal(), list.kruskal());
+    assert_eq!(csr.edge(3, 1), list.edge(3, 1));
+    assert_eq!(csr.degree(8), 0);
+
+    let mut list = list;
+    assert!(csr.remove_edge(1, 5, Both));
+    assert!(list.remove_edge(1, 5, Both));
+    assert!(!csr.remove_edge(4, 1, Single));
+    assert_eq!(csr.repair(), list.repair());
+    assert_eq!(csr.edges(), list.edges());
+
+    let (relabeled, _) = csr.relabel();
+    assert_eq!(relabeled.representation(), Repr::Csr);
+    assert_eq!(relabeled.edges(), list.relabel().0.edges());
+    assert_eq!(csr.to_undirected().edges(), list.to_undirected().edges());
+
+    assert!(csr.remove_node(3));
+    assert!(list.remove_node(3));
+    assert_eq!(csr, list);
+}
+
+#[test]
+fn set_representation_converts_in_place() {
+    let mut g = build(Repr::List);
+    let before = g.clone();
+
+    g.set_representation(Repr::Csr);
+    assert_eq!(g.representation(), Repr::Csr);
+    assert_eq!(g, before);
+
+    g.set_representation(Repr::List);
+    assert_eq!(g, before);
+}
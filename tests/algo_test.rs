@@ -0,0 +1,180 @@
+use graph_algorithm_tui::algo::{self, GraphLike};
+use graph_algorithm_tui::graph::{EdgeType::Single, Graph, GraphBuilder};
+use graph_algorithm_tui::implicit::{Indexed, KnightMoves, Square, WordLadder};
+use graph_algorithm_tui::trace::StepEvent;
+use graph_algorithm_tui::weight::Weight;
+
+// 用户自己的图结构：邻接矩阵，0 表示没有边
+struct Matrix(Vec<Vec<i64>>);
+
+impl GraphLike for Matrix {
+    type Weight = i64;
+
+    fn nodes(&self) -> Vec<i64> {
+        (1..=self.0.len() as i64).collect()
+    }
+
+    fn neighbors(&self, u: i64) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.0[(u - 1) as usize]
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w != 0)
+            .map(|(v, &w)| (v as i64 + 1, w))
+    }
+}
+
+#[test]
+fn user_type_matches_graph() {
+    let m = Matrix(vec![
+        vec![0, 4, 1, 0],
+        vec![4, 0, 2, 5],
+        vec![1, 2, 0, 8],
+        vec![0, 5, 8, 0],
+    ]);
+    let g = Graph::from_edges(&[(1, 2, 4), (1, 3, 1), (2, 3, 2), (2, 4, 5), (3, 4, 8)]);
+
+    assert_eq!(algo::dfs(&m, 1), g.dfs(1));
+    assert_eq!(algo::bfs(&m, 1), g.bfs(1));
+    assert_eq!(algo::dijkstra(&m, 1).dist, g.dijkstra(1).dist);
+    assert_eq!(algo::dijkstra_steps(&m, 1), g.dijkstra_steps(1));
+    assert_eq!(algo::prim(&m, 1).total_weight, 8);
+    assert!(algo::prim(&m, 1).is_spanning);
+    assert_eq!(m.weight(2, 4), Some(5));
+    assert_eq!(m.weight(1, 4), None);
+}
+
+#[test]
+fn algorithms_run_on_implicit_graphs() {
+    let start = Square { col: 0, row: 0 };
+    let board = Indexed::new(KnightMoves { size: 8 }, start);
+    let result = algo::dijkstra(&board, 1);
+    assert_eq!(result.dist.len(), 64);
+
+    // 骑士从 a1 到 h8 至少 6 步
+    let corner = board.id_of(&Square { col: 7, row: 7 }).unwrap();
+    assert_eq!(result.dist[&corner], 6);
+    let path = result.path_to(corner).unwrap();
+    assert_eq!(path.len(), 7);
+    let second = board.node(path[1]).unwrap().to_string();
+    assert!(second == "b3" || second == "c2");
+
+    let words = Indexed::new(WordLadder::builtin(), "cold".to_string());
+    let (order, _) = algo::bfs(&words, 1);
+    assert!(order.contains(&words.id_of(&"warm".to_string()).unwrap()));
+    assert_eq!(words.nodes().len(), order.len());
+}
+
+#[test]
+fn topological_sort_and_dag_shortest_paths() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 3)
+        .directed_edge(1, 3, 6)
+        .directed_edge(2, 3, -4)
+        .directed_edge(3, 4, 2)
+        .directed_edge(5, 4, 1)
+        .build();
+    assert_eq!(g.topological_sort(), Some(vec![1, 2, 3, 5, 4]));
+
+    // 负权边也能得到正确结果；5 在拓扑序中但从 1 不可达
+    let result = g.dag_shortest_paths(1).unwrap();
+    assert_eq!(result.dist[&3], -1);
+    assert_eq!(result.dist[&4], 1);
+    assert!(!result.dist.contains_key(&5));
+    assert_eq!(result.path_to(4), Some(vec![1, 2, 3, 4]));
+    assert_eq!(result.visited_nodes, vec![1, 2, 3, 4]);
+    let steps = g.dag_shortest_paths_steps(1).unwrap();
+    assert_eq!(steps.len(), 4 + 4);
+
+    let mut cyclic = g.clone();
+    cyclic.add_edge(4, 1, 1, Single);
+    assert_eq!(cyclic.topological_sort(), None);
+    assert!(cyclic.dag_shortest_paths(1).is_none());
+    // 无向边相当于一个二元环
+    assert!(!Graph::from_edges(&[(1, 2, 1)]).is_acyclic());
+}
+
+#[test]
+fn bellman_ford_handles_negative_weights_and_cycles() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 4)
+        .directed_edge(1, 3, 5)
+        .directed_edge(3, 2, -3)
+        .directed_edge(2, 4, 2)
+        .directed_edge(5, 1, 1)
+        .build();
+    let result = g.bellman_ford(1);
+    assert_eq!(result.negative_cycle, None);
+    assert_eq!(result.paths.dist[&2], 2);
+    assert_eq!(result.paths.dist[&4], 4);
+    assert!(!result.paths.dist.contains_key(&5));
+    assert_eq!(result.paths.path_to(4), Some(vec![1, 3, 2, 4]));
+    // 第 2 轮更新了 2 和 4，第 3 轮没有更新，提前结束
+    assert_eq!(result.rounds.len(), 3);
+    let steps = result.steps();
+    assert_eq!(steps[0], StepEvent::Round { round: 1 });
+    assert_eq!(
+        steps
+            .iter()
+            .filter(|s| matches!(s, StepEvent::Round { .. }))
+            .count(),
+        3
+    );
+
+    let mut cyclic = g.clone();
+    cyclic.add_edge(4, 3, -7, Single);
+    let result = cyclic.bellman_ford(1);
+    let cycle = result.negative_cycle.clone().unwrap();
+    // 3 -> 2 -> 4 -> 3 总权重 -3 + 2 - 7
+    assert_eq!(cycle.weight, -8);
+    let mut nodes = cycle.nodes.clone();
+    nodes.sort();
+    assert_eq!(nodes, vec![2, 3, 4]);
+    assert!(matches!(
+        result.steps().last(),
+        Some(StepEvent::NegativeCycle { .. })
+    ));
+
+    // 负环从起点不可达时不报告
+    assert!(cyclic.bellman_ford(5).negative_cycle.is_some());
+    let mut unreachable = cyclic.clone();
+    unreachable.remove_node(1);
+    assert_eq!(unreachable.bellman_ford(5).negative_cycle, None);
+    // 无向的负权边本身就是负环
+    let undirected = Graph::from_edges(&[(1, 2, 1), (2, 3, -1)]);
+    assert_eq!(
+        undirected.bellman_ford(1).negative_cycle.unwrap().weight,
+        -2
+    );
+}
+
+#[test]
+fn near_max_weights_do_not_wrap_around() {
+    let big = i64::MAX - 1;
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, big)
+        .directed_edge(2, 3, 10)
+        .directed_edge(1, 4, 1)
+        .directed_edge(4, 3, big)
+        .build();
+
+    // 1 -> 2 -> 3 和 1 -> 4 -> 3 都超出 i64，3 按不可达处理而不是得到负的距离
+    for result in [
+        g.dijkstra(1),
+        g.dag_shortest_paths(1).unwrap(),
+        g.bellman_ford(1).paths,
+    ] {
+        assert_eq!(result.dist[&2], big);
+        assert_eq!(result.dist[&4], 1);
+        assert!(!result.dist.contains_key(&3));
+        assert_eq!(result.path_to(3), None);
+    }
+
+    // 生成树的总权重封顶为 i64::MAX
+    let mst = Graph::from_edges(&[(1, 2, big), (2, 3, big)]).prim(1);
+    assert!(mst.is_spanning);
+    assert_eq!(mst.total_weight, i64::MAX);
+
+    assert_eq!(i64::MAX.add_or_infinity(-5), i64::MAX);
+    assert_eq!(i64::MIN.add_or_infinity(-5), i64::MIN);
+    assert_eq!(f64::MAX.add_or_infinity(f64::MAX), f64::INFINITY);
+}
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use graph_algorithm_tui::allpairs;
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+
+#[test]
+fn distances_run_dijkstra_from_every_node() {
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 2), (1, 3, 5)]);
+    let progress = AtomicUsize::new(0);
+
+    let all = allpairs::distances(&g, &progress, &AtomicBool::new(false)).unwrap();
+    assert_eq!(progress.load(Ordering::Relaxed), 3);
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[&1][&3], 3);
+    assert_eq!(all[&3], g.dijkstra(3).dist);
+}
+
+#[test]
+fn betweenness_counts_shortest_paths_through_node() {
+    // 1 - 2 - 3 为一条链，4 经两条等长路径 (4-1-2 与 4-5-2) 到达 2
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (4, 1, 1), (4, 5, 1), (5, 2, 1)]);
+    let progress = AtomicUsize::new(0);
+
+    let c = allpairs::betweenness(&g, &progress, &AtomicBool::new(false)).unwrap();
+    assert_eq!(progress.load(Ordering::Relaxed), 5);
+    assert_eq!(c[&3], 0.0);
+    // 1-3、3-5、3-4 的最短路径都经过 2，1-5 的两条中有一条经过 2
+    assert!((c[&2] - 3.5).abs() < 1e-9);
+    // 4 -> 2、4 -> 3 各有一半经过 1
+    assert!((c[&1] - 1.0).abs() < 1e-9);
+
+    let d = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .build();
+    let c = allpairs::betweenness(&d, &progress, &AtomicBool::new(false)).unwrap();
+    assert_eq!((c[&1], c[&2], c[&3]), (0.0, 1.0, 0.0));
+}
+
+#[test]
+fn cancelled_run_returns_none() {
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 2), (1, 3, 5)]);
+    let progress = AtomicUsize::new(0);
+    let cancel = AtomicBool::new(true);
+
+    assert!(allpairs::distances(&g, &progress, &cancel).is_none());
+    assert!(allpairs::betweenness(&g, &progress, &cancel).is_none());
+    assert_eq!(progress.load(Ordering::Relaxed), 0);
+}
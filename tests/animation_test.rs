@@ -0,0 +1,269 @@
+use graph_algorithm_tui::animation::{
+    AnimationPlayer, AnimationStep, Pacing, REPLAY_HOLD_SECONDS, Replay, STEP_SECONDS,
+};
+use graph_algorithm_tui::graph::{EdgeType::Single, Graph};
+use graph_algorithm_tui::trace::{AlgorithmTrace, StepEvent, TraceAlgorithm};
+
+#[test]
+fn steps_light_up_in_order_on_the_step_interval() {
+    let mut player = AnimationPlayer::new(vec![1, 2, 3], vec![(1, 2), (2, 3)]);
+    assert!(player.is_running());
+    assert_eq!(player.progress(), (0, 5));
+
+    // 界面开始时立即点亮起点
+    assert_eq!(
+        player.step(),
+        Some(AnimationStep::Node { index: 0, node: 1 })
+    );
+    assert!(player.tick(STEP_SECONDS / 2.0).is_empty());
+    assert_eq!(
+        player.tick(STEP_SECONDS / 2.0),
+        vec![AnimationStep::Edge {
+            index: 0,
+            edge: (1, 2)
+        }]
+    );
+    assert_eq!(player.visited_edges(), vec![(1, 2)]);
+    assert_eq!(player.visited_nodes(), vec![1]);
+
+    // 一次经过较长时间时连续走多步
+    assert_eq!(
+        player.tick(STEP_SECONDS * 3.0),
+        vec![
+            AnimationStep::Node { index: 1, node: 2 },
+            AnimationStep::Edge {
+                index: 1,
+                edge: (2, 3)
+            },
+            AnimationStep::Node { index: 2, node: 3 },
+        ]
+    );
+    assert!(!player.is_running());
+    assert_eq!(player.visited_nodes(), vec![1, 2, 3]);
+    assert_eq!(player.progress(), (5, 5));
+    assert!(player.tick(1.0).is_empty());
+    assert_eq!(player.step(), None);
+}
+
+#[test]
+fn empty_and_single_channel_animations() {
+    let mut empty = AnimationPlayer::default();
+    assert!(!empty.is_running());
+    assert!(empty.tick(1.0).is_empty());
+    assert!(empty.visited_nodes().is_empty());
+
+    // 只有节点的动画（拓扑序）每步点亮一个节点
+    let mut order = AnimationPlayer::new(vec![4, 5], Vec::new());
+    assert_eq!(
+        order.step(),
+        Some(AnimationStep::Node { index: 0, node: 4 })
+    );
+    assert_eq!(
+        order.step(),
+        Some(AnimationStep::Node { index: 1, node: 5 })
+    );
+    assert!(!order.is_running());
+
+    // 只有边的步骤记录也能完整播放
+    let steps: Vec<StepEvent> = vec![
+        StepEvent::TreeEdge { u: 1, v: 2 },
+        StepEvent::TreeEdge { u: 2, v: 3 },
+    ];
+    let mut edges = AnimationPlayer::from_events(&steps);
+    edges.tick(STEP_SECONDS * 2.0);
+    assert_eq!(edges.visited_edges(), vec![(1, 2), (2, 3)]);
+    assert!(edges.visited_nodes().is_empty());
+}
+
+// Dijkstra 连续松弛多条边，动画按步骤记录的顺序播放，不再假定节点和边交替
+#[test]
+fn consecutive_edges_follow_the_trace_order() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 1, Single);
+    g.add_edge(1, 3, 4, Single);
+    g.add_edge(2, 3, 1, Single);
+
+    let trace = AlgorithmTrace::new(&g, TraceAlgorithm::Dijkstra, 1);
+    let mut player = AnimationPlayer::from_trace(&trace);
+    assert_eq!(
+        player.steps(),
+        &[
+            AnimationStep::Node { index: 0, node: 1 },
+            AnimationStep::Edge {
+                index: 0,
+                edge: (1, 2)
+            },
+            AnimationStep::Edge {
+                index: 1,
+                edge: (1, 3)
+            },
+            AnimationStep::Node { index: 1, node: 2 },
+            AnimationStep::Edge {
+                index: 2,
+                edge: (2, 3)
+            },
+            AnimationStep::Node { index: 2, node: 3 },
+        ]
+    );
+
+    // 全部播完后点亮的节点和边与算法输出一致
+    let result = g.dijkstra(1);
+    while player.step().is_some() {}
+    assert_eq!(player.visited_nodes(), result.visited_nodes);
+    assert_eq!(player.visited_edges(), result.visited_edges);
+}
+
+// 从步骤记录构造的动画与界面中各算法的节点/边顺序一致
+#[test]
+fn from_trace_matches_algorithm_output() {
+    let g = Graph::from_edges(&[(1, 2, 4), (1, 3, 1), (3, 2, 2), (2, 4, 5), (3, 4, 8)]);
+
+    let dfs = AnimationPlayer::from_trace(&AlgorithmTrace::new(&g, TraceAlgorithm::Dfs, 1));
+    assert_eq!((dfs.nodes(), dfs.edges()), g.dfs(1));
+    let bfs = AnimationPlayer::from_trace(&AlgorithmTrace::new(&g, TraceAlgorithm::Bfs, 1));
+    assert_eq!((bfs.nodes(), bfs.edges()), g.bfs(1));
+    let mst = g.prim(1);
+    let prim = AnimationPlayer::from_trace(&AlgorithmTrace::new(&g, TraceAlgorithm::Prim, 1));
+    assert_eq!(
+        (prim.nodes(), prim.edges()),
+        (mst.nodes.clone(), mst.tree_edges())
+    );
+
+    // 邮递员回路的每条边同时点亮终点
+    let tour: Vec<StepEvent> = vec![
+        StepEvent::TourEdge {
+            u: 1,
+            v: 2,
+            w: 1,
+            total: 1,
+        },
+        StepEvent::TourEdge {
+            u: 2,
+            v: 1,
+            w: 1,
+            total: 2,
+        },
+    ];
+    assert_eq!(AnimationPlayer::from_events(&tour).nodes(), vec![1, 2, 1]);
+}
+
+#[test]
+fn seeking_pauses_and_resume_continues_from_there() {
+    let mut player = AnimationPlayer::new(vec![1, 2, 3], vec![(1, 2), (2, 3)]);
+    while player.step().is_some() {}
+    assert_eq!(
+        player.current(),
+        Some(AnimationStep::Node { index: 2, node: 3 })
+    );
+
+    // 往回跳到第 2 步之后：只剩起点和第一条边
+    player.seek(2);
+    assert!(player.is_paused());
+    assert!(player.is_running());
+    assert!(!player.is_playing());
+    assert_eq!(player.visited_nodes(), vec![1]);
+    assert_eq!(player.visited_edges(), vec![(1, 2)]);
+    assert_eq!(
+        player.current(),
+        Some(AnimationStep::Edge {
+            index: 0,
+            edge: (1, 2)
+        })
+    );
+    assert!(player.tick(1.0).is_empty());
+
+    player.resume();
+    assert_eq!(
+        player.tick(STEP_SECONDS),
+        vec![AnimationStep::Node { index: 1, node: 2 }]
+    );
+    assert_eq!(player.progress(), (3, 5));
+
+    // 越界时停在两端
+    player.seek(0);
+    assert_eq!(player.current(), None);
+    assert!(player.visited_nodes().is_empty());
+    player.seek(99);
+    assert_eq!(player.progress(), (5, 5));
+    assert!(!player.is_running());
+}
+
+#[test]
+fn replay_waits_after_finishing_and_cycles_the_playlist() {
+    // 没有播放列表时重复上一次运行的菜单项
+    let mut repeat = Replay::default();
+    assert_eq!(
+        repeat.tick(REPLAY_HOLD_SECONDS / 2.0, true, Some("Dfs")),
+        None
+    );
+    // 动画又开始播放时重新计时
+    assert_eq!(repeat.tick(1.0, false, Some("Dfs")), None);
+    assert_eq!(
+        repeat.tick(REPLAY_HOLD_SECONDS / 2.0, true, Some("Dfs")),
+        None
+    );
+    assert_eq!(
+        repeat.tick(REPLAY_HOLD_SECONDS / 2.0, true, Some("Dfs")),
+        Some("Dfs".to_string())
+    );
+    assert_eq!(repeat.tick(REPLAY_HOLD_SECONDS, true, None), None);
+
+    let mut cycle = Replay::new(vec!["Bfs".to_string(), "Prim".to_string()]);
+    let names: Vec<Option<String>> = (0..3)
+        .map(|_| cycle.tick(REPLAY_HOLD_SECONDS, true, Some("Dfs")))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("Bfs".to_string()),
+            Some("Prim".to_string()),
+            Some("Bfs".to_string())
+        ]
+    );
+}
+
+// 按算法的节奏：出堆停留 2 步、松弛半步，松弛失败的说明步骤加在前一步上
+#[test]
+fn pacing_sets_how_long_each_kind_of_step_stays() {
+    let steps: Vec<StepEvent> = vec![
+        StepEvent::Settle { u: 1, dist: 0 },
+        StepEvent::Relax {
+            u: 1,
+            v: 2,
+            base: 0,
+            w: 1,
+            old: i64::MAX,
+        },
+        StepEvent::NoRelax {
+            u: 1,
+            v: 1,
+            base: 0,
+            w: 1,
+            current: 0,
+        },
+        StepEvent::Settle { u: 2, dist: 1 },
+    ];
+    let pacing = Pacing {
+        node: 2.0,
+        edge: 0.5,
+        note: 0.25,
+    };
+    let mut player = AnimationPlayer::from_events_paced(&steps, pacing);
+    assert_eq!(player.hold(), STEP_SECONDS);
+
+    player.step();
+    assert!((player.hold() - STEP_SECONDS * 2.0).abs() < 1e-9);
+    assert!(player.tick(STEP_SECONDS * 1.75).is_empty());
+    assert_eq!(player.tick(STEP_SECONDS / 2.0).len(), 1);
+    assert!((player.hold() - STEP_SECONDS * 0.75).abs() < 1e-9);
+    assert!(player.tick(STEP_SECONDS / 4.0).is_empty());
+    assert_eq!(
+        player.tick(STEP_SECONDS / 2.0),
+        vec![AnimationStep::Node { index: 1, node: 2 }]
+    );
+
+    // 默认节奏每步相同
+    let mut plain = AnimationPlayer::from_events(&steps);
+    plain.step();
+    assert_eq!(plain.hold(), STEP_SECONDS);
+}
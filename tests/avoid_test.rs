@@ -0,0 +1,68 @@
+use graph_algorithm_tui::algo::{self, GraphLike};
+use graph_algorithm_tui::avoid::Forbidden;
+use graph_algorithm_tui::graph::{EdgeType, Graph};
+
+// 1-2-4 最短，绕开 2 时走 1-3-4
+fn diamond() -> Graph {
+    Graph::from_edges(&[(1, 2, 1), (2, 4, 1), (1, 3, 2), (3, 4, 2)])
+}
+
+#[test]
+fn forbidden_node_is_routed_around() {
+    let g = diamond();
+    let mut forbidden = Forbidden::default();
+    assert!(forbidden.toggle_node(2));
+
+    let view = forbidden.view(&g);
+    assert_eq!(view.nodes(), vec![1, 3, 4]);
+    let result = algo::dijkstra(&view, 1);
+    assert_eq!(result.path_to(4), Some(vec![1, 3, 4]));
+    assert_eq!(result.dist.get(&2), None);
+    // 原图不变
+    assert_eq!(g.dijkstra(1).path_to(4), Some(vec![1, 2, 4]));
+
+    assert!(!forbidden.toggle_node(2));
+    assert!(forbidden.is_empty());
+}
+
+#[test]
+fn forbidden_edge_blocks_both_directions() {
+    let g = diamond();
+    let mut forbidden = Forbidden::default();
+    forbidden.toggle_edge(4, 2);
+    assert!(forbidden.has_edge(2, 4));
+    assert_eq!(forbidden.edges(), vec![(2, 4)]);
+
+    let view = forbidden.view(&g);
+    assert_eq!(view.weight(2, 4), None);
+    assert_eq!(view.weight(4, 2), None);
+    assert_eq!(view.weight(1, 2), Some(1));
+    assert_eq!(algo::dijkstra(&view, 1).path_to(4), Some(vec![1, 3, 4]));
+    // 只断了这条边，2 本身仍可到达
+    let (reached, _) = algo::bfs(&view, 4);
+    assert_eq!(reached, vec![4, 3, 1, 2]);
+}
+
+#[test]
+fn forbidden_source_reaches_nothing() {
+    let g = diamond();
+    let mut forbidden = Forbidden::default();
+    forbidden.toggle_node(1);
+    let result = algo::dijkstra(&forbidden.view(&g), 1);
+    assert_eq!(result.dist.len(), 1);
+    assert!(!forbidden.allows(1, 2));
+}
+
+#[test]
+fn retain_drops_marks_for_deleted_parts() {
+    let mut g = diamond();
+    let mut forbidden = Forbidden::default();
+    forbidden.toggle_node(3);
+    forbidden.toggle_edge(1, 2);
+    assert!(!forbidden.retain(&g));
+
+    g.remove_edge(1, 2, EdgeType::Both);
+    g.remove_node(3);
+    assert!(forbidden.retain(&g));
+    assert!(forbidden.is_empty());
+}
@@ -0,0 +1,47 @@
+use graph_algorithm_tui::bell::{BEEP_GAP_SECONDS, Bell, BellPattern, Cue};
+
+// 每帧经过 dt 秒，统计 frames 帧中响了几声
+fn beeps(bell: &mut Bell, frames: usize, dt: f64) -> usize {
+    (0..frames).filter(|_| bell.tick(dt)).count()
+}
+
+#[test]
+fn cues_ring_the_configured_number_of_times() {
+    let mut bell = Bell::new(true, BellPattern::default());
+    bell.cue(Cue::NegativeCycle);
+    assert!(bell.tick(0.0));
+    assert!(!bell.tick(BEEP_GAP_SECONDS / 2.0));
+    assert_eq!(beeps(&mut bell, 100, BEEP_GAP_SECONDS / 2.0), 2);
+
+    // 前一组没响完时新的一组只补足次数，不叠加
+    bell.cue(Cue::Complete);
+    bell.cue(Cue::Settle);
+    assert_eq!(beeps(&mut bell, 100, BEEP_GAP_SECONDS), 2);
+
+    // 次数为 0 的事件不响
+    let mut quiet = Bell::new(
+        true,
+        BellPattern {
+            settle: 0,
+            ..BellPattern::default()
+        },
+    );
+    quiet.cue(Cue::Settle);
+    assert_eq!(beeps(&mut quiet, 10, BEEP_GAP_SECONDS), 0);
+}
+
+#[test]
+fn disabled_bell_stays_silent() {
+    let mut bell = Bell::default();
+    bell.cue(Cue::Complete);
+    assert_eq!(beeps(&mut bell, 10, BEEP_GAP_SECONDS), 0);
+
+    // 关闭时丢弃还没响完的提示
+    let mut bell = Bell::new(true, BellPattern::default());
+    bell.cue(Cue::NegativeCycle);
+    assert!(bell.tick(0.0));
+    bell.enabled = false;
+    assert_eq!(beeps(&mut bell, 10, BEEP_GAP_SECONDS), 0);
+    bell.enabled = true;
+    assert_eq!(beeps(&mut bell, 10, BEEP_GAP_SECONDS), 0);
+}
@@ -0,0 +1,292 @@
+use graph_algorithm_tui::command::{self, Command, History, Setting};
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::turns::{TurnRestrictions, TurnRule};
+
+#[test]
+fn parses_commands_with_helpful_errors() {
+    assert_eq!(
+        Command::<i64>::parse(":edge 3 7 12"),
+        Ok(Command::Edge {
+            u: 3,
+            v: 7,
+            w: 12,
+            directed: false
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("arc 1 2"),
+        Ok(Command::Edge {
+            u: 1,
+            v: 2,
+            w: 1,
+            directed: true
+        })
+    );
+    assert_eq!(Command::<i64>::parse("delnode 4"), Ok(Command::DelNode(4)));
+    assert_eq!(
+        Command::<i64>::parse("run dijkstra 3"),
+        Ok(Command::Run {
+            algorithm: "dijkstra".to_string(),
+            source: Some(3)
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("run Karger 最小割"),
+        Ok(Command::Run {
+            algorithm: "Karger 最小割".to_string(),
+            source: None
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("save demo.txt"),
+        Ok(Command::Save("demo.txt".to_string()))
+    );
+
+    assert_eq!(Command::<i64>::parse("clear"), Ok(Command::Clear));
+    assert_eq!(
+        Command::<i64>::parse("set color off"),
+        Ok(Command::Set(Setting::Color(false)))
+    );
+    assert_eq!(
+        Command::<i64>::parse("set precision 0"),
+        Ok(Command::Set(Setting::Precision(0)))
+    );
+    assert_eq!(
+        Command::<i64>::parse("set replay on"),
+        Ok(Command::Set(Setting::Replay(true)))
+    );
+    assert_eq!(
+        Command::<i64>::parse("set bell off"),
+        Ok(Command::Set(Setting::Bell(false)))
+    );
+    // 菜单项名称可以含空格，用逗号分隔
+    assert_eq!(
+        Command::<i64>::parse("playlist Dfs, DAG 最短路径,Prim"),
+        Ok(Command::Playlist(vec![
+            "Dfs".to_string(),
+            "DAG 最短路径".to_string(),
+            "Prim".to_string()
+        ]))
+    );
+    assert_eq!(
+        Command::<i64>::parse("playlist"),
+        Ok(Command::Playlist(Vec::new()))
+    );
+    assert_eq!(
+        Command::<i64>::parse("forbid 3"),
+        Ok(Command::ForbidNode(3))
+    );
+    assert_eq!(
+        Command::<i64>::parse("forbid 2 4"),
+        Ok(Command::ForbidEdge(2, 4))
+    );
+    assert_eq!(
+        Command::<i64>::parse("forbid clear"),
+        Ok(Command::ClearForbidden)
+    );
+    assert_eq!(
+        Command::<i64>::parse("turn 1 2 3"),
+        Ok(Command::Turn {
+            from: 1,
+            via: 2,
+            to: 3,
+            penalty: None
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("turn 3 2 3 5"),
+        Ok(Command::Turn {
+            from: 3,
+            via: 2,
+            to: 3,
+            penalty: Some(5)
+        })
+    );
+    assert_eq!(Command::<i64>::parse("turn clear"), Ok(Command::ClearTurns));
+    assert_eq!(
+        Command::<i64>::parse("group 北区 1 4 7"),
+        Ok(Command::Group {
+            name: "北区".to_string(),
+            nodes: vec![1, 4, 7]
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("group 南区"),
+        Ok(Command::Group {
+            name: "南区".to_string(),
+            nodes: Vec::new()
+        })
+    );
+    assert_eq!(
+        Command::<i64>::parse("group clear"),
+        Ok(Command::ClearGroups)
+    );
+    assert!(Command::<i64>::parse("group 北区 x").is_err());
+    assert!(Command::<i64>::parse("turn 1 2").is_err());
+    assert!(
+        Command::<i64>::parse("set theme dark")
+            .unwrap_err()
+            .starts_with("未知设置")
+    );
+    assert!(
+        Command::<i64>::parse("set grid maybe")
+            .unwrap_err()
+            .starts_with("用法")
+    );
+
+    assert_eq!(
+        Command::<i64>::parse("delnode"),
+        Err("用法: delnode <u>".to_string())
+    );
+    assert!(
+        Command::<i64>::parse("edge a 2")
+            .unwrap_err()
+            .contains("整数")
+    );
+    assert!(
+        Command::<i64>::parse("edge 1 2 x")
+            .unwrap_err()
+            .contains("权重")
+    );
+    assert!(
+        Command::<i64>::parse("frobnicate")
+            .unwrap_err()
+            .starts_with("未知命令")
+    );
+}
+
+#[test]
+fn completes_to_the_longest_common_prefix() {
+    let names = command::COMMANDS.iter().map(|c| c.name);
+    assert_eq!(
+        command::complete("ru", names.clone()),
+        Some("run".to_string())
+    );
+    assert_eq!(
+        command::complete("de", names.clone()),
+        Some("del".to_string())
+    );
+    assert_eq!(command::complete("x", names), None);
+    assert_eq!(
+        command::complete("dij", ["Dijkstra", "DAG 最短路径"]),
+        Some("Dijkstra".to_string())
+    );
+}
+
+#[test]
+fn script_round_trips_the_graph() {
+    let mut g: Graph = GraphBuilder::new()
+        .edge(1, 2, 3)
+        .directed_edge(2, 3, 4)
+        .node(9)
+        .build();
+    let loaded: Graph = command::load_script(&command::script(&g)).unwrap();
+    assert_eq!(loaded, g);
+
+    g.add_node(10);
+    assert!(command::script(&g).contains("node 10"));
+    assert!(command::load_script::<i64>("run bfs").is_err());
+}
+
+#[test]
+fn script_keeps_groups_and_reports_unsaved_fields() {
+    let mut g: Graph = GraphBuilder::new().edge(1, 2, 3).edge(2, 3, 1).build();
+    g.set_group(1, "东区");
+    g.set_group(3, "东区");
+    g.set_group(2, "西 区");
+    let text = command::script(&g);
+    assert!(text.contains("group 东区 1 3\n"));
+    let loaded: Graph = command::load_script(&text).unwrap();
+    assert_eq!(loaded.group_of(3), Some("东区"));
+    assert_eq!(loaded.group_of(2), None);
+    assert_eq!(command::unsaved_fields(&g), vec!["名称含空格的分组"]);
+
+    g.set_node_label(1, "北京");
+    g.set_capacity(2, 3, 5);
+    g.set_edge_label(1, 2, "京港");
+    assert_eq!(
+        command::unsaved_fields(&g),
+        vec!["节点标签", "名称含空格的分组", "边标签", "边容量"]
+    );
+    assert!(command::load_script::<i64>("group 东区 1").is_err());
+}
+
+#[test]
+fn project_file_keeps_turn_restrictions() {
+    let g: Graph = GraphBuilder::new().edge(1, 2, 1).edge(2, 3, 1).build();
+    let mut turns = TurnRestrictions::default();
+    turns.set((1, 2, 3), TurnRule::Ban);
+    turns.set((3, 2, 1), TurnRule::Penalty(4));
+    let text = command::script(&g) + &command::turn_script(&turns);
+    assert!(text.contains("turn 1 2 3\n"));
+    assert!(text.contains("turn 3 2 1 4\n"));
+
+    assert_eq!(command::load_project(&text), Ok((g.clone(), turns)));
+    // 只要图时 turn 行被忽略
+    assert_eq!(command::load_script::<i64>(&text), Ok(g));
+}
+
+#[test]
+fn complete_line_covers_commands_menu_items_and_node_ids() {
+    let names = ["Dijkstra", "DAG 最短路径", "Bfs"];
+    let nodes = [3, 7, 12, 15];
+    assert_eq!(command::complete_line("ar", &names, &nodes), "arc");
+    assert_eq!(
+        command::complete_line("run dij", &names, &nodes),
+        "run Dijkstra"
+    );
+    assert_eq!(
+        command::complete_line("run Dijkstra 1", &names, &nodes),
+        "run Dijkstra 1"
+    );
+    assert_eq!(
+        command::complete_line("edge 3 7", &names, &nodes),
+        "edge 3 7"
+    );
+    assert_eq!(
+        command::complete_line("delnode 1", &names, &nodes),
+        "delnode 1"
+    );
+    assert_eq!(
+        command::complete_line("run bfs 15", &names, &nodes),
+        "run bfs 15"
+    );
+    assert_eq!(
+        command::complete_line("deledge 12 1", &names, &nodes),
+        "deledge 12 1"
+    );
+    assert_eq!(command::complete_line("node 7", &names, &nodes), "node 7");
+    assert_eq!(
+        command::complete_line("edge 3 1", &names, &[3, 120]),
+        "edge 3 120"
+    );
+}
+
+#[test]
+fn hint_shows_usage_or_errors_while_typing() {
+    assert_eq!(command::hint::<i64>("del"), "deledge <u> <v> | delnode <u>");
+    assert_eq!(command::hint::<i64>("edge 3"), "用法: edge <u> <v> [权重]");
+    assert_eq!(command::hint::<i64>("edge 3 7"), "添加无向边，权重默认为 1");
+    assert!(command::hint::<i64>("zz").starts_with("未知命令"));
+}
+
+#[test]
+fn history_navigates_and_restores_the_draft() {
+    let mut h = History::parse("edge 1 2\nrun bfs\nrun bfs\n\n");
+    assert_eq!(h.entries(), ["edge 1 2", "run bfs"]);
+    assert_eq!(History::parse(&h.to_text()), h);
+
+    assert_eq!(h.newer(), None);
+    assert_eq!(h.older("no"), Some("run bfs"));
+    assert_eq!(h.older("ignored"), Some("edge 1 2"));
+    assert_eq!(h.older("ignored"), Some("edge 1 2"));
+    assert_eq!(h.newer(), Some("run bfs"));
+    assert_eq!(h.newer(), Some("no"));
+    assert_eq!(h.newer(), None);
+
+    for i in 0..command::HISTORY_LIMIT + 5 {
+        h.push(&format!("node {}", i));
+    }
+    assert_eq!(h.entries().len(), command::HISTORY_LIMIT);
+    assert_eq!(h.entries()[0], "node 5");
+}
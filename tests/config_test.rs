@@ -0,0 +1,39 @@
+use graph_algorithm_tui::bell::BellPattern;
+use graph_algorithm_tui::config::Config;
+
+#[test]
+fn parse_round_trips_and_ignores_unknown_lines() {
+    let config = Config {
+        canvas_ratio: 55,
+        panel_ratio: 60,
+        bell: true,
+        bell_pattern: BellPattern {
+            settle: 0,
+            negative_cycle: 5,
+            complete: 1,
+        },
+    };
+    assert_eq!(Config::parse(&config.to_text()), config);
+
+    let text = "# 注释\nunknown = 3\ncanvas_ratio=40\n  panel_ratio =  abc\nno equals sign\n";
+    let parsed = Config::parse(text);
+    assert_eq!(parsed.canvas_ratio, 40);
+    assert_eq!(parsed.panel_ratio, Config::default().panel_ratio);
+    assert_eq!(Config::parse(""), Config::default());
+}
+
+#[test]
+fn parse_clamps_ratios_to_usable_range() {
+    let parsed = Config::parse("canvas_ratio = 100\npanel_ratio = 5\n");
+    assert_eq!(parsed.canvas_ratio, Config::CANVAS_RANGE.1);
+    assert_eq!(parsed.panel_ratio, Config::PANEL_RANGE.0);
+}
+
+#[test]
+fn parse_reads_bell_switch_and_pattern() {
+    let parsed = Config::parse("bell = on\nbell_complete = 4\nbell_settle = x\n");
+    assert!(parsed.bell);
+    assert_eq!(parsed.bell_pattern.complete, 4);
+    assert_eq!(parsed.bell_pattern.settle, BellPattern::default().settle);
+    assert!(!Config::parse("bell = maybe").bell);
+}
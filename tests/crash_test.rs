@@ -0,0 +1,31 @@
+use std::panic;
+
+use graph_algorithm_tui::crash;
+use graph_algorithm_tui::graph::{EdgeType::Single, Graph};
+
+#[test]
+fn panic_message_reads_str_and_string_payloads() {
+    let payload = panic::catch_unwind(|| panic!("固定消息")).unwrap_err();
+    assert_eq!(crash::panic_message(payload.as_ref()), "固定消息");
+
+    let n = 3;
+    let payload = panic::catch_unwind(|| panic!("节点 {} 不存在", n)).unwrap_err();
+    assert_eq!(crash::panic_message(payload.as_ref()), "节点 3 不存在");
+
+    let payload = panic::catch_unwind(|| panic::panic_any(42)).unwrap_err();
+    assert!(crash::panic_message(payload.as_ref()).contains("非字符串"));
+}
+
+#[test]
+fn crash_log_lists_every_edge() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Single);
+    g.add_edge(2, 3, 7, Single);
+    g.add_node(4);
+
+    let log = crash::crash_log("程序崩溃: boom", &g);
+    assert!(log.starts_with("程序崩溃: boom\n"));
+    assert!(log.contains("# 节点: [1, 2, 3, 4]"));
+    assert!(log.contains("\n1 2 5\n"));
+    assert!(log.contains("\n2 3 7\n"));
+}
@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::time::Duration;
+
+use graph_algorithm_tui::exact::{self, Budget};
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+
+#[test]
+fn exact_solvers_find_optimum_on_small_graphs() {
+    let (cancel, explored) = (AtomicBool::new(false), AtomicUsize::new(0));
+    let budget = Budget::new(Duration::from_secs(10), &cancel, &explored);
+
+    // 正方形加两条对角线（K4），对角线较重：最短回路沿外圈走
+    let k4 = Graph::from_edges(&[
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 4, 1),
+        (4, 1, 1),
+        (1, 3, 5),
+        (2, 4, 5),
+    ]);
+    let tsp = exact::tsp(&k4, &budget);
+    assert!(tsp.finished);
+    assert_eq!(tsp.best.unwrap().1, 4);
+
+    let colors = exact::coloring(&k4, &budget).best.unwrap();
+    assert_eq!(colors.values().max(), Some(&3));
+    for (u, v, _, _) in k4.edges() {
+        assert_ne!(colors[&u], colors[&v]);
+    }
+
+    // 五边形：至少 3 个节点才能覆盖，2-近似给出 4 个
+    let c5 = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 5, 1), (5, 1, 1)]);
+    assert_eq!(c5.vertex_cover_2approx().0.len(), 4);
+    let cover = exact::vertex_cover(&c5, &budget);
+    assert!(cover.finished);
+    assert_eq!(cover.best.unwrap().len(), 3);
+    assert!(explored.into_inner() > 0);
+
+    // 单向路径没有回路
+    let path = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .build();
+    let (cancel, explored) = (AtomicBool::new(false), AtomicUsize::new(0));
+    let budget = Budget::new(Duration::from_secs(10), &cancel, &explored);
+    assert_eq!(exact::tsp(&path, &budget).best, None);
+}
+
+#[test]
+fn cancelled_search_returns_best_so_far() {
+    let (cancel, explored) = (AtomicBool::new(true), AtomicUsize::new(0));
+    let budget = Budget::new(Duration::from_secs(10), &cancel, &explored);
+    let c5 = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 5, 1), (5, 1, 1)]);
+
+    let cover = exact::vertex_cover(&c5, &budget);
+    assert!(!cover.finished);
+    // 没来得及搜索，返回作为初始解的近似覆盖
+    assert_eq!(cover.best.unwrap().len(), 4);
+    let colors = exact::coloring(&c5, &budget);
+    assert!(!colors.finished && colors.best.is_some());
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use graph_algorithm_tui::export::{csv, dot};
+use graph_algorithm_tui::graph::{EdgeType, Graph};
+
+#[test]
+fn node_metrics_csv_has_one_row_per_node() {
+    let mut g = Graph::from_edges(&[(1, 2, 4), (2, 3, 1), (1, 3, 7)]);
+    g.add_node(5);
+    g.set_node_label(3, "北京, 朝阳");
+
+    assert_eq!(
+        csv::node_metrics(&g, 1, None, 2),
+        "node,label,degree,bfs_depth,distance\n\
+         1,,2,0,0\n\
+         2,,2,1,4\n\
+         3,\"北京, 朝阳\",2,1,5\n\
+         5,,0,,\n"
+    );
+
+    let centrality = HashMap::from([(1, 0.5)]);
+    let with = csv::node_metrics(&g, 1, Some(&centrality), 2);
+    assert!(with.starts_with("node,label,degree,bfs_depth,distance,centrality\n1,,2,0,0,0.5\n"));
+    assert!(with.ends_with("5,,0,,,\n"));
+}
+
+#[test]
+fn dot_draws_arrows_only_for_one_way_edges() {
+    let mut g = Graph::from_edges(&[(1, 2, 4)]);
+    g.add_edge(2, 3, 1, EdgeType::Single);
+    g.set_node_label(1, "起点 \"A\"");
+
+    assert_eq!(
+        dot::to_dot(&g, 0),
+        "digraph G {\n\
+         \x20   1 [label=\"起点 \\\"A\\\"\"];\n\
+         \x20   2;\n\
+         \x20   3;\n\
+         \x20   1 -> 2 [label=\"4\", dir=none];\n\
+         \x20   2 -> 3 [label=\"1\"];\n\
+         }\n"
+    );
+}
+
+#[test]
+fn dot_parse_reads_back_exported_graphs_and_common_syntax() {
+    let mut g = Graph::from_edges(&[(1, 2, 4)]);
+    g.add_edge(2, 3, 1, EdgeType::Single);
+    g.add_node(7);
+    g.set_node_label(1, "起点 \"A\"; 1");
+    assert_eq!(dot::parse::<i64>(&dot::to_dot(&g, 0)), Ok(g));
+
+    let text = "// 编辑器里手写的图\n\
+                graph demo {\n\
+                \x20   node [shape=circle]\n\
+                \x20   rankdir=LR;\n\
+                \x20   1 -- 2 -- 3 [weight = 5 label=\"x\"];\n\
+                \x20   \"4\" -- 1\n\
+                }\n";
+    let parsed: Graph = dot::parse(text).unwrap();
+    assert_eq!(
+        parsed,
+        Graph::from_edges(&[(1, 2, 5), (2, 3, 5), (4, 1, 1)])
+    );
+
+    assert!(
+        dot::parse::<i64>("digraph { a -> b }")
+            .unwrap_err()
+            .contains("整数")
+    );
+    assert!(dot::parse::<i64>("1 -> 2").is_err());
+}
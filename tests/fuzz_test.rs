@@ -0,0 +1,34 @@
+use graph_algorithm_tui::fuzz::{self, Invariant};
+use graph_algorithm_tui::harness::Case;
+
+#[test]
+fn invariants_hold_on_random_graphs() {
+    assert!(fuzz::fuzz(200, 42).is_none());
+
+    let case = Case::random(3, 8, 16);
+    for invariant in Invariant::ALL {
+        assert_eq!(invariant.check(&case), Ok(()), "{}", invariant.name());
+    }
+}
+
+#[test]
+fn shrink_finds_minimal_counterexample() {
+    let case = Case {
+        n: 6,
+        edges: vec![
+            (1, 2, 7),
+            (2, 3, 4),
+            (3, 4, 9),
+            (4, 1, 2),
+            (1, 3, 5),
+            (4, 5, 3),
+        ],
+        source: 1,
+    };
+    // 人为的"失败"条件：图中有环
+    let has_cycle = |c: &Case| c.graph().kruskal().edges.len() < c.edges.len();
+
+    let shrunk = fuzz::shrink(case, has_cycle);
+    assert_eq!(shrunk.edges, vec![(3, 4, 1), (4, 1, 1), (1, 3, 1)]);
+    assert_eq!(shrunk.n, 4);
+}
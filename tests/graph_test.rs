@@ -1,4 +1,9 @@
-use graph_algorithm_tui::graph::{EdgeType::Both, EdgeType::Single, Graph};
+use graph_algorithm_tui::graph::{
+    self, AddEdgePolicy, EdgeAttr, EdgeDirection, EdgeType::Both, EdgeType::Single, Graph,
+    GraphBuilder, GraphIssue,
+};
+use graph_algorithm_tui::harness::Case;
+use graph_algorithm_tui::weight::Weight;
 
 #[test]
 fn runs_main_logic_without_panic() {
@@ -15,3 +20,940 @@ fn runs_main_logic_without_panic() {
     g.dijkstra(1);
     g.prim(1);
 }
+
+#[test]
+fn communities_split_two_triangles() {
+    let mut g = Graph::new();
+    for (u, v) in [(1, 2), (2, 3), (1, 3), (4, 5), (5, 6), (4, 6), (3, 4)] {
+        g.add_edge(u, v, 1, Both);
+    }
+
+    let labels = g.communities();
+    assert_eq!(labels[&1], labels[&2]);
+    assert_eq!(labels[&2], labels[&3]);
+    assert_eq!(labels[&4], labels[&5]);
+    assert_eq!(labels[&5], labels[&6]);
+    assert_ne!(labels[&1], labels[&6]);
+
+    g.set_group(1, "a");
+    g.set_group(2, "a");
+    assert_eq!(g.groups()["a"], vec![1, 2]);
+    assert_eq!(g.group_of(3), None);
+}
+
+#[test]
+fn float_weights_use_epsilon_comparisons() {
+    let mut g: Graph<f64> = Graph::default();
+    g.add_edge(1, 2, 0.1, Single);
+    g.add_edge(2, 3, 0.2, Single);
+    g.add_edge(1, 3, 0.3, Single);
+
+    let result = g.dijkstra(1);
+    assert!(result.dist[&3].approx_eq(0.3));
+    // 0.1 + 0.2 与 0.3 在误差范围内相等，不应被视为更短的路径
+    assert_eq!(result.path_to(3), Some(vec![1, 3]));
+
+    let total = g.prim(1).total_weight;
+    assert_eq!(total.format(2), "0.3");
+    assert_eq!(650.0_f64.format(2), "650");
+    assert_eq!(f64::parse(" 12.5 "), Some(12.5));
+    assert_eq!(f64::parse("inf"), None);
+}
+
+#[test]
+fn edges_carry_weight_capacity_and_label() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Both);
+    g.add_edge(2, 3, 7, Single);
+    g.set_capacity(1, 2, 3);
+    g.set_edge_label(2, 3, "桥");
+
+    assert_eq!(g.edge(2, 1).unwrap().capacity, 3);
+    assert_eq!(g.edge(1, 2).unwrap().get(EdgeAttr::Weight), 5);
+    assert_eq!(g.edge(2, 3).unwrap().label.as_deref(), Some("桥"));
+
+    let mut caps = g.edges_with(EdgeAttr::Capacity);
+    caps.sort();
+    assert_eq!(caps.len(), 2);
+    assert!(caps.contains(&(2, 3, 7, Single)));
+}
+
+#[test]
+fn scaling_keeps_shortest_paths_but_shifting_does_not() {
+    // 1 -> 3 直达 5，绕经 2 为 2 + 2 = 4
+    let g = Graph::from_edges(&[(1, 2, 2), (2, 3, 2), (1, 3, 5)]);
+    assert_eq!(g.dijkstra(1).path_to(3), Some(vec![1, 2, 3]));
+
+    let scaled = g.scale_weights(3).unwrap();
+    assert_eq!(scaled.edge_weight(3, 1), Some(15));
+    assert_eq!(scaled.dijkstra(1).path_to(3), Some(vec![1, 2, 3]));
+
+    // 每条边加 2 后绕路为 8，直达为 7
+    let shifted = g.shift_weights(2).unwrap();
+    assert_eq!(shifted.dijkstra(1).path_to(3), Some(vec![1, 3]));
+
+    assert!(g.scale_weights(i64::MAX / 4).is_none());
+    assert!(g.shift_weights(i64::MAX - 3).is_none());
+}
+
+#[test]
+fn edit_edge_updates_both_records_by_endpoints() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Both);
+    g.add_edge(2, 3, 7, Single);
+
+    assert!(g.edit_edge(2, 1, |e| e.weight += 1));
+    assert_eq!(g.edge_weight(1, 2), Some(6));
+    assert_eq!(g.edge_weight(2, 1), Some(6));
+    assert!(g.edit_edge(3, 2, |e| e.weight -= 2));
+    assert_eq!(g.edge_weight(2, 3), Some(5));
+    assert!(!g.edit_edge(1, 3, |e| e.weight = 0));
+}
+
+#[test]
+fn node_attrs_store_strings_and_numbers() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 1, Both);
+    g.set_node_attr(1, "name", "北京");
+    g.set_node_attr(1, "population", 2189_i64);
+
+    assert_eq!(g.get_node_attr(1, "name").unwrap().as_str(), Some("北京"));
+    assert_eq!(
+        g.get_node_attr(1, "population").unwrap().as_num(),
+        Some(2189.0)
+    );
+    assert_eq!(g.get_node_attr(2, "name"), None);
+
+    let keys: Vec<&str> = g.node_attrs(1).map(|(k, _)| k).collect();
+    assert_eq!(keys, vec!["name", "population"]);
+
+    assert!(g.remove_node_attr(1, "name").is_some());
+    assert_eq!(g.get_node_attr(1, "name"), None);
+}
+
+#[test]
+fn builder_matches_add_edge_construction() {
+    let built = GraphBuilder::new()
+        .edge(1, 2, 3)
+        .directed_edge(2, 3, 4)
+        .node(7)
+        .build();
+
+    let mut nodes = built.nodes();
+    nodes.sort();
+    assert_eq!(nodes, vec![1, 2, 3, 7]);
+    assert_eq!(built.edge(2, 1).unwrap().weight, 3);
+    assert!(built.edge(3, 2).is_none());
+
+    let g = Graph::from_edges(&[(1, 2, 3), (2, 3, 4)]);
+    assert_eq!(g.dijkstra(1).dist[&3], 7);
+}
+
+#[test]
+fn accessors_answer_without_rebuilding() {
+    let g = GraphBuilder::new()
+        .edge(1, 2, 3)
+        .directed_edge(2, 3, 4)
+        .build();
+
+    let mut n: Vec<i64> = g.neighbors(2).collect();
+    n.sort();
+    assert_eq!(n, vec![1, 3]);
+    assert_eq!(g.degree(2), 2);
+    assert_eq!(g.degree(3), 0);
+    assert!(g.contains_node(3));
+    assert!(!g.contains_node(4));
+    assert!(g.contains_edge(2, 3));
+    assert!(!g.contains_edge(3, 2));
+    assert_eq!(g.edge_weight(1, 2), Some(3));
+    assert_eq!(g.edge_weight(1, 3), None);
+}
+
+#[test]
+fn iteration_order_is_deterministic() {
+    let edges = [(5, 9, 1), (1, 5, 1), (1, 3, 1), (3, 9, 1), (1, 2, 1)];
+    let a = Graph::from_edges(&edges);
+    let b = Graph::from_edges(&edges);
+
+    assert_eq!(a.nodes(), vec![1, 2, 3, 5, 9]);
+    assert_eq!(a.edges(), b.edges());
+    assert_eq!(a.bfs(1), b.bfs(1));
+    assert_eq!(a.bfs(1).0, vec![1, 5, 3, 2, 9]);
+    assert_eq!(a.dfs(1).0, vec![1, 5, 9, 3, 2]);
+}
+
+#[test]
+fn isolated_nodes_are_reported_unreachable() {
+    let mut g = Graph::from_edges(&[(1, 2, 1)]);
+    g.add_node(5);
+    g.add_node(1);
+
+    assert_eq!(g.nodes(), vec![1, 2, 5]);
+    assert_eq!(g.degree(5), 0);
+    assert_eq!(g.bfs(1).0, vec![1, 2]);
+    assert_eq!(g.unreachable_from(1), vec![5]);
+    assert_eq!(g.unreachable_from(5), vec![1, 2]);
+}
+
+#[test]
+fn reachable_from_follows_edge_direction() {
+    let g = GraphBuilder::new()
+        .directed_edge(3, 1, 1)
+        .directed_edge(1, 2, 1)
+        .edge(4, 5, 1)
+        .build();
+
+    assert_eq!(g.reachable_from(3), vec![1, 2, 3]);
+    assert_eq!(g.reachable_from(1), vec![1, 2]);
+    assert_eq!(g.reachable_from(5), vec![4, 5]);
+}
+
+#[test]
+fn one_way_edges_mark_graph_directed() {
+    let mut g = GraphBuilder::new()
+        .edge(1, 2, 1)
+        .directed_edge(2, 3, 4)
+        .directed_edge(3, 1, 2)
+        .directed_edge(3, 4, 1)
+        .node(5)
+        .build();
+
+    assert!(g.is_directed());
+    assert_eq!(g.one_way_edges(), vec![(2, 3), (3, 1), (3, 4)]);
+    assert_eq!(
+        g.strongly_connected_components(),
+        vec![vec![1, 2, 3], vec![4], vec![5]]
+    );
+
+    assert_eq!(g.symmetrize(), 3);
+    assert!(!g.is_directed());
+    assert_eq!(g.edge_weight(3, 2), Some(4));
+    assert_eq!(
+        g.strongly_connected_components(),
+        vec![vec![1, 2, 3, 4], vec![5]]
+    );
+}
+
+#[test]
+fn symmetrize_pairs_every_parallel_arc() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 1, Single);
+    g.add_edge(1, 3, 2, Single);
+    g.add_edge(1, 2, 5, Single);
+
+    assert_eq!(g.one_way_edges(), vec![(1, 2), (1, 3)]);
+    assert_eq!(g.symmetrize(), 3);
+    assert!(!g.is_directed());
+    let mut back: Vec<_> = g
+        .out_edges(2)
+        .map(|e| (e.to, e.weight, e.edge_type))
+        .collect();
+    back.sort_by_key(|&(_, w, _)| w);
+    assert_eq!(back, vec![(1, 1, Both), (1, 5, Both)]);
+    assert!(g.out_edges(1).all(|e| e.edge_type == Both));
+}
+
+#[test]
+fn to_undirected_and_orient_round_trip() {
+    let mut g = GraphBuilder::new()
+        .directed_edge(1, 2, 5)
+        .directed_edge(2, 1, 3)
+        .directed_edge(2, 3, 4)
+        .directed_edge(4, 4, 1)
+        .build();
+    g.set_edge_label(2, 3, "桥");
+
+    let u = g.to_undirected();
+    assert!(!u.is_directed());
+    assert_eq!(u.edge_weight(1, 2), Some(3));
+    assert_eq!(u.edge_weight(2, 1), Some(3));
+    assert_eq!(u.edge(3, 2).unwrap().label.as_deref(), Some("桥"));
+    assert_eq!(u.degree(4), 1);
+    assert_eq!(u.nodes(), g.nodes());
+
+    let o = u.orient_by(|a, b| a + b > 3);
+    assert_eq!(o.one_way_edges(), vec![(2, 1), (2, 3)]);
+    assert_eq!(o.edge_weight(2, 1), Some(3));
+    assert!(o.contains_edge(4, 4));
+    assert_eq!(o.to_undirected().edges(), u.edges());
+
+    let r = u.orient_random(7);
+    assert_eq!(r.one_way_edges().len(), 2);
+    assert_eq!(r.one_way_edges(), u.orient_random(7).one_way_edges());
+}
+
+#[test]
+fn relabel_compacts_ids_and_keeps_structure() {
+    let mut g = GraphBuilder::new()
+        .edge(100, 7, 3)
+        .directed_edge(7, 5000, 4)
+        .node(-2)
+        .build();
+    g.set_node_attr(5000, "name", "终点");
+
+    let (r, originals) = g.relabel();
+    assert_eq!(originals, vec![-2, 7, 100, 5000]);
+    assert_eq!(r.nodes(), vec![1, 2, 3, 4]);
+    assert_eq!(r.edge_weight(3, 2), Some(3));
+    assert_eq!(r.edge_weight(2, 4), Some(4));
+    assert!(!r.contains_edge(4, 2));
+    assert_eq!(r.get_node_attr(4, "name"), g.get_node_attr(5000, "name"));
+}
+
+#[test]
+fn validate_and_repair_duplicates_and_self_loops() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Both);
+    g.add_edge(1, 2, 3, Both);
+    g.add_edge(3, 3, 1, Single);
+    g.add_edge(2, 4, 1, Single);
+    g.add_edge(4, 2, 9, Single);
+
+    let issues = g.validate();
+    assert!(issues.contains(&GraphIssue::DuplicateEdge {
+        u: 1,
+        v: 2,
+        count: 2
+    }));
+    assert!(issues.contains(&GraphIssue::DuplicateEdge {
+        u: 2,
+        v: 1,
+        count: 2
+    }));
+    assert!(issues.contains(&GraphIssue::SelfLoop { u: 3 }));
+    assert!(issues.contains(&GraphIssue::AsymmetricEdge {
+        u: 2,
+        v: 4,
+        forward: 1,
+        backward: 9
+    }));
+
+    assert_eq!(g.repair(), 3);
+    assert_eq!(g.edge_weight(1, 2), Some(3));
+    assert_eq!(g.degree(1), 1);
+    assert!(!g.contains_edge(3, 3));
+    assert_eq!(g.validate().len(), 1);
+}
+
+#[test]
+fn walk_step_is_proportional_to_weights() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(1, 3, 3)
+        .node(4)
+        .build();
+
+    assert_eq!(g.walk_step(1, 0.0), Some(2));
+    assert_eq!(g.walk_step(1, 0.24), Some(2));
+    assert_eq!(g.walk_step(1, 0.25), Some(3));
+    assert_eq!(g.walk_step(1, 0.99), Some(3));
+    assert_eq!(g.walk_step(4, 0.5), None);
+}
+
+#[test]
+fn find_node_by_id_or_label() {
+    let mut g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1)]);
+    g.set_node_label(2, "上海");
+    g.set_node_label(3, "北京");
+
+    assert_eq!(g.find_node("3"), Some(3));
+    assert_eq!(g.find_node(" 上海 "), Some(2));
+    assert_eq!(g.find_node("9"), None);
+    assert_eq!(g.find_node("广州"), None);
+
+    // 标签唯一，重复设置时从原节点上移走
+    g.set_node_label(1, "上海");
+    assert_eq!(g.find_node("上海"), Some(1));
+    assert_eq!(g.node_label(2), None);
+
+    let (r, _) = g.relabel();
+    assert_eq!(r.find_node("北京"), Some(3));
+}
+
+#[test]
+fn add_edge_policy_merges_duplicates() {
+    let build = |policy| {
+        let mut g = Graph::new();
+        g.add_edge_with(1, 2, 5, Both, policy);
+        g.add_edge_with(1, 2, 3, Both, policy);
+        g.add_edge_with(2, 1, 4, Single, policy);
+        g
+    };
+
+    assert_eq!(build(AddEdgePolicy::Parallel).degree(1), 2);
+    assert_eq!(build(AddEdgePolicy::Parallel).degree(2), 3);
+    for (policy, forward, backward) in [
+        (AddEdgePolicy::KeepMin, 3, 3),
+        (AddEdgePolicy::KeepMax, 5, 5),
+        (AddEdgePolicy::Sum, 8, 12),
+        (AddEdgePolicy::KeepLast, 3, 4),
+    ] {
+        let g = build(policy);
+        assert_eq!(g.degree(1), 1);
+        assert_eq!(g.edge_weight(1, 2), Some(forward));
+        assert_eq!(g.edge_weight(2, 1), Some(backward));
+    }
+
+    let mut g = Graph::new();
+    g.add_edge_with(3, 3, 2, Both, AddEdgePolicy::Sum);
+    g.add_edge_with(3, 3, 2, Both, AddEdgePolicy::Sum);
+    assert_eq!(g.edge_weight(3, 3), Some(4));
+    g.add_edge_with(3, 3, i64::MAX - 1, Both, AddEdgePolicy::Sum);
+    assert_eq!(g.edge_weight(3, 3), Some(i64::MAX));
+    assert_eq!(AddEdgePolicy::parse("MIN"), Some(AddEdgePolicy::KeepMin));
+}
+
+#[test]
+fn contract_merges_edges_by_policy() {
+    let build = || {
+        let mut g = Graph::from_edges(&[(1, 2, 1), (1, 3, 3), (2, 3, 4), (2, 4, 2)]);
+        g.set_node_label(2, "乙");
+        g
+    };
+
+    let mut g = build();
+    assert!(g.contract(1, 2, AddEdgePolicy::Sum));
+    assert_eq!(g.nodes(), vec![1, 3, 4]);
+    assert_eq!(g.edge_weight(1, 3), Some(7));
+    assert_eq!(g.edge_weight(3, 1), Some(7));
+    assert_eq!(g.edge_weight(4, 1), Some(2));
+    assert!(!g.contains_edge(1, 1));
+    assert_eq!(g.find_node("乙"), None);
+
+    let mut g = build();
+    assert!(g.contract(3, 2, AddEdgePolicy::KeepMin));
+    assert_eq!(g.edge_weight(1, 3), Some(1));
+    assert_eq!(g.degree(3), 2);
+
+    let mut g = build();
+    assert!(g.contract(1, 2, AddEdgePolicy::Parallel));
+    assert_eq!(g.degree(1), 3);
+    assert!(!g.contract(1, 2, AddEdgePolicy::Sum));
+    assert!(!g.contract(1, 1, AddEdgePolicy::Sum));
+
+    assert!(g.remove_node(4));
+    assert!(!g.remove_node(4));
+    assert_eq!(g.degree(1), 2);
+}
+
+#[test]
+fn kruskal_matches_prim_total() {
+    let g = Graph::from_edges(&[(1, 2, 4), (2, 3, 1), (1, 3, 2), (3, 4, 5), (2, 4, 7)]);
+    let kruskal = g.kruskal();
+    let prim = g.prim(1);
+
+    assert_eq!(kruskal.edges, vec![(2, 3, 1), (1, 3, 2), (3, 4, 5)]);
+    assert_eq!(kruskal.total_weight, 8);
+    assert_eq!(prim.total_weight, 8);
+    assert_eq!(prim.edges, vec![(1, 3, 2), (3, 2, 1), (3, 4, 5)]);
+    assert!(prim.is_spanning && kruskal.is_spanning);
+
+    let mut g = g;
+    g.add_node(9);
+    assert!(!g.prim(1).is_spanning);
+    assert!(!g.kruskal().is_spanning);
+}
+
+#[test]
+fn kruskal_uses_lightest_parallel_edge() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 17, Both);
+    g.add_edge(1, 2, 1, Both);
+
+    assert_eq!(g.kruskal().edges, vec![(1, 2, 1)]);
+    assert_eq!(g.prim(1).total_weight, 1);
+}
+
+#[test]
+fn update_edge_matches_full_recompute() {
+    for seed in 0..200 {
+        let case = Case::random(seed, 8, 14);
+        let mut g = case.graph();
+        let edges = g.edges();
+        if edges.is_empty() {
+            continue;
+        }
+        let mut result = g.dijkstra(1);
+
+        // 依次把几条边改大、改小，每次都与重新计算的结果对照
+        for (i, &(u, v, w, _)) in edges.iter().enumerate().take(4) {
+            let before = result.dist.clone();
+            let new = if i % 2 == 0 { w + 7 } else { 1 };
+            assert!(g.set_edge_weight(u, v, new));
+
+            let changed = result.update_edge(&g, u, v).unwrap();
+            let fresh = g.dijkstra(1);
+            assert_eq!(result.dist, fresh.dist, "seed {} edge {}-{}", seed, u, v);
+
+            let mut expected: Vec<i64> = g
+                .nodes()
+                .into_iter()
+                .filter(|x| before.get(x) != fresh.dist.get(x))
+                .collect();
+            expected.sort();
+            assert_eq!(changed, expected);
+            for (&c, &p) in &result.parent {
+                assert!(
+                    g.out_edges(p)
+                        .any(|e| e.to == c && result.dist[&p] + e.weight == result.dist[&c])
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn update_edge_refuses_negative_weights() {
+    let mut g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1)]);
+    let mut result = g.dijkstra(1);
+    let before = result.clone();
+    assert!(g.set_edge_weight(2, 3, -1));
+    assert_eq!(result.update_edge(&g, 2, 3), None);
+    assert_eq!(result, before);
+}
+
+#[test]
+fn path_to_walks_the_shortest_path_tree() {
+    let mut g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (1, 3, 5), (3, 4, 1)]);
+    g.add_node(9);
+
+    let result = g.dijkstra(1);
+    assert_eq!(result.path_to(4), Some(vec![1, 2, 3, 4]));
+    assert_eq!(result.path_to(1), Some(vec![1]));
+    assert_eq!(result.path_to(9), None);
+
+    let (_, tree) = g.bfs(1);
+    assert_eq!(graph::tree_path(1, &tree, 4), Some(vec![1, 3, 4]));
+    assert_eq!(graph::tree_path(1, &tree, 9), None);
+}
+
+#[test]
+fn karger_finds_the_bridge_between_two_triangles() {
+    let g = Graph::from_edges(&[
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 1, 1),
+        (4, 5, 1),
+        (5, 6, 1),
+        (6, 4, 1),
+        (3, 4, 7),
+    ]);
+
+    for seed in 0..20 {
+        let trial = g.karger_trial(seed);
+        assert_eq!(trial.contractions.len(), 4);
+        assert!(!trial.side.is_empty() && trial.side.len() < 6);
+        assert!(!trial.cut_edges.is_empty());
+    }
+
+    let best = g.karger_min_cut(50, 1);
+    assert_eq!(best.side, vec![1, 2, 3]);
+    assert_eq!(best.cut_edges, vec![(3, 4, 7)]);
+    assert_eq!(best.cut_weight(), 7);
+
+    // 不连通时不需要割任何边
+    let mut g = g;
+    g.add_node(9);
+    assert!(g.karger_min_cut(5, 0).cut_edges.is_empty());
+}
+
+#[test]
+fn set_direction_cycles_and_keeps_attributes() {
+    let mut g = Graph::from_edges(&[(1, 2, 5), (2, 3, 1)]);
+    g.set_edge_label(1, 2, "a");
+    assert_eq!(g.direction(1, 2), Some(EdgeDirection::Both));
+
+    let mut dir = EdgeDirection::Both;
+    for expected in [
+        EdgeDirection::Forward,
+        EdgeDirection::Backward,
+        EdgeDirection::Both,
+    ] {
+        dir = dir.next();
+        assert_eq!(dir, expected);
+        assert!(g.set_direction(1, 2, dir));
+        assert_eq!(g.direction(1, 2), Some(dir));
+    }
+
+    assert!(g.set_direction(1, 2, EdgeDirection::Backward));
+    assert!(!g.contains_edge(1, 2));
+    assert_eq!(g.out_edges(2).filter(|e| e.to == 1).count(), 1);
+    assert_eq!(g.edge_weight(2, 1), Some(5));
+    assert_eq!(g.edge(2, 1).and_then(|e| e.label.as_deref()), Some("a"));
+    assert_eq!(g.direction(2, 1), Some(EdgeDirection::Forward));
+    assert_eq!(g.one_way_edges(), vec![(2, 1)]);
+
+    assert!(!g.set_direction(1, 3, EdgeDirection::Both));
+    assert_eq!(g.direction(1, 3), None);
+}
+
+#[test]
+fn induced_subgraph_keeps_inner_edges_and_metadata() {
+    let mut g = Graph::from_edges(&[(1, 2, 5), (2, 3, 1), (3, 4, 2), (1, 4, 7)]);
+    g.add_edge(3, 2, 9, Single);
+    g.set_group(2, "a");
+
+    let sub = g.induced_subgraph(&[2, 3, 4, 99]);
+    assert_eq!(sub.nodes(), vec![2, 3, 4]);
+    assert_eq!(sub.edge_weight(2, 3), Some(1));
+    assert_eq!(sub.edge_weight(3, 4), Some(2));
+    assert_eq!(sub.out_edges(3).filter(|e| e.to == 2).count(), 2);
+    assert!(!sub.contains_edge(1, 2));
+    assert!(!sub.contains_edge(4, 1));
+    assert_eq!(sub.group_of(2), Some("a"));
+}
+
+#[test]
+fn bfs_multi_grows_from_every_source() {
+    // 1 - 2 - 3 - 4 - 5，起点 1 和 5，中间的 3 由先入队的 1 一侧发现
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 5, 1)]);
+    let (order, edges) = g.bfs_multi(&[1, 5]);
+    assert_eq!(order, vec![1, 5, 2, 4, 3]);
+    assert_eq!(edges, vec![(1, 2), (5, 4), (2, 3)]);
+
+    assert_eq!(g.bfs_multi(&[1]), g.bfs(1));
+    // 重复的起点只算一次
+    assert_eq!(g.bfs_multi(&[1, 1]).0, g.bfs(1).0);
+}
+
+#[test]
+fn paste_remaps_conflicting_ids() {
+    let mut g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1)]);
+    g.set_node_label(1, "a");
+    let mut piece = Graph::from_edges(&[(3, 7, 4), (7, 8, 5)]);
+    piece.set_node_label(3, "a");
+    piece.set_node_label(8, "b");
+
+    let mapping = g.paste(&piece);
+    // 3 与已有节点冲突，改为两图最大编号 8 之后的 9；7、8 保持不变
+    assert_eq!(mapping[&3], 9);
+    assert_eq!(mapping[&7], 7);
+    assert_eq!(mapping[&8], 8);
+    assert_eq!(g.nodes(), vec![1, 2, 3, 7, 8, 9]);
+    assert_eq!(g.edge_weight(9, 7), Some(4));
+    assert_eq!(g.edge_weight(7, 8), Some(5));
+    assert_eq!(g.edge_weight(2, 3), Some(1));
+    assert!(!g.contains_edge(3, 7));
+
+    assert_eq!(g.find_node("a"), Some(1));
+    assert_eq!(g.node_label(9), None);
+    assert_eq!(g.find_node("b"), Some(8));
+}
+
+#[test]
+fn distance_rows_sort_by_node_or_distance() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Single);
+    g.add_edge(1, 3, 1, Single);
+    g.add_edge(3, 2, 1, Single);
+    g.add_edge(1, 4, 2, Single);
+    g.add_node(5);
+
+    let result = g.dijkstra(1);
+    let rows = result.rows(graph::DistanceOrder::Node);
+    assert_eq!(
+        rows.iter().map(|r| r.node).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(rows[0].parent, None);
+    assert_eq!(rows[1].dist, 2);
+    assert_eq!(rows[1].parent, Some(3));
+    assert_eq!(rows[1].path, vec![1, 3, 2]);
+
+    let rows = result.rows(graph::DistanceOrder::Distance);
+    // 2 和 4 的距离都是 2，按编号排
+    assert_eq!(
+        rows.iter().map(|r| r.node).collect::<Vec<_>>(),
+        vec![1, 3, 2, 4]
+    );
+}
+
+#[test]
+fn edges_keep_direction_per_record() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 5, Both);
+    g.add_edge(2, 3, 7, Single);
+    g.add_edge(3, 2, 4, Single);
+    assert_eq!(
+        g.edges(),
+        vec![(1, 2, 5, Both), (2, 3, 7, Single), (3, 2, 4, Single)]
+    );
+
+    // 删掉无向边的一个方向后，剩下的一侧成为单向边
+    g.remove_edge(2, 1, Single);
+    assert_eq!(g.edges()[0], (1, 2, 5, Single));
+    assert_eq!(g.edge(1, 2).unwrap().edge_type, Single);
+
+    g.set_direction(2, 3, EdgeDirection::Both);
+    assert!(g.edges().contains(&(2, 3, 7, Both)));
+    g.symmetrize();
+    assert!(g.edges().contains(&(1, 2, 5, Both)));
+    assert!(
+        g.orient_by(|_, _| true)
+            .edges()
+            .iter()
+            .all(|e| e.3 == Single)
+    );
+    assert!(g.to_undirected().edges().iter().all(|e| e.3 == Both));
+}
+
+#[test]
+fn graph_equality_ignores_insertion_order() {
+    use std::collections::HashSet;
+
+    let a = Graph::from_edges(&[(1, 2, 4), (2, 3, 1), (1, 3, 7)]);
+    let b = Graph::from_edges(&[(3, 1, 7), (2, 3, 1), (2, 1, 4)]);
+    assert_eq!(a, b);
+    assert_eq!(HashSet::from([a.clone(), b.clone()]).len(), 1);
+
+    // 权重、方向、平行边和节点元数据的差异都会被发现
+    assert_ne!(a, Graph::from_edges(&[(1, 2, 4), (2, 3, 1), (1, 3, 8)]));
+    let mut one_way = a.clone();
+    one_way.set_direction(1, 3, EdgeDirection::Forward);
+    assert_ne!(a, one_way);
+    let mut parallel = a.clone();
+    parallel.add_edge(1, 2, 4, Both);
+    assert_ne!(a, parallel);
+    let mut labeled = b.clone();
+    labeled.set_node_label(2, "中转");
+    assert_ne!(a, labeled);
+
+    // 删除属性后元数据为空，与从未设置过相同
+    let mut attributed = b.clone();
+    attributed.set_node_attr(3, "人口", 5);
+    assert_ne!(a, attributed);
+    attributed.remove_node_attr(3, "人口");
+    assert_eq!(a, attributed);
+    assert_eq!(a.canonical_edges().len(), 6);
+}
+
+#[test]
+fn uniform_weights_detection() {
+    assert!(Graph::new().has_uniform_weights());
+    assert!(Graph::from_edges(&[(1, 2, 3), (2, 3, 3)]).has_uniform_weights());
+    assert!(!Graph::from_edges(&[(1, 2, 3), (2, 3, 4)]).has_uniform_weights());
+
+    let mut g: Graph<f64> = Graph::default();
+    g.add_edge(1, 2, 0.1 + 0.2, Both);
+    g.add_edge(2, 3, 0.3, Single);
+    assert!(g.has_uniform_weights());
+}
+
+#[test]
+fn reachability_follows_direction() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .edge(3, 4, 1)
+        .directed_edge(1, 4, 9)
+        .node(5)
+        .build();
+
+    assert!(g.is_reachable(1, 3));
+    assert!(!g.is_reachable(3, 1));
+    assert!(g.is_reachable(4, 3));
+    assert!(g.is_reachable(5, 5));
+    assert!(!g.is_reachable(1, 5));
+    assert!(!g.is_reachable(1, 42));
+    // 边数最少，不看权重
+    assert_eq!(g.reach_path(1, 4), Some(vec![1, 4]));
+    assert_eq!(g.reach_path(2, 4), Some(vec![2, 3, 4]));
+}
+
+#[test]
+fn girth_and_min_weight_cycle() {
+    // 三角形 1-2-3 与四边形 3-4-5-6，四边形权重更小
+    let g = GraphBuilder::new()
+        .edge(1, 2, 5)
+        .edge(2, 3, 5)
+        .edge(3, 1, 5)
+        .edge(3, 4, 1)
+        .edge(4, 5, 1)
+        .edge(5, 6, 1)
+        .edge(6, 3, 1)
+        .build();
+    assert_eq!(g.girth().map(|c| c.len()), Some(3));
+    let (cycle, w) = g.min_weight_cycle().unwrap();
+    assert_eq!((cycle.len(), w), (4, 4));
+
+    // 一条无向边不构成环；单向边要顺着方向才能回来
+    assert_eq!(GraphBuilder::new().edge(1, 2, 1).build().girth(), None);
+    let path = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .directed_edge(1, 3, 1)
+        .build();
+    assert_eq!(path.girth(), None);
+    let directed = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 1, 1)
+        .build();
+    assert_eq!(directed.girth(), Some(vec![1, 2]));
+
+    let mut looped = g.clone();
+    looped.add_edge(4, 4, 9, Single);
+    assert_eq!(looped.girth(), Some(vec![4]));
+}
+
+#[test]
+fn disjoint_paths_between_two_nodes() {
+    // 两个三角形共用节点 3：1 到 5 有两条边不相交的路径，但都经过 3
+    let g = GraphBuilder::new()
+        .edge(1, 2, 1)
+        .edge(2, 3, 1)
+        .edge(1, 3, 1)
+        .edge(3, 4, 1)
+        .edge(4, 5, 1)
+        .edge(3, 5, 1)
+        .build();
+    let paths = g.edge_disjoint_paths(1, 5);
+    assert_eq!(paths.len(), 2);
+    let mut used = std::collections::HashSet::new();
+    for p in &paths {
+        assert_eq!((p[0], p[p.len() - 1]), (1, 5));
+        for w in p.windows(2) {
+            assert!(g.contains_edge(w[0], w[1]));
+            assert!(used.insert((w[0].min(w[1]), w[0].max(w[1]))));
+        }
+    }
+    assert_eq!(g.vertex_disjoint_paths(1, 5).len(), 1);
+    assert_eq!(g.vertex_disjoint_paths(1, 3).len(), 2);
+
+    // 单向边只能顺着方向用
+    let d = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .directed_edge(1, 3, 1)
+        .build();
+    assert_eq!(d.edge_disjoint_paths(1, 3).len(), 2);
+    assert!(d.edge_disjoint_paths(3, 1).is_empty());
+    assert!(g.edge_disjoint_paths(1, 1).is_empty());
+}
+
+#[test]
+fn connectivity_and_minimal_cuts() {
+    // 两个三角形共用节点 3：删去 3 即不连通，边连通度为 2
+    let g = GraphBuilder::new()
+        .edge(1, 2, 1)
+        .edge(2, 3, 1)
+        .edge(1, 3, 1)
+        .edge(3, 4, 1)
+        .edge(4, 5, 1)
+        .edge(3, 5, 1)
+        .build();
+    assert_eq!(g.vertex_connectivity(), (1, vec![3]));
+    let (k, cut) = g.edge_connectivity();
+    assert_eq!((k, cut.len()), (2, 2));
+    let mut rest = g.clone();
+    for &(u, v) in &cut {
+        rest.remove_edge(u, v, Both);
+    }
+    assert_eq!(rest.connected_components().len(), 2);
+
+    // 完全图 K4 的点连通度为 3，没有点割
+    let k4 = Graph::from_edges(&[
+        (1, 2, 1),
+        (1, 3, 1),
+        (1, 4, 1),
+        (2, 3, 1),
+        (2, 4, 1),
+        (3, 4, 1),
+    ]);
+    assert_eq!(k4.vertex_connectivity(), (3, vec![]));
+    assert_eq!(k4.edge_connectivity().0, 3);
+
+    // 有向环强连通，删去任意一条边即不再强连通
+    let ring = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .directed_edge(3, 1, 1)
+        .build();
+    assert_eq!(ring.edge_connectivity().0, 1);
+}
+
+#[test]
+fn chinese_postman_repeats_shortest_paths_between_odd_nodes() {
+    // 正方形加一条对角线：1、3 为奇度节点，最短路 1-2-3 上的边各多走一遍
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 1, 1), (1, 3, 5)]);
+    let result = g.chinese_postman().unwrap();
+    assert_eq!(result.cost, 11);
+    assert_eq!(result.repeated.len(), 2);
+    assert_eq!(result.tour.len(), 7);
+    assert_eq!(result.tour[0].0, 1);
+    assert_eq!(result.tour[result.tour.len() - 1].1, 1);
+    for pair in result.tour.windows(2) {
+        assert_eq!(pair[0].1, pair[1].0);
+    }
+    assert_eq!(result.tour.iter().map(|&(_, _, w)| w).sum::<i64>(), 11);
+    for (u, v, _, _) in g.edges() {
+        assert!(
+            result
+                .tour
+                .iter()
+                .any(|&(a, b, _)| (a, b) == (u, v) || (a, b) == (v, u))
+        );
+    }
+
+    // 欧拉图不需要重复走边；有单向边或不连通时无解
+    let triangle = Graph::from_edges(&[(1, 2, 2), (2, 3, 2), (3, 1, 2)]);
+    assert_eq!(triangle.chinese_postman().unwrap().repeated, vec![]);
+    let one_way = GraphBuilder::new().directed_edge(1, 2, 1).build();
+    assert_eq!(one_way.chinese_postman(), None);
+    let split = Graph::from_edges(&[(1, 2, 1), (3, 4, 1)]);
+    assert_eq!(split.chinese_postman(), None);
+
+    // 配对费用之和超出 i64 时不会溢出
+    let h = i64::MAX / 3;
+    let wide = Graph::from_edges(&[(1, 2, h), (2, 3, 1), (3, 4, h), (2, 5, h), (3, 6, h)]);
+    let result = wide.chinese_postman().unwrap();
+    assert_eq!(result.cost, i64::MAX);
+    assert_eq!(result.repeated.len(), 5);
+}
+
+#[test]
+fn vertex_cover_and_independent_set_approximations() {
+    // 路径 1-2-3-4-5
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 5, 1)]);
+    let (cover, matching) = g.vertex_cover_2approx();
+    assert_eq!(matching, vec![(1, 2), (3, 4)]);
+    assert_eq!(cover, vec![1, 2, 3, 4]);
+    for (u, v, _, _) in g.edges() {
+        assert!(cover.contains(&u) || cover.contains(&v));
+    }
+
+    let set = g.greedy_independent_set();
+    assert_eq!(set, vec![1, 3, 5]);
+    for &u in &set {
+        assert!(set.iter().all(|&v| !g.contains_edge(u, v)));
+    }
+
+    // 星形：贪心先取度数小的叶子
+    let star = Graph::from_edges(&[(1, 2, 1), (1, 3, 1), (1, 4, 1)]);
+    assert_eq!(star.greedy_independent_set(), vec![2, 3, 4]);
+    let mut looped = star.clone();
+    looped.add_edge(2, 2, 1, Single);
+    assert!(!looped.greedy_independent_set().contains(&2));
+}
+
+#[test]
+fn greedy_dominating_set_covers_every_node() {
+    // 两个星形由一条边相连：选两个中心即可
+    let g = Graph::from_edges(&[
+        (1, 2, 1),
+        (1, 3, 1),
+        (1, 4, 1),
+        (5, 6, 1),
+        (5, 7, 1),
+        (5, 8, 1),
+        (4, 8, 1),
+    ]);
+    let rounds = g.greedy_dominating_set();
+    let picked: Vec<i64> = rounds.iter().map(|&(u, _)| u).collect();
+    assert_eq!(picked, vec![1, 5]);
+    assert_eq!(rounds[0].1, vec![1, 2, 3, 4]);
+
+    let mut covered: Vec<i64> = rounds.into_iter().flat_map(|(_, gained)| gained).collect();
+    covered.sort();
+    assert_eq!(covered, g.nodes());
+    assert!(Graph::<i64>::default().greedy_dominating_set().is_empty());
+}
@@ -0,0 +1,60 @@
+use graph_algorithm_tui::harness::{self, Case, CheckAlgorithm};
+
+#[test]
+fn cases_are_reproducible_from_seed() {
+    let a = Case::random(7, 6, 12);
+    let b = Case::random(7, 6, 12);
+    assert_eq!(a.edges, b.edges);
+    assert!(
+        a.edges
+            .iter()
+            .all(|&(u, v, w)| u != v && (1..=6).contains(&u) && w > 0)
+    );
+
+    let input = a.input(CheckAlgorithm::Dijkstra);
+    assert!(input.starts_with(&format!("dijkstra 6 {} 1\n", a.edges.len())));
+}
+
+#[test]
+fn expected_output_marks_unreachable_nodes() {
+    let case = Case {
+        n: 3,
+        edges: vec![(1, 2, 5)],
+        source: 1,
+    };
+    assert_eq!(case.expected(CheckAlgorithm::Dijkstra), "1 0\n2 5\n3 INF\n");
+    assert_eq!(case.expected(CheckAlgorithm::Bfs), "1 2\n");
+    assert_eq!(case.expected(CheckAlgorithm::Prim), "5\n");
+}
+
+#[test]
+fn first_difference_ignores_trailing_whitespace() {
+    assert_eq!(
+        harness::first_difference("1 0\n2 5\n", "1 0  \n2 5\n\n"),
+        None
+    );
+    assert_eq!(
+        harness::first_difference("1 0\n2 5\n", "1 0\n2 6\n"),
+        Some("第 2 行: 期望 \"2 5\"，实际 \"2 6\"".to_string())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn check_reports_first_failing_case() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // 只输出起点距离的错误求解器
+    let path = std::env::temp_dir().join(format!("wrong_solver_{}.sh", std::process::id()));
+    std::fs::write(&path, "#!/bin/sh\ncat > /dev/null\necho '1 0'\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let failure = harness::check(&path, CheckAlgorithm::Dijkstra, 5, 1)
+        .unwrap()
+        .unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(failure.case, 1);
+    assert!(failure.diff.starts_with("第 2 行"));
+    assert!(failure.input.starts_with("dijkstra 2 "));
+}
@@ -0,0 +1,43 @@
+use graph_algorithm_tui::implicit::{
+    Explore, ImplicitGraph, KnightMoves, LazyBfs, Square, WordLadder,
+};
+
+#[test]
+fn word_ladder_finds_shortest_chain() {
+    let words = WordLadder::builtin();
+    let mut neighbors = words.neighbors(&"cold".to_string());
+    neighbors.sort();
+    assert_eq!(neighbors, vec!["bold", "cord", "gold", "mold"]);
+
+    let mut bfs = LazyBfs::new(words, "cold".to_string(), "warm".to_string());
+    let path = bfs.run().unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(path.first().map(String::as_str), Some("cold"));
+    assert_eq!(path.last().map(String::as_str), Some("warm"));
+    assert!(bfs.generated() < 40);
+}
+
+#[test]
+fn knight_bfs_expands_lazily() {
+    let corner = Square { col: 0, row: 0 };
+    assert_eq!(corner.to_string(), "a1");
+    assert_eq!(KnightMoves { size: 5 }.neighbors(&corner).len(), 2);
+
+    let goal = Square { col: 1, row: 2 };
+    let mut bfs = LazyBfs::new(KnightMoves { size: 8 }, corner, goal);
+    let first = bfs.step().unwrap();
+    assert_eq!(first.u, 1);
+    assert_eq!(
+        first.discovered,
+        vec![(2, "b3".to_string()), (3, "c2".to_string())]
+    );
+    // 目标在第一次展开时就被发现，之后不再生成邻居
+    assert!(bfs.step().is_none());
+    assert_eq!(bfs.goal(), Some(2));
+    assert_eq!(bfs.path_to(2), Some(vec![1, 2]));
+    assert_eq!(bfs.generated(), 3);
+
+    let mut bfs = LazyBfs::new(KnightMoves { size: 3 }, corner, Square { col: 1, row: 1 });
+    assert_eq!(bfs.run(), None);
+    assert_eq!(bfs.generated(), 8);
+}
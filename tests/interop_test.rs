@@ -0,0 +1,28 @@
+#![cfg(feature = "petgraph")]
+
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+
+#[test]
+fn petgraph_conversion_round_trips() {
+    let g = GraphBuilder::new()
+        .edge(1, 2, 3)
+        .directed_edge(2, 3, 4)
+        .node(7)
+        .build();
+
+    let pg: petgraph::Graph<i64, i64> = (&g).into();
+    assert_eq!(pg.node_count(), 4);
+    assert_eq!(pg.edge_count(), 3);
+
+    let back = Graph::from(&pg);
+    assert_eq!(back.nodes(), g.nodes());
+    assert_eq!(back.edges(), g.edges());
+    assert!(!back.contains_edge(3, 2));
+
+    let mut un = petgraph::graph::UnGraph::<i64, i64>::new_undirected();
+    let a = un.add_node(10);
+    let b = un.add_node(20);
+    un.add_edge(a, b, 5);
+    let back = Graph::from(&un);
+    assert_eq!(back.edge_weight(20, 10), Some(5));
+}
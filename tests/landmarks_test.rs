@@ -0,0 +1,100 @@
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::harness::Case;
+use graph_algorithm_tui::landmarks::{Landmarks, astar};
+
+// n × n 的网格，边权都为 1，节点编号按行从 1 开始
+fn grid(n: i64) -> Graph {
+    let mut edges = Vec::new();
+    for r in 0..n {
+        for c in 0..n {
+            let u = r * n + c + 1;
+            if c + 1 < n {
+                edges.push((u, u + 1, 1));
+            }
+            if r + 1 < n {
+                edges.push((u, u + n, 1));
+            }
+        }
+    }
+    Graph::from_edges(&edges)
+}
+
+#[test]
+fn farthest_point_selection_spreads_landmarks() {
+    let g = grid(5);
+    let landmarks = Landmarks::select(&g, 3);
+    // 离 1 最远的是对角 25，之后是另外两个角（距离相同取编号小的）
+    assert_eq!(landmarks.nodes, vec![25, 1, 5]);
+    assert_eq!(Landmarks::select(&g, 100).len(), 25);
+    assert!(Landmarks::select(&Graph::<i64>::new(), 4).is_empty());
+}
+
+#[test]
+fn lower_bound_never_overestimates() {
+    for seed in 0..20 {
+        let g = Case::random(seed, 12, 25).graph();
+        let landmarks = Landmarks::select(&g, 3);
+        for s in 1..=12 {
+            let exact = g.dijkstra(s).dist;
+            for t in 1..=12 {
+                let bound = landmarks.lower_bound(s, t);
+                match exact.get(&t) {
+                    Some(&d) => assert!(bound <= d, "{} -> {}: {} > {}", s, t, bound, d),
+                    None => assert!(bound >= 0),
+                }
+            }
+            assert_eq!(landmarks.lower_bound(s, s), 0);
+        }
+    }
+}
+
+#[test]
+fn landmark_astar_matches_dijkstra_with_fewer_expansions() {
+    let g = grid(10);
+    let landmarks = Landmarks::select(&g, 4);
+    let alt = landmarks.search(&g, 1, 100);
+    let plain = astar(&g, 1, 100, |_| 0);
+    assert_eq!(alt.route.as_ref().map(|(d, _)| *d), Some(18));
+    assert_eq!(plain.route.as_ref().map(|(d, _)| *d), Some(18));
+    assert_eq!(alt.route.unwrap().1.len(), 19);
+    // 角上有地标时启发函数是精确的，只展开路径上的节点
+    assert_eq!(alt.expanded.len(), 19);
+    assert_eq!(plain.expanded.len(), 100);
+
+    // 与终点距离相同的节点谁先出堆不确定，只比较总数
+    let (mut alt_total, mut plain_total) = (0, 0);
+    for seed in 0..20 {
+        let g = Case::random(seed, 15, 30).graph();
+        let landmarks = Landmarks::select(&g, 2);
+        for t in 1..=15 {
+            let alt = landmarks.search(&g, 1, t);
+            let plain = astar(&g, 1, t, |_| 0);
+            assert_eq!(
+                alt.route.map(|(d, _)| d),
+                plain.route.map(|(d, _)| d),
+                "种子 {}，终点 {}",
+                seed,
+                t
+            );
+            alt_total += alt.expanded.len();
+            plain_total += plain.expanded.len();
+        }
+    }
+    assert!(alt_total < plain_total);
+}
+
+#[test]
+fn directed_edges_and_unreachable_targets() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 2)
+        .directed_edge(2, 3, 2)
+        .directed_edge(3, 1, 1)
+        .directed_edge(4, 1, 1)
+        .build();
+    let landmarks = Landmarks::select(&g, 2);
+    // 反向走不通，下界要用"到地标"的距离
+    assert_eq!(landmarks.search(&g, 3, 2).route, Some((3, vec![3, 1, 2])));
+    let unreachable = landmarks.search(&g, 1, 4);
+    assert_eq!(unreachable.route, None);
+    assert_eq!(unreachable.expanded, vec![1, 2, 3]);
+}
@@ -0,0 +1,141 @@
+use graph_algorithm_tui::graph::{EdgeType::Both, Graph};
+use graph_algorithm_tui::layout;
+
+#[test]
+fn radial_tree_places_nodes_on_depth_rings() {
+    let mut g = Graph::new();
+    g.add_edge(1, 2, 1, Both);
+    g.add_edge(1, 3, 1, Both);
+    g.add_edge(2, 4, 1, Both);
+
+    let (_, edges) = g.bfs(1);
+    let pos = layout::radial_tree(1, &edges, 2.0);
+
+    assert_eq!(layout::tree_depth(1, &edges), 2);
+    for (node, ring) in [(1, 0.0), (2, 2.0), (3, 2.0), (4, 4.0)] {
+        let (x, y) = pos[&node];
+        assert!(((x * x + y * y).sqrt() - ring).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn tween_interpolates_between_layouts() {
+    let from = [(1, (0.0, 0.0))].into_iter().collect();
+    let to = [(1, (4.0, -2.0)), (2, (1.0, 1.0))].into_iter().collect();
+    let mut tween = layout::Tween::new(from, to, 1.0);
+
+    tween.step(0.5);
+    assert_eq!(tween.positions()[&1], (2.0, -1.0));
+    assert!(!tween.is_finished());
+
+    tween.step(0.75);
+    assert!(tween.is_finished());
+    assert_eq!(tween.positions()[&1], (4.0, -2.0));
+    assert_eq!(tween.positions()[&2], (1.0, 1.0));
+}
+
+#[test]
+fn spectral_layout_orders_a_path_along_one_axis() {
+    let nodes = [1, 2, 3, 4, 5];
+    let edges = [(1, 2), (2, 3), (3, 4), (4, 5)];
+    let pos = layout::spectral(&nodes, &edges, 10.0, 10.0);
+
+    // 路径图的 Fiedler 向量单调，x 坐标应按路径顺序单调排列
+    let xs: Vec<f64> = nodes.iter().map(|u| pos[u].0).collect();
+    let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+    let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+    assert!(increasing || decreasing, "{:?}", xs);
+    assert!(xs.iter().all(|x| x.abs() <= 5.0 + 1e-9));
+}
+
+#[test]
+fn quad_bezier_bends_to_the_left_and_keeps_endpoints() {
+    let points = layout::quad_bezier((0.0, 0.0), (10.0, 0.0), 0.2, 4);
+    assert_eq!(points.len(), 5);
+    assert_eq!(points[0], (0.0, 0.0));
+    assert_eq!(points[4], (10.0, 0.0));
+    // 中点偏移为控制点偏移的一半: 0.2 * 10 / 2
+    assert!((points[2].0 - 5.0).abs() < 1e-9);
+    assert!((points[2].1 - 1.0).abs() < 1e-9);
+
+    let straight = layout::quad_bezier((0.0, 0.0), (10.0, 0.0), 0.0, 4);
+    assert!(straight.iter().all(|p| p.1 == 0.0));
+}
+
+#[test]
+fn cooling_settles_after_calm_frames_and_wakes() {
+    let mut cooling = layout::Cooling::new(0.01, 3);
+    assert!(!cooling.observe(0.5));
+    assert!(!cooling.observe(0.001));
+    assert!(!cooling.observe(0.001));
+    // 中途有一次较大位移，重新计数
+    assert!(!cooling.observe(0.2));
+    for _ in 0..2 {
+        assert!(!cooling.observe(0.0));
+    }
+    assert!(cooling.observe(0.0));
+    assert!(cooling.is_settled());
+
+    cooling.wake();
+    assert!(!cooling.is_settled());
+}
+
+#[test]
+fn separate_pushes_overlapping_nodes_apart() {
+    let mut positions = vec![(0.0, 0.0), (0.5, 0.0), (0.5, 0.0), (10.0, 0.0)];
+    let fixed = [true, false, false, false];
+    for _ in 0..50 {
+        layout::separate(&mut positions, &fixed, 2.0);
+    }
+
+    // 固定节点不动，离得远的节点不受影响
+    assert_eq!(positions[0], (0.0, 0.0));
+    assert_eq!(positions[3], (10.0, 0.0));
+    for i in 0..3 {
+        for j in i + 1..3 {
+            let (a, b) = (positions[i], positions[j]);
+            let d = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+            assert!(d > 1.99, "{:?} {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn spring_lengths_pull_edges_to_their_rest_length() {
+    let mut positions = vec![(0.0, 0.0), (1.0, 0.0), (0.0, 10.0)];
+    let fixed = [true, false, false];
+    let springs = [(0, 1, 4.0), (0, 2, 2.0)];
+    for _ in 0..100 {
+        layout::spring_lengths(&mut positions, &fixed, &springs, 0.2);
+    }
+
+    assert_eq!(positions[0], (0.0, 0.0));
+    assert!((positions[1].0 - 4.0).abs() < 1e-6 && positions[1].1.abs() < 1e-9);
+    assert!(positions[2].0.abs() < 1e-9 && (positions[2].1 - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn nearest_polyline_picks_the_closest_edge_within_range() {
+    assert_eq!(
+        layout::segment_distance((1.0, 1.0), (0.0, 0.0), (2.0, 0.0)),
+        1.0
+    );
+    // 垂足落在线段外时取到端点的距离
+    assert_eq!(
+        layout::segment_distance((5.0, 4.0), (0.0, 0.0), (2.0, 0.0)),
+        5.0
+    );
+    assert_eq!(
+        layout::segment_distance((3.0, 4.0), (0.0, 0.0), (0.0, 0.0)),
+        5.0
+    );
+
+    let lines = vec![
+        vec![(0.0, 0.0), (4.0, 0.0)],
+        vec![(0.0, 1.0), (2.0, 1.5), (4.0, 1.0)],
+    ];
+    assert_eq!(layout::nearest_polyline((2.0, 0.4), &lines, 1.0), Some(0));
+    assert_eq!(layout::nearest_polyline((2.0, 1.2), &lines, 1.0), Some(1));
+    assert_eq!(layout::nearest_polyline((2.0, 5.0), &lines, 1.0), None);
+    assert_eq!(layout::nearest_polyline((0.0, 0.0), &[], 1.0), None);
+}
@@ -0,0 +1,46 @@
+use graph_algorithm_tui::graph::{EdgeType::Both, Graph};
+use graph_algorithm_tui::metrics::{GraphMetrics, HISTORY_LIMIT, MetricsHistory, sparkline_levels};
+
+#[test]
+fn metrics_track_edges_components_and_forest_weight() {
+    let mut g = Graph::from_edges(&[(1, 2, 4), (2, 3, 1), (1, 3, 2)]);
+    g.add_node(4);
+    assert_eq!(
+        GraphMetrics::of(&g),
+        GraphMetrics {
+            edges: 3,
+            components: 2,
+            mst_weight: 3.0,
+        }
+    );
+
+    let mut history = MetricsHistory::default();
+    assert!(history.record(&g));
+    // 图没有变化时不重复记录
+    assert!(!history.record(&g));
+    g.add_edge(3, 4, 5, Both);
+    assert!(history.record(&g));
+    assert_eq!(history.edges(), vec![3.0, 4.0]);
+    assert_eq!(history.components(), vec![2.0, 1.0]);
+    assert_eq!(history.mst_weights(), vec![3.0, 8.0]);
+}
+
+#[test]
+fn history_keeps_only_the_latest_samples() {
+    let mut history = MetricsHistory::default();
+    let mut g: Graph = Graph::default();
+    for u in 0..HISTORY_LIMIT as i64 + 10 {
+        g.add_edge(u, u + 1, 1, Both);
+        history.record(&g);
+    }
+    assert_eq!(history.samples().len(), HISTORY_LIMIT);
+    assert_eq!(history.last().unwrap().edges, HISTORY_LIMIT + 10);
+}
+
+#[test]
+fn sparkline_levels_span_the_range_including_negative_weights() {
+    assert_eq!(sparkline_levels(&[-2.0, 0.0, 2.0], 5), vec![1, 3, 5]);
+    // 全部相同时画成最低的一条线
+    assert_eq!(sparkline_levels(&[3.5, 3.5], 8), vec![1, 1]);
+    assert!(sparkline_levels(&[], 8).is_empty());
+}
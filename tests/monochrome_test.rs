@@ -0,0 +1,46 @@
+use graph_algorithm_tui::monochrome;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+
+#[test]
+fn requested_honors_no_color_and_dumb_terminals() {
+    assert!(monochrome::requested(Some("1"), Some("xterm-256color")));
+    assert!(monochrome::requested(None, Some("dumb")));
+    // NO_COLOR 为空字符串时视为未设置
+    assert!(!monochrome::requested(Some(""), Some("xterm")));
+    assert!(!monochrome::requested(None, None));
+}
+
+#[test]
+fn apply_replaces_colors_with_modifiers_and_ascii() {
+    let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+    buf.set_string(0, 0, "⣿", Style::default().fg(Color::Yellow));
+    buf.set_string(1, 0, "⣿", Style::default().fg(Color::LightBlue));
+    buf.set_string(2, 0, "⠁", Style::default().fg(Color::DarkGray));
+    buf.set_string(3, 0, "│", Style::default());
+    buf.set_string(
+        4,
+        0,
+        "图",
+        Style::default().fg(Color::White).bg(Color::Blue),
+    );
+
+    monochrome::apply(&mut buf);
+
+    let cell = |x| &buf[(x, 0)];
+    assert_eq!(cell(0).symbol(), "#");
+    assert!(cell(0).modifier.contains(Modifier::BOLD));
+    assert_eq!(cell(1).symbol(), "*");
+    assert_eq!(cell(1).modifier, Modifier::empty());
+    assert_eq!(cell(2).symbol(), ".");
+    assert!(cell(2).modifier.contains(Modifier::DIM));
+    assert_eq!(cell(3).symbol(), "|");
+    assert_eq!(cell(4).symbol(), "图");
+    assert!(cell(4).modifier.contains(Modifier::REVERSED));
+    assert!(
+        buf.content
+            .iter()
+            .all(|c| c.fg == Color::Reset && c.bg == Color::Reset)
+    );
+}
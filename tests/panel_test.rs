@@ -0,0 +1,18 @@
+use graph_algorithm_tui::panel::Panel;
+
+#[test]
+fn panels_cycle_in_both_directions() {
+    let mut panel = Panel::default();
+    assert_eq!(panel, Panel::Menu);
+    for _ in 0..Panel::ALL.len() {
+        panel = panel.next();
+    }
+    assert_eq!(panel, Panel::Menu);
+
+    assert_eq!(Panel::Menu.next(), Panel::Inspector);
+    assert_eq!(Panel::Menu.prev(), Panel::Log);
+    assert_eq!(Panel::Log.next().prev(), Panel::Log);
+    assert_eq!(Panel::Frontier.index(), 2);
+    assert_eq!(Panel::Distances.next(), Panel::Trend);
+    assert_eq!(Panel::Trend.title(), "趋势");
+}
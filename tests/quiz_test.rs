@@ -0,0 +1,39 @@
+use graph_algorithm_tui::graph::Graph;
+use graph_algorithm_tui::quiz::{Quiz, QuizAnswer};
+
+#[test]
+fn dijkstra_quiz_scores_answers_against_trace() {
+    let g = Graph::from_edges(&[(1, 2, 1), (1, 3, 4), (2, 3, 1), (3, 4, 1)]);
+    let mut quiz = Quiz::dijkstra(&g, 1);
+
+    assert_eq!(
+        quiz.answer(QuizAnswer::Node(2)),
+        Some((true, QuizAnswer::Node(2)))
+    );
+    assert_eq!(
+        quiz.answer(QuizAnswer::Node(4)),
+        Some((false, QuizAnswer::Node(3)))
+    );
+    assert_eq!(
+        quiz.answer(QuizAnswer::Node(4)),
+        Some((true, QuizAnswer::Node(4)))
+    );
+    assert!(quiz.is_finished());
+    assert_eq!(quiz.score(), (2, 3, 3));
+    assert_eq!(quiz.answer(QuizAnswer::Node(4)), None);
+}
+
+#[test]
+fn kruskal_quiz_accepts_ties_in_any_order() {
+    let g = Graph::from_edges(&[(1, 2, 1), (3, 4, 1), (2, 3, 5)]);
+    let mut quiz = Quiz::kruskal(&g);
+
+    // 两条权为 1 的边先选哪条都算对，端点顺序无关
+    assert_eq!(
+        quiz.answer(QuizAnswer::edge(4, 3)),
+        Some((true, QuizAnswer::Edge(3, 4)))
+    );
+    assert!(quiz.answer(QuizAnswer::edge(1, 2)).unwrap().0);
+    assert_eq!(quiz.answered().count(), 2);
+    assert!(quiz.question().is_some());
+}
@@ -0,0 +1,126 @@
+use graph_algorithm_tui::animation::Pacing;
+use graph_algorithm_tui::menu::{MenuItem, MenuState};
+use graph_algorithm_tui::registry::{Algorithm, AlgorithmRegistry};
+use graph_algorithm_tui::summary::GraphFacts;
+
+#[derive(Default)]
+struct Ctx {
+    ran: Vec<String>,
+}
+
+fn registry() -> AlgorithmRegistry<Ctx> {
+    let mut r = AlgorithmRegistry::new();
+    r.register(Algorithm::new("遍历", "Dfs", |c: &mut Ctx, _| {
+        c.ran.push("dfs".to_string())
+    }))
+    .register(
+        Algorithm::new("MST", "Prim", |c: &mut Ctx, _| {
+            c.ran.push("prim".to_string())
+        })
+        .undirected()
+        .pacing(Pacing {
+            edge: 1.5,
+            ..Pacing::default()
+        }),
+    )
+    .register(Algorithm::new("遍历", "Bfs", |c: &mut Ctx, _| {
+        c.ran.push("bfs".to_string())
+    }))
+    .register(
+        Algorithm::new("图", "筛选", |c: &mut Ctx, args: &[String]| {
+            c.ran.push(args.join("|"))
+        })
+        .param("最小")
+        .param("最大"),
+    )
+    .register(Algorithm::new("", "退出", |c: &mut Ctx, _| {
+        c.ran.push("exit".to_string())
+    }));
+    r
+}
+
+#[test]
+fn menu_groups_by_category_in_registration_order() {
+    let menu = registry().menu();
+    let names: Vec<&str> = menu.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["遍历", "MST", "图", "退出"]);
+    let traversal: Vec<&str> = menu[0].children.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(traversal, ["Dfs", "Bfs"]);
+    assert!(menu[3].children.is_empty());
+}
+
+#[test]
+fn lookup_and_run_by_name() {
+    let r = registry();
+    let mut ctx = Ctx::default();
+
+    let bfs = r.get("Bfs").unwrap();
+    (bfs.run)(&mut ctx, &[]);
+    let filter = r.get("筛选").unwrap();
+    assert_eq!(filter.params.len(), 2);
+    assert_eq!(filter.params[1].label, "最大");
+    (filter.run)(&mut ctx, &["1".to_string(), "5".to_string()]);
+    assert_eq!(ctx.ran, ["bfs", "1|5"]);
+
+    assert!(r.get("Prim").unwrap().undirected);
+    assert_eq!(r.get("Prim").unwrap().pacing.edge, 1.5);
+    assert_eq!(r.get("Dfs").unwrap().pacing, Pacing::default());
+    assert!(!bfs.undirected);
+    assert!(r.get("Kruskal").is_none());
+}
+
+#[test]
+fn registering_same_name_replaces_runner() {
+    let mut r = registry();
+    r.register(Algorithm::new("其他", "Dfs", |c: &mut Ctx, _| {
+        c.ran.push("new dfs".to_string())
+    }));
+    let mut ctx = Ctx::default();
+    (r.get("Dfs").unwrap().run)(&mut ctx, &[]);
+    assert_eq!(ctx.ran, ["new dfs"]);
+    assert_eq!(r.iter().count(), 5);
+}
+
+#[test]
+fn menu_for_hides_inapplicable_algorithms() {
+    let mut r = registry();
+    r.register(
+        Algorithm::new("遍历", "拓扑排序", |c: &mut Ctx, _| {
+            c.ran.push("topo".to_string())
+        })
+        .when(GraphFacts::is_dag),
+    )
+    .register(
+        Algorithm::new("DAG", "DAG 最短路径", |c: &mut Ctx, _| {
+            c.ran.push("dag".to_string())
+        })
+        .when(GraphFacts::is_dag),
+    );
+    let dag = GraphFacts {
+        directed: true,
+        acyclic: true,
+        ..GraphFacts::default()
+    };
+
+    let names = |menu: &[MenuItem]| menu.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+    let all = r.menu_for(&dag);
+    assert_eq!(names(&all), ["遍历", "MST", "图", "退出", "DAG"]);
+    assert_eq!(names(&all[0].children), ["Dfs", "Bfs", "拓扑排序"]);
+
+    // 没有适用项的分组整组隐藏
+    let undirected = r.menu_for(&GraphFacts::default());
+    assert_eq!(names(&undirected), ["遍历", "MST", "图", "退出"]);
+    assert_eq!(names(&undirected[0].children), ["Dfs", "Bfs"]);
+
+    // 重新生成菜单时停留在原来的子菜单和选中项
+    let mut state = MenuState::new(all);
+    state.enter();
+    state.down();
+    state.down();
+    state.set_items(r.menu_for(&GraphFacts::default()));
+    assert_eq!(state.nav_stack, [0]);
+    assert_eq!(state.list_state.selected(), Some(0));
+    state.down();
+    state.set_items(r.menu_for(&dag));
+    assert_eq!(state.list_state.selected(), Some(1));
+}
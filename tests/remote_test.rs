@@ -0,0 +1,102 @@
+#![cfg(feature = "remote")]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use graph_algorithm_tui::command::Command;
+use graph_algorithm_tui::remote::{Request, Response, Server, State};
+
+#[test]
+fn requests_parse_with_defaults_and_map_to_commands() {
+    let request = Request::parse(r#"{"cmd": "add_edge", "u": 1, "v": 2}"#).unwrap();
+    assert_eq!(
+        request.to_command(),
+        Some(Ok(Command::Edge {
+            u: 1,
+            v: 2,
+            w: 1.0,
+            directed: false
+        }))
+    );
+    let request = Request::parse(r#"{"cmd": "run_algorithm", "name": "bfs"}"#).unwrap();
+    assert_eq!(
+        request.to_command(),
+        Some(Ok(Command::Run {
+            algorithm: "bfs".to_string(),
+            source: None
+        }))
+    );
+    assert_eq!(
+        Request::parse(r#"{"cmd": "get_state"}"#),
+        Ok(Request::GetState)
+    );
+    assert_eq!(Request::GetState.to_command(), None);
+
+    let request = Request::parse(r#"{"cmd": "command", "line": "frobnicate"}"#).unwrap();
+    assert!(matches!(request.to_command(), Some(Err(_))));
+
+    // 读写文件的命令不能远程执行
+    for line in ["save /tmp/x.txt", "load /etc/passwd"] {
+        let request = Request::Command {
+            line: line.to_string(),
+        };
+        assert!(matches!(request.to_command(), Some(Err(_))), "{}", line);
+    }
+    let request = Request::parse(r#"{"cmd": "command", "line": "delnode 4"}"#).unwrap();
+    assert_eq!(request.to_command(), Some(Ok(Command::DelNode(4))));
+    assert!(Request::parse(r#"{"cmd": "explode"}"#).is_err());
+    assert!(Request::parse("not json").is_err());
+}
+
+#[test]
+fn server_forwards_requests_and_writes_replies() {
+    let server = Server::start(0).unwrap();
+    let mut client = TcpStream::connect(server.addr()).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+
+    // 界面线程的角色：逐个取出请求并应答
+    let ui = thread::spawn(move || {
+        let mut handled = Vec::new();
+        while handled.len() < 2 {
+            let Some(pending) = server.poll() else {
+                thread::sleep(Duration::from_millis(5));
+                continue;
+            };
+            let response = match &pending.request {
+                Ok(Request::GetState) => Response::state(State {
+                    nodes: vec![1, 2],
+                    edges: vec![(1, 2, 3.0, true)],
+                    ..State::default()
+                }),
+                Ok(_) => Response::ok(vec!["已添加边".to_string()]),
+                Err(e) => Response::error(e.clone()),
+            };
+            handled.push(pending.request.clone());
+            pending.reply(response);
+        }
+        handled
+    });
+
+    writeln!(client, r#"{{"cmd": "add_edge", "u": 1, "v": 2, "w": 3}}"#).unwrap();
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let response: Response = serde_json::from_str(&line).unwrap();
+    assert!(response.ok);
+    assert_eq!(response.log, vec!["已添加边".to_string()]);
+
+    // 空行被忽略
+    writeln!(client).unwrap();
+    writeln!(client, r#"{{"cmd": "get_state"}}"#).unwrap();
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    let response: Response = serde_json::from_str(&line).unwrap();
+    let state = response.state.unwrap();
+    assert_eq!(state.nodes, vec![1, 2]);
+    assert_eq!(state.edges, vec![(1, 2, 3.0, true)]);
+
+    let handled = ui.join().unwrap();
+    assert_eq!(handled.len(), 2);
+    assert_eq!(handled[1], Ok(Request::GetState));
+}
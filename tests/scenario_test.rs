@@ -0,0 +1,60 @@
+use graph_algorithm_tui::avoid::Forbidden;
+use graph_algorithm_tui::graph::{EdgeType::Both, Graph};
+use graph_algorithm_tui::scenario::{
+    Comparison, Side, changed_weights, follow_topology, shortest_path,
+};
+
+// 平时走 1-2-4，高峰期 2-4 堵车改走 1-3-4
+fn rush_hour() -> (Graph, Graph) {
+    let normal = Graph::from_edges(&[(1, 2, 1), (2, 4, 1), (1, 3, 2), (3, 4, 2)]);
+    let mut rush = normal.clone();
+    rush.set_edge_weight(2, 4, 9);
+    (normal, rush)
+}
+
+#[test]
+fn comparison_shows_both_routes_and_changed_edges() {
+    let (normal, rush) = rush_hour();
+    assert_eq!(changed_weights(&normal, &rush), vec![(2, 4, 1, 9)]);
+
+    let comparison = Comparison::new(&normal, &rush, 1, 4);
+    assert_eq!(comparison.a, Some((2, vec![1, 2, 4])));
+    assert_eq!(comparison.b, Some((4, vec![1, 3, 4])));
+    assert!(!comparison.same_route());
+
+    let same = Comparison::new(&normal, &normal, 1, 4);
+    assert!(same.same_route());
+    assert!(same.changed.is_empty());
+
+    // 3 禁行时高峰期也只能走 1-2-4
+    let mut forbidden = Forbidden::default();
+    forbidden.toggle_node(3);
+    let detour = Comparison::avoiding(&normal, &rush, 1, 4, &forbidden);
+    assert_eq!(detour.b, Some((10, vec![1, 2, 4])));
+    assert!(detour.same_route());
+}
+
+#[test]
+fn unreachable_targets_and_edges_missing_from_one_side() {
+    let (normal, mut rush) = rush_hour();
+    rush.add_edge(4, 5, 1, Both);
+    // 只在一方存在的边不算边权改变
+    assert_eq!(changed_weights(&rush, &normal), vec![(2, 4, 9, 1)]);
+    assert_eq!(shortest_path(&normal, 1, 5), None);
+    assert_eq!(shortest_path(&rush, 1, 5), Some((5, vec![1, 3, 4, 5])));
+
+    assert_eq!(Side::default().other(), Side::B);
+    assert_eq!(Side::B.other().name(), "A");
+}
+
+#[test]
+fn other_scenario_follows_topology_but_keeps_its_weights() {
+    let (mut normal, rush) = rush_hour();
+    normal.add_edge(4, 5, 3, Both);
+    normal.remove_edge(1, 3, Both);
+    let synced = follow_topology(&normal, &rush);
+    assert_eq!(synced.edge(2, 4).map(|e| e.weight), Some(9));
+    assert_eq!(synced.edge(4, 5).map(|e| e.weight), Some(3));
+    assert!(synced.edge(1, 3).is_none());
+    assert_eq!(changed_weights(&normal, &synced), vec![(2, 4, 1, 9)]);
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "serde")]
+
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::trace::{AlgorithmTrace, StateSnapshot, TraceAlgorithm};
+
+#[test]
+fn graph_round_trips_through_json() {
+    let mut g = GraphBuilder::new()
+        .edge(1, 2, 3)
+        .directed_edge(2, 3, 4)
+        .node(7)
+        .build();
+    g.set_node_attr(1, "name", "起点");
+    g.set_group(2, "A");
+    g.set_edge_label(2, 3, "桥");
+
+    let json = serde_json::to_string(&g).unwrap();
+    let back: Graph = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back.nodes(), g.nodes());
+    assert_eq!(back.edges(), g.edges());
+    assert_eq!(back.get_node_attr(1, "name"), g.get_node_attr(1, "name"));
+    assert_eq!(back.group_of(2), Some("A"));
+    assert_eq!(back.edge(2, 3), g.edge(2, 3));
+
+    let result = serde_json::to_string(&g.dijkstra(1)).unwrap();
+    assert!(result.contains("\"3\":7"));
+}
+
+#[test]
+fn trace_snapshots_serialize_to_json() {
+    let g = Graph::from_edges(&[(1, 2, 3), (2, 3, 4)]);
+    let snapshots = AlgorithmTrace::new(&g, TraceAlgorithm::Dijkstra, 1).snapshots();
+
+    let json = serde_json::to_string(&snapshots).unwrap();
+    assert!(json.contains("\"Relax\""));
+    let back: Vec<StateSnapshot> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, snapshots);
+}
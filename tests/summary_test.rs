@@ -0,0 +1,68 @@
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::summary::{GraphFacts, GraphSummary};
+
+#[test]
+fn summary_characterizes_graph() {
+    let mut g = Graph::from_edges(&[(1, 2, 1), (2, 3, 2), (3, 4, 1), (4, 1, 5)]);
+    g.add_node(9);
+
+    let s = GraphSummary::new(&g, 1);
+    assert_eq!(s.nodes, 5);
+    assert_eq!(s.edges, 4);
+    assert!(!s.directed);
+    assert_eq!(s.components, 2);
+    assert!(s.bipartite);
+    assert_eq!(s.mst_weight, Some(4));
+    assert_eq!(s.diameter, Some(4));
+    assert_eq!(
+        s.distances,
+        vec![
+            (1, Some(0)),
+            (2, Some(1)),
+            (3, Some(3)),
+            (4, Some(4)),
+            (9, None)
+        ]
+    );
+
+    // 有孤立节点，不连通
+    assert_eq!(s.vertex_connectivity, Some((0, vec![])));
+    assert_eq!(s.edge_connectivity, Some((0, vec![])));
+
+    let rows = s.rows(2);
+    assert!(rows.contains(&("最小生成森林权重".to_string(), "4".to_string())));
+    assert!(rows.contains(&("点连通度".to_string(), "0".to_string())));
+    assert!(rows.contains(&("dist(1 -> 9)".to_string(), "∞".to_string())));
+}
+
+#[test]
+fn summary_skips_mst_on_directed_graph() {
+    let g = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 1)
+        .directed_edge(3, 1, 1)
+        .build();
+
+    let s = GraphSummary::new(&g, 1);
+    assert!(s.directed);
+    assert!(!s.bipartite);
+    assert_eq!(s.components, 1);
+    assert_eq!(s.mst_weight, None);
+    assert_eq!(s.diameter, Some(2));
+}
+
+#[test]
+fn facts_describe_structure() {
+    let cycle = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 1, 1)]);
+    let facts = GraphFacts::new(&cycle);
+    assert!(!facts.directed && !facts.acyclic && !facts.is_dag());
+    assert!(facts.connected && facts.bipartite && !facts.weighted);
+
+    let dag = GraphBuilder::new()
+        .directed_edge(1, 2, 1)
+        .directed_edge(2, 3, 5)
+        .node(9)
+        .build();
+    let facts = GraphFacts::new(&dag);
+    assert!(facts.is_dag() && facts.weighted && !facts.connected);
+}
@@ -0,0 +1,48 @@
+use graph_algorithm_tui::graph::AddEdgePolicy;
+use graph_algorithm_tui::temporal::{EdgeEvent, Timeline};
+
+#[test]
+fn snapshot_applies_events_up_to_time() {
+    let timeline: Timeline = Timeline::parse(
+        "# 注释\n\
+         3 - 1 2\n\
+         1 + 1 2 5\n\
+         2 + 2 3 7\n\
+         4 + 1 2 9\n",
+    )
+    .unwrap();
+
+    assert_eq!(timeline.times(), vec![1, 2, 3, 4]);
+    assert_eq!(timeline.snapshot_at(0).nodes(), Vec::<i64>::new());
+    assert_eq!(timeline.snapshot_at(2).edge_weight(2, 1), Some(5));
+
+    let g = timeline.snapshot_at(3);
+    assert!(!g.contains_edge(1, 2));
+    assert!(g.contains_node(1));
+    assert_eq!(g.edge_weight(3, 2), Some(7));
+
+    // 再次添加时替换而不是叠加
+    let g = timeline.snapshot_at(4);
+    assert_eq!(g.edge_weight(1, 2), Some(9));
+    assert_eq!(g.degree(1), 1);
+
+    let g = timeline.snapshot_with(2, AddEdgePolicy::Sum);
+    assert_eq!(g.edge_weight(1, 2), Some(5));
+    let twice: Timeline = Timeline::parse("1 + 1 2 5\n2 + 1 2 9\n").unwrap();
+    assert_eq!(
+        twice.snapshot_with(2, AddEdgePolicy::Sum).edge_weight(2, 1),
+        Some(14)
+    );
+    assert_eq!(twice.snapshot_with(2, AddEdgePolicy::Parallel).degree(1), 2);
+}
+
+#[test]
+fn parse_reports_bad_lines() {
+    let err = Timeline::<i64>::parse("1 + 1 2 5\n2 * 1 2\n").unwrap_err();
+    assert!(err.contains("第 2 行"));
+
+    let mut timeline = Timeline::default();
+    timeline.push(5, EdgeEvent::Add { u: 1, v: 2, w: 1.5 });
+    timeline.push(1, EdgeEvent::Remove { u: 1, v: 2 });
+    assert_eq!(timeline.times(), vec![1, 5]);
+}
@@ -0,0 +1,31 @@
+use graph_algorithm_tui::text;
+
+#[test]
+fn width_counts_full_width_characters_twice() {
+    assert_eq!(text::width("abc"), 3);
+    assert_eq!(text::width("节点"), 4);
+    assert_eq!(text::width("1 北京"), 6);
+}
+
+#[test]
+fn truncate_respects_display_width() {
+    assert_eq!(text::truncate("北京", 4), "北京");
+    assert_eq!(text::truncate("北京大学", 5), "北京…");
+    // 放不下半个全角字符时不拆开
+    assert_eq!(text::truncate("北京大学", 4), "北…");
+    assert_eq!(text::truncate("station", 4), "sta…");
+    assert!(text::width(&text::truncate("a北京大学", 6)) <= 6);
+    assert_eq!(text::truncate("abc", 0), "");
+}
+
+#[test]
+fn padding_aligns_mixed_columns() {
+    assert_eq!(text::pad_right("节点", 6), "节点  ");
+    assert_eq!(text::pad_left("节点", 6), "  节点");
+    assert_eq!(text::pad_left("12", 4), "  12");
+    assert_eq!(text::pad_right("太长的文字", 2), "太长的文字");
+    assert_eq!(
+        text::width(&text::pad_right("实际%", 8)),
+        text::width(&text::pad_right("12.5", 8))
+    );
+}
@@ -0,0 +1,115 @@
+use graph_algorithm_tui::graph::{EdgeType::Single, Graph};
+use graph_algorithm_tui::trace::{
+    AlgorithmTrace, LogEvents, StepEvent, StepKind, TraceAlgorithm, filter_log,
+};
+
+fn split(steps: &[StepEvent]) -> (Vec<i64>, Vec<(i64, i64)>) {
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for step in steps {
+        match (step.kind(), step) {
+            (StepKind::Node, StepEvent::Visit { u } | StepEvent::Settle { u, .. }) => {
+                nodes.push(*u)
+            }
+            (
+                StepKind::Edge,
+                StepEvent::TreeEdge { u, v }
+                | StepEvent::Relax { u, v, .. }
+                | StepEvent::MstEdge { u, v, .. },
+            ) => edges.push((*u, *v)),
+            _ => {}
+        }
+    }
+    (nodes, edges)
+}
+
+// 步骤中的节点/边序列必须与算法本身的输出一致，动画才能与日志对应
+#[test]
+fn steps_line_up_with_algorithm_output() {
+    let g = Graph::from_edges(&[(1, 2, 4), (1, 3, 1), (3, 2, 2), (2, 4, 5), (3, 4, 8)]);
+
+    assert_eq!(split(&g.dfs_steps(1)), g.dfs(1));
+    assert_eq!(split(&g.bfs_steps(1)), g.bfs(1));
+    let result = g.dijkstra(1);
+    assert_eq!(
+        split(&g.dijkstra_steps(1)),
+        (result.visited_nodes, result.visited_edges)
+    );
+    let mst = g.prim(1);
+    assert_eq!(
+        split(&g.prim_steps(1)),
+        (mst.nodes.clone(), mst.tree_edges())
+    );
+}
+
+#[test]
+fn relaxations_explain_their_reason() {
+    let mut g = Graph::new();
+    g.add_edge(1, 3, 3, Single);
+    g.add_edge(1, 2, 1, Single);
+    g.add_edge(2, 3, 5, Single);
+
+    let lines: Vec<String> = g.dijkstra_steps(1).iter().map(|s| s.explain(0)).collect();
+    assert!(lines.contains(&"松弛 1→3: 0+3 < ∞，更新 dist[3]=3".to_string()));
+    assert!(lines.contains(&"松弛 2→3: 1+5 ≥ 3，不更新".to_string()));
+    assert_eq!(
+        StepEvent::<i64>::Relax {
+            u: 1,
+            v: 3,
+            base: 0,
+            w: 3,
+            old: i64::MAX
+        }
+        .terse(),
+        Some("访问边: 1 -> 3".to_string())
+    );
+}
+
+#[test]
+fn snapshots_track_queue_and_distances() {
+    let g = Graph::from_edges(&[(1, 2, 4), (1, 3, 1), (3, 2, 2), (2, 4, 5)]);
+
+    let bfs = AlgorithmTrace::new(&g, TraceAlgorithm::Bfs, 1).snapshots();
+    assert_eq!(bfs.len(), g.bfs_steps(1).len());
+    // 取出 1 后发现 2、3，此时队列为 [2, 3]
+    let after_first_layer = bfs
+        .iter()
+        .find(|s| s.event == StepEvent::Visit { u: 3 })
+        .unwrap();
+    assert_eq!(after_first_layer.frontier, vec![2, 3]);
+    assert!(bfs.last().unwrap().frontier.is_empty());
+
+    let dijkstra = AlgorithmTrace::new(&g, TraceAlgorithm::Dijkstra, 1).snapshots();
+    let settled_3 = dijkstra
+        .iter()
+        .find(|s| matches!(s.event, StepEvent::Settle { u: 3, .. }))
+        .unwrap();
+    assert_eq!(settled_3.dist[&2], 4);
+    assert_eq!(settled_3.frontier, vec![2]);
+    let last = dijkstra.last().unwrap();
+    assert_eq!(last.dist[&2], 3);
+    assert_eq!(last.dist[&4], 8);
+    assert_eq!(last.visited.len(), 4);
+}
+
+#[test]
+fn log_filter_by_event_and_substring() {
+    let g = Graph::from_edges(&[(1, 2, 4), (1, 3, 1), (3, 2, 2)]);
+    let mut lines: Vec<String> = g.dijkstra_steps(1).iter().map(|s| s.explain(0)).collect();
+    lines.push("访问节点: 2".to_string());
+
+    let visits = filter_log(&lines, LogEvents::Visits, "");
+    assert!(!visits.is_empty());
+    assert!(
+        visits
+            .iter()
+            .all(|l| l.starts_with("确定节点") || l.starts_with("访问节点"))
+    );
+
+    let relax = filter_log(&lines, LogEvents::Relaxations, "");
+    assert!(relax.iter().all(|l| l.starts_with("松弛")));
+    assert_eq!(filter_log(&lines, LogEvents::Relaxations, "1→3").len(), 1);
+    assert_eq!(filter_log(&lines, LogEvents::All, "").len(), lines.len());
+    assert!(filter_log(&lines, LogEvents::Visits, "松弛").is_empty());
+    assert_eq!(LogEvents::All.next().next().next(), LogEvents::All);
+}
@@ -0,0 +1,101 @@
+use graph_algorithm_tui::avoid::Forbidden;
+use graph_algorithm_tui::graph::{EdgeType, Graph};
+use graph_algorithm_tui::turns::{TurnRestrictions, TurnRule, shortest_path};
+
+// 1 - 2 - 3
+// |   |   |
+// 4 - 5 - 6
+// 最短为 1-2-3-6（3），其次 1-2-5-6（4）、1-4-5-6（6）
+fn grid() -> Graph {
+    Graph::from_edges(&[
+        (1, 2, 1),
+        (2, 3, 1),
+        (3, 6, 1),
+        (2, 5, 1),
+        (1, 4, 2),
+        (4, 5, 2),
+        (5, 6, 2),
+    ])
+}
+
+#[test]
+fn bans_and_penalties_change_the_route() {
+    let g = grid();
+    let mut turns = TurnRestrictions::default();
+    let route = shortest_path(&g, &turns, 1, 6).unwrap();
+    assert_eq!((route.dist, route.path), (3, vec![1, 2, 3, 6]));
+
+    // 不许在 3 掉头，否则 1-2-3-2-5-6 也是一条路线
+    turns.set((2, 3, 2), TurnRule::Ban);
+
+    turns.set((2, 3, 6), TurnRule::Ban);
+    let route = shortest_path(&g, &turns, 1, 6).unwrap();
+    assert_eq!((route.dist, route.path), (4, vec![1, 2, 5, 6]));
+    assert!(route.penalties.is_empty());
+
+    // 在 2 右转进 5 罚 3 之后，绕外圈反而更近
+    turns.set((1, 2, 5), TurnRule::Penalty(3));
+    let route = shortest_path(&g, &turns, 1, 6).unwrap();
+    assert_eq!((route.dist, route.path), (6, vec![1, 4, 5, 6]));
+
+    turns.set((1, 4, 5), TurnRule::Penalty(2));
+    let route = shortest_path(&g, &turns, 1, 6).unwrap();
+    assert_eq!(route.dist, 7);
+    assert_eq!(route.path, vec![1, 2, 5, 6]);
+    assert_eq!(route.penalties, vec![((1, 2, 5), 3)]);
+
+    // 罚时为 0 即取消限制
+    turns.set((1, 2, 5), TurnRule::Penalty(0));
+    assert_eq!(turns.get((1, 2, 5)), None);
+    assert_eq!(turns.len(), 3);
+}
+
+#[test]
+fn route_may_pass_a_junction_twice() {
+    // 从 1 到 2 后不能直接转向 3，只能绕 2-4-5 的环再从另一个方向进入 2
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1), (2, 4, 1), (4, 5, 1), (5, 2, 1)]);
+    let mut turns = TurnRestrictions::default();
+    turns.set((1, 2, 3), TurnRule::Ban);
+    // 允许掉头时在 4 掉头最近
+    let route = shortest_path(&g, &turns, 1, 3).unwrap();
+    assert_eq!((route.dist, route.path), (4, vec![1, 2, 4, 2, 3]));
+
+    turns.set((2, 4, 2), TurnRule::Ban);
+    turns.set((2, 5, 2), TurnRule::Ban);
+    let route = shortest_path(&g, &turns, 1, 3).unwrap();
+    assert_eq!(route.dist, 5);
+    assert_eq!(route.path.len(), 6);
+    assert_eq!(route.path.iter().filter(|&&u| u == 2).count(), 2);
+    assert!(route.settled > g.nodes().len());
+}
+
+#[test]
+fn unreachable_when_every_turn_is_banned() {
+    let g = Graph::from_edges(&[(1, 2, 1), (2, 3, 1)]);
+    let mut turns = TurnRestrictions::default();
+    assert_eq!(shortest_path(&g, &turns, 2, 2).unwrap().path, vec![2]);
+    turns.set((1, 2, 3), TurnRule::Ban);
+    assert_eq!(shortest_path(&g, &turns, 1, 3), None);
+    assert_eq!(turns.extra((1, 2, 3)), None);
+    assert_eq!(turns.extra((3, 2, 1)), Some(0));
+
+    // 也可以在绕开禁行标记的视图上搜索
+    let mut forbidden = Forbidden::default();
+    forbidden.toggle_node(2);
+    assert_eq!(shortest_path(&forbidden.view(&g), &turns, 3, 1), None);
+}
+
+#[test]
+fn retain_drops_turns_over_deleted_edges() {
+    let mut g = grid();
+    let mut turns = TurnRestrictions::default();
+    turns.set((2, 3, 6), TurnRule::Ban);
+    turns.set((4, 5, 6), TurnRule::Penalty(1));
+    assert!(!turns.retain(&g));
+    g.remove_edge(3, 6, EdgeType::Both);
+    assert!(turns.retain(&g));
+    assert_eq!(
+        turns.iter().collect::<Vec<_>>(),
+        vec![((4, 5, 6), TurnRule::Penalty(1))]
+    );
+}
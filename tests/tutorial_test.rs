@@ -0,0 +1,58 @@
+use graph_algorithm_tui::tutorial::{StepMark, Tutorial};
+
+// 教程里高亮的节点/边顺序必须与算法的真实执行顺序一致
+#[test]
+fn builtin_tutorials_follow_algorithm_traces() {
+    let tutorials: Vec<Tutorial> = Tutorial::builtin();
+    assert_eq!(tutorials.len(), 3);
+
+    for t in tutorials {
+        let (nodes, edges) = match t.algorithm.as_str() {
+            // Dijkstra 的边记录包含所有松弛，教程只讲确定节点时的最短路径树边
+            "dijkstra" => {
+                let result = t.graph.dijkstra(t.start);
+                let nodes = result.visited_nodes;
+                let edges = nodes
+                    .iter()
+                    .skip(1)
+                    .map(|v| (result.parent[v], *v))
+                    .collect();
+                (nodes, edges)
+            }
+            "bfs" => t.graph.bfs(t.start),
+            "prim" => {
+                let mst = t.graph.prim(t.start);
+                (mst.nodes.clone(), mst.tree_edges())
+            }
+            other => panic!("未知算法 {}", other),
+        };
+
+        let marked_nodes: Vec<i64> = t
+            .steps
+            .iter()
+            .filter_map(|s| match s.mark {
+                Some(StepMark::Node(u)) => Some(u),
+                Some(StepMark::Edge(_, v)) if t.algorithm != "dijkstra" => Some(v),
+                _ => None,
+            })
+            .collect();
+        let marked_edges: Vec<(i64, i64)> = t
+            .steps
+            .iter()
+            .filter_map(|s| match s.mark {
+                Some(StepMark::Edge(u, v)) => Some((u, v)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(marked_nodes, nodes, "{}", t.title);
+        assert_eq!(marked_edges, edges, "{}", t.title);
+    }
+}
+
+#[test]
+fn parse_rejects_unknown_lines() {
+    let err = Tutorial::<i64>::parse("title 测试\nstep path 1 2\n").unwrap_err();
+    assert!(err.contains("第 2 行"));
+    assert!(Tutorial::<i64>::parse("edge 1 2 3\n").is_err());
+}
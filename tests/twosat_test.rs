@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use graph_algorithm_tui::harness::Case;
+use graph_algorithm_tui::twosat::{TwoSat, TwoSatResult};
+
+#[test]
+fn parses_clauses_and_dimacs() {
+    let p = TwoSat::parse("1 -2, -1 3; 2\n").unwrap();
+    assert_eq!(p.clauses, vec![(1, -2), (-1, 3), (2, 2)]);
+    assert_eq!(p.variables(), vec![1, 2, 3]);
+
+    let p = TwoSat::parse("c 注释\np cnf 2 2\n1 2 0\n-1 -2 0\n").unwrap();
+    assert_eq!(p.clauses, vec![(1, 2), (-1, -2)]);
+
+    assert!(TwoSat::parse("1 2 3").is_err());
+    assert!(TwoSat::parse("1 x").is_err());
+}
+
+#[test]
+fn implication_graph_has_both_literals() {
+    let g = TwoSat::parse("1 -2").unwrap().implication_graph::<i64>();
+    assert_eq!(g.nodes(), vec![-2, -1, 1, 2]);
+    assert_eq!(g.node_label(-2), Some("¬x2"));
+    assert!(g.edge(-1, -2).is_some());
+    assert!(g.edge(2, 1).is_some());
+    assert_eq!(g.edges().len(), 2);
+}
+
+#[test]
+fn reports_conflicting_variable() {
+    // x1 ∨ x1 与 ¬x1 ∨ ¬x1 互相矛盾
+    match TwoSat::parse("1, -1").unwrap().solve() {
+        TwoSatResult::Conflict { var, component } => {
+            assert_eq!(var, 1);
+            assert_eq!(component, vec![-1, 1]);
+        }
+        other => panic!("应当无解: {:?}", other),
+    }
+}
+
+#[test]
+fn solve_agrees_with_brute_force() {
+    for seed in 0..200 {
+        // 借用随机图生成器产生子句：边 (u, v, w) 的 w 奇偶决定是否取反
+        let case = Case::random(seed, 5, 8);
+        let clauses = case
+            .edges
+            .iter()
+            .map(|&(u, v, w)| {
+                (
+                    if w % 2 == 0 { u } else { -u },
+                    if w % 3 == 0 { -v } else { v },
+                )
+            })
+            .collect();
+        let p = TwoSat { clauses };
+        let vars = p.variables();
+
+        let brute = (0..1u32 << vars.len()).any(|mask| {
+            let assignment: BTreeMap<i64, bool> = vars
+                .iter()
+                .enumerate()
+                .map(|(i, &k)| (k, mask >> i & 1 == 1))
+                .collect();
+            p.is_satisfied_by(&assignment)
+        });
+        match p.solve() {
+            TwoSatResult::Satisfiable(assignment) => {
+                assert!(brute, "seed {}", seed);
+                assert!(p.is_satisfied_by(&assignment), "seed {}", seed);
+            }
+            TwoSatResult::Conflict { .. } => assert!(!brute, "seed {}", seed),
+        }
+    }
+}
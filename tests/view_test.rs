@@ -0,0 +1,56 @@
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::view::{GraphView, GraphViewState};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::{Block, Borders, StatefulWidget};
+
+fn row_text(buf: &Buffer, y: u16) -> String {
+    (0..buf.area.width)
+        .map(|x| buf[(x, y)].symbol().to_string())
+        .collect()
+}
+
+#[test]
+fn renders_nodes_weights_and_highlights_into_a_buffer() {
+    let g: Graph = GraphBuilder::new().edge(1, 2, 42).node(7).build();
+    let mut state =
+        GraphViewState::new([(1, (-10.0, 0.0)), (2, (10.0, 0.0))].into_iter().collect());
+    state.visited_nodes.insert(1);
+
+    let area = Rect::new(0, 0, 60, 21);
+    let mut buf = Buffer::empty(area);
+    GraphView::new(&g)
+        .block(Block::default().title("GraphView").borders(Borders::ALL))
+        .render(area, &mut buf, &mut state);
+
+    // 缺少坐标的节点 7 被补上坐标
+    assert!(state.positions.contains_key(&7));
+
+    let text: Vec<String> = (0..area.height).map(|y| row_text(&buf, y)).collect();
+    assert!(text[0].contains("GraphView"));
+    assert!(text.iter().any(|row| row.contains("42")));
+    for id in ["1", "2", "7"] {
+        assert!(text.iter().any(|row| row.contains(id)), "缺少节点 {}", id);
+    }
+    // 已访问的节点 1 画成黄色，其余节点为浅蓝
+    let colors: Vec<Color> = buf.content.iter().map(|c| c.fg).collect();
+    assert!(colors.contains(&Color::Yellow));
+    assert!(colors.contains(&Color::LightBlue));
+}
+
+#[test]
+fn fixed_bounds_leave_out_of_view_nodes_undrawn() {
+    let g: Graph = GraphBuilder::new().node(5).node(6).build();
+    let mut state =
+        GraphViewState::new([(5, (0.0, 0.0)), (6, (100.0, 100.0))].into_iter().collect());
+    state.bounds = Some(([-5.0, 5.0], [-5.0, 5.0]));
+
+    let area = Rect::new(0, 0, 20, 10);
+    let mut buf = Buffer::empty(area);
+    GraphView::new(&g).render(area, &mut buf, &mut state);
+
+    let text: String = (0..area.height).map(|y| row_text(&buf, y)).collect();
+    assert!(text.contains('5'));
+    assert!(!text.contains('6'));
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "i128")]
+
+use graph_algorithm_tui::graph::{Graph, GraphBuilder};
+use graph_algorithm_tui::weight::Weight;
+
+#[test]
+fn i128_weights_go_beyond_i64() {
+    let big = i64::MAX as i128;
+    let g: Graph<i128> = GraphBuilder::default()
+        .directed_edge(1, 2, big)
+        .directed_edge(2, 3, big)
+        .directed_edge(1, 3, 3 * big)
+        .build();
+
+    let result = g.dijkstra(1);
+    assert_eq!(result.dist[&3], 2 * big);
+    assert_eq!(result.path_to(3), Some(vec![1, 2, 3]));
+    assert_eq!(g.prim(1).total_weight, 2 * big);
+
+    assert_eq!(i128::MAX.add_or_infinity(1), i128::infinity());
+    assert!(g.scale_weights(i128::MAX / 2).is_none());
+}
+
+#[test]
+fn huge_weights_are_abbreviated_in_labels() {
+    let w: i128 = 123_456_789_012_345_678_901_234;
+    assert_eq!(w.format(0), "123456789012345678901234");
+    assert_eq!(w.abbreviated(0), "1.23e23");
+    assert_eq!((-w).abbreviated(0), "-1.23e23");
+    assert_eq!(123_456_789_012i128.abbreviated(0), "123456789012");
+    assert_eq!(1.5e30f64.abbreviated(2), "1.50e30");
+    assert_eq!(2.25f64.abbreviated(2), "2.25");
+}